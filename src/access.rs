@@ -0,0 +1,265 @@
+//!Per-peer access control
+//!
+//![AccessControl] is ordered list of allow and deny rules, matched against remote address or TLS
+//!identity of the peer. The first matching rule decides, while peers matching no rule are subject
+//!to default [Action].
+//!
+//!Common case of IP allow and deny lists in CIDR notation is covered by [ip_lists](AccessControl::ip_lists).
+//!
+//!Policy is attached to listener via [listen_with](crate::Socket::listen_with), checking each
+//!connection before any message is exchanged. Rejected pipes are closed right away and logged
+//!using nng's logger with auth facility, hence rejections are visible once logging is enabled
+//!(i.e. via [enable_logging](crate::utils::enable_logging)).
+//!
+//!Attached policy is subscribed to socket's [PipeNotifier](crate::notify::PipeNotifier), applying
+//!only to connections of its listener, while it can be checked manually for any pipe via
+//![is_allowed](AccessControl::is_allowed).
+//!
+//!## Usage
+//!
+//!```rust
+//!use nng_c::Socket;
+//!use nng_c::access::{AccessControl, Action, Rule};
+//!
+//!use core::net::{IpAddr, Ipv4Addr};
+//!
+//!let policy = AccessControl::new(Action::Deny).allow(Rule::Network(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 0)), 8))
+//!                                             .deny(Rule::Ip(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1))))
+//!                                             .allow(Rule::Local);
+//!
+//!let server = Socket::pair0().expect("create socket");
+//!server.listen_with("inproc://access-example".into(), &policy).expect("listen");
+//!```
+
+use crate::ErrorCode;
+use crate::error::error;
+use crate::options::{Address, Options, RemoteAddr, TlsPeerCn};
+use crate::notify::{PipeEvent, PipeNotifier};
+use crate::pipe::Pipe;
+use crate::socket::Listener;
+use crate::sys;
+
+use core::net::IpAddr;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+///Decision of the policy
+pub enum Action {
+    ///Peer is allowed to connect
+    Allow,
+    ///Connection is closed
+    Deny,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+///Rule matching peer
+pub enum Rule {
+    ///Matches exact IP address of the peer
+    Ip(IpAddr),
+    ///Matches IP address within network, specified by address and prefix length
+    Network(IpAddr, u8),
+    ///Matches common name of peer's TLS certificate
+    TlsCn(String),
+    ///Matches peers of local transports (inproc, ipc and abstract sockets)
+    Local,
+    ///Matches any peer
+    Any,
+}
+
+#[inline]
+fn in_network(ip: IpAddr, network: IpAddr, prefix: u8) -> bool {
+    #[inline(always)]
+    fn mask(bits: u32, prefix: u8) -> u128 {
+        match prefix as u32 {
+            0 => 0,
+            prefix if prefix >= bits => u128::MAX,
+            prefix => u128::MAX << (128 - prefix) >> (128 - bits),
+        }
+    }
+
+    match (ip, network) {
+        (IpAddr::V4(ip), IpAddr::V4(network)) => {
+            let mask = mask(32, prefix) as u32;
+            u32::from(ip) & mask == u32::from(network) & mask
+        },
+        (IpAddr::V6(ip), IpAddr::V6(network)) => {
+            let mask = mask(128, prefix);
+            u128::from(ip) & mask == u128::from(network) & mask
+        },
+        _ => false,
+    }
+}
+
+impl Rule {
+    fn matches(&self, addr: &Address, pipe: Option<&Pipe>) -> bool {
+        //IPv4 peers of dual stack listener are reported as IPv4-mapped addresses
+        let ip = addr.socket_addr().map(|addr| addr.ip().to_canonical());
+        match self {
+            Self::Ip(expected) => ip == Some(expected.to_canonical()),
+            Self::Network(network, prefix) => match ip {
+                Some(ip) => in_network(ip, network.to_canonical(), *prefix),
+                None => false,
+            },
+            Self::TlsCn(expected) => match pipe.map(Pipe::get_prop::<TlsPeerCn>) {
+                Some(Ok(TlsPeerCn(name))) => name == *expected,
+                _ => false,
+            },
+            Self::Local => matches!(addr, Address::Inproc(_) | Address::Ipc(_) | Address::Abstract(_)),
+            Self::Any => true,
+        }
+    }
+}
+
+impl core::str::FromStr for Rule {
+    type Err = ErrorCode;
+
+    ///Parses IP address (i.e. `10.0.0.1`) or network in CIDR notation (i.e. `10.0.0.0/8`)
+    ///
+    ///Returns `NNG_EADDRINVAL` if address is invalid or prefix length exceeds its size.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let invalid = || error(sys::nng_errno_enum::NNG_EADDRINVAL);
+        match text.split_once('/') {
+            Some((ip, prefix)) => {
+                let ip = ip.parse::<IpAddr>().map_err(|_| invalid())?;
+                let prefix = prefix.parse::<u8>().map_err(|_| invalid())?;
+                let max = if ip.is_ipv4() { 32 } else { 128 };
+                if prefix > max {
+                    return Err(invalid());
+                }
+                Ok(Self::Network(ip, prefix))
+            },
+            None => text.parse().map(Self::Ip).map_err(|_| invalid()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+///Access control policy
+pub struct AccessControl {
+    rules: Vec<(Action, Rule)>,
+    default: Action,
+}
+
+impl AccessControl {
+    #[inline]
+    ///Creates new policy without rules, which applies `default` action to all peers
+    pub const fn new(default: Action) -> Self {
+        Self {
+            rules: Vec::new(),
+            default,
+        }
+    }
+
+    #[inline]
+    ///Adds `rule` to allow matching peers
+    pub fn allow(mut self, rule: Rule) -> Self {
+        self.rules.push((Action::Allow, rule));
+        self
+    }
+
+    #[inline]
+    ///Adds `rule` to deny matching peers
+    pub fn deny(mut self, rule: Rule) -> Self {
+        self.rules.push((Action::Deny, rule));
+        self
+    }
+
+    ///Creates policy from IP `allow` and `deny` lists, containing addresses or networks in CIDR notation.
+    ///
+    ///Denied peers are rejected even if they are allowed. If `allow` list is not empty, IP peers
+    ///not matching it are rejected, otherwise they are accepted.
+    ///Peers of local transports are always accepted, as lists only apply to TCP and TLS connections.
+    ///
+    ///Returns `NNG_EADDRINVAL` if any entry cannot be parsed as [Rule].
+    pub fn ip_lists(allow: &[&str], deny: &[&str]) -> Result<Self, ErrorCode> {
+        let default = if allow.is_empty() {
+            Action::Allow
+        } else {
+            Action::Deny
+        };
+
+        let mut policy = Self::new(default);
+        policy.rules.reserve(deny.len() + allow.len() + 1);
+        for entry in deny {
+            policy.rules.push((Action::Deny, entry.parse()?));
+        }
+        policy.rules.push((Action::Allow, Rule::Local));
+        for entry in allow {
+            policy.rules.push((Action::Allow, entry.parse()?));
+        }
+
+        Ok(policy)
+    }
+
+    #[inline(always)]
+    ///Returns default action
+    pub fn default_action(&self) -> Action {
+        self.default
+    }
+
+    #[inline(always)]
+    ///Returns rules in order of evaluation
+    pub fn rules(&self) -> &[(Action, Rule)] {
+        &self.rules
+    }
+
+    ///Returns action of the first rule, matching peer with remote address `addr`
+    ///
+    ///Rules relying on pipe properties (i.e. [TlsCn](Rule::TlsCn)) never match, as there is no pipe.
+    pub fn check_addr(&self, addr: &Address) -> Action {
+        self.rules.iter().find(|(_, rule)| rule.matches(addr, None)).map_or(self.default, |(action, _)| *action)
+    }
+
+    ///Returns action of the first rule, matching peer of the `pipe`
+    pub fn check(&self, pipe: &Pipe) -> Action {
+        let addr = match pipe.get_prop::<RemoteAddr>() {
+            Ok(RemoteAddr(addr)) => addr,
+            Err(_) => Address::Unspecified,
+        };
+        self.rules.iter().find(|(_, rule)| rule.matches(&addr, Some(pipe))).map_or(self.default, |(action, _)| *action)
+    }
+
+    #[inline]
+    ///Returns whether peer of the `pipe` is allowed
+    pub fn is_allowed(&self, pipe: &Pipe) -> bool {
+        self.check(pipe) == Action::Allow
+    }
+
+    ///Attaches policy to the `listener`, closing pipes of denied peers.
+    ///
+    ///Policy is subscribed to socket's [PipeNotifier], and removed once listener is closed,
+    ///including when it fails to start.
+    pub fn attach(self, listener: &Listener) -> Result<(), ErrorCode> {
+        let notifier = PipeNotifier::install_raw(listener.1)?;
+        notifier.subscribe_listener(listener.0, move |pipe, event| if event == PipeEvent::AddPre && !self.is_allowed(&pipe) {
+            log_rejection(&pipe);
+            let _ = pipe.close();
+        });
+        Ok(())
+    }
+}
+
+impl Options<Listener> for AccessControl {
+    #[inline]
+    fn apply(&self, target: &Listener) -> Result<(), ErrorCode> {
+        self.clone().attach(target)
+    }
+}
+
+//Logs rejection using nng's logger
+fn log_rejection(pipe: &Pipe) {
+    let addr = match pipe.get_prop::<RemoteAddr>() {
+        Ok(RemoteAddr(addr)) => addr,
+        Err(_) => Address::Unspecified,
+    };
+
+    let mut msg = alloc::format!("rejected connection from {} (pipe {})", addr, pipe.id());
+    //Address is user controlled and may contain NUL
+    msg.retain(|ch| ch != '\0');
+    msg.push('\0');
+    unsafe {
+        sys::nng_log_auth(sys::nng_log_level::NNG_LOG_WARN, "NNG-ACCESS\0".as_ptr() as _, "%s\0".as_ptr() as _, msg.as_ptr());
+    }
+}
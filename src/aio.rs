@@ -1,11 +1,14 @@
-use core::ffi::c_void;
-use core::{ptr, task, hint, mem};
+use core::ffi::{c_uint, c_void};
+use core::{ptr, task, hint, mem, time};
+use core::future::Future;
+use core::pin::Pin;
 use core::cell::UnsafeCell;
 use core::sync::atomic::{AtomicBool, AtomicU8, Ordering};
 
 use alloc::boxed::Box;
 
 use crate::error::{error, ErrorCode};
+use crate::fallible::try_box;
 use crate::msg::Message;
 
 use nng_c_sys as sys;
@@ -219,12 +222,12 @@ pub(crate) struct Aio {
 
 impl Aio {
     pub(crate) fn new() -> Result<Self, ErrorCode> {
-        let state = Box::new(State {
+        let state = try_box(State {
             ready: AtomicBool::new(false),
             waker: AtomicWaker::new(),
             aio: ptr::null_mut(),
 
-        });
+        })?;
         let state = Box::leak(state);
         let result = unsafe {
             sys::nng_aio_alloc(&mut state.aio, Some(aio_callback), state as *mut _ as *mut _)
@@ -246,6 +249,13 @@ impl Aio {
         self.state.is_ready()
     }
 
+    #[inline(always)]
+    pub(crate) fn is_busy(&self) -> bool {
+        unsafe {
+            sys::nng_aio_busy(self.state.aio)
+        }
+    }
+
     #[inline]
     pub(crate) fn as_ptr(&self) -> *mut sys::nng_aio {
         self.state.aio
@@ -279,6 +289,57 @@ impl Aio {
         Ok(())
     }
 
+    ///Returns result of completed operation
+    pub(crate) fn get_result(&self) -> Result<(), ErrorCode> {
+        let result = unsafe {
+            sys::nng_aio_result(self.state.aio)
+        };
+
+        match result {
+            0 => Ok(()),
+            code => Err(error(code)),
+        }
+    }
+
+    #[inline]
+    ///Returns number of bytes transferred by completed operation
+    pub(crate) fn get_count(&self) -> usize {
+        unsafe {
+            sys::nng_aio_count(self.state.aio)
+        }
+    }
+
+    ///Takes output of completed operation by its index, leaving null in its place
+    pub(crate) fn take_output(&self, idx: c_uint) -> *mut c_void {
+        unsafe {
+            let output = sys::nng_aio_get_output(self.state.aio, idx);
+            sys::nng_aio_set_output(self.state.aio, idx, ptr::null_mut());
+            output
+        }
+    }
+
+    #[inline]
+    ///Stops operation, waiting for its completion
+    pub(crate) fn stop(&self) {
+        unsafe {
+            sys::nng_aio_stop(self.state.aio)
+        }
+    }
+
+    ///Sets IO vector for the operation
+    ///
+    ///IO vector is copied, but buffers must remain valid until operation is complete.
+    pub(crate) fn set_iov(&self, iov: &[sys::nng_iov]) -> Result<(), ErrorCode> {
+        let result = unsafe {
+            sys::nng_aio_set_iov(self.state.aio, iov.len() as _, iov.as_ptr())
+        };
+
+        match result {
+            0 => Ok(()),
+            code => Err(error(code)),
+        }
+    }
+
     ///Extracts message from AIO, if any
     ///
     ///This obviously None, if operation involved no message receiving,
@@ -327,3 +388,34 @@ impl Drop for Aio {
         }
     }
 }
+
+//Future, completing after specified duration
+pub(crate) struct Sleep {
+    aio: Aio,
+}
+
+impl Sleep {
+    pub(crate) fn new(duration: time::Duration) -> Result<Self, ErrorCode> {
+        let aio = Aio::new()?;
+        unsafe {
+            sys::nng_sleep_aio(duration.as_millis() as _, aio.as_ptr());
+        }
+
+        Ok(Self {
+            aio
+        })
+    }
+}
+
+impl Future for Sleep {
+    type Output = Result<(), ErrorCode>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut task::Context<'_>) -> task::Poll<Self::Output> {
+        if self.aio.is_ready() {
+            task::Poll::Ready(self.aio.get_result())
+        } else {
+            self.aio.register_waker(ctx.waker());
+            task::Poll::Pending
+        }
+    }
+}
@@ -0,0 +1,260 @@
+//!Request load balancing across multiple endpoints
+//!
+//!nng's req protocol distributes requests over connected pipes in round-robin fashion, regardless
+//!of how well peers behave, and keeps retrying dead peers.
+//!
+//![Balancer] instead maintains separate connection per endpoint, tracking its health via pipe
+//!events and outcome of requests, preferring endpoints that are connected and have least failures.
+//!Endpoints can be added by url or resolved by service name via [Resolver].
+
+use crate::ErrorCode;
+use crate::error::error;
+use crate::msg::Message;
+use crate::notify::{PipeEvent, PipeNotifier, Subscription};
+use crate::pipe::Pipe;
+use crate::socket::{ConnectOptions, Socket};
+use crate::str::String;
+use crate::options::Options;
+use crate::resolve::Resolver;
+use crate::sys;
+use crate::utils::sync::Mutex;
+
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+struct State {
+    //Ids of attached pipes
+    pipes: Mutex<Vec<i32>>,
+    failures: AtomicU32,
+}
+
+impl State {
+    #[inline]
+    fn failure(&self) {
+        self.failures.fetch_add(1, Ordering::AcqRel);
+    }
+
+    #[inline]
+    fn success(&self) {
+        self.failures.store(0, Ordering::Release);
+    }
+
+    #[inline]
+    fn health(&self) -> Health {
+        Health {
+            connections: self.pipes.lock().len(),
+            failures: self.failures.load(Ordering::Acquire),
+        }
+    }
+
+    fn on_pipe(&self, pipe: Pipe, event: PipeEvent) {
+        match event {
+            PipeEvent::AddPost => self.pipes.lock().push(pipe.id()),
+            //Also delivered for pipes rejected before being attached, which are not lost connections
+            PipeEvent::RemPost => {
+                let removed = {
+                    let mut pipes = self.pipes.lock();
+                    match pipes.iter().position(|id| *id == pipe.id()) {
+                        Some(idx) => {
+                            pipes.swap_remove(idx);
+                            true
+                        },
+                        None => false,
+                    }
+                };
+                if removed {
+                    self.failure();
+                }
+            },
+            PipeEvent::AddPre => (),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+///Health of the endpoint
+pub struct Health {
+    ///Number of currently established connections with endpoint
+    pub connections: usize,
+    ///Number of failures since last successful request
+    ///
+    ///Failure is either failed request or lost established connection
+    pub failures: u32,
+}
+
+impl Health {
+    #[inline(always)]
+    ///Returns whether endpoint is connected
+    pub const fn is_connected(&self) -> bool {
+        self.connections > 0
+    }
+}
+
+struct Endpoint {
+    socket: Socket,
+    state: Arc<State>,
+    _subscription: Subscription,
+}
+
+impl Endpoint {
+    fn new(url: String<'_>, options: &impl Options<Socket>) -> Result<Self, ErrorCode> {
+        let socket = Socket::req0()?;
+        options.apply(&socket)?;
+
+        let state = Arc::new(State {
+            pipes: Mutex::new(Vec::new())?,
+            failures: AtomicU32::new(0),
+        });
+        let subscription = {
+            let state = state.clone();
+            PipeNotifier::install(&socket)?.subscribe(move |pipe, event| state.on_pipe(pipe, event))
+        };
+
+        //Connect in background as endpoint may be temporary unavailable
+        socket.connect_with(url, ConnectOptions::new().with_async())?;
+
+        Ok(Self {
+            socket,
+            state,
+            _subscription: subscription,
+        })
+    }
+}
+
+///REQ client balancing requests across multiple endpoints
+///
+///Each endpoint is served by its own req0 socket, connecting in background.
+///When selecting endpoint for request, connected endpoints with least failures are preferred
+///with round-robin selection among equally healthy endpoints.
+///If none of endpoints is connected, then endpoints are selected in round-robin fashion.
+///
+///Note that you should set [RecvTimeout](crate::options::RecvTimeout) via options to detect
+///unresponsive endpoints, otherwise request waits for reply forever.
+pub struct Balancer {
+    endpoints: Vec<Endpoint>,
+    cursor: usize,
+}
+
+impl Balancer {
+    #[inline(always)]
+    ///Creates new instance without endpoints
+    pub const fn new() -> Self {
+        Self {
+            endpoints: Vec::new(),
+            cursor: 0,
+        }
+    }
+
+    #[inline]
+    ///Adds endpoint with `url`, starting connecting to it in background.
+    pub fn add(&mut self, url: String<'_>) -> Result<(), ErrorCode> {
+        self.add_with(url, &())
+    }
+
+    ///Adds endpoint with `url`, starting connecting to it in background.
+    ///
+    ///Allows to provide custom `options` to initialize endpoint's socket with.
+    pub fn add_with<T: Options<Socket>>(&mut self, url: String<'_>, options: &T) -> Result<(), ErrorCode> {
+        let endpoint = Endpoint::new(url, options)?;
+        self.endpoints.push(endpoint);
+        Ok(())
+    }
+
+    #[inline]
+    ///Adds all endpoints of the `service`, returned by `resolver`.
+    ///
+    ///Returns number of added endpoints.
+    pub fn add_resolved<R: Resolver + ?Sized>(&mut self, resolver: &R, service: &str) -> Result<usize, ErrorCode> {
+        self.add_resolved_with(resolver, service, &())
+    }
+
+    ///Adds all endpoints of the `service`, returned by `resolver`, initializing their sockets with `options`.
+    ///
+    ///Returns number of added endpoints.
+    pub fn add_resolved_with<R: Resolver + ?Sized, T: Options<Socket>>(&mut self, resolver: &R, service: &str, options: &T) -> Result<usize, ErrorCode> {
+        let urls = resolver.resolve(service)?;
+        for url in urls.iter() {
+            self.add_with(url.as_str().into(), options)?;
+        }
+        Ok(urls.len())
+    }
+
+    #[inline(always)]
+    ///Returns number of endpoints
+    pub fn len(&self) -> usize {
+        self.endpoints.len()
+    }
+
+    #[inline(always)]
+    ///Returns whether there are no endpoints
+    pub fn is_empty(&self) -> bool {
+        self.endpoints.is_empty()
+    }
+
+    #[inline]
+    ///Returns health of endpoint by its index, in order of addition
+    pub fn health(&self, idx: usize) -> Option<Health> {
+        self.endpoints.get(idx).map(|endpoint| endpoint.state.health())
+    }
+
+    fn select(&mut self) -> Option<usize> {
+        let len = self.endpoints.len();
+        if len == 0 {
+            return None;
+        }
+
+        let start = self.cursor % len;
+        let mut selected = None;
+        let mut selected_failures = u32::MAX;
+
+        for offset in 0..len {
+            let idx = (start + offset) % len;
+            let health = self.endpoints[idx].state.health();
+            if health.is_connected() && (selected.is_none() || health.failures < selected_failures) {
+                selected = Some(idx);
+                selected_failures = health.failures;
+            }
+        }
+
+        let selected = selected.unwrap_or(start);
+        self.cursor = selected.wrapping_add(1);
+        Some(selected)
+    }
+
+    ///Sends request to the selected endpoint, waiting for its reply
+    ///
+    ///Returns error if there are no endpoints.
+    ///Otherwise any failure is accounted in health of selected endpoint.
+    pub fn request(&mut self, msg: Message) -> Result<Message, ErrorCode> {
+        let idx = match self.select() {
+            Some(idx) => idx,
+            None => return Err(error(sys::nng_errno_enum::NNG_ENOENT)),
+        };
+
+        let endpoint = &self.endpoints[idx];
+        if let Err((_, error)) = endpoint.socket.send_msg(msg) {
+            endpoint.state.failure();
+            return Err(error.into());
+        }
+
+        match endpoint.socket.recv_msg() {
+            Ok(reply) => {
+                endpoint.state.success();
+                Ok(reply)
+            },
+            Err(error) => {
+                endpoint.state.failure();
+                Err(error.into())
+            }
+        }
+    }
+}
+
+impl Default for Balancer {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
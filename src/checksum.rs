@@ -0,0 +1,225 @@
+//!Message integrity checks
+//!
+//!nng transports rely on underlying connection for integrity, which may not be enough when
+//!messages travel through proxies or transports you don't fully trust.
+//!
+//![append] stores CRC-32 (IEEE 802.3) of the body at its end, in network byte order, while [verify]
+//!checks and strips it, reporting corruption as [ChecksumError].
+//![Checked] applies both transparently around socket's send and receive.
+//!
+//!Both peers must use checksum, as it is part of the message body.
+//!
+//!## Usage
+//!
+//!```rust
+//!use nng_c::{checksum, Message};
+//!
+//!let mut msg = Message::new().expect("create message");
+//!msg.append(b"payload").expect("append");
+//!checksum::append(&mut msg).expect("append checksum");
+//!
+//!checksum::verify(&mut msg).expect("valid checksum");
+//!assert_eq!(msg.body(), b"payload");
+//!```
+
+use crate::ErrorCode;
+use crate::error::error;
+use crate::msg::Message;
+use crate::socket::Socket;
+use crate::sys;
+
+use core::fmt;
+
+///Size of checksum in bytes
+pub const SIZE: usize = 4;
+
+//CRC-32 (IEEE 802.3) lookup table
+const CRC_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut idx = 0;
+    while idx < table.len() {
+        let mut crc = idx as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[idx] = crc;
+        idx += 1;
+    }
+    table
+};
+
+#[derive(Copy, Clone)]
+//Incremental CRC-32
+pub(crate) struct Crc32(u32);
+
+impl Crc32 {
+    #[inline(always)]
+    pub(crate) const fn new() -> Self {
+        Self(0xFFFF_FFFF)
+    }
+
+    #[inline]
+    pub(crate) fn update(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = CRC_TABLE[((self.0 ^ *byte as u32) & 0xFF) as usize] ^ (self.0 >> 8);
+        }
+    }
+
+    #[inline(always)]
+    pub(crate) const fn finish(self) -> u32 {
+        !self.0
+    }
+}
+
+#[inline]
+///Computes CRC-32 (IEEE 802.3) of `bytes`
+pub fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = Crc32::new();
+    crc.update(bytes);
+    crc.finish()
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+///Error of receiving message with checksum
+pub enum ChecksumError {
+    ///Socket failed to receive message
+    Socket(ErrorCode),
+    ///Message is too short to contain checksum
+    Truncated,
+    ///Checksum does not match content, which indicates corrupted message
+    Mismatch {
+        ///Checksum stored in the message
+        expected: u32,
+        ///Checksum of received content
+        actual: u32,
+    },
+}
+
+impl ChecksumError {
+    #[inline(always)]
+    ///Returns whether message is corrupted, rather than socket failed
+    pub const fn is_corrupted(&self) -> bool {
+        !matches!(self, Self::Socket(_))
+    }
+}
+
+impl From<ChecksumError> for ErrorCode {
+    #[inline]
+    fn from(checksum: ChecksumError) -> Self {
+        match checksum {
+            ChecksumError::Socket(code) => code,
+            ChecksumError::Truncated | ChecksumError::Mismatch { .. } => error(sys::nng_errno_enum::NNG_EPROTO),
+        }
+    }
+}
+
+impl fmt::Display for ChecksumError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Socket(code) => fmt.write_fmt(format_args!("unable to receive message: {}", code)),
+            Self::Truncated => fmt.write_str("message is too short to contain checksum"),
+            Self::Mismatch { expected, actual } => fmt.write_fmt(format_args!("checksum mismatch: expected {:08x}, got {:08x}", expected, actual)),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ChecksumError {}
+
+#[inline]
+///Appends checksum of the body to the `msg`
+pub fn append(msg: &mut Message) -> Result<(), ErrorCode> {
+    let crc = crc32(msg.body());
+    msg.append_u32(crc)
+}
+
+///Verifies checksum at the end of `msg` body, stripping it if valid.
+///
+///Message is left unchanged on error.
+pub fn verify(msg: &mut Message) -> Result<(), ChecksumError> {
+    let body = msg.body();
+    if body.len() < SIZE {
+        return Err(ChecksumError::Truncated);
+    }
+
+    let (content, checksum) = body.split_at(body.len() - SIZE);
+    let expected = u32::from_be_bytes([checksum[0], checksum[1], checksum[2], checksum[3]]);
+    let actual = crc32(content);
+    if expected == actual {
+        msg.truncate(content.len());
+        Ok(())
+    } else {
+        Err(ChecksumError::Mismatch {
+            expected,
+            actual,
+        })
+    }
+}
+
+///Socket wrapper, appending checksum to sent messages and verifying it on received ones
+pub struct Checked<'a> {
+    socket: &'a Socket,
+}
+
+impl<'a> Checked<'a> {
+    #[inline(always)]
+    ///Creates new wrapper over `socket`
+    pub const fn new(socket: &'a Socket) -> Self {
+        Self {
+            socket,
+        }
+    }
+
+    #[inline(always)]
+    ///Returns underlying socket
+    pub fn socket(&self) -> &'a Socket {
+        self.socket
+    }
+
+    ///Sends `msg` with checksum appended.
+    ///
+    ///On failure returns message with checksum stripped.
+    pub fn send_msg(&self, mut msg: Message) -> Result<(), (Message, ErrorCode)> {
+        if let Err(error) = append(&mut msg) {
+            return Err((msg, error));
+        }
+
+        self.socket.send_msg(msg).map_err(|(mut msg, error)| {
+            msg.truncate(msg.len() - SIZE);
+            (msg, error.into())
+        })
+    }
+
+    ///Receives message, verifying and stripping its checksum.
+    pub fn recv_msg(&self) -> Result<Message, ChecksumError> {
+        let mut msg = self.socket.recv_msg().map_err(|error| ChecksumError::Socket(error.into()))?;
+        verify(&mut msg)?;
+        Ok(msg)
+    }
+
+    ///Receives message without waiting, verifying and stripping its checksum.
+    ///
+    ///Returns `None` if no message is available.
+    pub fn try_recv_msg(&self) -> Result<Option<Message>, ChecksumError> {
+        match self.socket.try_recv_msg().map_err(|error| ChecksumError::Socket(error.into()))? {
+            Some(mut msg) => {
+                verify(&mut msg)?;
+                Ok(Some(msg))
+            },
+            None => Ok(None),
+        }
+    }
+}
+
+impl fmt::Debug for Checked<'_> {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Checked").field("socket", &self.socket).finish()
+    }
+}
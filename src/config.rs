@@ -0,0 +1,452 @@
+//!Declarative socket configuration
+//!
+//![SocketConfig] can be deserialized from any format supported by [serde](https://crates.io/crates/serde)
+//!and used to create socket via [Socket::from_config].
+//!
+//!## Example (TOML)
+//!
+//!```toml
+//!protocol = "req0"
+//!name = "client"
+//!connect = ["tcp://127.0.0.1:5555"]
+//!async_connect = true
+//!send_timeout_ms = 1000
+//!recv_timeout_ms = 5000
+//!
+//![reconnect]
+//!min_ms = 100
+//!max_ms = 10000
+//!
+//![tls]
+//!ca_file = "/etc/ssl/ca.pem"
+//!server_name = "example.com"
+//!```
+//!
+//!## Hot reload
+//!
+//![Reconciler] keeps socket in sync with changing config: on [reconcile](Reconciler::reconcile) it
+//!compares desired config with the current state and applies only the difference, leaving
+//!unaffected connections intact.
+
+use crate::ErrorCode;
+use crate::error::{error, Op, OpError};
+use crate::endpoints::{Endpoints, Kind};
+use crate::socket::{ConnectOptions, Socket};
+pub use crate::socket::Protocol;
+use crate::options::{self, Options};
+use crate::url::Scheme;
+use crate::{str, sys, tls};
+
+use core::{fmt, time};
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use serde::Deserialize;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+///Reconnect configuration
+///
+///Refer to [Reconnect](crate::options::Reconnect) for details.
+pub struct ReconnectConfig {
+    ///Minimum time in milliseconds to wait before reconnecting
+    pub min_ms: Option<u64>,
+    ///Maximum time in milliseconds to wait before reconnecting
+    pub max_ms: Option<u64>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+///TLS configuration
+///
+///Applied to listeners and dialers of the socket with `tls+tcp` or `wss` URL, using server and
+///client mode respectively, while other transports ignore it.
+pub struct TlsConfig {
+    ///Path to PEM file with CA certificate chain and optional revocation list
+    pub ca_file: Option<String>,
+    ///Path to PEM file with both own certificate and its private key
+    pub cert_key_file: Option<String>,
+    ///Passphrase to decrypt private key
+    pub key_pass: Option<String>,
+    ///Server name for client connections
+    pub server_name: Option<String>,
+    ///Authentication mode, overriding default one
+    pub auth: Option<tls::Auth>,
+}
+
+impl TlsConfig {
+    //Returns whether transport of the `url` uses TLS config
+    fn is_used(url: &str) -> bool {
+        matches!(Scheme::from_url(url.as_bytes()), Some(Scheme::Tls | Scheme::Wss))
+    }
+
+    fn build(&self, config: Option<tls::Config>) -> Result<tls::Config, ErrorCode> {
+        let config = match config {
+            Some(config) => config,
+            None => return Err(error(sys::nng_errno_enum::NNG_ENOMEM)),
+        };
+
+        if let Some(ca_file) = self.ca_file.as_ref() {
+            config.ca_file(str::String::try_new(ca_file.as_bytes())?)?;
+        }
+        if let Some(cert_key_file) = self.cert_key_file.as_ref() {
+            let pass = self.key_pass.as_ref().map(|pass| str::String::try_new(pass.as_bytes())).transpose()?;
+            config.cert_key_file(str::String::try_new(cert_key_file.as_bytes())?, pass)?;
+        }
+        if let Some(auth) = self.auth {
+            config.auth_mode(auth)?;
+        }
+
+        Ok(config)
+    }
+
+    #[inline]
+    ///Creates server side config for listeners
+    pub fn server(&self) -> Result<tls::Config, ErrorCode> {
+        self.build(tls::Config::server())
+    }
+
+    ///Creates client side config for dialers
+    pub fn client(&self) -> Result<tls::Config, ErrorCode> {
+        let config = self.build(tls::Config::client())?;
+        if let Some(server_name) = self.server_name.as_ref() {
+            config.server_name(server_name)?;
+        }
+        Ok(config)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+///Socket configuration
+///
+///All fields, except `protocol`, are optional.
+///Durations are specified in milliseconds.
+pub struct SocketConfig {
+    ///Socket protocol
+    pub protocol: Protocol,
+    ///Whether to create socket in raw mode
+    #[serde(default)]
+    pub raw: bool,
+    ///Socket name
+    #[serde(default)]
+    pub name: Option<String>,
+    ///URLs to listen on
+    #[serde(default)]
+    pub listen: Vec<String>,
+    ///URLs to connect to
+    #[serde(default)]
+    pub connect: Vec<String>,
+    ///Whether to connect in background, instead of waiting for connection to be established
+    #[serde(default)]
+    pub async_connect: bool,
+    ///Topics to subscribe to. Only valid for `sub0` protocol
+    #[serde(default)]
+    pub subscribe: Vec<String>,
+    ///Send timeout
+    #[serde(default)]
+    pub send_timeout_ms: Option<u64>,
+    ///Receive timeout
+    #[serde(default)]
+    pub recv_timeout_ms: Option<u64>,
+    ///Number of messages to buffer for sending
+    #[serde(default)]
+    pub send_buf: Option<u16>,
+    ///Number of messages to buffer for receiving
+    #[serde(default)]
+    pub recv_buf: Option<u16>,
+    ///Maximum size of message to receive in bytes
+    #[serde(default)]
+    pub recv_max_size: Option<usize>,
+    ///Reconnect configuration
+    #[serde(default)]
+    pub reconnect: Option<ReconnectConfig>,
+    ///TLS configuration
+    #[serde(default)]
+    pub tls: Option<TlsConfig>,
+}
+
+impl SocketConfig {
+    #[inline]
+    ///Creates config with specified `protocol` and the rest fields as defaults
+    pub const fn new(protocol: Protocol) -> Self {
+        Self {
+            protocol,
+            raw: false,
+            name: None,
+            listen: Vec::new(),
+            connect: Vec::new(),
+            async_connect: false,
+            subscribe: Vec::new(),
+            send_timeout_ms: None,
+            recv_timeout_ms: None,
+            send_buf: None,
+            recv_buf: None,
+            recv_max_size: None,
+            reconnect: None,
+            tls: None,
+        }
+    }
+
+    //Returns whether socket options, applied via `Options`, are the same
+    fn same_options(&self, other: &Self) -> bool {
+        self.name == other.name && self.subscribe == other.subscribe
+                                && self.send_timeout_ms == other.send_timeout_ms
+                                && self.recv_timeout_ms == other.recv_timeout_ms
+                                && self.send_buf == other.send_buf
+                                && self.recv_buf == other.recv_buf
+                                && self.recv_max_size == other.recv_max_size
+                                && self.reconnect == other.reconnect
+    }
+}
+
+impl Options<Socket> for SocketConfig {
+    ///Applies socket options of the config, excluding protocol, listen and connect URLs.
+    fn apply(&self, target: &Socket) -> Result<(), ErrorCode> {
+        if let Some(name) = self.name.as_ref() {
+            match options::SocketName::new(name) {
+                Some(name) => name.apply(target)?,
+                None => return Err(error(sys::nng_errno_enum::NNG_EINVAL)),
+            }
+        }
+        if let Some(timeout) = self.send_timeout_ms {
+            options::SendTimeout(time::Duration::from_millis(timeout)).apply(target)?;
+        }
+        if let Some(timeout) = self.recv_timeout_ms {
+            options::RecvTimeout(time::Duration::from_millis(timeout)).apply(target)?;
+        }
+        if let Some(size) = self.send_buf {
+            options::SendBuf(size).apply(target)?;
+        }
+        if let Some(size) = self.recv_buf {
+            options::RecvBuf(size).apply(target)?;
+        }
+        if let Some(size) = self.recv_max_size {
+            options::RecvMaxSize(size).apply(target)?;
+        }
+        if let Some(reconnect) = self.reconnect.as_ref() {
+            options::Reconnect {
+                min_time: reconnect.min_ms.map(time::Duration::from_millis),
+                max_time: reconnect.max_ms.map(time::Duration::from_millis),
+            }.apply(target)?;
+        }
+        for topic in self.subscribe.iter() {
+            options::Subscribe(topic.as_bytes()).apply(target)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Socket {
+    ///Creates new socket from `config`
+    ///
+    ///Socket is created with configured protocol, options are applied and then socket starts
+    ///listening and connecting to configured URLs in order.
+    ///
+    ///Error reports failed operation together with URL and id of the endpoint, if any.
+    pub fn from_config(config: &SocketConfig) -> Result<Self, OpError> {
+        let socket = config.protocol.open(config.raw).map_err(|error| OpError::new(Op::Configure, error))?;
+        config.apply(&socket).map_err(|error| OpError::new(Op::Configure, error))?;
+
+        for url in config.listen.iter() {
+            let error = |code| OpError::new(Op::Listen, code).with_url(url);
+            let tls = config.tls.as_ref().filter(|_| TlsConfig::is_used(url));
+            let url = str::String::try_new(url.as_bytes()).map_err(error)?;
+            match tls {
+                Some(tls) => socket.add_listener(url, &tls.server().map_err(error)?)?,
+                None => socket.add_listener(url, &())?,
+            };
+        }
+
+        let connect = if config.async_connect {
+            ConnectOptions::new().with_async()
+        } else {
+            ConnectOptions::new()
+        };
+        for url in config.connect.iter() {
+            let error = |code| OpError::new(Op::Dial, code).with_url(url);
+            let tls = config.tls.as_ref().filter(|_| TlsConfig::is_used(url));
+            let url = str::String::try_new(url.as_bytes()).map_err(error)?;
+            match tls {
+                Some(tls) => socket.add_dialer(url, connect.with_dialer(tls.client().map_err(error)?))?,
+                None => socket.add_dialer(url, connect.clone())?,
+            };
+        }
+
+        Ok(socket)
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+///Changes, applied by [reconcile](Reconciler::reconcile)
+pub struct Changes {
+    ///Number of opened listeners and dialers
+    pub added: usize,
+    ///Number of closed listeners and dialers
+    pub removed: usize,
+    ///Whether socket options are re-applied
+    pub options: bool,
+}
+
+impl Changes {
+    #[inline(always)]
+    ///Returns whether nothing has changed
+    pub const fn is_empty(&self) -> bool {
+        self.added == 0 && self.removed == 0 && !self.options
+    }
+}
+
+///Socket, kept in sync with its config
+///
+///Reconciler owns endpoints of the socket, created from config, and tracks applied config:
+///
+///- Removed listen and connect URLs are closed, together with their connections;
+///- New listen and connect URLs are opened;
+///- Changed options are re-applied, while removed topics are unsubscribed;
+///- Change of TLS config re-opens endpoints with TLS transport, as TLS config is bound to endpoint on creation.
+///
+///Options, removed from config, are left as they are, since nng provides no way to restore defaults.
+///Protocol of the socket cannot be changed.
+pub struct Reconciler<'a> {
+    endpoints: Endpoints<'a>,
+    config: SocketConfig,
+}
+
+impl<'a> Reconciler<'a> {
+    ///Applies `config` to the `socket`, opening all of its endpoints.
+    ///
+    ///Socket must be created with configured protocol, i.e. via [Protocol::open].
+    pub fn new(socket: &'a Socket, config: SocketConfig) -> Result<Self, OpError> {
+        config.apply(socket).map_err(|error| OpError::new(Op::Configure, error))?;
+        let mut this = Self {
+            endpoints: socket.endpoints(),
+            config,
+        };
+        for url in this.config.listen.iter() {
+            Self::listen(&mut this.endpoints, &this.config, url)?;
+        }
+        for url in this.config.connect.iter() {
+            Self::connect(&mut this.endpoints, &this.config, url)?;
+        }
+        Ok(this)
+    }
+
+    #[inline(always)]
+    ///Returns underlying socket
+    pub fn socket(&self) -> &'a Socket {
+        self.endpoints.socket()
+    }
+
+    #[inline(always)]
+    ///Returns last applied config
+    pub fn config(&self) -> &SocketConfig {
+        &self.config
+    }
+
+    #[inline(always)]
+    ///Returns endpoints of the socket
+    pub fn endpoints(&self) -> &Endpoints<'a> {
+        &self.endpoints
+    }
+
+    fn listen(endpoints: &mut Endpoints<'a>, config: &SocketConfig, url: &str) -> Result<(), OpError> {
+        let error = |code| OpError::new(Op::Listen, code).with_url(url);
+        let tls = config.tls.as_ref().filter(|_| TlsConfig::is_used(url));
+        let url = str::String::try_new(url.as_bytes()).map_err(error)?;
+        match tls {
+            Some(tls) => endpoints.listen_with(url, &tls.server().map_err(error)?)?,
+            None => endpoints.listen(url)?,
+        };
+        Ok(())
+    }
+
+    fn connect(endpoints: &mut Endpoints<'a>, config: &SocketConfig, url: &str) -> Result<(), OpError> {
+        let error = |code| OpError::new(Op::Dial, code).with_url(url);
+        let tls = config.tls.as_ref().filter(|_| TlsConfig::is_used(url));
+        let url = str::String::try_new(url.as_bytes()).map_err(error)?;
+        let connect = if config.async_connect {
+            ConnectOptions::new().with_async()
+        } else {
+            ConnectOptions::new()
+        };
+        match tls {
+            Some(tls) => endpoints.connect_with(url, connect.with_dialer(tls.client().map_err(error)?))?,
+            None => endpoints.connect_with(url, connect)?,
+        };
+        Ok(())
+    }
+
+    //Closes endpoints of `kind`, which are not in `urls`, returning number of closed endpoints
+    //
+    //If `reopen_tls` is set, endpoints using TLS config are closed as well.
+    fn close_removed(&mut self, kind: Kind, urls: &[String], reopen_tls: bool) -> Result<usize, OpError> {
+        let removed = self.endpoints.iter().filter(|endpoint| endpoint.kind == kind)
+                                           .filter(|endpoint| !urls.contains(&endpoint.url) || (reopen_tls && TlsConfig::is_used(&endpoint.url)))
+                                           .map(|endpoint| (endpoint.id, endpoint.url.clone()))
+                                           .collect::<Vec<_>>();
+        let op = match kind {
+            Kind::Listener => Op::Listen,
+            Kind::Dialer => Op::Dial,
+        };
+        for (id, url) in removed.iter() {
+            if let Err(error) = self.endpoints.remove(*id) {
+                return Err(OpError::new(op, error).with_url(url).with_endpoint(*id));
+            }
+        }
+        Ok(removed.len())
+    }
+
+    ///Applies difference between current and `desired` config.
+    ///
+    ///Returns error if protocol differs or on failure to apply change, in which case changes,
+    ///applied before failure, remain in effect and next call retries the rest.
+    pub fn reconcile(&mut self, desired: &SocketConfig) -> Result<Changes, OpError> {
+        if desired.protocol != self.config.protocol || desired.raw != self.config.raw {
+            return Err(OpError::new(Op::Configure, error(sys::nng_errno_enum::NNG_EINVAL)));
+        }
+
+        let mut changes = Changes::default();
+        let reopen = desired.tls != self.config.tls;
+
+        let socket = self.endpoints.socket();
+        for topic in self.config.subscribe.iter().filter(|topic| !desired.subscribe.contains(topic)) {
+            match options::Unsubscribe(topic.as_bytes()).apply(socket) {
+                Ok(()) => (),
+                //Already unsubscribed by previous attempt, that failed later
+                Err(error) if error.raw_code() == sys::nng_errno_enum::NNG_ENOENT => (),
+                Err(error) => return Err(OpError::new(Op::Configure, error)),
+            }
+        }
+        if !desired.same_options(&self.config) {
+            desired.apply(socket).map_err(|error| OpError::new(Op::Configure, error))?;
+            changes.options = true;
+        }
+
+        changes.removed += self.close_removed(Kind::Listener, &desired.listen, reopen)?;
+        changes.removed += self.close_removed(Kind::Dialer, &desired.connect, reopen)?;
+
+        for url in desired.listen.iter() {
+            if !self.endpoints.iter().any(|endpoint| endpoint.kind == Kind::Listener && endpoint.url == *url) {
+                Self::listen(&mut self.endpoints, desired, url)?;
+                changes.added += 1;
+            }
+        }
+        for url in desired.connect.iter() {
+            if !self.endpoints.iter().any(|endpoint| endpoint.kind == Kind::Dialer && endpoint.url == *url) {
+                Self::connect(&mut self.endpoints, desired, url)?;
+                changes.added += 1;
+            }
+        }
+
+        self.config = desired.clone();
+        Ok(changes)
+    }
+}
+
+impl fmt::Debug for Reconciler<'_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Reconciler").field("endpoints", &self.endpoints).field("config", &self.config).finish()
+    }
+}
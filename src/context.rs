@@ -0,0 +1,350 @@
+//!Context module
+//!
+//!Context allows independent state machine of the protocol on the same socket,
+//!so that multiple requests can be processed concurrently.
+use crate::ErrorCode;
+use crate::error::error;
+use crate::msg::Message;
+use crate::socket::{Socket, FutureReq, FutureResp};
+use crate::options::Options;
+use crate::sys;
+
+use core::{mem, fmt, ops, ptr, task};
+use core::ffi::c_int;
+use core::future::Future;
+use core::pin::Pin;
+
+#[repr(transparent)]
+///Socket's context
+///
+///Only some protocols support contexts (i.e. req0, rep0, sub0, surveyor0, respondent0).
+pub struct Context(pub(crate) sys::nng_ctx);
+
+impl Context {
+    ///Creates new context on the `socket`
+    pub fn new(socket: &Socket) -> Result<Self, ErrorCode> {
+        let mut ctx = sys::nng_ctx {
+            id: 0
+        };
+
+        let result = unsafe {
+            sys::nng_ctx_open(&mut ctx, **socket)
+        };
+
+        match result {
+            0 => Ok(Self(ctx)),
+            code => Err(error(code)),
+        }
+    }
+
+    #[inline(always)]
+    ///Returns context's identifier
+    pub fn id(&self) -> c_int {
+        unsafe {
+            sys::nng_ctx_id(self.0)
+        }
+    }
+
+    #[inline(always)]
+    ///Closes context.
+    ///
+    ///Returns `true` if operation had effect
+    ///Otherwise, if context is already closed, returns `false`
+    pub fn close(&self) -> bool {
+        unsafe {
+            sys::nng_ctx_close(self.0) == 0
+        }
+    }
+
+    #[inline(always)]
+    ///Sets options on the context
+    ///
+    ///Context inherits socket's options on creation, but can override options relevant to its
+    ///state machine (i.e. timeouts).
+    pub fn set_opt<T: Options<Self>>(&self, opts: T) -> Result<(), ErrorCode> {
+        opts.apply(self)
+    }
+
+    fn recv_msg_inner<const FLAGS: c_int>(&self) -> Result<Message, ErrorCode> {
+        let mut msg = ptr::null_mut();
+        let result = unsafe {
+            sys::nng_ctx_recvmsg(self.0, &mut msg, FLAGS)
+        };
+
+        match ptr::NonNull::new(msg) {
+            Some(ptr) => Ok(Message(ptr)),
+            None => Err(error(result)),
+        }
+    }
+
+    #[inline]
+    ///Receives pending message, waiting forever if none is available.
+    ///
+    ///If underlying protocol doesn't support receiving messages, this shall return error always
+    pub fn recv_msg(&self) -> Result<Message, ErrorCode> {
+        self.recv_msg_inner::<0>()
+    }
+
+    #[inline]
+    ///Receives pending message without waiting.
+    ///
+    ///Returns None if no message is available.
+    pub fn try_recv_msg(&self) -> Result<Option<Message>, ErrorCode> {
+        match self.recv_msg_inner::<{sys::NNG_FLAG_NONBLOCK}>() {
+            Ok(msg) => Ok(Some(msg)),
+            Err(error) if error.is_would_block() => Ok(None),
+            Err(error) => Err(error)
+        }
+    }
+
+    #[inline]
+    ///Sends message over the context.
+    ///
+    ///If successful takes ownership of message.
+    ///Otherwise returns message with error code.
+    pub fn send_msg(&self, msg: Message) -> Result<(), (Message, ErrorCode)> {
+        self.send_msg_inner::<0>(msg)
+    }
+
+    #[inline]
+    ///Attempts to send message over the context without waiting.
+    ///
+    ///If message cannot be sent immediately, returns it with would-block error.
+    pub fn try_send_msg(&self, msg: Message) -> Result<(), (Message, ErrorCode)> {
+        self.send_msg_inner::<{sys::NNG_FLAG_NONBLOCK}>(msg)
+    }
+
+    fn send_msg_inner<const FLAGS: c_int>(&self, msg: Message) -> Result<(), (Message, ErrorCode)> {
+        let result = unsafe {
+            sys::nng_ctx_sendmsg(self.0, msg.as_ptr(), FLAGS)
+        };
+
+        match result {
+            0 => {
+                mem::forget(msg);
+                Ok(())
+            },
+            code => Err((msg, error(code))),
+        }
+    }
+
+    #[inline]
+    ///Creates new future that attempts to receive message from the context.
+    pub fn recv_msg_async(&self) -> Result<FutureResp, ErrorCode> {
+        FutureResp::start(|aio| unsafe {
+            sys::nng_ctx_recv(self.0, aio)
+        })
+    }
+
+    #[inline]
+    ///Sends message over the context asynchronously.
+    ///
+    ///If successful takes ownership of message.
+    ///Otherwise returns message with error code.
+    pub fn send_msg_async(&self, msg: Message) -> Result<FutureReq, ErrorCode> {
+        FutureReq::start(msg, |aio| unsafe {
+            sys::nng_ctx_send(self.0, aio)
+        })
+    }
+
+    #[inline]
+    ///Sends request `msg` over the context, returning future resolving to the matching reply.
+    ///
+    ///Only one request can be outstanding on the context, so sending new request cancels previous one.
+    ///Use [Socket::request] to send requests concurrently.
+    pub fn request(&self, msg: Message) -> FutureRequest<'_> {
+        FutureRequest::start(RequestCtx::Borrowed(self), msg)
+    }
+
+    #[inline]
+    ///Receives request, waiting forever if none is available.
+    ///
+    ///Returns request with [Responder] which should be used to reply on this context.
+    ///
+    ///Context is borrowed until reply is sent or `Responder` is dropped, preventing to receive new
+    ///request before current one is answered.
+    pub fn recv_request(&mut self) -> Result<(Message, Responder<'_>), ErrorCode> {
+        let msg = self.recv_msg()?;
+        Ok((msg, Responder {
+            ctx: self
+        }))
+    }
+}
+
+impl fmt::Debug for Context {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_fmt(format_args!("Context(id={})", self.0.id))
+    }
+}
+
+impl Drop for Context {
+    #[inline(always)]
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+enum RequestCtx<'a> {
+    Owned(Context),
+    Borrowed(&'a Context),
+}
+
+impl ops::Deref for RequestCtx<'_> {
+    type Target = Context;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Self::Owned(ctx) => ctx,
+            Self::Borrowed(ctx) => ctx,
+        }
+    }
+}
+
+enum RequestState {
+    Failed(ErrorCode),
+    Send(FutureReq),
+    Recv(FutureResp),
+    Done,
+}
+
+///Future of the request-reply roundtrip
+///
+///Created by [Socket::request] or [Context::request].
+///Dropping it cancels request.
+pub struct FutureRequest<'a> {
+    //Declared before context, so that pending operation is stopped before context is closed
+    state: RequestState,
+    ctx: Option<RequestCtx<'a>>,
+}
+
+impl<'a> FutureRequest<'a> {
+    fn start(ctx: RequestCtx<'a>, msg: Message) -> Self {
+        match ctx.send_msg_async(msg) {
+            Ok(fut) => Self {
+                state: RequestState::Send(fut),
+                ctx: Some(ctx),
+            },
+            Err(error) => Self::failed(error),
+        }
+    }
+
+    #[inline(always)]
+    fn failed(error: ErrorCode) -> Self {
+        Self {
+            state: RequestState::Failed(error),
+            ctx: None,
+        }
+    }
+
+    #[inline(always)]
+    ///Returns whether request is sent or waiting for reply
+    pub fn is_pending(&self) -> bool {
+        matches!(self.state, RequestState::Send(_) | RequestState::Recv(_))
+    }
+
+    #[inline]
+    ///Cancels request, completing future with `NNG_ECANCELED`
+    ///
+    ///Refer to [abort](Self::abort)
+    pub fn cancel(&mut self) {
+        self.abort(error(sys::nng_errno_enum::NNG_ECANCELED))
+    }
+
+    ///Aborts request, completing future with specified `error`
+    ///
+    ///Pending operation is stopped right away, so that new request can be sent on the same context
+    ///immediately, while reply to the aborted request is discarded.
+    ///Has no effect if request is already complete.
+    pub fn abort(&mut self, error: ErrorCode) {
+        if self.is_pending() {
+            //Dropping operation waits for its completion
+            self.state = RequestState::Failed(error);
+        }
+    }
+}
+
+impl FutureRequest<'static> {
+    #[inline]
+    pub(crate) fn new(socket: &Socket, msg: Message) -> Self {
+        match Context::new(socket) {
+            Ok(ctx) => Self::start(RequestCtx::Owned(ctx), msg),
+            Err(error) => Self::failed(error),
+        }
+    }
+}
+
+impl fmt::Debug for FutureRequest<'_> {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("FutureRequest").field("ctx", &self.ctx.as_deref()).finish()
+    }
+}
+
+impl Future for FutureRequest<'_> {
+    type Output = Result<Message, ErrorCode>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut task::Context<'_>) -> task::Poll<Self::Output> {
+        let this = self.get_mut();
+        loop {
+            match &mut this.state {
+                RequestState::Failed(_) => match mem::replace(&mut this.state, RequestState::Done) {
+                    RequestState::Failed(error) => return task::Poll::Ready(Err(error)),
+                    _ => unreachable!(),
+                },
+                RequestState::Send(fut) => match Pin::new(fut).poll(ctx) {
+                    task::Poll::Pending => return task::Poll::Pending,
+                    task::Poll::Ready(Ok(())) => {
+                        let recv = match &this.ctx {
+                            Some(ctx) => ctx.recv_msg_async(),
+                            None => Err(error(sys::nng_errno_enum::NNG_ESTATE)),
+                        };
+                        this.state = match recv {
+                            Ok(fut) => RequestState::Recv(fut),
+                            Err(error) => RequestState::Failed(error),
+                        };
+                    },
+                    task::Poll::Ready(Err((_, error))) => {
+                        this.state = RequestState::Done;
+                        return task::Poll::Ready(Err(error));
+                    }
+                },
+                RequestState::Recv(fut) => {
+                    let result = match Pin::new(fut).poll(ctx) {
+                        task::Poll::Pending => return task::Poll::Pending,
+                        task::Poll::Ready(Ok(Some(resp))) => Ok(resp),
+                        task::Poll::Ready(Ok(None)) => Err(error(sys::nng_errno_enum::NNG_EINTERNAL)),
+                        task::Poll::Ready(Err(error)) => Err(error),
+                    };
+                    this.state = RequestState::Done;
+                    return task::Poll::Ready(result);
+                },
+                RequestState::Done => return task::Poll::Ready(Err(error(sys::nng_errno_enum::NNG_ESTATE))),
+            }
+        }
+    }
+}
+
+///Handle to reply on the context, request was received from.
+pub struct Responder<'a> {
+    ctx: &'a mut Context,
+}
+
+impl Responder<'_> {
+    #[inline(always)]
+    ///Sends `msg` as reply to the request.
+    ///
+    ///If successful takes ownership of message.
+    ///Otherwise returns message with error code.
+    pub fn reply(self, msg: Message) -> Result<(), (Message, ErrorCode)> {
+        self.ctx.send_msg(msg)
+    }
+}
+
+impl fmt::Debug for Responder<'_> {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_fmt(format_args!("Responder(ctx={})", self.ctx.0.id))
+    }
+}
@@ -0,0 +1,143 @@
+//!Correlation ID propagation
+//!
+//!Correlation ID allows to trace request as it travels across multiple hops of nng topology (devices, brokers).
+//!
+//!ID is stored in message's [metadata](crate::metadata) region, so devices forward it as it is,
+//!while broker can read it via [peek](CorrelationId::peek) and stamp it onto outgoing message.
+//!
+//![TraceContext] is stored in the same region.
+//!
+//!Note that pub/sub filters messages by body prefix, therefore region must not be used with sub0 subscriptions
+//!other than empty one.
+
+use crate::ErrorCode;
+use crate::metadata::{self, Tag};
+use crate::msg::Message;
+use crate::socket::Socket;
+use crate::sys;
+
+use core::fmt;
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[repr(transparent)]
+///Correlation ID of the message
+pub struct CorrelationId(pub u64);
+
+impl CorrelationId {
+    #[inline]
+    ///Generates random ID using nng's random generator
+    pub fn generate() -> Self {
+        let (high, low) = unsafe {
+            (sys::nng_random(), sys::nng_random())
+        };
+        Self((high as u64) << 32 | low as u64)
+    }
+
+    ///Reads ID from `msg` without removing it
+    pub fn peek(msg: &Message) -> Option<Self> {
+        match metadata::get(msg, Tag::Correlation)? {
+            [b1, b2, b3, b4, b5, b6, b7, b8] => Some(Self(u64::from_be_bytes([*b1, *b2, *b3, *b4, *b5, *b6, *b7, *b8]))),
+            _ => None,
+        }
+    }
+
+    ///Removes ID from `msg`, returning it
+    ///
+    ///Returns `None` if message has no correlation ID, in which case message is left unchanged.
+    pub fn extract(msg: &mut Message) -> Option<Self> {
+        let id = Self::peek(msg)?;
+        //Region only shrinks, so re-inserting it is not expected to fail
+        let _ = metadata::set(msg, Tag::Correlation, None);
+        Some(id)
+    }
+
+    #[inline]
+    ///Stamps ID onto `msg`, replacing existing one, if any.
+    pub fn stamp(self, msg: &mut Message) -> Result<(), ErrorCode> {
+        metadata::set(msg, Tag::Correlation, Some(&self.0.to_be_bytes()))
+    }
+}
+
+impl fmt::Debug for CorrelationId {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, fmt)
+    }
+}
+
+impl fmt::Display for CorrelationId {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_fmt(format_args!("{:016x}", self.0))
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+///Distributed tracing context of the message, following W3C trace context
+pub struct TraceContext {
+    ///Trace ID
+    pub trace_id: u128,
+    ///ID of the span, which sent message
+    pub span_id: u64,
+    ///Trace flags (i.e. whether trace is sampled)
+    pub flags: u8,
+}
+
+impl TraceContext {
+    //Trace ID, span ID and flags
+    const SIZE: usize = 16 + 8 + 1;
+
+    ///Reads trace context from `msg` without removing it
+    pub fn peek(msg: &Message) -> Option<Self> {
+        let value = metadata::get(msg, Tag::Trace)?;
+        if value.len() != Self::SIZE {
+            return None;
+        }
+
+        let mut trace_id = [0u8; 16];
+        trace_id.copy_from_slice(&value[..16]);
+        let mut span_id = [0u8; 8];
+        span_id.copy_from_slice(&value[16..24]);
+        Some(Self {
+            trace_id: u128::from_be_bytes(trace_id),
+            span_id: u64::from_be_bytes(span_id),
+            flags: value[24],
+        })
+    }
+
+    ///Removes trace context from `msg`, returning it
+    ///
+    ///Returns `None` if message has no trace context, in which case message is left unchanged.
+    pub fn extract(msg: &mut Message) -> Option<Self> {
+        let context = Self::peek(msg)?;
+        //Region only shrinks, so re-inserting it is not expected to fail
+        let _ = metadata::set(msg, Tag::Trace, None);
+        Some(context)
+    }
+
+    ///Stamps trace context onto `msg`, replacing existing one, if any.
+    pub fn stamp(&self, msg: &mut Message) -> Result<(), ErrorCode> {
+        let mut value = [0u8; Self::SIZE];
+        value[..16].copy_from_slice(&self.trace_id.to_be_bytes());
+        value[16..24].copy_from_slice(&self.span_id.to_be_bytes());
+        value[24] = self.flags;
+        metadata::set(msg, Tag::Trace, Some(&value))
+    }
+}
+
+///Stamps `id` onto `msg` and sends it via `socket`
+///
+///On error, message is returned with ID stamped.
+pub fn send_msg(socket: &Socket, mut msg: Message, id: CorrelationId) -> Result<(), (Message, ErrorCode)> {
+    if let Err(error) = id.stamp(&mut msg) {
+        return Err((msg, error));
+    }
+    socket.send_msg(msg).map_err(|(msg, error)| (msg, error.into()))
+}
+
+///Receives message from `socket`, extracting its correlation ID, if any.
+pub fn recv_msg(socket: &Socket) -> Result<(Message, Option<CorrelationId>), ErrorCode> {
+    let mut msg = socket.recv_msg()?;
+    let id = CorrelationId::extract(&mut msg);
+    Ok((msg, id))
+}
@@ -0,0 +1,200 @@
+//!mDNS/DNS-SD endpoint discovery
+//!
+//![Discovery] advertises listening sockets on local network via multicast DNS, and resolves
+//!advertised peers into urls, which can be passed to [connect](crate::Socket::connect), so that LAN
+//!deployments do not need hardcoded addresses.
+//!
+//!Service `name` is advertised as DNS-SD service type `_<name>._tcp.local.`, with each listening
+//!socket being its instance. Scheme of the listening url is stored in TXT record, hence both
+//!`tcp` and `tls+tcp` listeners are supported.
+//!
+//!Requires feature `mdns`
+//!
+//!## Usage
+//!
+//!```rust,no_run
+//!use nng_c::Socket;
+//!use nng_c::discovery::Discovery;
+//!
+//!use core::time;
+//!
+//!const URL: &str = "tcp://0.0.0.0:5555";
+//!
+//!let discovery = Discovery::new().expect("start mDNS");
+//!
+//!let server = Socket::rep0().expect("create server");
+//!server.listen(URL.into()).expect("listen");
+//!let _advertisement = discovery.advertise("orders", "server-1", URL).expect("advertise");
+//!
+//!let client = Socket::req0().expect("create client");
+//!let resolver = discovery.resolver(time::Duration::from_secs(1));
+//!client.connect_all(&resolver, "orders").expect("connect");
+//!```
+
+use crate::ErrorCode;
+use crate::error::error;
+use crate::resolve::Resolver;
+use crate::sys;
+
+use core::{fmt, time};
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+///Top level domain of advertised services
+pub const DOMAIN: &str = "local.";
+///TXT record key, storing scheme of the url
+pub const SCHEME_KEY: &str = "scheme";
+
+fn mdns_error(mdns: mdns_sd::Error) -> ErrorCode {
+    match mdns {
+        mdns_sd::Error::Again => error(sys::nng_errno_enum::NNG_EAGAIN),
+        mdns_sd::Error::ParseIpAddr(_) => error(sys::nng_errno_enum::NNG_EADDRINVAL),
+        _ => error(sys::nng_errno_enum::NNG_EINVAL),
+    }
+}
+
+#[inline]
+///Returns DNS-SD service type of the service `name`
+pub fn service_type(name: &str) -> String {
+    format!("_{}._tcp.{}", name, DOMAIN)
+}
+
+//Splits url into scheme and port
+fn split_url(url: &str) -> Option<(&str, u16)> {
+    let (scheme, addr) = url.split_once("://")?;
+    let addr = addr.trim_end_matches('\0');
+    let (_, port) = addr.rsplit_once(':')?;
+    Some((scheme, port.parse().ok()?))
+}
+
+///Advertised instance of the service, which is withdrawn on drop
+pub struct Advertisement<'a> {
+    discovery: &'a Discovery,
+    fullname: String,
+}
+
+impl Advertisement<'_> {
+    #[inline(always)]
+    ///Returns full DNS-SD name of the instance
+    pub fn fullname(&self) -> &str {
+        &self.fullname
+    }
+}
+
+impl fmt::Debug for Advertisement<'_> {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Advertisement").field("fullname", &self.fullname).finish()
+    }
+}
+
+impl Drop for Advertisement<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        let _ = self.discovery.daemon.unregister(&self.fullname);
+    }
+}
+
+///mDNS responder and resolver
+pub struct Discovery {
+    daemon: mdns_sd::ServiceDaemon,
+}
+
+impl Discovery {
+    #[inline]
+    ///Starts mDNS daemon in background thread
+    pub fn new() -> Result<Self, ErrorCode> {
+        Ok(Self {
+            daemon: mdns_sd::ServiceDaemon::new().map_err(mdns_error)?,
+        })
+    }
+
+    ///Advertises `instance` of the service `name`, listening on `url`.
+    ///
+    ///Advertised addresses are addresses of the host, updated whenever they change, hence `url`
+    ///should be TCP based (i.e. `tcp` or `tls+tcp`) and is only used to determine scheme and port.
+    ///
+    ///Returns error if `url` has no port.
+    pub fn advertise<'a>(&'a self, name: &str, instance: &str, url: &str) -> Result<Advertisement<'a>, ErrorCode> {
+        let (scheme, port) = match split_url(url) {
+            Some(url) => url,
+            None => return Err(error(sys::nng_errno_enum::NNG_EADDRINVAL)),
+        };
+
+        let host = format!("{}.{}", instance, DOMAIN);
+        let properties = [(SCHEME_KEY, scheme)];
+        let info = mdns_sd::ServiceInfo::new(&service_type(name), instance, &host, (), port, &properties[..]).map_err(mdns_error)?
+                                                                                                           .enable_addr_auto();
+        let fullname = String::from(info.get_fullname());
+        self.daemon.register(info).map_err(mdns_error)?;
+        Ok(Advertisement {
+            discovery: self,
+            fullname,
+        })
+    }
+
+    ///Resolves instances of the service `name`, returning their urls.
+    ///
+    ///Network is queried for the whole `timeout`, as there is no way to know whether all instances
+    ///responded. Instance with multiple addresses is returned as url per address.
+    pub fn resolve(&self, name: &str, timeout: time::Duration) -> Result<Vec<String>, ErrorCode> {
+        let service_type = service_type(name);
+        let events = self.daemon.browse(&service_type).map_err(mdns_error)?;
+        let deadline = std::time::Instant::now() + timeout;
+
+        let mut urls = Vec::new();
+        while let Ok(event) = events.recv_deadline(deadline) {
+            if let mdns_sd::ServiceEvent::ServiceResolved(info) = event {
+                let scheme = info.get_property_val_str(SCHEME_KEY).unwrap_or("tcp");
+                for addr in info.get_addresses() {
+                    let addr = std::net::SocketAddr::new(*addr, info.get_port());
+                    urls.push(format!("{}://{}", scheme, addr));
+                }
+            }
+        }
+        let _ = self.daemon.stop_browse(&service_type);
+
+        urls.sort_unstable();
+        urls.dedup();
+        Ok(urls)
+    }
+
+    #[inline(always)]
+    ///Returns [Resolver], querying network for `timeout`
+    pub fn resolver(&self, timeout: time::Duration) -> MdnsResolver<'_> {
+        MdnsResolver {
+            discovery: self,
+            timeout,
+        }
+    }
+}
+
+impl fmt::Debug for Discovery {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Discovery").finish()
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+///[Resolver] of services, advertised via mDNS
+pub struct MdnsResolver<'a> {
+    discovery: &'a Discovery,
+    timeout: time::Duration,
+}
+
+impl Resolver for MdnsResolver<'_> {
+    #[inline]
+    fn resolve(&self, service: &str) -> Result<Vec<String>, ErrorCode> {
+        self.discovery.resolve(service, self.timeout)
+    }
+}
+
+impl Drop for Discovery {
+    #[inline]
+    fn drop(&mut self) {
+        let _ = self.daemon.shutdown();
+    }
+}
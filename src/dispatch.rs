@@ -0,0 +1,186 @@
+//!Message dispatcher
+//!
+//!Services exposing multiple commands on single socket (i.e. rep0 or pull0) need to route each
+//!message to its handler. [Dispatcher] extracts key of the message and invokes handler, registered
+//!for this key, or fallback handler if there is none.
+//!
+//!Key is either:
+//!
+//!- value of the [header](crate::headers::Headers), i.e. [ROUTING_KEY](crate::headers::Headers::ROUTING_KEY);
+//!- topic, which is the longest registered key, that body starts with, same as [Topics](crate::pubsub::Topics).
+//!
+//!Headers are only read, hence handler receives message as it is and can extract them itself.
+//!
+//!Handler may return reply, which is sent back over the socket by [serve](Dispatcher::serve).
+//!
+//!## Usage
+//!
+//!```rust
+//!use nng_c::Message;
+//!use nng_c::dispatch::Dispatcher;
+//!use nng_c::headers::Headers;
+//!
+//!let mut dispatcher = Dispatcher::by_header(Headers::ROUTING_KEY).route(b"ping", |_| {
+//!    let mut reply = Message::new().expect("create message");
+//!    reply.append(b"pong").expect("append");
+//!    Some(reply)
+//!}).fallback(|_| None);
+//!
+//!let mut headers = Headers::new();
+//!headers.set_str(Headers::ROUTING_KEY, "ping").expect("set routing key");
+//!let mut msg = Message::new().expect("create message");
+//!headers.write(&mut msg).expect("write headers");
+//!
+//!let reply = dispatcher.dispatch(msg).expect("routed").expect("have reply");
+//!assert_eq!(reply.body(), b"pong");
+//!```
+
+use crate::ErrorCode;
+use crate::headers::Headers;
+use crate::msg::Message;
+use crate::socket::Socket;
+
+use core::fmt;
+
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+///Handler of the message, returning optional reply
+pub type Handler<'a> = Box<dyn FnMut(Message) -> Option<Message> + 'a>;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+///Key of the message, used for routing
+pub enum Key {
+    ///Value of the header with specified name
+    Header(String),
+    ///The longest registered key, that body starts with
+    Topic,
+}
+
+///Router of messages to handlers
+pub struct Dispatcher<'a> {
+    key: Key,
+    handlers: Vec<(Vec<u8>, Handler<'a>)>,
+    fallback: Option<Handler<'a>>,
+}
+
+impl<'a> Dispatcher<'a> {
+    #[inline]
+    ///Creates new dispatcher without handlers, routing messages by `key`
+    pub const fn new(key: Key) -> Self {
+        Self {
+            key,
+            handlers: Vec::new(),
+            fallback: None,
+        }
+    }
+
+    #[inline]
+    ///Creates new dispatcher, routing messages by value of the header `name`
+    pub fn by_header(name: &str) -> Self {
+        Self::new(Key::Header(name.into()))
+    }
+
+    #[inline]
+    ///Creates new dispatcher, routing messages by topic
+    pub const fn by_topic() -> Self {
+        Self::new(Key::Topic)
+    }
+
+    #[inline(always)]
+    ///Returns key used for routing
+    pub fn key(&self) -> &Key {
+        &self.key
+    }
+
+    ///Registers `handler` for messages with `key`, replacing existing one, if any
+    pub fn route<F: FnMut(Message) -> Option<Message> + 'a>(mut self, key: &[u8], handler: F) -> Self {
+        let handler = Box::new(handler);
+        match self.handlers.iter_mut().find(|(existing, _)| existing == key) {
+            Some((_, existing)) => *existing = handler,
+            None => self.handlers.push((key.to_vec(), handler)),
+        }
+        self
+    }
+
+    #[inline]
+    ///Registers `handler` for messages, which match no other handler
+    pub fn fallback<F: FnMut(Message) -> Option<Message> + 'a>(mut self, handler: F) -> Self {
+        self.fallback = Some(Box::new(handler));
+        self
+    }
+
+    #[inline]
+    ///Returns whether there is handler for `key`
+    pub fn has_route(&self, key: &[u8]) -> bool {
+        self.handlers.iter().any(|(existing, _)| existing == key)
+    }
+
+    //Returns index of the handler for `msg`
+    fn find(&self, msg: &Message) -> Option<usize> {
+        match &self.key {
+            Key::Header(name) => {
+                //Malformed headers are treated as absent
+                let headers = Headers::read(msg).ok()??;
+                let value = headers.get_bytes(name)?;
+                self.handlers.iter().position(|(key, _)| key == value)
+            },
+            Key::Topic => {
+                let body = msg.body();
+                self.handlers.iter().enumerate()
+                                    .filter(|(_, (key, _))| body.starts_with(key))
+                                    .max_by_key(|(_, (key, _))| key.len())
+                                    .map(|(idx, _)| idx)
+            },
+        }
+    }
+
+    ///Routes `msg` to its handler, returning its reply.
+    ///
+    ///If there is no matching handler nor fallback, returns message back as error.
+    pub fn dispatch(&mut self, msg: Message) -> Result<Option<Message>, Message> {
+        let handler = match self.find(&msg) {
+            Some(idx) => &mut self.handlers[idx].1,
+            None => match self.fallback.as_mut() {
+                Some(fallback) => fallback,
+                None => return Err(msg),
+            },
+        };
+        Ok(handler(msg))
+    }
+
+    ///Receives single message from `socket` and dispatches it, sending reply back, if any.
+    ///
+    ///Returns whether message is routed, while unrouted message is dropped.
+    pub fn serve_once(&mut self, socket: &Socket) -> Result<bool, ErrorCode> {
+        let msg = socket.recv_msg()?;
+        match self.dispatch(msg) {
+            Ok(Some(reply)) => {
+                socket.send_msg(reply).map_err(|(_, error)| error)?;
+                Ok(true)
+            },
+            Ok(None) => Ok(true),
+            Err(_) => Ok(false),
+        }
+    }
+
+    ///Dispatches messages from `socket` until it fails (i.e. it is closed), returning its error.
+    pub fn serve(&mut self, socket: &Socket) -> ErrorCode {
+        loop {
+            if let Err(error) = self.serve_once(socket) {
+                break error;
+            }
+        }
+    }
+}
+
+impl fmt::Debug for Dispatcher<'_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let routes = self.handlers.iter().map(|(key, _)| key).collect::<Vec<_>>();
+        fmt.debug_struct("Dispatcher").field("key", &self.key)
+                                      .field("routes", &routes)
+                                      .field("fallback", &self.fallback.is_some())
+                                      .finish()
+    }
+}
@@ -0,0 +1,218 @@
+//!Runtime endpoint management
+//!
+//![Socket::listen](crate::Socket::listen) and [Socket::connect](crate::Socket::connect) hand
+//!created listeners and dialers over to the socket, leaving no way to close them individually.
+//!
+//![Endpoints] keeps track of listeners and dialers it creates, allowing to add and remove them on
+//!live socket, so that topology changes do not require socket to be re-created.
+//!Removing endpoint closes its listener or dialer together with all of its pipes, while other
+//!connections remain unaffected.
+//!
+//!Endpoints are owned by socket, hence they remain open when manager is dropped.
+//!
+//!## Usage
+//!
+//!```rust
+//!use nng_c::Socket;
+//!
+//!let server = Socket::pair1_poly().expect("create socket");
+//!let mut endpoints = server.endpoints();
+//!let primary = endpoints.listen("inproc://endpoints-example-primary".into()).expect("listen");
+//!endpoints.listen("inproc://endpoints-example-secondary".into()).expect("listen");
+//!
+//!assert!(endpoints.remove(primary).expect("remove"));
+//!assert_eq!(endpoints.len(), 1);
+//!assert!(endpoints.find("inproc://endpoints-example-secondary").is_some());
+//!```
+
+use crate::ErrorCode;
+use crate::error::{error, OpError};
+use crate::options::Options;
+use crate::socket::{ConnectOptions, Dialer, Listener, Socket};
+use crate::str;
+use crate::sys;
+
+use core::fmt;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+///Kind of the endpoint
+pub enum Kind {
+    ///Listener, accepting incoming connections
+    Listener,
+    ///Dialer, connecting to remote peer
+    Dialer,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+///Endpoint of the socket
+pub struct Endpoint {
+    ///Kind of the endpoint
+    pub kind: Kind,
+    ///Id of the listener or dialer
+    pub id: u32,
+    ///Url of the endpoint
+    pub url: String,
+}
+
+impl Endpoint {
+    #[inline]
+    fn url(url: &str::String<'_>) -> String {
+        String::from_utf8_lossy(url.as_bytes()).into_owned()
+    }
+
+    fn close(&self) -> Result<(), ErrorCode> {
+        let result = match self.kind {
+            Kind::Listener => unsafe {
+                sys::nng_listener_close(sys::nng_listener { id: self.id })
+            },
+            Kind::Dialer => unsafe {
+                sys::nng_dialer_close(sys::nng_dialer { id: self.id })
+            },
+        };
+
+        match result {
+            0 => Ok(()),
+            //Endpoint is already closed, i.e. together with socket
+            sys::nng_errno_enum::NNG_ENOENT | sys::nng_errno_enum::NNG_ECLOSED => Ok(()),
+            code => Err(error(code)),
+        }
+    }
+}
+
+///Manager of socket's listeners and dialers
+pub struct Endpoints<'a> {
+    socket: &'a Socket,
+    endpoints: Vec<Endpoint>,
+}
+
+impl<'a> Endpoints<'a> {
+    #[inline(always)]
+    ///Creates new manager of `socket` without endpoints
+    pub const fn new(socket: &'a Socket) -> Self {
+        Self {
+            socket,
+            endpoints: Vec::new(),
+        }
+    }
+
+    #[inline(always)]
+    ///Returns underlying socket
+    pub fn socket(&self) -> &'a Socket {
+        self.socket
+    }
+
+    #[inline(always)]
+    ///Returns number of endpoints
+    pub fn len(&self) -> usize {
+        self.endpoints.len()
+    }
+
+    #[inline(always)]
+    ///Returns whether there are no endpoints
+    pub fn is_empty(&self) -> bool {
+        self.endpoints.is_empty()
+    }
+
+    #[inline]
+    ///Returns iterator over endpoints in order of addition
+    pub fn iter(&self) -> impl Iterator<Item = &Endpoint> {
+        self.endpoints.iter()
+    }
+
+    #[inline]
+    ///Returns endpoint by its `id`
+    pub fn get(&self, id: u32) -> Option<&Endpoint> {
+        self.endpoints.iter().find(|endpoint| endpoint.id == id)
+    }
+
+    #[inline]
+    ///Returns first endpoint with `url`
+    pub fn find(&self, url: &str) -> Option<&Endpoint> {
+        let url = url.trim_end_matches('\0');
+        self.endpoints.iter().find(|endpoint| endpoint.url == url)
+    }
+
+    #[inline]
+    ///Starts listening on `url`, returning id of the listener
+    pub fn listen(&mut self, url: str::String<'_>) -> Result<u32, OpError> {
+        self.listen_with(url, &())
+    }
+
+    ///Starts listening on `url`, initializing listener with `options`.
+    ///
+    ///Returns id of the listener
+    pub fn listen_with<T: Options<Listener>>(&mut self, url: str::String<'_>, options: &T) -> Result<u32, OpError> {
+        let endpoint_url = Endpoint::url(&url);
+        let id = self.socket.add_listener(url, options)?;
+        self.endpoints.push(Endpoint {
+            kind: Kind::Listener,
+            id,
+            url: endpoint_url,
+        });
+        Ok(id)
+    }
+
+    #[inline]
+    ///Connects to `url`, returning id of the dialer
+    pub fn connect(&mut self, url: str::String<'_>) -> Result<u32, OpError> {
+        self.connect_with(url, ConnectOptions::new())
+    }
+
+    ///Connects to `url` with `options`, returning id of the dialer
+    pub fn connect_with<T: Options<Dialer>>(&mut self, url: str::String<'_>, options: ConnectOptions<T>) -> Result<u32, OpError> {
+        let endpoint_url = Endpoint::url(&url);
+        let id = self.socket.add_dialer(url, options)?;
+        self.endpoints.push(Endpoint {
+            kind: Kind::Dialer,
+            id,
+            url: endpoint_url,
+        });
+        Ok(id)
+    }
+
+    ///Closes endpoint with `id`, returning `false` if there is no such endpoint.
+    pub fn remove(&mut self, id: u32) -> Result<bool, ErrorCode> {
+        let idx = match self.endpoints.iter().position(|endpoint| endpoint.id == id) {
+            Some(idx) => idx,
+            None => return Ok(false),
+        };
+        self.endpoints[idx].close()?;
+        self.endpoints.remove(idx);
+        Ok(true)
+    }
+
+    ///Closes all endpoints with `url`, returning number of closed endpoints.
+    pub fn remove_url(&mut self, url: &str) -> Result<usize, ErrorCode> {
+        let url = url.trim_end_matches('\0');
+        let mut removed = 0;
+        let mut idx = 0;
+        while idx < self.endpoints.len() {
+            if self.endpoints[idx].url == url {
+                self.endpoints[idx].close()?;
+                self.endpoints.remove(idx);
+                removed += 1;
+            } else {
+                idx += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    ///Closes all endpoints
+    pub fn clear(&mut self) -> Result<(), ErrorCode> {
+        while let Some(endpoint) = self.endpoints.last() {
+            endpoint.close()?;
+            self.endpoints.pop();
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Endpoints<'_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Endpoints").field("socket", &self.socket).field("endpoints", &self.endpoints).finish()
+    }
+}
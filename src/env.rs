@@ -0,0 +1,223 @@
+//!Environment based configuration
+//!
+//!Helpers to configure socket from environment variables, suitable for 12-factor style deployments.
+//!
+//!## URLs
+//!
+//!Variables with URLs may contain multiple URLs separated by comma, e.g.
+//!`MYAPP_LISTEN="tcp://0.0.0.0:5555,ipc:///tmp/myapp.ipc"`
+//!
+//!## Options
+//!
+//![Socket::apply_env] reads following variables, using provided `prefix`:
+//!
+//!- `<prefix>_SEND_TIMEOUT_MS` - [SendTimeout](crate::options::SendTimeout) in milliseconds;
+//!- `<prefix>_RECV_TIMEOUT_MS` - [RecvTimeout](crate::options::RecvTimeout) in milliseconds;
+//!- `<prefix>_SEND_BUF` - [SendBuf](crate::options::SendBuf) in number of messages;
+//!- `<prefix>_RECV_BUF` - [RecvBuf](crate::options::RecvBuf) in number of messages;
+//!- `<prefix>_RECV_MAX_SIZE` - [RecvMaxSize](crate::options::RecvMaxSize) in bytes;
+//!- `<prefix>_RECONNECT_MIN_MS` - minimum time of [Reconnect](crate::options::Reconnect) in milliseconds;
+//!- `<prefix>_RECONNECT_MAX_MS` - maximum time of [Reconnect](crate::options::Reconnect) in milliseconds.
+//!
+//!Unset variables are ignored.
+
+use crate::ErrorCode;
+use crate::socket::{ConnectOptions, Socket};
+use crate::options::{self, Options};
+use crate::str;
+
+use core::{fmt, time};
+use core::str::FromStr;
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+#[derive(Debug)]
+///Environment configuration error
+pub enum Error {
+    ///Required variable is not set
+    Missing(String),
+    ///Variable's value is not valid
+    Invalid {
+        ///Variable name
+        var: String,
+        ///Invalid value
+        value: String,
+        ///Reason
+        reason: &'static str,
+    },
+    ///Failed to apply variable's value
+    Nng {
+        ///Variable name
+        var: String,
+        ///Value that failed to apply
+        value: String,
+        ///Error code
+        code: ErrorCode,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing(var) => fmt.write_fmt(format_args!("{var}: variable is not set")),
+            Self::Invalid { var, value, reason } => fmt.write_fmt(format_args!("{var}: invalid value '{value}': {reason}")),
+            Self::Nng { var, value, code } => fmt.write_fmt(format_args!("{var}: cannot apply '{value}': {code}")),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+fn read(var: &str) -> Result<Option<String>, Error> {
+    match std::env::var(var) {
+        Ok(value) => Ok(Some(value)),
+        Err(std::env::VarError::NotPresent) => Ok(None),
+        Err(std::env::VarError::NotUnicode(value)) => Err(Error::Invalid {
+            var: var.into(),
+            value: value.to_string_lossy().into_owned(),
+            reason: "not unicode",
+        }),
+    }
+}
+
+fn parse<T: FromStr>(var: &str) -> Result<Option<T>, Error> {
+    match read(var)? {
+        Some(value) => match value.trim().parse() {
+            Ok(result) => Ok(Some(result)),
+            Err(_) => Err(Error::Invalid {
+                var: var.into(),
+                reason: "not a valid number",
+                value,
+            }),
+        },
+        None => Ok(None),
+    }
+}
+
+///Reads list of comma separated URLs from variable `var`.
+///
+///Returns error if variable is not set, empty or any URL is not in `<scheme>://<address>` format.
+pub fn urls(var: &str) -> Result<Vec<String>, Error> {
+    let value = match read(var)? {
+        Some(value) => value,
+        None => return Err(Error::Missing(var.into())),
+    };
+
+    let mut result = Vec::new();
+    for url in value.split(',').map(|url| url.trim()).filter(|url| !url.is_empty()) {
+        match url.split_once("://") {
+            Some((scheme, address)) if !scheme.is_empty() && !address.is_empty() => result.push(url.into()),
+            _ => return Err(Error::Invalid {
+                var: var.into(),
+                value: url.into(),
+                reason: "expected URL in format <scheme>://<address>",
+            }),
+        }
+    }
+
+    if result.is_empty() {
+        Err(Error::Invalid {
+            var: var.into(),
+            value,
+            reason: "no URL specified",
+        })
+    } else {
+        Ok(result)
+    }
+}
+
+fn apply<T: Options<Socket>>(socket: &Socket, var: &str, value: impl fmt::Display, option: T) -> Result<(), Error> {
+    option.apply(socket).map_err(|code| Error::Nng {
+        var: var.into(),
+        value: format!("{value}"),
+        code,
+    })
+}
+
+impl Socket {
+    ///Starts listening on all URLs specified by environment variable `var`
+    ///
+    ///Refer to [urls] for format.
+    pub fn listen_from_env(&self, var: &str) -> Result<(), Error> {
+        for url in urls(var)? {
+            if let Err(code) = self.listen(str::String::new(url.as_bytes())) {
+                return Err(Error::Nng {
+                    var: var.into(),
+                    value: url,
+                    code,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    ///Connects to all URLs specified by environment variable `var`
+    ///
+    ///Connection is performed in background, as peer may not be available yet.
+    ///
+    ///Refer to [urls] for format.
+    pub fn connect_from_env(&self, var: &str) -> Result<(), Error> {
+        for url in urls(var)? {
+            if let Err(code) = self.connect_with(str::String::new(url.as_bytes()), ConnectOptions::new().with_async()) {
+                return Err(Error::Nng {
+                    var: var.into(),
+                    value: url,
+                    code,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    ///Applies common options, specified by environment variables starting with `prefix`
+    ///
+    ///Refer to [module](index.html) documentation for list of variables.
+    pub fn apply_env(&self, prefix: &str) -> Result<(), Error> {
+        let var = format!("{prefix}_SEND_TIMEOUT_MS");
+        if let Some(timeout) = parse::<u64>(&var)? {
+            apply(self, &var, timeout, options::SendTimeout(time::Duration::from_millis(timeout)))?;
+        }
+
+        let var = format!("{prefix}_RECV_TIMEOUT_MS");
+        if let Some(timeout) = parse::<u64>(&var)? {
+            apply(self, &var, timeout, options::RecvTimeout(time::Duration::from_millis(timeout)))?;
+        }
+
+        let var = format!("{prefix}_SEND_BUF");
+        if let Some(size) = parse::<u16>(&var)? {
+            apply(self, &var, size, options::SendBuf(size))?;
+        }
+
+        let var = format!("{prefix}_RECV_BUF");
+        if let Some(size) = parse::<u16>(&var)? {
+            apply(self, &var, size, options::RecvBuf(size))?;
+        }
+
+        let var = format!("{prefix}_RECV_MAX_SIZE");
+        if let Some(size) = parse::<usize>(&var)? {
+            apply(self, &var, size, options::RecvMaxSize(size))?;
+        }
+
+        let var = format!("{prefix}_RECONNECT_MIN_MS");
+        if let Some(time) = parse::<u64>(&var)? {
+            apply(self, &var, time, options::Reconnect {
+                min_time: Some(time::Duration::from_millis(time)),
+                max_time: None,
+            })?;
+        }
+
+        let var = format!("{prefix}_RECONNECT_MAX_MS");
+        if let Some(time) = parse::<u64>(&var)? {
+            apply(self, &var, time, options::Reconnect {
+                min_time: None,
+                max_time: Some(time::Duration::from_millis(time)),
+            })?;
+        }
+
+        Ok(())
+    }
+}
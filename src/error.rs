@@ -1,19 +1,30 @@
 //!NNG error definition
 
-use core::ptr;
+use core::{fmt, ptr, time};
+use core::convert::TryFrom;
 use core::ffi::c_int;
 use core::ffi::CStr;
 
+use alloc::string::String;
+
 pub use error_code::ErrorCode;
 
 use crate::sys;
 
+///Result with nng error by default
+pub type Result<T, E = ErrorCode> = core::result::Result<T, E>;
+
+///Upper limit of backoff between retries of [retry_if_would_block](ResultExt::retry_if_would_block)
+pub const MAX_RETRY_BACKOFF: time::Duration = time::Duration::from_secs(1);
+
 ///Extension to error code with shortcut for some meaningful checks
 pub trait NngError {
     ///Returns whether error code indicates cancellation of future.
     fn is_cancelled(&self) -> bool;
     ///Returns whether error code indicates operation timed out.
     fn is_timed_out(&self) -> bool;
+    ///Returns whether error code indicates that object is closed.
+    fn is_closed(&self) -> bool;
     ///Returns whether error code indicates aborted connection.
     fn is_conn_aborted(&self) -> bool;
     ///Returns whether error code indicates connection has been reset.
@@ -26,6 +37,8 @@ pub trait NngError {
     ///
     ///This is mostly indicates invalid local configuration (i.e. no TLS certificate etc)
     fn is_crypto(&self) -> bool;
+    ///Returns whether error code indicates that operation would block.
+    fn is_would_block(&self) -> bool;
 }
 
 impl NngError for ErrorCode {
@@ -39,6 +52,11 @@ impl NngError for ErrorCode {
         self.raw_code() == sys::nng_errno_enum::NNG_ETIMEDOUT
     }
 
+    #[inline(always)]
+    fn is_closed(&self) -> bool {
+        self.raw_code() == sys::nng_errno_enum::NNG_ECLOSED
+    }
+
     #[inline(always)]
     fn is_conn_aborted(&self) -> bool {
         self.raw_code() == sys::nng_errno_enum::NNG_ECONNABORTED
@@ -63,6 +81,52 @@ impl NngError for ErrorCode {
     fn is_crypto(&self) -> bool {
         self.raw_code() == sys::nng_errno_enum::NNG_ECRYPTO
     }
+
+    #[inline(always)]
+    fn is_would_block(&self) -> bool {
+        ErrorCode::is_would_block(self)
+    }
+}
+
+///Extension to results of non-blocking operations
+pub trait ResultExt<T, E = ErrorCode> {
+    ///Converts would-block error into `Ok(None)`
+    fn ok_if_would_block(self) -> Result<Option<T>, E>;
+
+    ///Retries operation via `op` up to `attempts` times as long as it fails with would-block error.
+    ///
+    ///Waits `backoff` before first retry, doubling it after each attempt up to [MAX_RETRY_BACKOFF],
+    ///unless `backoff` is already greater.
+    ///Returns last result, including would-block error if all attempts are exhausted.
+    fn retry_if_would_block<F: FnMut() -> Result<T, E>>(self, attempts: usize, backoff: time::Duration, op: F) -> Result<T, E>;
+}
+
+impl<T, E: NngError> ResultExt<T, E> for Result<T, E> {
+    #[inline]
+    fn ok_if_would_block(self) -> Result<Option<T>, E> {
+        match self {
+            Ok(value) => Ok(Some(value)),
+            Err(error) if error.is_would_block() => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+
+    fn retry_if_would_block<F: FnMut() -> Result<T, E>>(self, attempts: usize, mut backoff: time::Duration, mut op: F) -> Result<T, E> {
+        let mut result = self;
+        for _ in 0..attempts {
+            match result {
+                Err(error) if error.is_would_block() => unsafe {
+                    sys::nng_msleep(c_int::try_from(backoff.as_millis()).unwrap_or(c_int::MAX));
+                },
+                result => return result,
+            }
+            if backoff < MAX_RETRY_BACKOFF {
+                backoff = core::cmp::min(backoff.saturating_mul(2), MAX_RETRY_BACKOFF);
+            }
+            result = op();
+        }
+        result
+    }
 }
 
 static CATEGORY: error_code::Category = error_code::Category {
@@ -72,8 +136,13 @@ static CATEGORY: error_code::Category = error_code::Category {
     message,
 };
 
+#[inline(always)]
+fn is_nng_category(category: &'static error_code::Category) -> bool {
+    ptr::eq(&CATEGORY, category) || is_misuse_category(category)
+}
+
 fn equivalent(code: c_int, other: &ErrorCode) -> bool {
-    ptr::eq(&CATEGORY, other.category()) && code == other.raw_code()
+    is_nng_category(other.category()) && code == other.raw_code()
 }
 
 fn is_would_block(code: c_int) -> bool {
@@ -100,3 +169,447 @@ fn message(code: c_int, _: &mut error_code::MessageBuf) -> &str {
 pub(crate) fn error(code: c_int) -> ErrorCode {
     ErrorCode::new(code, &CATEGORY)
 }
+
+macro_rules! misuse_category {
+    ($name:ident, $message:literal) => {
+        static $name: error_code::Category = {
+            fn message(_: c_int, _: &mut error_code::MessageBuf) -> &str {
+                $message
+            }
+
+            error_code::Category {
+                name: "NngError",
+                equivalent,
+                is_would_block,
+                message,
+            }
+        };
+    };
+}
+
+#[cfg(debug_assertions)]
+misuse_category!(RECV_ON_SEND_ONLY, "protocol of the socket can only send messages (i.e. pub0 or push0), hence it cannot receive");
+#[cfg(debug_assertions)]
+misuse_category!(SEND_ON_RECV_ONLY, "protocol of the socket can only receive messages (i.e. sub0 or pull0), hence it cannot send");
+#[cfg(debug_assertions)]
+misuse_category!(NO_PENDING_REQUEST, "rep0 socket must receive request before sending reply");
+
+#[cfg(debug_assertions)]
+#[derive(Copy, Clone, Debug)]
+//Obviously invalid use of the protocol, detected in debug builds
+pub(crate) enum Misuse {
+    RecvOnSendOnly,
+    SendOnRecvOnly,
+    NoPendingRequest,
+}
+
+#[cfg(debug_assertions)]
+#[cold]
+#[inline(never)]
+//Creates nng error with raw code nng would return, but with descriptive message
+pub(crate) fn misuse(misuse: Misuse) -> ErrorCode {
+    match misuse {
+        Misuse::RecvOnSendOnly => ErrorCode::new(sys::nng_errno_enum::NNG_ENOTSUP, &RECV_ON_SEND_ONLY),
+        Misuse::SendOnRecvOnly => ErrorCode::new(sys::nng_errno_enum::NNG_ENOTSUP, &SEND_ON_RECV_ONLY),
+        Misuse::NoPendingRequest => ErrorCode::new(sys::nng_errno_enum::NNG_ESTATE, &NO_PENDING_REQUEST),
+    }
+}
+
+misuse_category!(TLS_DISABLED, "tls+tcp:// transport is not available, as `tls` feature is disabled");
+misuse_category!(WEBSOCKET_DISABLED, "ws:// transport is not available, as `websocket` feature is disabled");
+misuse_category!(WEBSOCKET_TLS_DISABLED, "wss:// transport is not available, as it requires both `websocket` and `tls` features");
+
+#[derive(Copy, Clone, Debug)]
+//Transport, which is not compiled in due to disabled cargo feature
+pub(crate) enum MissingFeature {
+    Tls,
+    Websocket,
+    WebsocketTls,
+}
+
+#[cold]
+#[inline(never)]
+//Creates nng error with raw code nng would return, but naming feature to enable
+pub(crate) fn missing_feature(feature: MissingFeature) -> ErrorCode {
+    match feature {
+        MissingFeature::Tls => ErrorCode::new(sys::nng_errno_enum::NNG_ENOTSUP, &TLS_DISABLED),
+        MissingFeature::Websocket => ErrorCode::new(sys::nng_errno_enum::NNG_ENOTSUP, &WEBSOCKET_DISABLED),
+        MissingFeature::WebsocketTls => ErrorCode::new(sys::nng_errno_enum::NNG_ENOTSUP, &WEBSOCKET_TLS_DISABLED),
+    }
+}
+
+fn is_misuse_category(category: &'static error_code::Category) -> bool {
+    #[cfg(debug_assertions)]
+    if ptr::eq(&RECV_ON_SEND_ONLY, category) || ptr::eq(&SEND_ON_RECV_ONLY, category) || ptr::eq(&NO_PENDING_REQUEST, category) {
+        return true;
+    }
+
+    ptr::eq(&TLS_DISABLED, category) || ptr::eq(&WEBSOCKET_DISABLED, category) || ptr::eq(&WEBSOCKET_TLS_DISABLED, category)
+}
+
+#[inline]
+///Creates error code with nng's category from raw `code`
+///
+///This is useful to create application defined errors, for example to abort futures with.
+pub fn nng_error(code: c_int) -> ErrorCode {
+    error(code)
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+///Error codes of nng, mirroring `nng_errno_enum`
+///
+///Allows to match on error without importing [sys](crate::sys)
+pub enum Errno {
+    ///Interrupted (`NNG_EINTR`)
+    Intr,
+    ///Out of memory (`NNG_ENOMEM`)
+    NoMem,
+    ///Invalid argument (`NNG_EINVAL`)
+    Inval,
+    ///Resource busy (`NNG_EBUSY`)
+    Busy,
+    ///Timed out (`NNG_ETIMEDOUT`)
+    TimedOut,
+    ///Connection refused (`NNG_ECONNREFUSED`)
+    ConnRefused,
+    ///Object closed (`NNG_ECLOSED`)
+    Closed,
+    ///Try again (would block) (`NNG_EAGAIN`)
+    Again,
+    ///Not supported (`NNG_ENOTSUP`)
+    NotSup,
+    ///Address in use (`NNG_EADDRINUSE`)
+    AddrInUse,
+    ///Incorrect state (`NNG_ESTATE`)
+    State,
+    ///Entry not found (`NNG_ENOENT`)
+    NoEnt,
+    ///Protocol error (`NNG_EPROTO`)
+    Proto,
+    ///Destination unreachable (`NNG_EUNREACHABLE`)
+    Unreachable,
+    ///Address invalid (`NNG_EADDRINVAL`)
+    AddrInval,
+    ///Permission denied (`NNG_EPERM`)
+    Perm,
+    ///Message too large (`NNG_EMSGSIZE`)
+    MsgSize,
+    ///Connection aborted (`NNG_ECONNABORTED`)
+    ConnAborted,
+    ///Connection reset (`NNG_ECONNRESET`)
+    ConnReset,
+    ///Operation canceled (`NNG_ECANCELED`)
+    Canceled,
+    ///Out of files (`NNG_ENOFILES`)
+    NoFiles,
+    ///Out of space (`NNG_ENOSPC`)
+    NoSpc,
+    ///Resource already exists (`NNG_EEXIST`)
+    Exist,
+    ///Read only resource (`NNG_EREADONLY`)
+    ReadOnly,
+    ///Write only resource (`NNG_EWRITEONLY`)
+    WriteOnly,
+    ///Cryptographic error (`NNG_ECRYPTO`)
+    Crypto,
+    ///Peer could not be authenticated (`NNG_EPEERAUTH`)
+    PeerAuth,
+    ///Option requires argument (`NNG_ENOARG`)
+    NoArg,
+    ///Ambiguous option (`NNG_EAMBIGUOUS`)
+    Ambiguous,
+    ///Incorrect type (`NNG_EBADTYPE`)
+    BadType,
+    ///Connection shutdown (`NNG_ECONNSHUT`)
+    ConnShut,
+    ///Internal error detected (`NNG_EINTERNAL`)
+    Internal,
+    ///System error with OS specific `errno` (`NNG_ESYSERR`)
+    System(c_int),
+    ///Transport specific error (`NNG_ETRANERR`)
+    Transport(c_int),
+}
+
+impl Errno {
+    ///Returns raw nng error code
+    pub const fn into_raw(self) -> c_int {
+        match self {
+            Self::Intr => sys::nng_errno_enum::NNG_EINTR,
+            Self::NoMem => sys::nng_errno_enum::NNG_ENOMEM,
+            Self::Inval => sys::nng_errno_enum::NNG_EINVAL,
+            Self::Busy => sys::nng_errno_enum::NNG_EBUSY,
+            Self::TimedOut => sys::nng_errno_enum::NNG_ETIMEDOUT,
+            Self::ConnRefused => sys::nng_errno_enum::NNG_ECONNREFUSED,
+            Self::Closed => sys::nng_errno_enum::NNG_ECLOSED,
+            Self::Again => sys::nng_errno_enum::NNG_EAGAIN,
+            Self::NotSup => sys::nng_errno_enum::NNG_ENOTSUP,
+            Self::AddrInUse => sys::nng_errno_enum::NNG_EADDRINUSE,
+            Self::State => sys::nng_errno_enum::NNG_ESTATE,
+            Self::NoEnt => sys::nng_errno_enum::NNG_ENOENT,
+            Self::Proto => sys::nng_errno_enum::NNG_EPROTO,
+            Self::Unreachable => sys::nng_errno_enum::NNG_EUNREACHABLE,
+            Self::AddrInval => sys::nng_errno_enum::NNG_EADDRINVAL,
+            Self::Perm => sys::nng_errno_enum::NNG_EPERM,
+            Self::MsgSize => sys::nng_errno_enum::NNG_EMSGSIZE,
+            Self::ConnAborted => sys::nng_errno_enum::NNG_ECONNABORTED,
+            Self::ConnReset => sys::nng_errno_enum::NNG_ECONNRESET,
+            Self::Canceled => sys::nng_errno_enum::NNG_ECANCELED,
+            Self::NoFiles => sys::nng_errno_enum::NNG_ENOFILES,
+            Self::NoSpc => sys::nng_errno_enum::NNG_ENOSPC,
+            Self::Exist => sys::nng_errno_enum::NNG_EEXIST,
+            Self::ReadOnly => sys::nng_errno_enum::NNG_EREADONLY,
+            Self::WriteOnly => sys::nng_errno_enum::NNG_EWRITEONLY,
+            Self::Crypto => sys::nng_errno_enum::NNG_ECRYPTO,
+            Self::PeerAuth => sys::nng_errno_enum::NNG_EPEERAUTH,
+            Self::NoArg => sys::nng_errno_enum::NNG_ENOARG,
+            Self::Ambiguous => sys::nng_errno_enum::NNG_EAMBIGUOUS,
+            Self::BadType => sys::nng_errno_enum::NNG_EBADTYPE,
+            Self::ConnShut => sys::nng_errno_enum::NNG_ECONNSHUT,
+            Self::Internal => sys::nng_errno_enum::NNG_EINTERNAL,
+            Self::System(code) => sys::nng_errno_enum::NNG_ESYSERR | code,
+            Self::Transport(code) => sys::nng_errno_enum::NNG_ETRANERR | code,
+        }
+    }
+}
+
+impl TryFrom<c_int> for Errno {
+    type Error = c_int;
+
+    ///Converts raw nng error code, returning it back if it is not known
+    fn try_from(code: c_int) -> Result<Self, Self::Error> {
+        match code {
+            sys::nng_errno_enum::NNG_EINTR => Ok(Self::Intr),
+            sys::nng_errno_enum::NNG_ENOMEM => Ok(Self::NoMem),
+            sys::nng_errno_enum::NNG_EINVAL => Ok(Self::Inval),
+            sys::nng_errno_enum::NNG_EBUSY => Ok(Self::Busy),
+            sys::nng_errno_enum::NNG_ETIMEDOUT => Ok(Self::TimedOut),
+            sys::nng_errno_enum::NNG_ECONNREFUSED => Ok(Self::ConnRefused),
+            sys::nng_errno_enum::NNG_ECLOSED => Ok(Self::Closed),
+            sys::nng_errno_enum::NNG_EAGAIN => Ok(Self::Again),
+            sys::nng_errno_enum::NNG_ENOTSUP => Ok(Self::NotSup),
+            sys::nng_errno_enum::NNG_EADDRINUSE => Ok(Self::AddrInUse),
+            sys::nng_errno_enum::NNG_ESTATE => Ok(Self::State),
+            sys::nng_errno_enum::NNG_ENOENT => Ok(Self::NoEnt),
+            sys::nng_errno_enum::NNG_EPROTO => Ok(Self::Proto),
+            sys::nng_errno_enum::NNG_EUNREACHABLE => Ok(Self::Unreachable),
+            sys::nng_errno_enum::NNG_EADDRINVAL => Ok(Self::AddrInval),
+            sys::nng_errno_enum::NNG_EPERM => Ok(Self::Perm),
+            sys::nng_errno_enum::NNG_EMSGSIZE => Ok(Self::MsgSize),
+            sys::nng_errno_enum::NNG_ECONNABORTED => Ok(Self::ConnAborted),
+            sys::nng_errno_enum::NNG_ECONNRESET => Ok(Self::ConnReset),
+            sys::nng_errno_enum::NNG_ECANCELED => Ok(Self::Canceled),
+            sys::nng_errno_enum::NNG_ENOFILES => Ok(Self::NoFiles),
+            sys::nng_errno_enum::NNG_ENOSPC => Ok(Self::NoSpc),
+            sys::nng_errno_enum::NNG_EEXIST => Ok(Self::Exist),
+            sys::nng_errno_enum::NNG_EREADONLY => Ok(Self::ReadOnly),
+            sys::nng_errno_enum::NNG_EWRITEONLY => Ok(Self::WriteOnly),
+            sys::nng_errno_enum::NNG_ECRYPTO => Ok(Self::Crypto),
+            sys::nng_errno_enum::NNG_EPEERAUTH => Ok(Self::PeerAuth),
+            sys::nng_errno_enum::NNG_ENOARG => Ok(Self::NoArg),
+            sys::nng_errno_enum::NNG_EAMBIGUOUS => Ok(Self::Ambiguous),
+            sys::nng_errno_enum::NNG_EBADTYPE => Ok(Self::BadType),
+            sys::nng_errno_enum::NNG_ECONNSHUT => Ok(Self::ConnShut),
+            sys::nng_errno_enum::NNG_EINTERNAL => Ok(Self::Internal),
+            code if code & sys::nng_errno_enum::NNG_ESYSERR != 0 => Ok(Self::System(code & !sys::nng_errno_enum::NNG_ESYSERR)),
+            code if code & sys::nng_errno_enum::NNG_ETRANERR != 0 => Ok(Self::Transport(code & !sys::nng_errno_enum::NNG_ETRANERR)),
+            code => Err(code),
+        }
+    }
+}
+
+impl TryFrom<ErrorCode> for Errno {
+    type Error = ErrorCode;
+
+    ///Converts error code of nng, returning it back if it is of other category or not known
+    fn try_from(code: ErrorCode) -> Result<Self, Self::Error> {
+        if is_nng_category(code.category()) {
+            Self::try_from(code.raw_code()).map_err(|_| code)
+        } else {
+            Err(code)
+        }
+    }
+}
+
+impl From<Errno> for ErrorCode {
+    #[inline(always)]
+    fn from(errno: Errno) -> Self {
+        error(errno.into_raw())
+    }
+}
+
+impl fmt::Display for Errno {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&ErrorCode::from(*self), fmt)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+///Operation, that failed
+pub enum Op {
+    ///Creating socket or applying its options
+    Configure,
+    ///Starting listener
+    Listen,
+    ///Starting dialer
+    Dial,
+    ///Sending message
+    Send,
+    ///Receiving message
+    Recv,
+}
+
+impl Op {
+    #[inline]
+    ///Returns textual name of the operation
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Configure => "configure",
+            Self::Listen => "listen",
+            Self::Dial => "dial",
+            Self::Send => "send",
+            Self::Recv => "recv",
+        }
+    }
+}
+
+impl fmt::Display for Op {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str(self.as_str())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+///Error code with context of the operation, that failed
+///
+///Can be converted into bare [ErrorCode], dropping context
+pub struct OpError {
+    op: Op,
+    url: Option<String>,
+    endpoint: Option<u32>,
+    code: ErrorCode,
+}
+
+impl OpError {
+    #[inline]
+    ///Creates new error for `op` failed with `code`
+    pub fn new(op: Op, code: ErrorCode) -> Self {
+        Self {
+            op,
+            url: None,
+            endpoint: None,
+            code,
+        }
+    }
+
+    #[inline]
+    ///Attaches `url` of the endpoint, operation was performed with
+    pub fn with_url(mut self, url: &str) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+
+    #[inline]
+    ///Attaches id of the listener or dialer, operation was performed with
+    pub fn with_endpoint(mut self, endpoint: u32) -> Self {
+        self.endpoint = Some(endpoint);
+        self
+    }
+
+    #[inline(always)]
+    ///Returns failed operation
+    pub fn op(&self) -> Op {
+        self.op
+    }
+
+    #[inline(always)]
+    ///Returns url of the endpoint, if known
+    pub fn url(&self) -> Option<&str> {
+        self.url.as_deref()
+    }
+
+    #[inline(always)]
+    ///Returns id of the listener or dialer, if known
+    pub fn endpoint(&self) -> Option<u32> {
+        self.endpoint
+    }
+
+    #[inline(always)]
+    ///Returns underlying error code
+    pub fn code(&self) -> ErrorCode {
+        self.code
+    }
+}
+
+impl From<OpError> for ErrorCode {
+    #[inline(always)]
+    fn from(error: OpError) -> Self {
+        error.code
+    }
+}
+
+impl fmt::Display for OpError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str(self.op.as_str())?;
+        if let Some(url) = &self.url {
+            fmt.write_fmt(format_args!(" {}", url))?;
+        }
+        if let Some(endpoint) = self.endpoint {
+            fmt.write_fmt(format_args!(" (endpoint {})", endpoint))?;
+        }
+        fmt.write_fmt(format_args!(": {}", self.code))
+    }
+}
+
+impl NngError for OpError {
+    #[inline(always)]
+    fn is_cancelled(&self) -> bool {
+        self.code.is_cancelled()
+    }
+
+    #[inline(always)]
+    fn is_timed_out(&self) -> bool {
+        self.code.is_timed_out()
+    }
+
+    #[inline(always)]
+    fn is_closed(&self) -> bool {
+        self.code.is_closed()
+    }
+
+    #[inline(always)]
+    fn is_conn_aborted(&self) -> bool {
+        self.code.is_conn_aborted()
+    }
+
+    #[inline(always)]
+    fn is_conn_reset(&self) -> bool {
+        self.code.is_conn_reset()
+    }
+
+    #[inline(always)]
+    fn is_conn_refused(&self) -> bool {
+        self.code.is_conn_refused()
+    }
+
+    #[inline(always)]
+    fn is_peer_auth(&self) -> bool {
+        self.code.is_peer_auth()
+    }
+
+    #[inline(always)]
+    fn is_crypto(&self) -> bool {
+        self.code.is_crypto()
+    }
+
+    #[inline(always)]
+    fn is_would_block(&self) -> bool {
+        self.code.is_would_block()
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for OpError {}
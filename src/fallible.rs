@@ -0,0 +1,44 @@
+//Fallible allocations, reporting failure as NNG_ENOMEM instead of aborting
+use crate::error::{error, ErrorCode};
+use crate::sys;
+
+use core::alloc::Layout;
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+#[cold]
+#[inline(never)]
+fn out_of_memory() -> ErrorCode {
+    error(sys::nng_errno_enum::NNG_ENOMEM)
+}
+
+//Moves `value` onto heap
+pub(crate) fn try_box<T>(value: T) -> Result<Box<T>, ErrorCode> {
+    let layout = Layout::new::<T>();
+    if layout.size() == 0 {
+        return Ok(Box::new(value));
+    }
+
+    let ptr = unsafe {
+        alloc::alloc::alloc(layout)
+    } as *mut T;
+    if ptr.is_null() {
+        return Err(out_of_memory());
+    }
+
+    unsafe {
+        ptr.write(value);
+        Ok(Box::from_raw(ptr))
+    }
+}
+
+#[inline]
+//Creates vector with exactly `capacity`
+pub(crate) fn try_vec<T>(capacity: usize) -> Result<Vec<T>, ErrorCode> {
+    let mut vec = Vec::new();
+    match vec.try_reserve_exact(capacity) {
+        Ok(()) => Ok(vec),
+        Err(_) => Err(out_of_memory()),
+    }
+}
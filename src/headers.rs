@@ -0,0 +1,243 @@
+//!Key/value message headers
+//!
+//![Headers] provides standard place for small metadata, such as routing keys, content types and TTLs.
+//!
+//!Same as [CorrelationId](crate::correlation::CorrelationId) and [TraceContext](crate::correlation::TraceContext),
+//!headers are stored in message's [metadata](crate::metadata) region, as nng's message header is owned by protocol.
+//!Entries are encoded as key length (u8), key, value length (u16) and value. All integers are in network byte order.
+//!
+//!## Usage
+//!
+//!```rust
+//!use nng_c::Message;
+//!use nng_c::headers::Headers;
+//!
+//!let mut headers = Headers::new();
+//!headers.set_str(Headers::CONTENT_TYPE, "application/json").expect("set content type");
+//!headers.set_u64("attempt", 2).expect("set attempt");
+//!
+//!let mut msg = Message::new().expect("create message");
+//!msg.append(b"{}").expect("append");
+//!headers.write(&mut msg).expect("write headers");
+//!
+//!let headers = Headers::extract(&mut msg).expect("valid headers").expect("have headers");
+//!assert_eq!(headers.get_str(Headers::CONTENT_TYPE), Some("application/json"));
+//!assert_eq!(headers.get_u64("attempt"), Some(2));
+//!assert_eq!(msg.body(), b"{}");
+//!```
+
+use crate::ErrorCode;
+use crate::error::error;
+use crate::metadata::{self, Tag};
+use crate::msg::Message;
+use crate::sys;
+
+use core::time;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+///Key/value metadata of the message
+pub struct Headers {
+    entries: Vec<(String, Vec<u8>)>,
+}
+
+impl Headers {
+    ///Maximum length of the key in bytes
+    pub const MAX_KEY_LEN: usize = u8::MAX as usize;
+    ///Maximum length of the value in bytes
+    pub const MAX_VALUE_LEN: usize = u16::MAX as usize;
+
+    ///Standard key for content type of the payload
+    pub const CONTENT_TYPE: &'static str = "content-type";
+    ///Standard key for routing key of the message
+    pub const ROUTING_KEY: &'static str = "routing-key";
+    ///Standard key for time to live of the message, stored as milliseconds
+    pub const TTL: &'static str = "ttl";
+
+    #[inline]
+    ///Creates empty headers
+    pub const fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    #[inline(always)]
+    ///Returns number of entries
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[inline(always)]
+    ///Returns whether there are no entries
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    #[inline]
+    ///Returns iterator over entries
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &[u8])> {
+        self.entries.iter().map(|(key, value)| (key.as_str(), value.as_slice()))
+    }
+
+    ///Sets `key` to `value`, replacing existing value.
+    ///
+    ///Returns error if key or value exceeds maximum length.
+    pub fn set_bytes(&mut self, key: &str, value: &[u8]) -> Result<(), ErrorCode> {
+        if key.len() > Self::MAX_KEY_LEN || value.len() > Self::MAX_VALUE_LEN {
+            return Err(error(sys::nng_errno_enum::NNG_EINVAL));
+        }
+
+        match self.entries.iter_mut().find(|(existing, _)| existing == key) {
+            Some((_, existing)) => {
+                existing.clear();
+                existing.extend_from_slice(value);
+            },
+            None => self.entries.push((key.into(), value.to_vec())),
+        }
+        Ok(())
+    }
+
+    #[inline]
+    ///Sets `key` to string `value`
+    pub fn set_str(&mut self, key: &str, value: &str) -> Result<(), ErrorCode> {
+        self.set_bytes(key, value.as_bytes())
+    }
+
+    #[inline]
+    ///Sets `key` to integer `value`
+    pub fn set_u64(&mut self, key: &str, value: u64) -> Result<(), ErrorCode> {
+        self.set_bytes(key, &value.to_be_bytes())
+    }
+
+    #[inline]
+    ///Sets `key` to duration `value` with millisecond precision
+    pub fn set_duration(&mut self, key: &str, value: time::Duration) -> Result<(), ErrorCode> {
+        self.set_u64(key, value.as_millis() as u64)
+    }
+
+    ///Removes `key`, returning whether it was present
+    pub fn remove(&mut self, key: &str) -> bool {
+        match self.entries.iter().position(|(existing, _)| existing == key) {
+            Some(idx) => {
+                self.entries.remove(idx);
+                true
+            },
+            None => false,
+        }
+    }
+
+    #[inline]
+    ///Returns value of `key`
+    pub fn get_bytes(&self, key: &str) -> Option<&[u8]> {
+        self.entries.iter().find(|(existing, _)| existing == key).map(|(_, value)| value.as_slice())
+    }
+
+    #[inline]
+    ///Returns value of `key`, if it is valid string
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        self.get_bytes(key).and_then(|value| core::str::from_utf8(value).ok())
+    }
+
+    #[inline]
+    ///Returns value of `key`, if it is integer
+    pub fn get_u64(&self, key: &str) -> Option<u64> {
+        let value = self.get_bytes(key)?;
+        let mut bytes = [0u8; 8];
+        if value.len() != bytes.len() {
+            return None;
+        }
+        bytes.copy_from_slice(value);
+        Some(u64::from_be_bytes(bytes))
+    }
+
+    #[inline]
+    ///Returns value of `key`, if it is duration
+    pub fn get_duration(&self, key: &str) -> Option<time::Duration> {
+        self.get_u64(key).map(time::Duration::from_millis)
+    }
+
+    #[inline]
+    ///Returns [CONTENT_TYPE](Self::CONTENT_TYPE)
+    pub fn content_type(&self) -> Option<&str> {
+        self.get_str(Self::CONTENT_TYPE)
+    }
+
+    #[inline]
+    ///Returns [ROUTING_KEY](Self::ROUTING_KEY)
+    pub fn routing_key(&self) -> Option<&str> {
+        self.get_str(Self::ROUTING_KEY)
+    }
+
+    #[inline]
+    ///Returns [TTL](Self::TTL)
+    pub fn ttl(&self) -> Option<time::Duration> {
+        self.get_duration(Self::TTL)
+    }
+
+    ///Writes headers into `msg`, replacing existing ones, if any.
+    pub fn write(&self, msg: &mut Message) -> Result<(), ErrorCode> {
+        let mut value = Vec::with_capacity(self.entries.iter().map(|(key, value)| 3 + key.len() + value.len()).sum::<usize>());
+        for (key, entry) in self.entries.iter() {
+            value.push(key.len() as u8);
+            value.extend_from_slice(key.as_bytes());
+            value.extend_from_slice(&(entry.len() as u16).to_be_bytes());
+            value.extend_from_slice(entry);
+        }
+
+        metadata::set(msg, Tag::Headers, Some(&value))
+    }
+
+    //Parses encoded entries
+    fn parse(mut entries: &[u8]) -> Result<Self, ErrorCode> {
+        let mut headers = Self::new();
+        while let Some((key_len, rest)) = entries.split_first() {
+            let key_len = *key_len as usize;
+            if rest.len() < key_len + 2 {
+                return Err(error(sys::nng_errno_enum::NNG_EPROTO));
+            }
+            let (key, rest) = rest.split_at(key_len);
+            let key = match core::str::from_utf8(key) {
+                Ok(key) => key,
+                Err(_) => return Err(error(sys::nng_errno_enum::NNG_EPROTO)),
+            };
+            let value_len = u16::from_be_bytes([rest[0], rest[1]]) as usize;
+            let rest = &rest[2..];
+            if rest.len() < value_len {
+                return Err(error(sys::nng_errno_enum::NNG_EPROTO));
+            }
+            let (value, rest) = rest.split_at(value_len);
+            headers.entries.push((key.into(), value.to_vec()));
+            entries = rest;
+        }
+
+        Ok(headers)
+    }
+
+    #[inline]
+    ///Reads headers from `msg` without removing them
+    ///
+    ///Returns `None` if message has no headers and error if headers are malformed.
+    pub fn read(msg: &Message) -> Result<Option<Self>, ErrorCode> {
+        match metadata::get(msg, Tag::Headers) {
+            Some(entries) => Self::parse(entries).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    ///Removes headers from `msg`, returning them
+    ///
+    ///Returns `None` if message has no headers, in which case message is left unchanged.
+    ///Returns error if headers are malformed, in which case they are removed regardless.
+    pub fn extract(msg: &mut Message) -> Result<Option<Self>, ErrorCode> {
+        let headers = match metadata::get(msg, Tag::Headers) {
+            Some(entries) => Self::parse(entries),
+            None => return Ok(None),
+        };
+        //Region only shrinks, so re-inserting it is not expected to fail
+        let _ = metadata::set(msg, Tag::Headers, None);
+        headers.map(Some)
+    }
+}
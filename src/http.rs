@@ -0,0 +1,606 @@
+//!HTTP server module
+//!
+//!Allows to serve plain HTTP endpoints and WebSocket connections over the same port.
+//!
+//!Server is shared by URL, hence websocket listeners created for the same host and port,
+//!register their handlers within the same server.
+
+use crate::ErrorCode;
+use crate::error::error;
+use crate::fallible::try_box;
+use crate::str::String;
+use crate::sys;
+
+use core::ffi::{c_void, CStr};
+use core::ptr::{self, NonNull};
+use core::fmt;
+
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+type Callback = Box<dyn Fn(&Request) -> Response + Send + Sync>;
+type Route = Arc<dyn Fn(&Request) -> Response + Send + Sync>;
+type Middleware = Arc<dyn Fn(&Request, Next<'_>) -> Response + Send + Sync>;
+
+///Continuation of the middleware chain, invoking next middleware or handler itself
+pub type Next<'a> = &'a dyn Fn(&Request) -> Response;
+
+#[inline]
+fn c_str<'a>(ptr: *const core::ffi::c_char) -> &'a str {
+    if ptr.is_null() {
+        ""
+    } else {
+        unsafe {
+            CStr::from_ptr(ptr).to_str().unwrap_or("")
+        }
+    }
+}
+
+///Incoming HTTP request
+pub struct Request {
+    ptr: NonNull<sys::nng_http_req>,
+}
+
+impl Request {
+    #[inline]
+    ///Returns request's method
+    pub fn method(&self) -> &str {
+        c_str(unsafe {
+            sys::nng_http_req_get_method(self.ptr.as_ptr())
+        })
+    }
+
+    #[inline]
+    ///Returns request's URI, including query
+    pub fn uri(&self) -> &str {
+        c_str(unsafe {
+            sys::nng_http_req_get_uri(self.ptr.as_ptr())
+        })
+    }
+
+    ///Returns value of header with `name`, if present
+    pub fn header(&self, name: &str) -> Option<&str> {
+        let name = String::new(name.as_bytes());
+        let value = unsafe {
+            sys::nng_http_req_get_header(self.ptr.as_ptr(), name.as_ptr() as _)
+        };
+
+        match value.is_null() {
+            true => None,
+            false => Some(c_str(value)),
+        }
+    }
+
+    ///Returns request's body
+    pub fn body(&self) -> &[u8] {
+        let mut data = ptr::null_mut();
+        let mut size = 0;
+        unsafe {
+            sys::nng_http_req_get_data(self.ptr.as_ptr(), &mut data, &mut size);
+        }
+
+        match data.is_null() {
+            true => &[],
+            false => unsafe {
+                core::slice::from_raw_parts(data as *const u8, size)
+            },
+        }
+    }
+}
+
+impl fmt::Debug for Request {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Request").field("method", &self.method()).field("uri", &self.uri()).finish()
+    }
+}
+
+#[derive(Clone, Debug)]
+///HTTP response
+pub struct Response {
+    status: u16,
+    headers: Vec<(alloc::string::String, alloc::string::String)>,
+    body: Vec<u8>,
+}
+
+impl Response {
+    #[inline]
+    ///Creates empty response with `status` code
+    pub const fn new(status: u16) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: Vec::new(),
+        }
+    }
+
+    #[inline(always)]
+    ///Creates empty response with status `200 OK`
+    pub const fn ok() -> Self {
+        Self::new(sys::nng_http_status::NNG_HTTP_STATUS_OK as _)
+    }
+
+    #[inline]
+    ///Adds header
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    #[inline]
+    ///Sets body
+    pub fn body<T: Into<Vec<u8>>>(mut self, body: T) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    #[inline(always)]
+    ///Returns status code
+    pub fn status(&self) -> u16 {
+        self.status
+    }
+
+    fn fill(&self, res: *mut sys::nng_http_res) -> Result<(), ErrorCode> {
+        let result = unsafe {
+            sys::nng_http_res_set_status(res, self.status)
+        };
+        if result != 0 {
+            return Err(error(result));
+        }
+
+        for (name, value) in self.headers.iter() {
+            let name = String::try_new(name.as_bytes())?;
+            let value = String::try_new(value.as_bytes())?;
+            let result = unsafe {
+                sys::nng_http_res_add_header(res, name.as_ptr() as _, value.as_ptr() as _)
+            };
+            if result != 0 {
+                return Err(error(result));
+            }
+        }
+
+        let result = unsafe {
+            sys::nng_http_res_copy_data(res, self.body.as_ptr() as _, self.body.len())
+        };
+        match result {
+            0 => Ok(()),
+            code => Err(error(code)),
+        }
+    }
+
+    fn into_raw(self) -> Result<*mut sys::nng_http_res, ErrorCode> {
+        let mut res = ptr::null_mut();
+        let result = unsafe {
+            sys::nng_http_res_alloc(&mut res)
+        };
+        if result != 0 {
+            return Err(error(result));
+        }
+
+        match self.fill(res) {
+            Ok(()) => Ok(res),
+            Err(error) => {
+                unsafe {
+                    sys::nng_http_res_free(res);
+                }
+                Err(error)
+            }
+        }
+    }
+}
+
+unsafe extern "C" fn handle_request(aio: *mut sys::nng_aio) {
+    let req = sys::nng_aio_get_input(aio, 0) as *mut sys::nng_http_req;
+    let handler = sys::nng_aio_get_input(aio, 1) as *mut sys::nng_http_handler;
+    let callback = sys::nng_http_handler_get_data(handler) as *const Callback;
+
+    let (req, callback) = match (NonNull::new(req), callback.as_ref()) {
+        (Some(req), Some(callback)) => (Request { ptr: req }, callback),
+        _ => return sys::nng_aio_finish(aio, sys::nng_errno_enum::NNG_EINVAL),
+    };
+
+    match (callback)(&req).into_raw() {
+        Ok(res) => {
+            sys::nng_aio_set_output(aio, 0, res as _);
+            sys::nng_aio_finish(aio, 0);
+        },
+        Err(error) => sys::nng_aio_finish(aio, error.raw_code()),
+    }
+}
+
+unsafe extern "C" fn free_callback(data: *mut c_void) {
+    if !data.is_null() {
+        drop(Box::from_raw(data as *mut Callback));
+    }
+}
+
+///HTTP request handler
+///
+///By default handler serves only `GET` requests to exactly matching path.
+pub struct Handler(NonNull<sys::nng_http_handler>);
+
+unsafe impl Send for Handler {}
+unsafe impl Sync for Handler {}
+
+impl Handler {
+    ///Creates new handler for `path`, invoking `callback` to serve request.
+    ///
+    ///`callback` is invoked by nng's worker, hence it should not block for long.
+    pub fn new<F: Fn(&Request) -> Response + Send + Sync + 'static>(path: &str, callback: F) -> Result<Self, ErrorCode> {
+        let path = String::try_new(path.as_bytes())?;
+        let mut ptr = ptr::null_mut();
+        let result = unsafe {
+            sys::nng_http_handler_alloc(&mut ptr, path.as_ptr() as _, Some(handle_request))
+        };
+        if result != 0 {
+            return Err(error(result));
+        }
+        let this = match NonNull::new(ptr) {
+            Some(ptr) => Self(ptr),
+            None => return Err(error(sys::nng_errno_enum::NNG_ENOMEM)),
+        };
+
+        let callback: Callback = try_box(callback)?;
+        let callback = Box::into_raw(try_box(callback)?);
+        let result = unsafe {
+            sys::nng_http_handler_set_data(this.0.as_ptr(), callback as _, Some(free_callback))
+        };
+        match result {
+            0 => Ok(this),
+            code => {
+                unsafe {
+                    free_callback(callback as _);
+                }
+                Err(error(code))
+            }
+        }
+    }
+
+    ///Sets HTTP `method` to be handled
+    pub fn method(self, method: &str) -> Result<Self, ErrorCode> {
+        let method = String::try_new(method.as_bytes())?;
+        let result = unsafe {
+            sys::nng_http_handler_set_method(self.0.as_ptr(), method.as_ptr() as _)
+        };
+
+        match result {
+            0 => Ok(self),
+            code => Err(error(code)),
+        }
+    }
+
+    ///Sets handler to serve any method
+    pub fn any_method(self) -> Result<Self, ErrorCode> {
+        let result = unsafe {
+            sys::nng_http_handler_set_method(self.0.as_ptr(), ptr::null())
+        };
+
+        match result {
+            0 => Ok(self),
+            code => Err(error(code)),
+        }
+    }
+
+    ///Sets handler to serve whole sub-tree of its path, unless there is more specific handler
+    pub fn tree(self) -> Result<Self, ErrorCode> {
+        let result = unsafe {
+            sys::nng_http_handler_set_tree(self.0.as_ptr())
+        };
+
+        match result {
+            0 => Ok(self),
+            code => Err(error(code)),
+        }
+    }
+}
+
+struct ScopeRoute {
+    method: Option<alloc::string::String>,
+    path: alloc::string::String,
+    tree: bool,
+    callback: Route,
+}
+
+fn join_path(prefix: &str, path: &str) -> alloc::string::String {
+    let prefix = prefix.trim_end_matches('/');
+    let path = path.trim_start_matches('/');
+    match (prefix.is_empty(), path.is_empty()) {
+        (true, _) => alloc::format!("/{}", path),
+        (false, true) => prefix.into(),
+        (false, false) => alloc::format!("{}/{}", prefix, path),
+    }
+}
+
+fn chain(middleware: &[Middleware], callback: &Route, req: &Request) -> Response {
+    match middleware.split_first() {
+        Some((first, rest)) => (first)(req, &|req: &Request| chain(rest, callback, req)),
+        None => (callback)(req),
+    }
+}
+
+///Group of routes under common path prefix with shared middleware
+///
+///Scopes can be nested, in which case prefixes are concatenated and middleware of outer scope is
+///invoked before middleware of inner scope.
+///
+///## Usage
+///
+///```rust,no_run
+///use nng_c::http::{Response, Scope, Server};
+///
+///let users = Scope::new("/users").get("/", |_| Response::ok().body("[]"));
+///let api = Scope::new("/api/v1").middleware(|req, next| {
+///    match req.header("Authorization") {
+///        Some(_) => next(req),
+///        None => Response::new(401),
+///    }
+///}).scope(users);
+///
+///let server = Server::hold("http://127.0.0.1:8080".into()).expect("hold server");
+///server.mount(api).expect("mount api");
+///server.start().expect("start server");
+///```
+pub struct Scope {
+    prefix: alloc::string::String,
+    middleware: Vec<Middleware>,
+    routes: Vec<ScopeRoute>,
+    scopes: Vec<Scope>,
+}
+
+impl Scope {
+    #[inline]
+    ///Creates new empty scope for `prefix`
+    pub fn new(prefix: &str) -> Self {
+        Self {
+            prefix: prefix.into(),
+            middleware: Vec::new(),
+            routes: Vec::new(),
+            scopes: Vec::new(),
+        }
+    }
+
+    #[inline]
+    ///Adds `middleware` to all routes of the scope, including nested scopes.
+    ///
+    ///Middleware is invoked in order of addition, and can either respond on its own or invoke `next`
+    pub fn middleware<F: Fn(&Request, Next<'_>) -> Response + Send + Sync + 'static>(mut self, middleware: F) -> Self {
+        self.middleware.push(Arc::new(middleware));
+        self
+    }
+
+    fn add_route<F: Fn(&Request) -> Response + Send + Sync + 'static>(mut self, method: Option<&str>, path: &str, tree: bool, callback: F) -> Self {
+        self.routes.push(ScopeRoute {
+            method: method.map(Into::into),
+            path: path.into(),
+            tree,
+            callback: Arc::new(callback),
+        });
+        self
+    }
+
+    #[inline]
+    ///Adds route for `method` and `path` relative to the scope's prefix
+    pub fn route<F: Fn(&Request) -> Response + Send + Sync + 'static>(self, method: &str, path: &str, callback: F) -> Self {
+        self.add_route(Some(method), path, false, callback)
+    }
+
+    #[inline]
+    ///Adds route for `GET` requests
+    pub fn get<F: Fn(&Request) -> Response + Send + Sync + 'static>(self, path: &str, callback: F) -> Self {
+        self.route("GET", path, callback)
+    }
+
+    #[inline]
+    ///Adds route for `POST` requests
+    pub fn post<F: Fn(&Request) -> Response + Send + Sync + 'static>(self, path: &str, callback: F) -> Self {
+        self.route("POST", path, callback)
+    }
+
+    #[inline]
+    ///Adds route for any method, serving whole sub-tree of `path`, unless there is more specific route
+    pub fn fallback<F: Fn(&Request) -> Response + Send + Sync + 'static>(self, path: &str, callback: F) -> Self {
+        self.add_route(None, path, true, callback)
+    }
+
+    #[inline]
+    ///Nests `scope` within this scope
+    pub fn scope(mut self, scope: Scope) -> Self {
+        self.scopes.push(scope);
+        self
+    }
+
+    fn collect(self, prefix: &str, middleware: &[Middleware], handlers: &mut Vec<Handler>) -> Result<(), ErrorCode> {
+        let prefix = join_path(prefix, &self.prefix);
+        let mut all_middleware = Vec::with_capacity(middleware.len() + self.middleware.len());
+        all_middleware.extend_from_slice(middleware);
+        all_middleware.extend(self.middleware);
+
+        for route in self.routes {
+            let middleware = all_middleware.clone();
+            let callback = route.callback;
+            let mut handler = Handler::new(&join_path(&prefix, &route.path), move |req| chain(&middleware, &callback, req))?;
+            handler = match route.method {
+                Some(method) => handler.method(&method)?,
+                None => handler.any_method()?,
+            };
+            if route.tree {
+                handler = handler.tree()?;
+            }
+            handlers.push(handler);
+        }
+
+        for scope in self.scopes {
+            scope.collect(&prefix, &all_middleware, handlers)?;
+        }
+
+        Ok(())
+    }
+
+    ///Creates handlers for all routes, including nested scopes
+    pub fn into_handlers(self) -> Result<Vec<Handler>, ErrorCode> {
+        let mut handlers = Vec::new();
+        self.collect("", &[], &mut handlers)?;
+        Ok(handlers)
+    }
+}
+
+impl fmt::Debug for Scope {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Scope").field("prefix", &self.prefix)
+                                 .field("middleware", &self.middleware.len())
+                                 .field("routes", &self.routes.len())
+                                 .field("scopes", &self.scopes)
+                                 .finish()
+    }
+}
+
+impl fmt::Debug for Handler {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_fmt(format_args!("Handler({:p})", self.0))
+    }
+}
+
+impl Drop for Handler {
+    #[inline(always)]
+    fn drop(&mut self) {
+        unsafe {
+            sys::nng_http_handler_free(self.0.as_ptr())
+        }
+    }
+}
+
+///HTTP server
+pub struct Server {
+    ptr: NonNull<sys::nng_http_server>,
+    url: NonNull<sys::nng_url>,
+}
+
+unsafe impl Send for Server {}
+unsafe impl Sync for Server {}
+
+impl Server {
+    ///Gets server for `url`, creating it if necessary
+    ///
+    ///Server is shared with other users of the same address (i.e. websocket listeners)
+    pub fn hold(url: String<'_>) -> Result<Self, ErrorCode> {
+        let mut parsed = ptr::null_mut();
+        let result = unsafe {
+            sys::nng_url_parse(&mut parsed, url.as_ptr() as _)
+        };
+        if result != 0 {
+            return Err(error(result));
+        }
+        let url = match NonNull::new(parsed) {
+            Some(url) => url,
+            None => return Err(error(sys::nng_errno_enum::NNG_ENOMEM)),
+        };
+
+        let mut ptr = ptr::null_mut();
+        let result = unsafe {
+            sys::nng_http_server_hold(&mut ptr, url.as_ptr())
+        };
+        match (result, NonNull::new(ptr)) {
+            (0, Some(ptr)) => Ok(Self {
+                ptr,
+                url,
+            }),
+            (result, _) => {
+                unsafe {
+                    sys::nng_url_free(url.as_ptr());
+                }
+                match result {
+                    0 => Err(error(sys::nng_errno_enum::NNG_ENOMEM)),
+                    code => Err(error(code)),
+                }
+            }
+        }
+    }
+
+    ///Adds `handler` to the server
+    ///
+    ///Returns error if there is already handler for the same path and method
+    pub fn add_handler(&self, handler: Handler) -> Result<(), ErrorCode> {
+        let result = unsafe {
+            sys::nng_http_server_add_handler(self.ptr.as_ptr(), handler.0.as_ptr())
+        };
+
+        match result {
+            0 => {
+                //Handler is owned by server now
+                core::mem::forget(handler);
+                Ok(())
+            },
+            code => Err(error(code)),
+        }
+    }
+
+    ///Adds all routes of the `scope` to the server
+    ///
+    ///Returns error if any route conflicts with already registered handler, in which case routes
+    ///preceding it remain registered.
+    pub fn mount(&self, scope: Scope) -> Result<(), ErrorCode> {
+        for handler in scope.into_handlers()? {
+            self.add_handler(handler)?;
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "websocket")]
+    ///Creates websocket listener on `path`, sharing port with this server
+    ///
+    ///Listener is started right away, and accepts connections once server is started.
+    ///Each message sent over accepted stream is transmitted as single websocket frame.
+    pub fn websocket(&self, path: &str) -> Result<crate::stream::Listener, ErrorCode> {
+        let url = unsafe {
+            self.url.as_ref()
+        };
+        let scheme = match c_str(url.u_scheme) {
+            "https" => "wss",
+            _ => "ws",
+        };
+        let url = alloc::format!("{}://{}{}", scheme, c_str(url.u_host), path);
+
+        let listener = crate::stream::Listener::new(String::try_new(url.as_bytes())?)?;
+        listener.listen()?;
+        Ok(listener)
+    }
+
+    ///Starts server
+    pub fn start(&self) -> Result<(), ErrorCode> {
+        let result = unsafe {
+            sys::nng_http_server_start(self.ptr.as_ptr())
+        };
+
+        match result {
+            0 => Ok(()),
+            code => Err(error(code)),
+        }
+    }
+
+    #[inline]
+    ///Stops server
+    pub fn stop(&self) {
+        unsafe {
+            sys::nng_http_server_stop(self.ptr.as_ptr())
+        }
+    }
+}
+
+impl fmt::Debug for Server {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_fmt(format_args!("Server({:p})", self.ptr))
+    }
+}
+
+impl Drop for Server {
+    #[inline(always)]
+    fn drop(&mut self) {
+        unsafe {
+            sys::nng_http_server_release(self.ptr.as_ptr());
+            sys::nng_url_free(self.url.as_ptr());
+        }
+    }
+}
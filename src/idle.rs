@@ -0,0 +1,184 @@
+//!Idle connection reaper
+//!
+//!Peers behind NAT may silently disappear, leaving pipes open until TCP keep-alive notices,
+//!if ever. [IdleReaper] tracks time of the last activity per pipe and closes pipes which stay idle
+//!longer than configured duration.
+//!
+//!Activity is reported via hooks:
+//!
+//!- [observe](IdleReaper::observe) for every received message, or [recv_msg](IdleReaper::recv_msg)
+//!  which receives and observes message at once;
+//!- [touch](IdleReaper::touch) for any other activity of the pipe (i.e. sending reply).
+//!
+//!Pipes are tracked from the first activity, or from connection when reaper is attached to
+//![PipeNotifier](crate::notify::PipeNotifier) via [attach](IdleReaper::attach), which also
+//!forgets removed pipes.
+//!
+//![reap](IdleReaper::reap) should be invoked periodically (i.e. from receive loop or
+//![background thread](crate::utils::thread::spawn)) to close idle pipes.
+//!
+//!## Usage
+//!
+//!```rust
+//!use nng_c::Socket;
+//!use nng_c::idle::IdleReaper;
+//!use nng_c::notify::PipeNotifier;
+//!
+//!use core::time;
+//!
+//!let server = Socket::pair1_poly().expect("create socket");
+//!let notifier = PipeNotifier::install(&server).expect("install notifier");
+//!let reaper = IdleReaper::new(time::Duration::from_secs(60)).expect("create reaper");
+//!let _subscription = reaper.attach(&notifier);
+//!server.listen("inproc://idle-example".into()).expect("listen");
+//!
+//!//Invoke periodically
+//!let closed = reaper.reap();
+//!assert_eq!(closed, 0);
+//!```
+
+use crate::ErrorCode;
+use crate::msg::Message;
+use crate::notify::{PipeEvent, PipeNotifier, Subscription};
+use crate::pipe::Pipe;
+use crate::socket::Socket;
+use crate::utils::sync::Mutex;
+use crate::sys;
+
+use core::{fmt, time};
+use core::convert::TryInto;
+
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+#[inline(always)]
+fn now() -> sys::nng_time {
+    unsafe {
+        sys::nng_clock()
+    }
+}
+
+struct State {
+    timeout: sys::nng_time,
+    //Pipe id with time of its last activity
+    pipes: Mutex<Vec<(u32, sys::nng_time)>>,
+}
+
+#[derive(Clone)]
+///Tracker of pipe activity, closing idle pipes
+///
+///Reaper is cheap to clone, with all clones sharing the same state.
+pub struct IdleReaper {
+    state: Arc<State>,
+}
+
+impl IdleReaper {
+    ///Creates new reaper, closing pipes idle longer than `timeout`
+    ///
+    ///Returns error if unable to allocate lock.
+    pub fn new(timeout: time::Duration) -> Result<Self, ErrorCode> {
+        let timeout = timeout.as_millis().try_into().unwrap_or(sys::nng_time::MAX);
+        Ok(Self {
+            state: Arc::new(State {
+                timeout,
+                pipes: Mutex::new(Vec::new())?,
+            }),
+        })
+    }
+
+    #[inline]
+    ///Returns duration after which idle pipe is closed
+    pub fn timeout(&self) -> time::Duration {
+        time::Duration::from_millis(self.state.timeout)
+    }
+
+    #[inline]
+    ///Returns number of tracked pipes
+    pub fn len(&self) -> usize {
+        self.state.pipes.lock().len()
+    }
+
+    #[inline]
+    ///Returns whether there are no tracked pipes
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    ///Records activity of the `pipe`, starting to track it if necessary
+    pub fn touch(&self, pipe: &Pipe) {
+        let now = now();
+        let mut pipes = self.state.pipes.lock();
+        match pipes.iter_mut().find(|(id, _)| *id == pipe.0.id) {
+            Some((_, last_active)) => *last_active = now,
+            None => pipes.push((pipe.0.id, now)),
+        }
+    }
+
+    #[inline]
+    ///Records activity of the pipe, which delivered `msg`
+    ///
+    ///Has no effect if message has no pipe (i.e. it was not received from socket).
+    pub fn observe(&self, msg: &Message) {
+        if let Some(pipe) = msg.pipe() {
+            self.touch(&pipe);
+        }
+    }
+
+    #[inline]
+    ///Receives message from `socket`, recording activity of its pipe
+    pub fn recv_msg(&self, socket: &Socket) -> Result<Message, ErrorCode> {
+        let msg = socket.recv_msg()?;
+        self.observe(&msg);
+        Ok(msg)
+    }
+
+    #[inline]
+    ///Stops tracking `pipe`
+    pub fn forget(&self, pipe: &Pipe) {
+        self.state.pipes.lock().retain(|(id, _)| *id != pipe.0.id);
+    }
+
+    ///Returns how long `pipe` has been idle, if it is tracked
+    pub fn idle_time(&self, pipe: &Pipe) -> Option<time::Duration> {
+        let now = now();
+        self.state.pipes.lock().iter().find(|(id, _)| *id == pipe.0.id).map(|(_, last_active)| {
+            time::Duration::from_millis(now.saturating_sub(*last_active))
+        })
+    }
+
+    ///Closes pipes idle longer than timeout, returning number of closed pipes.
+    ///
+    ///Closed pipes are no longer tracked.
+    pub fn reap(&self) -> usize {
+        let now = now();
+        let mut idle = Vec::new();
+        self.state.pipes.lock().retain(|(id, last_active)| if now.saturating_sub(*last_active) > self.state.timeout {
+            idle.push(Pipe(sys::nng_pipe { id: *id }));
+            false
+        } else {
+            true
+        });
+
+        //Pipe may be already gone, in which case there is nothing to close
+        idle.iter().filter(|pipe| pipe.close().is_ok()).count()
+    }
+
+    ///Subscribes to `notifier`, tracking pipes from connection and forgetting them once removed.
+    ///
+    ///Reaper stays attached until returned subscription is dropped.
+    pub fn attach(&self, notifier: &PipeNotifier) -> Subscription {
+        let reaper = self.clone();
+        notifier.subscribe(move |pipe, event| match event {
+            PipeEvent::AddPost => reaper.touch(&pipe),
+            PipeEvent::RemPost => reaper.forget(&pipe),
+            PipeEvent::AddPre => (),
+        })
+    }
+}
+
+impl fmt::Debug for IdleReaper {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("IdleReaper").field("timeout", &self.timeout()).field("pipes", &self.len()).finish()
+    }
+}
@@ -0,0 +1,331 @@
+//!Instrumented socket
+//!
+//![Instrumented] wraps socket, recording number of messages, bytes and latency histogram of every
+//!send and receive operation performed through it.
+//!Unlike [stats](https://nng.nanomsg.org/man/v1.10.0/nng_stats.5.html) tree, it only tracks
+//!operations of interest and is read with few atomic loads via [Instrumented::snapshot].
+//!
+//!Latency is bucketed by powers of two of microseconds, hence percentiles are upper bounds, precise
+//!within factor of two.
+//!
+//!Requires feature `std`
+//!
+//!## Usage
+//!
+//!```rust
+//!use nng_c::{Message, Socket};
+//!use nng_c::instrument::Instrumented;
+//!
+//!const ADDR: &str = "inproc://instrument-example\0";
+//!
+//!let server = Instrumented::new(Socket::pair0().expect("create server"));
+//!server.listen(ADDR.into()).expect("listen");
+//!let client = Instrumented::new(Socket::pair0().expect("create client"));
+//!client.connect(ADDR.into()).expect("connect");
+//!
+//!let mut msg = Message::new().expect("create message");
+//!msg.append(b"ping").expect("append");
+//!client.send_msg(msg).expect("send");
+//!server.recv_msg().expect("receive");
+//!
+//!let snapshot = server.snapshot();
+//!assert_eq!(snapshot.recv.msgs, 1);
+//!assert_eq!(snapshot.recv.bytes, 4);
+//!assert_eq!(snapshot.recv.latency.count(), 1);
+//!```
+
+use crate::ErrorCode;
+use crate::msg::Message;
+use crate::socket::Socket;
+
+use core::{fmt, ops, time};
+use core::borrow::Borrow;
+use core::sync::atomic::{AtomicU64, Ordering};
+
+use std::time::Instant;
+
+///Number of histogram buckets, with last bucket also accounting for all latencies above its range
+pub const BUCKETS: usize = 32;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+///Latency histogram
+pub struct Histogram {
+    ///Number of operations per bucket, where bucket `idx` covers latencies below `2^idx` microseconds
+    pub buckets: [u64; BUCKETS],
+    ///Total latency of all operations
+    pub total: time::Duration,
+    ///Maximum latency
+    pub max: time::Duration,
+}
+
+impl Histogram {
+    #[inline]
+    ///Returns number of recorded operations
+    pub fn count(&self) -> u64 {
+        self.buckets.iter().sum()
+    }
+
+    ///Returns average latency
+    pub fn mean(&self) -> time::Duration {
+        match self.count() {
+            0 => time::Duration::ZERO,
+            count => time::Duration::from_micros(self.total.as_micros() as u64 / count),
+        }
+    }
+
+    ///Returns upper bound of `pct` percentile of latency, capped at [max](Self::max).
+    ///
+    ///`pct` above 100 is treated as 100.
+    pub fn percentile(&self, pct: u8) -> time::Duration {
+        let count = self.count();
+        if count == 0 {
+            return time::Duration::ZERO;
+        }
+
+        let rank = (count * u64::from(pct.min(100))).div_ceil(100);
+        let mut seen = 0;
+        for (idx, bucket) in self.buckets.iter().enumerate() {
+            seen += bucket;
+            if seen >= rank.max(1) {
+                return core::cmp::min(time::Duration::from_micros(1 << idx), self.max);
+            }
+        }
+        self.max
+    }
+}
+
+impl Default for Histogram {
+    #[inline(always)]
+    fn default() -> Self {
+        Self {
+            buckets: [0; BUCKETS],
+            total: time::Duration::ZERO,
+            max: time::Duration::ZERO,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+///Statistics of operation
+pub struct Operation {
+    ///Number of successfully transferred messages
+    pub msgs: u64,
+    ///Number of bytes of successfully transferred messages' bodies
+    pub bytes: u64,
+    ///Number of failed operations, excluding attempts that would block
+    pub failures: u64,
+    ///Latency of successful operations
+    pub latency: Histogram,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+///Snapshot of [Instrumented] socket statistics
+pub struct Snapshot {
+    ///Send operations
+    pub send: Operation,
+    ///Receive operations
+    pub recv: Operation,
+}
+
+struct AtomicOperation {
+    msgs: AtomicU64,
+    bytes: AtomicU64,
+    failures: AtomicU64,
+    buckets: [AtomicU64; BUCKETS],
+    total_us: AtomicU64,
+    max_us: AtomicU64,
+}
+
+impl AtomicOperation {
+    #[allow(clippy::declare_interior_mutable_const)]
+    const ZERO: AtomicU64 = AtomicU64::new(0);
+
+    #[inline(always)]
+    const fn new() -> Self {
+        Self {
+            msgs: AtomicU64::new(0),
+            bytes: AtomicU64::new(0),
+            failures: AtomicU64::new(0),
+            buckets: [Self::ZERO; BUCKETS],
+            total_us: AtomicU64::new(0),
+            max_us: AtomicU64::new(0),
+        }
+    }
+
+    fn succeeded(&self, size: usize, started: Instant) {
+        let elapsed = started.elapsed().as_micros() as u64;
+        let idx = match elapsed {
+            0 => 0,
+            elapsed => core::cmp::min((u64::BITS - elapsed.leading_zeros()) as usize, BUCKETS - 1),
+        };
+
+        self.msgs.fetch_add(1, Ordering::Relaxed);
+        self.bytes.fetch_add(size as u64, Ordering::Relaxed);
+        self.buckets[idx].fetch_add(1, Ordering::Relaxed);
+        self.total_us.fetch_add(elapsed, Ordering::Relaxed);
+        self.max_us.fetch_max(elapsed, Ordering::Relaxed);
+    }
+
+    #[inline(always)]
+    fn failed(&self, error: &ErrorCode) {
+        if !error.is_would_block() {
+            self.failures.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn get(&self) -> Operation {
+        let mut latency = Histogram::default();
+        for (bucket, value) in latency.buckets.iter_mut().zip(self.buckets.iter()) {
+            *bucket = value.load(Ordering::Relaxed);
+        }
+        latency.total = time::Duration::from_micros(self.total_us.load(Ordering::Relaxed));
+        latency.max = time::Duration::from_micros(self.max_us.load(Ordering::Relaxed));
+
+        Operation {
+            msgs: self.msgs.load(Ordering::Relaxed),
+            bytes: self.bytes.load(Ordering::Relaxed),
+            failures: self.failures.load(Ordering::Relaxed),
+            latency,
+        }
+    }
+
+    fn reset(&self) {
+        self.msgs.store(0, Ordering::Relaxed);
+        self.bytes.store(0, Ordering::Relaxed);
+        self.failures.store(0, Ordering::Relaxed);
+        for bucket in self.buckets.iter() {
+            bucket.store(0, Ordering::Relaxed);
+        }
+        self.total_us.store(0, Ordering::Relaxed);
+        self.max_us.store(0, Ordering::Relaxed);
+    }
+}
+
+///Socket decorator, recording statistics of its operations
+///
+///Socket can be owned or shared (i.e. `&Socket` or `Arc<Socket>`), while all other socket methods
+///are accessible via deref, without being recorded.
+pub struct Instrumented<S = Socket> {
+    socket: S,
+    send: AtomicOperation,
+    recv: AtomicOperation,
+}
+
+impl<S: Borrow<Socket>> Instrumented<S> {
+    #[inline(always)]
+    ///Wraps `socket` with zeroed statistics
+    pub const fn new(socket: S) -> Self {
+        Self {
+            socket,
+            send: AtomicOperation::new(),
+            recv: AtomicOperation::new(),
+        }
+    }
+
+    #[inline(always)]
+    ///Returns underlying socket
+    pub fn into_inner(self) -> S {
+        self.socket
+    }
+
+    #[inline]
+    ///Returns snapshot of statistics
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            send: self.send.get(),
+            recv: self.recv.get(),
+        }
+    }
+
+    #[inline]
+    ///Resets statistics to zero
+    pub fn reset(&self) {
+        self.send.reset();
+        self.recv.reset();
+    }
+
+    ///Sends message, recording its statistics
+    pub fn send_msg(&self, msg: Message) -> Result<(), (Message, ErrorCode)> {
+        let size = msg.len();
+        let started = Instant::now();
+        match self.socket.borrow().send_msg(msg) {
+            Ok(()) => {
+                self.send.succeeded(size, started);
+                Ok(())
+            },
+            Err((msg, error)) => {
+                let error = error.into();
+                self.send.failed(&error);
+                Err((msg, error))
+            }
+        }
+    }
+
+    ///Sends message without blocking, recording its statistics
+    pub fn try_send_msg(&self, msg: Message) -> Result<(), (Message, ErrorCode)> {
+        let size = msg.len();
+        let started = Instant::now();
+        match self.socket.borrow().try_send_msg(msg) {
+            Ok(()) => {
+                self.send.succeeded(size, started);
+                Ok(())
+            },
+            Err((msg, error)) => {
+                let error = error.into();
+                self.send.failed(&error);
+                Err((msg, error))
+            }
+        }
+    }
+
+    ///Receives message, recording its statistics
+    pub fn recv_msg(&self) -> Result<Message, ErrorCode> {
+        let started = Instant::now();
+        match self.socket.borrow().recv_msg() {
+            Ok(msg) => {
+                self.recv.succeeded(msg.len(), started);
+                Ok(msg)
+            },
+            Err(error) => {
+                let error = error.into();
+                self.recv.failed(&error);
+                Err(error)
+            }
+        }
+    }
+
+    ///Receives message without blocking, recording its statistics
+    pub fn try_recv_msg(&self) -> Result<Option<Message>, ErrorCode> {
+        let started = Instant::now();
+        match self.socket.borrow().try_recv_msg() {
+            Ok(Some(msg)) => {
+                self.recv.succeeded(msg.len(), started);
+                Ok(Some(msg))
+            },
+            Ok(None) => Ok(None),
+            Err(error) => {
+                let error = error.into();
+                self.recv.failed(&error);
+                Err(error)
+            }
+        }
+    }
+}
+
+impl<S: Borrow<Socket>> ops::Deref for Instrumented<S> {
+    type Target = Socket;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        self.socket.borrow()
+    }
+}
+
+impl<S: Borrow<Socket>> fmt::Debug for Instrumented<S> {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Instrumented").field("socket", self.socket.borrow())
+                                        .field("stats", &self.snapshot())
+                                        .finish()
+    }
+}
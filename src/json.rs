@@ -0,0 +1,142 @@
+//!JSON messages
+//!
+//!Encodes values as JSON straight into message body and decodes them back, which is the most
+//!common way to integrate with services written in other languages.
+//!
+//![Socket] and [Context] get `send_json` and `recv_json` methods, reporting which step failed via [JsonError].
+//!
+//!Requires feature `serde_json`
+//!
+//!## Usage
+//!
+//!```rust
+//!use nng_c::Socket;
+//!
+//!#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+//!struct Order {
+//!    id: u64,
+//!    item: String,
+//!}
+//!
+//!const ADDR: &str = "inproc://json-example\0";
+//!
+//!let server = Socket::pair0().expect("create server");
+//!server.listen(ADDR.into()).expect("listen");
+//!let client = Socket::pair0().expect("create client");
+//!client.connect(ADDR.into()).expect("connect");
+//!
+//!let order = Order { id: 1, item: "book".to_owned() };
+//!client.send_json(&order).expect("send");
+//!assert_eq!(server.recv_json::<Order>().expect("receive"), order);
+//!```
+
+use crate::ErrorCode;
+use crate::context::Context;
+use crate::error::error;
+use crate::msg::Message;
+use crate::socket::Socket;
+use crate::sys;
+
+use core::fmt;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+#[derive(Debug)]
+///Error of sending or receiving JSON message
+pub enum JsonError {
+    ///Socket failed to send or receive message
+    Socket(ErrorCode),
+    ///Value cannot be encoded as JSON
+    Encode(serde_json::Error),
+    ///Message body is not valid JSON of expected type
+    Decode(serde_json::Error),
+}
+
+impl JsonError {
+    #[inline(always)]
+    ///Returns whether error is caused by content of the message, rather than socket failure
+    pub const fn is_codec(&self) -> bool {
+        !matches!(self, Self::Socket(_))
+    }
+}
+
+impl From<JsonError> for ErrorCode {
+    #[inline]
+    fn from(json: JsonError) -> Self {
+        match json {
+            JsonError::Socket(code) => code,
+            JsonError::Encode(_) => error(sys::nng_errno_enum::NNG_EINVAL),
+            JsonError::Decode(_) => error(sys::nng_errno_enum::NNG_EPROTO),
+        }
+    }
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Socket(code) => fmt.write_fmt(format_args!("socket failure: {}", code)),
+            Self::Encode(error) => fmt.write_fmt(format_args!("unable to encode JSON: {}", error)),
+            Self::Decode(error) => fmt.write_fmt(format_args!("unable to decode JSON message body: {}", error)),
+        }
+    }
+}
+
+impl std::error::Error for JsonError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Socket(code) => Some(code),
+            Self::Encode(error) | Self::Decode(error) => Some(error),
+        }
+    }
+}
+
+///Creates message with `value` encoded as JSON body
+pub fn encode<T: Serialize + ?Sized>(value: &T) -> Result<Message, JsonError> {
+    let mut msg = match Message::new() {
+        Some(msg) => msg,
+        None => return Err(JsonError::Socket(error(sys::nng_errno_enum::NNG_ENOMEM))),
+    };
+    let body = serde_json::to_vec(value).map_err(JsonError::Encode)?;
+    msg.append(&body).map_err(JsonError::Socket)?;
+    Ok(msg)
+}
+
+#[inline]
+///Decodes body of the `msg` as JSON
+pub fn decode<T: DeserializeOwned>(msg: &Message) -> Result<T, JsonError> {
+    serde_json::from_slice(msg.body()).map_err(JsonError::Decode)
+}
+
+impl Socket {
+    #[inline]
+    ///Sends `value` encoded as JSON
+    pub fn send_json<T: Serialize + ?Sized>(&self, value: &T) -> Result<(), JsonError> {
+        let msg = encode(value)?;
+        self.send_msg(msg).map_err(|(_, error)| JsonError::Socket(error.into()))
+    }
+
+    #[inline]
+    ///Receives message, decoding its body as JSON
+    pub fn recv_json<T: DeserializeOwned>(&self) -> Result<T, JsonError> {
+        let msg = self.recv_msg().map_err(|error| JsonError::Socket(error.into()))?;
+        decode(&msg)
+    }
+}
+
+impl Context {
+    #[inline]
+    ///Sends `value` encoded as JSON
+    pub fn send_json<T: Serialize + ?Sized>(&self, value: &T) -> Result<(), JsonError> {
+        let msg = encode(value)?;
+        self.send_msg(msg).map_err(|(_, error)| JsonError::Socket(error))
+    }
+
+    #[inline]
+    ///Receives message, decoding its body as JSON
+    pub fn recv_json<T: DeserializeOwned>(&self) -> Result<T, JsonError> {
+        let msg = self.recv_msg().map_err(JsonError::Socket)?;
+        decode(&msg)
+    }
+}
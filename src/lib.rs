@@ -4,11 +4,34 @@
 //!
 //!## Features
 //!
-//!- `http` - Enables http transport;
+//!- `http` - Enables http transport and [http](http/index.html) server module;
 //!- `tls` - Enables TLS transpor;
-//!- `websocket` - Enables websocket transport. Implies `http` feature;
+//!- `websocket` - Enables websocket transport and [websocket](websocket/index.html) client. Implies `http` feature;
 //!- `log` - Enables logging via [log](https://crates.io/crates/log) crate;
-//!- `tracing` - Enables logging via [tracing](https://crates.io/crates/tracing) crate.
+//!- `tracing` - Enables logging via [tracing](https://crates.io/crates/tracing) crate;
+//!- `std` - Enables integration with standard library, such as [env](env/index.html) and [outbox](outbox/index.html) modules;
+//!- `counters` - Enables lightweight counters of sent and received messages, accessible via [Socket::counters](socket/struct.Socket.html#method.counters);
+//!- `stats` - Enables collection of nng statistics, accessible via [stats](stats/index.html) module. Implies `counters` feature;
+//!- `otel` - Enables [otel](otel/index.html) module to instrument sockets with [OpenTelemetry](https://crates.io/crates/opentelemetry) spans. Implies `std` feature;
+//!- `noise` - Enables [noise](noise/index.html) module to encrypt messages using [Noise](https://noiseprotocol.org) protocol. Implies `std` feature;
+//!- `tower` - Enables [tower](tower/index.html) module to use req0/rep0 sockets as [tower](https://crates.io/crates/tower) services;
+//!- `futures` - Implements [futures](https://crates.io/crates/futures-core) `Stream` for [Monitor](notify/struct.Monitor.html) and [PipeEvents](notify/struct.PipeEvents.html);
+//!- `mdns` - Enables [discovery](discovery/index.html) module to advertise and resolve endpoints via mDNS/DNS-SD. Implies `std` feature;
+//!- `signal` - Enables [shutdown](shutdown/index.html) module to drain and tear down sockets, devices and servers on Ctrl-C. Implies `std` feature;
+//!- `spin` - Enables busy-polling [spin_on](utils/executor/fn.spin_on.html) executor for targets without threads;
+//!- `test-util` - Enables [test_util](test_util/index.html) module with helpers for writing tests. Implies `std` feature;
+//!- `arbitrary` - Implements [arbitrary](https://crates.io/crates/arbitrary) `Arbitrary` for `Message` to use in property and fuzz testing;
+//!- `serde` - Enables [config](config/index.html) module to configure sockets via [serde](https://crates.io/crates/serde);
+//!- `serde_json` - Enables [json](json/index.html) module to send and receive JSON messages. Implies `std` and `serde` features;
+//!- `postcard` - Enables [postcard](postcard/index.html) module to send and receive compact [postcard](https://crates.io/crates/postcard) messages without `std`. Implies `serde` feature.
+//!
+//!## Allocation
+//!
+//!Allocations performed by the crate itself, that are part of fallible operation, report failure as `NNG_ENOMEM` error instead of aborting,
+//!which includes async operations, callbacks state and C strings of URLs or options.
+//!
+//!Infallible APIs, such as `Clone` or `From` implementations, follow standard library and abort (or panic) on allocation failure,
+//!and usually have fallible alternative (i.e. [Message::dup](struct.Message.html#method.dup) or [str::String::try_new](str/struct.String.html#method.try_new)).
 //!
 //!## Usage
 //!
@@ -71,17 +94,79 @@
 #![cfg_attr(feature = "cargo-clippy", allow(clippy::style))]
 
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
 mod defs;
+mod fallible;
 mod aio;
 pub mod str;
+pub mod url;
 pub use nng_c_sys as sys;
 mod msg;
-pub use msg::Message;
-mod error;
-pub use error::{ErrorCode, NngError};
+pub use msg::{Message, Hexdump};
+pub mod error;
+pub use error::{ErrorCode, NngError, nng_error, Errno, Op, OpError, Result, ResultExt};
 pub mod options;
 pub mod socket;
 pub use socket::Socket;
+pub mod endpoints;
+pub mod pipe;
+pub use pipe::Pipe;
+pub mod notify;
+pub mod access;
+pub mod idle;
+pub mod context;
+pub use context::Context;
+pub mod pool;
 pub mod tls;
+pub mod stream;
+#[cfg(feature = "http")]
+pub mod http;
+#[cfg(feature = "websocket")]
+pub mod websocket;
 pub mod utils;
+pub mod balance;
+pub mod resolve;
+pub mod raw;
+pub mod survey;
+pub mod pubsub;
+pub mod reliable;
+pub mod priority;
+pub mod correlation;
+pub mod headers;
+pub mod metadata;
+pub mod dispatch;
+pub mod mailbox;
+pub mod checksum;
+pub mod rate;
+#[cfg(feature = "stats")]
+pub mod stats;
+#[cfg(feature = "serde")]
+pub mod config;
+#[cfg(feature = "serde_json")]
+pub mod json;
+#[cfg(feature = "postcard")]
+pub mod postcard;
+#[cfg(feature = "tower")]
+pub mod tower;
+#[cfg(feature = "std")]
+pub mod env;
+#[cfg(feature = "std")]
+pub mod outbox;
+#[cfg(feature = "std")]
+pub mod transfer;
+#[cfg(feature = "std")]
+pub mod watchdog;
+#[cfg(feature = "std")]
+pub mod instrument;
+#[cfg(feature = "noise")]
+pub mod noise;
+#[cfg(feature = "mdns")]
+pub mod discovery;
+#[cfg(feature = "signal")]
+pub mod shutdown;
+#[cfg(feature = "otel")]
+pub mod otel;
+#[cfg(feature = "test-util")]
+pub mod test_util;
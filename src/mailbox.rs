@@ -0,0 +1,219 @@
+//!Actor-style mailbox
+//!
+//![Mailbox] owns inbox (socket or context) of an [Actor], delivering it typed messages one by one,
+//!so that handling logic is written as a state machine, instead of hand-written receive loop.
+//!
+//!While handling message, actor can reply via inbox or send to named peers, registered on mailbox.
+//!
+//!## Usage
+//!
+//!```rust
+//!use nng_c::{options, Message, Socket};
+//!use nng_c::mailbox::{Actor, Mailbox};
+//!
+//!use core::convert::TryFrom;
+//!
+//!enum Command {
+//!    Add(u8),
+//!    Stop,
+//!}
+//!
+//!impl TryFrom<Message> for Command {
+//!    type Error = Message;
+//!
+//!    fn try_from(msg: Message) -> Result<Self, Self::Error> {
+//!        match msg.body() {
+//!            [] => Ok(Command::Stop),
+//!            [value] => Ok(Command::Add(*value)),
+//!            _ => Err(msg),
+//!        }
+//!    }
+//!}
+//!
+//!struct Counter(u8);
+//!
+//!impl Actor for Counter {
+//!    type Message = Command;
+//!
+//!    fn handle(&mut self, msg: Command, mailbox: &mut Mailbox<'_>) {
+//!        match msg {
+//!            Command::Add(value) => self.0 += value,
+//!            Command::Stop => mailbox.stop(),
+//!        }
+//!    }
+//!}
+//!
+//!const ADDR: &str = "inproc://mailbox-example\0";
+//!
+//!let inbox = Socket::pair0().expect("create socket");
+//!inbox.set_opt(options::RecvBuf(8)).expect("set buffer");
+//!inbox.listen(ADDR.into()).expect("listen");
+//!let client = Socket::pair0().expect("create socket");
+//!client.connect(ADDR.into()).expect("connect");
+//!
+//!for body in [&[1u8][..], &[2], &[]] {
+//!    let mut msg = Message::new().expect("create message");
+//!    msg.append(body).expect("append");
+//!    client.send_msg(msg).expect("send");
+//!}
+//!
+//!let mut counter = Counter(0);
+//!Mailbox::new(&inbox).run(&mut counter).expect("run actor");
+//!assert_eq!(counter.0, 3);
+//!```
+
+use crate::{ErrorCode, NngError};
+use crate::context::Context;
+use crate::error::error;
+use crate::msg::Message;
+use crate::socket::Socket;
+use crate::sys;
+
+use core::convert::TryFrom;
+use core::fmt;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+///Actor, handling messages of its [Mailbox]
+pub trait Actor {
+    ///Type of messages, actor handles.
+    ///
+    ///Message, that cannot be converted, is dropped.
+    type Message: TryFrom<Message>;
+
+    ///Handles `msg`, using `mailbox` to reply or send messages to peers.
+    fn handle(&mut self, msg: Self::Message, mailbox: &mut Mailbox<'_>);
+}
+
+enum Inbox<'a> {
+    Socket(&'a Socket),
+    Context(Context),
+}
+
+impl fmt::Debug for Inbox<'_> {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Socket(socket) => fmt::Debug::fmt(socket, fmt),
+            Self::Context(ctx) => fmt::Debug::fmt(ctx, fmt),
+        }
+    }
+}
+
+///Mailbox of the actor
+pub struct Mailbox<'a> {
+    inbox: Inbox<'a>,
+    peers: Vec<(String, &'a Socket)>,
+    stopped: bool,
+}
+
+impl<'a> Mailbox<'a> {
+    #[inline(always)]
+    ///Creates new mailbox, receiving messages from `socket`
+    pub const fn new(socket: &'a Socket) -> Self {
+        Self {
+            inbox: Inbox::Socket(socket),
+            peers: Vec::new(),
+            stopped: false,
+        }
+    }
+
+    #[inline]
+    ///Creates new mailbox, receiving messages from its own context on `socket`.
+    ///
+    ///This allows to run multiple actors on the same socket, as long as protocol supports contexts.
+    pub fn with_context(socket: &Socket) -> Result<Self, ErrorCode> {
+        Context::new(socket).map(|ctx| Self {
+            inbox: Inbox::Context(ctx),
+            peers: Vec::new(),
+            stopped: false,
+        })
+    }
+
+    ///Registers `socket` as peer with `name`, replacing existing peer with the same name
+    pub fn peer(mut self, name: &str, socket: &'a Socket) -> Self {
+        match self.peers.iter_mut().find(|(existing, _)| existing == name) {
+            Some(peer) => peer.1 = socket,
+            None => self.peers.push((name.into(), socket)),
+        }
+        self
+    }
+
+    #[inline]
+    ///Returns whether peer with `name` is registered
+    pub fn has_peer(&self, name: &str) -> bool {
+        self.peers.iter().any(|(existing, _)| existing == name)
+    }
+
+    #[inline]
+    ///Receives next message from inbox, waiting forever if none is available.
+    pub fn recv(&self) -> Result<Message, ErrorCode> {
+        match &self.inbox {
+            Inbox::Socket(socket) => socket.recv_msg().map_err(Into::into),
+            Inbox::Context(ctx) => ctx.recv_msg(),
+        }
+    }
+
+    #[inline]
+    ///Sends `msg` via inbox, i.e. as reply to the last received request
+    pub fn reply(&self, msg: Message) -> Result<(), (Message, ErrorCode)> {
+        match &self.inbox {
+            Inbox::Socket(socket) => socket.send_msg(msg).map_err(|(msg, error)| (msg, error.into())),
+            Inbox::Context(ctx) => ctx.send_msg(msg),
+        }
+    }
+
+    ///Sends `msg` to the peer with `name`.
+    ///
+    ///Returns error if there is no such peer.
+    pub fn send(&self, name: &str, msg: Message) -> Result<(), (Message, ErrorCode)> {
+        match self.peers.iter().find(|(existing, _)| existing == name) {
+            Some((_, socket)) => socket.send_msg(msg).map_err(|(msg, error)| (msg, error.into())),
+            None => Err((msg, error(sys::nng_errno_enum::NNG_ENOENT))),
+        }
+    }
+
+    #[inline(always)]
+    ///Stops [run](Self::run) loop after current message is handled
+    pub fn stop(&mut self) {
+        self.stopped = true;
+    }
+
+    #[inline(always)]
+    ///Returns whether mailbox is stopped
+    pub fn is_stopped(&self) -> bool {
+        self.stopped
+    }
+
+    ///Delivers messages to the `actor` until it stops mailbox or inbox is closed.
+    ///
+    ///Mailbox can be run again after being stopped.
+    ///
+    ///Returns `Ok` when actor stopped or inbox is closed, otherwise first unexpected error.
+    pub fn run<A: Actor>(&mut self, actor: &mut A) -> Result<(), ErrorCode> {
+        self.stopped = false;
+        while !self.stopped {
+            let msg = match self.recv() {
+                Ok(msg) => msg,
+                Err(error) if error.is_closed() => break,
+                Err(error) => return Err(error),
+            };
+
+            if let Ok(msg) = A::Message::try_from(msg) {
+                actor.handle(msg, self);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Mailbox<'_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let peers = self.peers.iter().map(|(name, _)| name).collect::<Vec<_>>();
+        fmt.debug_struct("Mailbox").field("inbox", &self.inbox)
+                                   .field("peers", &peers)
+                                   .field("stopped", &self.stopped)
+                                   .finish()
+    }
+}
@@ -0,0 +1,130 @@
+//!Message metadata region
+//!
+//!nng's message header is owned by protocol (e.g. req/rep keep request ID and backtrace there) and
+//!is rewritten on every send, hence metadata, such as [CorrelationId](crate::correlation::CorrelationId),
+//![TraceContext](crate::correlation::TraceContext) and [Headers](crate::headers::Headers), is stored in
+//!single reserved region at the front of message body instead.
+//!
+//!Region consists of [MARKER], checksum (FNV-1a) and length of records, followed by records, each
+//!encoded as tag (u8), value length (u32) and value. All integers are in network byte order.
+//!Each kind of metadata is stored in its own record, hence they can be written and read in any order.
+//!
+//!Region is recognized only if its checksum matches and records are well formed, so payload,
+//!which merely starts with [MARKER], is left intact.
+//!
+//!Devices forward body as it is, so metadata survives any number of hops.
+//!
+//!Note that pub/sub filters messages by body prefix, therefore region must not be used with sub0 subscriptions
+//!other than empty one.
+
+use crate::ErrorCode;
+use crate::error::error;
+use crate::msg::Message;
+use crate::sys;
+
+use core::convert::TryFrom;
+
+use alloc::vec::Vec;
+
+///Marker, identifying metadata region
+pub const MARKER: u32 = 0x4e43_4d44;
+//Marker, checksum and length of records
+const PREFIX_SIZE: usize = 4 + 4 + 4;
+//Tag and length of value
+const RECORD_PREFIX_SIZE: usize = 1 + 4;
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+#[repr(u8)]
+pub(crate) enum Tag {
+    Correlation = 1,
+    Trace = 2,
+    Headers = 3,
+}
+
+#[inline(always)]
+fn read_u32(bytes: &[u8]) -> u32 {
+    u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+fn checksum(records: &[u8]) -> u32 {
+    records.iter().fold(0x811c_9dc5, |hash, byte| (hash ^ *byte as u32).wrapping_mul(0x0100_0193))
+}
+
+//Iterator over records, that are already validated
+struct Records<'a>(&'a [u8]);
+
+impl<'a> Iterator for Records<'a> {
+    type Item = (u8, &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (tag, rest) = self.0.split_first()?;
+        let len = read_u32(rest) as usize;
+        let (value, rest) = rest[4..].split_at(len);
+        self.0 = rest;
+        Some((*tag, value))
+    }
+}
+
+//Returns records of the region at the front of `body` together with size of the region
+fn region(body: &[u8]) -> Option<(&[u8], usize)> {
+    if body.len() < PREFIX_SIZE || body[..4] != MARKER.to_be_bytes() {
+        return None;
+    }
+
+    let len = read_u32(&body[8..]) as usize;
+    let records = body.get(PREFIX_SIZE..PREFIX_SIZE.checked_add(len)?)?;
+    if checksum(records) != read_u32(&body[4..]) {
+        return None;
+    }
+
+    let mut rest = records;
+    while !rest.is_empty() {
+        if rest.len() < RECORD_PREFIX_SIZE {
+            return None;
+        }
+        let len = read_u32(&rest[1..]) as usize;
+        rest = rest.get(RECORD_PREFIX_SIZE.checked_add(len)?..)?;
+    }
+
+    Some((records, PREFIX_SIZE + len))
+}
+
+///Returns size of metadata region at the front of `msg` body, or zero if there is none
+pub fn size(msg: &Message) -> usize {
+    region(msg.body()).map_or(0, |(_, size)| size)
+}
+
+//Returns value of the record with `tag`
+pub(crate) fn get(msg: &Message, tag: Tag) -> Option<&[u8]> {
+    let (records, _) = region(msg.body())?;
+    Records(records).find(|(existing, _)| *existing == tag as u8).map(|(_, value)| value)
+}
+
+//Sets value of the record with `tag`, removing it if `value` is `None`
+//
+//Region is removed once it has no records.
+pub(crate) fn set(msg: &mut Message, tag: Tag, value: Option<&[u8]>) -> Result<(), ErrorCode> {
+    let (records, size) = region(msg.body()).unwrap_or((&[], 0));
+
+    let mut new = Vec::with_capacity(PREFIX_SIZE + records.len() + value.map_or(0, |value| RECORD_PREFIX_SIZE + value.len()));
+    new.extend_from_slice(&MARKER.to_be_bytes());
+    new.extend_from_slice(&[0; 8]);
+    let others = Records(records).filter(|(existing, _)| *existing != tag as u8);
+    for (tag, value) in others.chain(value.map(|value| (tag as u8, value))) {
+        let len = u32::try_from(value.len()).map_err(|_| error(sys::nng_errno_enum::NNG_EMSGSIZE))?;
+        new.push(tag);
+        new.extend_from_slice(&len.to_be_bytes());
+        new.extend_from_slice(value);
+    }
+    let len = u32::try_from(new.len() - PREFIX_SIZE).map_err(|_| error(sys::nng_errno_enum::NNG_EMSGSIZE))?;
+    let sum = checksum(&new[PREFIX_SIZE..]);
+    new[4..8].copy_from_slice(&sum.to_be_bytes());
+    new[8..PREFIX_SIZE].copy_from_slice(&len.to_be_bytes());
+
+    msg.truncate_start(msg.len() - size);
+    if len == 0 {
+        Ok(())
+    } else {
+        msg.insert(&new)
+    }
+}
@@ -1,22 +1,39 @@
 use core::{ops, ptr, slice, mem, fmt};
+use core::convert::TryFrom;
 
 use crate::error::{ErrorCode, error};
+use crate::fallible::try_vec;
+use crate::options::Property;
+use crate::pipe::Pipe;
+
+use alloc::vec::Vec;
 
 use nng_c_sys::nng_msg;
 use nng_c_sys::{nng_msg_alloc, nng_msg_free, nng_msg_capacity, nng_msg_reserve};
-use nng_c_sys::{nng_msg_clear, nng_msg_dup};
+use nng_c_sys::{nng_msg_clear, nng_msg_dup, nng_msg_get_pipe, nng_msg_set_pipe};
 use nng_c_sys::{nng_msg_body, nng_msg_len};
 use nng_c_sys::{nng_msg_trim, nng_msg_chop};
 use nng_c_sys::{nng_msg_chop_u16, nng_msg_chop_u32, nng_msg_chop_u64};
 use nng_c_sys::{nng_msg_trim_u16, nng_msg_trim_u32, nng_msg_trim_u64};
 use nng_c_sys::{nng_msg_append, nng_msg_append_u16, nng_msg_append_u32, nng_msg_append_u64};
-use nng_c_sys::{nng_msg_insert, nng_msg_insert_u16, nng_msg_insert_u32, nng_msg_insert_u64};
-use nng_c_sys::{nng_msg_header, nng_msg_header_len};
+use nng_c_sys::{nng_msg_header, nng_msg_header_len, nng_msg_header_clear};
+use nng_c_sys::{nng_msg_header_append, nng_msg_header_insert};
+use nng_c_sys::{nng_msg_header_append_u32, nng_msg_header_insert_u32};
+use nng_c_sys::{nng_msg_header_chop_u32, nng_msg_header_trim_u32};
+
+//Version byte followed by header and body lengths
+const WIRE_PREFIX_SIZE: usize = 1 + 4 + 4;
 
 ///Message primitive
 pub struct Message(pub(crate) ptr::NonNull<nng_msg>);
 
+//Message is exclusively owned, hence it can be freely moved between threads
+unsafe impl Send for Message {}
+
 impl Message {
+    ///Version of the [to_wire](Self::to_wire) format
+    pub const WIRE_VERSION: u8 = 1;
+
     #[inline(always)]
     ///Creates empty message
     ///
@@ -128,6 +145,14 @@ impl Message {
         }
     }
 
+    #[inline(always)]
+    ///Splits body of [framed](crate::pubsub::framed) message into topic and payload
+    ///
+    ///Returns `None` if message is not framed.
+    pub fn split_topic(&self) -> Option<(&[u8], &[u8])> {
+        crate::pubsub::split_framed(self.body())
+    }
+
     #[inline(always)]
     ///Returns reference to the header content
     pub fn header(&self) -> &[u8] {
@@ -253,7 +278,7 @@ impl Message {
     ///
     ///Returns `Err` if there is not enough space
     pub fn insert_u16(&mut self, value: u16) -> Result<(), ErrorCode> {
-        self.push_inner(value, nng_msg_insert_u16)
+        self.insert(&value.to_be_bytes())
     }
 
     #[inline(always)]
@@ -261,7 +286,7 @@ impl Message {
     ///
     ///Returns `Err` if there is not enough space
     pub fn insert_u32(&mut self, value: u32) -> Result<(), ErrorCode> {
-        self.push_inner(value, nng_msg_insert_u32)
+        self.insert(&value.to_be_bytes())
     }
 
     #[inline(always)]
@@ -269,13 +294,114 @@ impl Message {
     ///
     ///Returns `Err` if there is not enough space
     pub fn insert_u64(&mut self, value: u64) -> Result<(), ErrorCode> {
-        self.push_inner(value, nng_msg_insert_u64)
+        self.insert(&value.to_be_bytes())
     }
 
     ///Inserts `bytes` at the start of the body.
+    ///
+    ///Note that whole body is moved on every insert, making it linear in the body's length.
+    ///Prefer to write prefix before appending the body when building large messages.
     pub fn insert(&mut self, bytes: &[u8]) -> Result<(), ErrorCode> {
+        //nng_msg_insert corrupts body, when message has some, but not enough, space in front of the body.
+        //Hence append bytes and move them in front instead.
+        self.append(bytes)?;
+        let len = self.len();
+        let body = unsafe {
+            slice::from_raw_parts_mut(nng_msg_body(self.0.as_ptr()) as *mut u8, len)
+        };
+        body.rotate_right(bytes.len());
+        Ok(())
+    }
+
+    #[inline(always)]
+    ///Get property of the message
+    pub fn get_prop<T: Property<Self>>(&self) -> Result<T, ErrorCode> {
+        T::get(self)
+    }
+
+    #[inline]
+    ///Returns pipe, message was received from
+    ///
+    ///Returns `None` if message was not received from socket
+    pub fn pipe(&self) -> Option<Pipe> {
+        let pipe = unsafe {
+            nng_msg_get_pipe(self.0.as_ptr())
+        };
+
+        if pipe.id > 0 {
+            Some(Pipe(pipe))
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    ///Sets `pipe` to send message over
+    ///
+    ///Only protocols that route messages by pipe take it into account.
+    pub fn set_pipe(&mut self, pipe: &Pipe) {
+        unsafe {
+            nng_msg_set_pipe(self.0.as_ptr(), pipe.0)
+        }
+    }
+
+    //header
+    #[inline(always)]
+    ///Clears content of the header.
+    pub fn header_clear(&mut self) {
+        unsafe {
+            nng_msg_header_clear(self.0.as_ptr())
+        }
+    }
+
+    #[inline(always)]
+    ///Extracts u32 from the end of header, decoding it from network byte order
+    ///
+    ///Returns `None` if there is not enough space
+    pub fn header_pop_u32(&mut self) -> Option<u32> {
+        self.pop_inner(nng_msg_header_chop_u32)
+    }
+
+    #[inline(always)]
+    ///Extracts u32 from the start of header, decoding it from network byte order
+    ///
+    ///Returns `None` if there is not enough space
+    pub fn header_pop_front_u32(&mut self) -> Option<u32> {
+        self.pop_inner(nng_msg_header_trim_u32)
+    }
+
+    #[inline(always)]
+    ///Appends u32 to the end of header, encoding it into network byte order
+    ///
+    ///Returns `Err` if there is not enough space
+    pub fn header_append_u32(&mut self, value: u32) -> Result<(), ErrorCode> {
+        self.push_inner(value, nng_msg_header_append_u32)
+    }
+
+    #[inline(always)]
+    ///Inserts u32 at the start of header, encoding it into network byte order
+    ///
+    ///Returns `Err` if there is not enough space
+    pub fn header_insert_u32(&mut self, value: u32) -> Result<(), ErrorCode> {
+        self.push_inner(value, nng_msg_header_insert_u32)
+    }
+
+    ///Appends `bytes` to the message header.
+    pub fn header_append(&mut self, bytes: &[u8]) -> Result<(), ErrorCode> {
+        let result = unsafe {
+            nng_msg_header_append(self.0.as_ptr(), bytes.as_ptr() as _, bytes.len())
+        };
+
+        match result {
+            0 => Ok(()),
+            code => Err(error(code)),
+        }
+    }
+
+    ///Inserts `bytes` at the start of the header.
+    pub fn header_insert(&mut self, bytes: &[u8]) -> Result<(), ErrorCode> {
         let result = unsafe {
-            nng_msg_insert(self.0.as_ptr(), bytes.as_ptr() as _, bytes.len())
+            nng_msg_header_insert(self.0.as_ptr(), bytes.as_ptr() as _, bytes.len())
         };
 
         match result {
@@ -283,15 +409,211 @@ impl Message {
             code => Err(error(code)),
         }
     }
+
+    //wire format
+    ///Serializes header and body of the message into stable framed format.
+    ///
+    ///Format consists of version byte ([WIRE_VERSION](Self::WIRE_VERSION)), header length and body length, both
+    ///encoded as u32 in network byte order, followed by header and body content.
+    ///
+    ///Frames can be concatenated and read back one by one via [split_wire](Self::split_wire)
+    ///
+    ///Returns `NNG_EMSGSIZE` if header or body is longer than `u32::MAX`.
+    ///
+    ///Aborts on allocation failure, use [try_to_wire](Self::try_to_wire) to handle it.
+    pub fn to_wire(&self) -> Result<Vec<u8>, ErrorCode> {
+        let prefix = self.wire_prefix()?;
+        let mut out = Vec::with_capacity(self.wire_len());
+        self.write_wire(&prefix, &mut out);
+        Ok(out)
+    }
+
+    ///Serializes message same as [to_wire](Self::to_wire)
+    ///
+    ///Returns `NNG_ENOMEM` if unable to allocate buffer.
+    pub fn try_to_wire(&self) -> Result<Vec<u8>, ErrorCode> {
+        let prefix = self.wire_prefix()?;
+        let mut out = try_vec(self.wire_len())?;
+        self.write_wire(&prefix, &mut out);
+        Ok(out)
+    }
+
+    #[inline(always)]
+    fn wire_len(&self) -> usize {
+        WIRE_PREFIX_SIZE + self.header().len() + self.body().len()
+    }
+
+    fn wire_prefix(&self) -> Result<[u8; WIRE_PREFIX_SIZE], ErrorCode> {
+        let header_len = u32::try_from(self.header().len()).map_err(|_| error(nng_c_sys::nng_errno_enum::NNG_EMSGSIZE))?;
+        let body_len = u32::try_from(self.body().len()).map_err(|_| error(nng_c_sys::nng_errno_enum::NNG_EMSGSIZE))?;
+        let mut prefix = [0u8; WIRE_PREFIX_SIZE];
+        prefix[0] = Self::WIRE_VERSION;
+        prefix[1..5].copy_from_slice(&header_len.to_be_bytes());
+        prefix[5..].copy_from_slice(&body_len.to_be_bytes());
+        Ok(prefix)
+    }
+
+    fn write_wire(&self, prefix: &[u8], out: &mut Vec<u8>) {
+        out.extend_from_slice(prefix);
+        out.extend_from_slice(self.header());
+        out.extend_from_slice(self.body());
+    }
+
+    ///Restores message from single frame, produced by [to_wire](Self::to_wire)
+    ///
+    ///Returns error if `bytes` is not exactly one valid frame.
+    pub fn from_wire(bytes: &[u8]) -> Result<Self, ErrorCode> {
+        match Self::split_wire(bytes)? {
+            (msg, []) => Ok(msg),
+            _ => Err(error(nng_c_sys::nng_errno_enum::NNG_EINVAL)),
+        }
+    }
+
+    ///Restores message from the first frame in `bytes`, returning it with remaining bytes
+    ///
+    ///Returns error if frame is incomplete or has unsupported version.
+    pub fn split_wire(bytes: &[u8]) -> Result<(Self, &[u8]), ErrorCode> {
+        if bytes.len() < WIRE_PREFIX_SIZE || bytes[0] != Self::WIRE_VERSION {
+            return Err(error(nng_c_sys::nng_errno_enum::NNG_EINVAL));
+        }
+
+        let header_len = u32::from_be_bytes([bytes[1], bytes[2], bytes[3], bytes[4]]) as usize;
+        let body_len = u32::from_be_bytes([bytes[5], bytes[6], bytes[7], bytes[8]]) as usize;
+        let bytes = &bytes[WIRE_PREFIX_SIZE..];
+        if bytes.len() < header_len || bytes.len() - header_len < body_len {
+            return Err(error(nng_c_sys::nng_errno_enum::NNG_EINVAL));
+        }
+        let (header, bytes) = bytes.split_at(header_len);
+        let (body, rest) = bytes.split_at(body_len);
+
+        let mut msg = match Self::new() {
+            Some(msg) => msg,
+            None => return Err(error(nng_c_sys::nng_errno_enum::NNG_ENOMEM)),
+        };
+        msg.header_append(header)?;
+        msg.append(body)?;
+        Ok((msg, rest))
+    }
+
+    #[inline(always)]
+    ///Returns formatter of header and body as offset, hex and ASCII dump, for debugging binary protocols.
+    ///
+    ///Each part is capped at [DEFAULT_LIMIT](Hexdump::DEFAULT_LIMIT) bytes, unless configured via [limit](Hexdump::limit).
+    pub fn hexdump(&self) -> Hexdump<'_> {
+        Hexdump {
+            msg: self,
+            limit: Hexdump::DEFAULT_LIMIT,
+        }
+    }
+}
+
+///Hex dump of the [Message], created via [hexdump](Message::hexdump)
+///
+///Formats each part in `hexdump -C` style:
+///
+///```text
+///header (4 bytes):
+///00000000  80 00 00 01                                       |....|
+///body (7 bytes):
+///00000000  70 61 79 6c 6f 61 64                              |payload|
+///```
+pub struct Hexdump<'a> {
+    msg: &'a Message,
+    limit: usize,
+}
+
+impl Hexdump<'_> {
+    ///Default number of bytes to dump per part
+    pub const DEFAULT_LIMIT: usize = 256;
+    const LINE_SIZE: usize = 16;
+
+    #[inline(always)]
+    ///Sets maximum number of bytes to dump per part, with rest being summarized.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    fn fmt_part(&self, name: &str, bytes: &[u8], fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_fmt(format_args!("{} ({} bytes):", name, bytes.len()))?;
+
+        let dumped = &bytes[..core::cmp::min(bytes.len(), self.limit)];
+        for (idx, line) in dumped.chunks(Self::LINE_SIZE).enumerate() {
+            fmt.write_fmt(format_args!("\n{:08x}  ", idx * Self::LINE_SIZE))?;
+            for idx in 0..Self::LINE_SIZE {
+                if idx == Self::LINE_SIZE / 2 {
+                    fmt.write_str(" ")?;
+                }
+                match line.get(idx) {
+                    Some(byte) => fmt.write_fmt(format_args!("{:02x} ", byte))?,
+                    None => fmt.write_str("   ")?,
+                }
+            }
+
+            fmt.write_str(" |")?;
+            for byte in line {
+                let ch = if byte.is_ascii_graphic() || *byte == b' ' {
+                    *byte as char
+                } else {
+                    '.'
+                };
+                fmt::Write::write_char(fmt, ch)?;
+            }
+            fmt.write_str("|")?;
+        }
+
+        if dumped.len() < bytes.len() {
+            fmt.write_fmt(format_args!("\n... {} more bytes", bytes.len() - dumped.len()))?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Hexdump<'_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_part("header", self.msg.header(), fmt)?;
+        fmt.write_str("\n")?;
+        self.fmt_part("body", self.msg.body(), fmt)
+    }
+}
+
+impl fmt::Debug for Hexdump<'_> {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, fmt)
+    }
 }
 
 impl Clone for Message {
     #[inline]
+    ///Panics on allocation failure, use [dup](Message::dup) to handle it.
     fn clone(&self) -> Self {
         self.dup().unwrap()
     }
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Message {
+    ///Generates message with header of up to 15 32-bit entries and body of up to 64KiB.
+    fn arbitrary(input: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        const MAX_HEADER_ENTRIES: usize = 15;
+        const MAX_BODY_SIZE: usize = 64 * 1024;
+
+        let mut msg = Self::new().unwrap();
+
+        let header_len = input.int_in_range(0..=MAX_HEADER_ENTRIES)?;
+        for _ in 0..header_len {
+            let entry = input.arbitrary()?;
+            msg.header_append_u32(entry).map_err(|_| arbitrary::Error::IncorrectFormat)?;
+        }
+
+        let body_len = core::cmp::min(input.arbitrary_len::<u8>()?, MAX_BODY_SIZE);
+        msg.append(input.bytes(body_len)?).map_err(|_| arbitrary::Error::IncorrectFormat)?;
+
+        Ok(msg)
+    }
+}
+
 impl ops::Deref for Message {
     type Target = ptr::NonNull<nng_msg>;
 
@@ -0,0 +1,292 @@
+//!Noise protocol encryption
+//!
+//!For ipc, inproc or tcp deployments where TLS is unavailable or overkill, [Session] encrypts
+//!message bodies using [Noise](https://noiseprotocol.org) `XX` handshake pattern:
+//!
+//!- Both peers authenticate each other with static keys, which are transmitted during handshake;
+//!- Handshake is performed in-band, as first three messages over the socket;
+//!- Afterwards each message body is encrypted, prefixed with nonce of its first frame.
+//!
+//!Noise limits size of encrypted message, therefore larger body is split into multiple frames
+//!within the same nng message, each prefixed with its length (u16 in network byte order).
+//!First frame starts with length of the whole body (u64 in network byte order), so that
+//!message with dropped frames is rejected.
+//!
+//!Session is intended for pair sockets, as handshake requires single peer.
+//!Nonces must be increasing, hence replayed messages are rejected, while lost messages are tolerated.
+//!
+//!Errors of encryption or authentication are reported as [NNG_ECRYPTO](crate::sys::nng_errno_enum::NNG_ECRYPTO).
+//!
+//!Requires feature `noise`
+//!
+//!## Usage
+//!
+//!```rust
+//!use nng_c::{Message, Socket};
+//!use nng_c::noise::{Keypair, Session};
+//!
+//!const ADDR: &str = "inproc://noise-example\0";
+//!
+//!let server = Socket::pair0().expect("create server");
+//!server.listen(ADDR.into()).expect("listen");
+//!let client = Socket::pair0().expect("create client");
+//!client.connect(ADDR.into()).expect("connect");
+//!
+//!let server_key = Keypair::generate().expect("generate key");
+//!let client_key = Keypair::generate().expect("generate key");
+//!
+//!std::thread::scope(|scope| {
+//!    let server = scope.spawn(|| {
+//!        let mut session = Session::respond(&server, &server_key).expect("handshake");
+//!        session.recv_msg().expect("receive").body().to_vec()
+//!    });
+//!
+//!    let mut session = Session::initiate(&client, &client_key).expect("handshake");
+//!    assert_eq!(session.remote_public_key(), server_key.public());
+//!
+//!    let mut msg = Message::new().expect("create message");
+//!    msg.append(b"secret").expect("append");
+//!    session.send_msg(msg).expect("send");
+//!
+//!    assert_eq!(server.join().expect("join server"), b"secret");
+//!});
+//!```
+
+use crate::ErrorCode;
+use crate::error::error;
+use crate::msg::Message;
+use crate::socket::Socket;
+use crate::sys;
+
+use core::fmt;
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+///Noise protocol, used by [Session]
+pub const PATTERN: &str = "Noise_XX_25519_ChaChaPoly_BLAKE2s";
+
+const NONCE_SIZE: usize = 8;
+const LEN_SIZE: usize = 2;
+const BODY_LEN_SIZE: usize = 8;
+//Limits set by Noise specification
+const MAX_FRAME_LEN: usize = 65535;
+const TAG_LEN: usize = 16;
+//Maximum size of plain text within single frame
+const MAX_FRAME_PAYLOAD: usize = MAX_FRAME_LEN - TAG_LEN;
+
+#[inline(always)]
+fn crypto_error(_: snow::Error) -> ErrorCode {
+    error(sys::nng_errno_enum::NNG_ECRYPTO)
+}
+
+#[inline]
+fn params() -> snow::params::NoiseParams {
+    match PATTERN.parse() {
+        Ok(params) => params,
+        //Pattern is constant and known to be valid
+        Err(_) => unreachable!(),
+    }
+}
+
+#[inline]
+fn new_message() -> Result<Message, ErrorCode> {
+    match Message::new() {
+        Some(msg) => Ok(msg),
+        None => Err(error(sys::nng_errno_enum::NNG_ENOMEM)),
+    }
+}
+
+#[derive(Clone)]
+///Static key pair, identifying peer
+pub struct Keypair {
+    private: Vec<u8>,
+    public: Vec<u8>,
+}
+
+impl Keypair {
+    ///Generates new random key pair
+    pub fn generate() -> Result<Self, ErrorCode> {
+        let keypair = snow::Builder::new(params()).generate_keypair().map_err(crypto_error)?;
+        Ok(Self {
+            private: keypair.private,
+            public: keypair.public,
+        })
+    }
+
+    #[inline]
+    ///Creates key pair from existing 32 byte `private` and `public` keys
+    pub fn new(private: &[u8], public: &[u8]) -> Self {
+        Self {
+            private: private.to_vec(),
+            public: public.to_vec(),
+        }
+    }
+
+    #[inline(always)]
+    ///Returns public key
+    pub fn public(&self) -> &[u8] {
+        &self.public
+    }
+}
+
+impl fmt::Debug for Keypair {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Keypair").field("public", &self.public).finish()
+    }
+}
+
+///Encrypted session over socket
+pub struct Session<'a> {
+    socket: &'a Socket,
+    transport: snow::StatelessTransportState,
+    send_nonce: u64,
+    recv_nonce: u64,
+}
+
+impl<'a> Session<'a> {
+    fn handshake(socket: &'a Socket, keypair: &Keypair, initiator: bool) -> Result<Self, ErrorCode> {
+        let builder = snow::Builder::new(params()).local_private_key(&keypair.private).map_err(crypto_error)?;
+        let mut handshake = match initiator {
+            true => builder.build_initiator(),
+            false => builder.build_responder(),
+        }.map_err(crypto_error)?;
+
+        let mut buffer = vec![0u8; MAX_FRAME_LEN];
+        while !handshake.is_handshake_finished() {
+            if handshake.is_my_turn() {
+                let len = handshake.write_message(&[], &mut buffer).map_err(crypto_error)?;
+                let mut msg = new_message()?;
+                msg.append(&buffer[..len])?;
+                socket.send_msg(msg).map_err(|(_, error)| error)?;
+            } else {
+                let msg = socket.recv_msg()?;
+                handshake.read_message(msg.body(), &mut buffer).map_err(crypto_error)?;
+            }
+        }
+
+        Ok(Self {
+            socket,
+            transport: handshake.into_stateless_transport_mode().map_err(crypto_error)?,
+            send_nonce: 0,
+            recv_nonce: 0,
+        })
+    }
+
+    #[inline]
+    ///Performs handshake as initiator, which should be side that connects.
+    pub fn initiate(socket: &'a Socket, keypair: &Keypair) -> Result<Self, ErrorCode> {
+        Self::handshake(socket, keypair, true)
+    }
+
+    #[inline]
+    ///Performs handshake as responder, which should be side that listens.
+    pub fn respond(socket: &'a Socket, keypair: &Keypair) -> Result<Self, ErrorCode> {
+        Self::handshake(socket, keypair, false)
+    }
+
+    #[inline(always)]
+    ///Returns underlying socket
+    pub fn socket(&self) -> &'a Socket {
+        self.socket
+    }
+
+    #[inline]
+    ///Returns static public key of the peer, which should be verified by user.
+    pub fn remote_public_key(&self) -> &[u8] {
+        //XX pattern always transmits static key of the peer
+        self.transport.get_remote_static().unwrap_or(&[])
+    }
+
+    ///Encrypts body of `msg` and sends it.
+    pub fn send_msg(&mut self, msg: Message) -> Result<(), ErrorCode> {
+        let body = msg.body();
+        let frames = (BODY_LEN_SIZE + body.len()).div_ceil(MAX_FRAME_PAYLOAD);
+        let mut encrypted = new_message()?;
+        encrypted.reserve(NONCE_SIZE + BODY_LEN_SIZE + body.len() + frames * (LEN_SIZE + TAG_LEN))?;
+        encrypted.header_append(msg.header())?;
+        encrypted.append_u64(self.send_nonce)?;
+
+        let mut buffer = vec![0u8; MAX_FRAME_LEN];
+        //First frame authenticates length of the body, hence it is always sent
+        let (chunk, mut remaining) = body.split_at(core::cmp::min(body.len(), MAX_FRAME_PAYLOAD - BODY_LEN_SIZE));
+        let mut first = Vec::with_capacity(BODY_LEN_SIZE + chunk.len());
+        first.extend_from_slice(&(body.len() as u64).to_be_bytes());
+        first.extend_from_slice(chunk);
+
+        let mut chunk = first.as_slice();
+        loop {
+            let len = self.transport.write_message(self.send_nonce, chunk, &mut buffer).map_err(crypto_error)?;
+            self.send_nonce += 1;
+            encrypted.append_u16(len as u16)?;
+            encrypted.append(&buffer[..len])?;
+
+            if remaining.is_empty() {
+                break;
+            }
+            let (next, rest) = remaining.split_at(core::cmp::min(remaining.len(), MAX_FRAME_PAYLOAD));
+            chunk = next;
+            remaining = rest;
+        }
+
+        self.socket.send_msg(encrypted).map_err(|(_, error)| error.into())
+    }
+
+    ///Receives message, decrypting its body.
+    ///
+    ///Returns error if message is not authentic, is incomplete or is replayed.
+    ///Rejected message does not affect session, so that following messages are still accepted.
+    pub fn recv_msg(&mut self) -> Result<Message, ErrorCode> {
+        let msg = self.socket.recv_msg()?;
+        let body = msg.body();
+        if body.len() < NONCE_SIZE {
+            return Err(error(sys::nng_errno_enum::NNG_ECRYPTO));
+        }
+
+        let mut nonce = [0u8; NONCE_SIZE];
+        nonce.copy_from_slice(&body[..NONCE_SIZE]);
+        let mut nonce = u64::from_be_bytes(nonce);
+        if nonce < self.recv_nonce {
+            return Err(error(sys::nng_errno_enum::NNG_ECRYPTO));
+        }
+
+        let mut decrypted = new_message()?;
+        decrypted.reserve(body.len())?;
+        decrypted.header_append(msg.header())?;
+
+        let mut buffer = vec![0u8; MAX_FRAME_LEN];
+        let mut frames = &body[NONCE_SIZE..];
+        while !frames.is_empty() {
+            if frames.len() < LEN_SIZE {
+                return Err(error(sys::nng_errno_enum::NNG_ECRYPTO));
+            }
+            let len = u16::from_be_bytes([frames[0], frames[1]]) as usize;
+            let frame = match frames.get(LEN_SIZE..LEN_SIZE + len) {
+                Some(frame) => frame,
+                None => return Err(error(sys::nng_errno_enum::NNG_ECRYPTO)),
+            };
+            let len = self.transport.read_message(nonce, frame, &mut buffer).map_err(crypto_error)?;
+            decrypted.append(&buffer[..len])?;
+            frames = &frames[LEN_SIZE + frame.len()..];
+            nonce += 1;
+        }
+
+        //Message without frames has no length and truncated message has wrong one
+        match decrypted.pop_front_u64() {
+            Some(len) if len == decrypted.len() as u64 => (),
+            _ => return Err(error(sys::nng_errno_enum::NNG_ECRYPTO)),
+        }
+
+        //Nonce is advanced only once whole message is verified
+        self.recv_nonce = nonce;
+        Ok(decrypted)
+    }
+}
+
+impl fmt::Debug for Session<'_> {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Session").field("socket", &self.socket).finish()
+    }
+}
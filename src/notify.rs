@@ -0,0 +1,611 @@
+//!Pipe notification multiplexer
+//!
+//!nng allows only single callback per pipe event of the socket.
+//![PipeNotifier] owns these callbacks and fans out events to any number of subscribers, each of
+//!which can be added or removed independently of others:
+//!
+//!- closures, registered via [subscribe](PipeNotifier::subscribe);
+//!- async streams, created via [events](PipeNotifier::events);
+//!- connection [Monitor], created via [monitor](PipeNotifier::monitor) or [Socket::monitor].
+//!
+//!With `futures` feature, both event streams implement `futures_core::Stream`.
+//!
+//!Notifier is created once per socket, and every utility of the crate, relying on pipe events
+//!(i.e. [set_accept_filter](crate::socket::Listener::set_accept_filter) or [AccessControl](crate::access::AccessControl)),
+//!subscribes through it, hence they can be freely combined.
+//!
+//!## Usage
+//!
+//!```rust
+//!use nng_c::Socket;
+//!use nng_c::notify::{PipeEvent, PipeNotifier};
+//!
+//!use core::sync::atomic::{AtomicUsize, Ordering};
+//!use std::sync::Arc;
+//!
+//!let server = Socket::pair0().expect("create socket");
+//!let notifier = PipeNotifier::install(&server).expect("install notifier");
+//!
+//!let connected = Arc::new(AtomicUsize::new(0));
+//!let counter = connected.clone();
+//!let subscription = notifier.subscribe(move |_, event| if event == PipeEvent::AddPost {
+//!    counter.fetch_add(1, Ordering::AcqRel);
+//!});
+//!//Events can be awaited via `events.next().await`
+//!let events = notifier.events().expect("subscribe stream");
+//!
+//!server.listen("inproc://notify-example".into()).expect("listen");
+//!
+//!//Stops receiving events
+//!drop(subscription);
+//!drop(events);
+//!assert_eq!(notifier.subscribers(), 0);
+//!```
+
+use crate::ErrorCode;
+use crate::error::error;
+use crate::options::{Address, RemoteAddr};
+use crate::pipe::Pipe;
+use crate::socket::Socket;
+use crate::utils::sync::Mutex;
+use crate::sys;
+
+use core::{fmt, hint, mem, ptr, task};
+use core::cell::UnsafeCell;
+use core::ffi::c_void;
+use core::future::Future;
+use core::pin::Pin;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use alloc::collections::VecDeque;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+///Event of the pipe
+pub enum PipeEvent {
+    ///Pipe is added, but not yet attached to the socket
+    ///
+    ///Closing pipe at this point rejects connection.
+    AddPre,
+    ///Pipe is attached to the socket
+    AddPost,
+    ///Pipe is removed from the socket
+    RemPost,
+}
+
+impl PipeEvent {
+    const ALL: [Self; 3] = [Self::AddPre, Self::AddPost, Self::RemPost];
+
+    #[inline(always)]
+    const fn into_raw(self) -> sys::nng_pipe_ev::Type {
+        match self {
+            Self::AddPre => sys::nng_pipe_ev::NNG_PIPE_EV_ADD_PRE,
+            Self::AddPost => sys::nng_pipe_ev::NNG_PIPE_EV_ADD_POST,
+            Self::RemPost => sys::nng_pipe_ev::NNG_PIPE_EV_REM_POST,
+        }
+    }
+
+    #[inline(always)]
+    const fn from_raw(event: sys::nng_pipe_ev::Type) -> Option<Self> {
+        match event {
+            sys::nng_pipe_ev::NNG_PIPE_EV_ADD_PRE => Some(Self::AddPre),
+            sys::nng_pipe_ev::NNG_PIPE_EV_ADD_POST => Some(Self::AddPost),
+            sys::nng_pipe_ev::NNG_PIPE_EV_REM_POST => Some(Self::RemPost),
+            _ => None,
+        }
+    }
+}
+
+type Callback = Arc<dyn Fn(Pipe, PipeEvent) + Send + Sync>;
+
+struct Subscriber {
+    id: usize,
+    //Listener, which pipes are only delivered to subscriber
+    listener: Option<u32>,
+    callback: Callback,
+}
+
+struct Subscribers {
+    next_id: usize,
+    list: Vec<Subscriber>,
+}
+
+impl Subscribers {
+    fn add(&mut self, listener: Option<u32>, callback: Callback) -> usize {
+        let id = self.next_id;
+        self.next_id = self.next_id.wrapping_add(1);
+        self.list.push(Subscriber {
+            id,
+            listener,
+            callback,
+        });
+        id
+    }
+}
+
+struct State {
+    subscribers: Mutex<Subscribers>,
+}
+
+impl State {
+    fn notify(&self, pipe: Pipe, event: PipeEvent) {
+        //Callbacks are invoked without lock, so that they can subscribe or unsubscribe
+        let listener = unsafe {
+            sys::nng_pipe_listener(pipe.0).id
+        };
+        let callbacks = self.subscribers.lock().list.iter().filter(|subscriber| match subscriber.listener {
+            Some(id) => id == listener,
+            None => true,
+        }).map(|subscriber| subscriber.callback.clone()).collect::<Vec<_>>();
+        for callback in callbacks {
+            (callback)(pipe, event);
+        }
+    }
+
+    fn unsubscribe(&self, id: usize) {
+        let callback = {
+            let mut subscribers = self.subscribers.lock();
+            subscribers.list.iter().position(|subscriber| subscriber.id == id).map(|idx| subscribers.list.remove(idx))
+        };
+        //Callback may own subscription, so it must be dropped without lock
+        drop(callback);
+    }
+
+    fn unsubscribe_listener(&self, listener: u32) {
+        let removed = {
+            let mut subscribers = self.subscribers.lock();
+            let mut removed = Vec::new();
+            let mut idx = 0;
+            while idx < subscribers.list.len() {
+                if subscribers.list[idx].listener == Some(listener) {
+                    removed.push(subscribers.list.remove(idx));
+                } else {
+                    idx += 1;
+                }
+            }
+            removed
+        };
+        drop(removed);
+    }
+}
+
+//Notifiers of open sockets, keyed by socket id.
+//
+//Guarded by spin lock, as it must be available without allocation of nng's mutex.
+struct Registry {
+    lock: AtomicBool,
+    notifiers: UnsafeCell<Vec<(u32, Arc<State>)>>,
+}
+
+unsafe impl Sync for Registry {}
+
+impl Registry {
+    fn with<R, F: FnOnce(&mut Vec<(u32, Arc<State>)>) -> R>(&self, cb: F) -> R {
+        while self.lock.compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed).is_err() {
+            hint::spin_loop();
+        }
+        let result = cb(unsafe {
+            &mut *self.notifiers.get()
+        });
+        self.lock.store(false, Ordering::Release);
+        result
+    }
+
+    #[inline]
+    fn find(&self, socket: u32) -> Option<Arc<State>> {
+        self.with(|notifiers| notifiers.iter().find(|(id, _)| *id == socket).map(|(_, state)| state.clone()))
+    }
+}
+
+static REGISTRY: Registry = Registry {
+    lock: AtomicBool::new(false),
+    notifiers: UnsafeCell::new(Vec::new()),
+};
+
+//Drops notifier of closed `socket`, as nng no longer invokes its callbacks.
+pub(crate) fn release(socket: sys::nng_socket) {
+    let state = REGISTRY.with(|notifiers| notifiers.iter().position(|(id, _)| *id == socket.id).map(|idx| notifiers.swap_remove(idx)));
+    //Subscribers may own sockets, so state must be dropped without lock
+    drop(state);
+}
+
+//Removes subscribers, bound to `listener` of the `socket`, once it is closed or failed to start.
+pub(crate) fn release_listener(socket: sys::nng_socket, listener: sys::nng_listener) {
+    if let Some(state) = REGISTRY.find(socket.id) {
+        state.unsubscribe_listener(listener.id);
+    }
+}
+
+unsafe extern "C" fn pipe_callback(pipe: sys::nng_pipe, event: sys::nng_pipe_ev::Type, data: *mut c_void) {
+    if data.is_null() {
+        return;
+    }
+
+    //State is kept in registry until socket is closed
+    let state = &*(data as *const State);
+    if let Some(event) = PipeEvent::from_raw(event) {
+        state.notify(Pipe(pipe), event);
+    }
+}
+
+#[derive(Clone)]
+///Multiplexer of socket's pipe events
+///
+///Events are delivered on nng's thread, which processes the pipe, hence subscribers should not block.
+///Only events that happen after subscription are delivered.
+pub struct PipeNotifier {
+    state: Arc<State>,
+}
+
+impl PipeNotifier {
+    ///Returns notifier of the `socket`, installing it on first use.
+    ///
+    ///Socket has single notifier, owning its pipe callbacks, hence every call returns the same
+    ///instance, which is kept until socket is closed.
+    #[inline]
+    pub fn install(socket: &Socket) -> Result<Self, ErrorCode> {
+        Self::install_raw(**socket)
+    }
+
+    pub(crate) fn install_raw(socket: sys::nng_socket) -> Result<Self, ErrorCode> {
+        REGISTRY.with(|notifiers| {
+            if let Some((_, state)) = notifiers.iter().find(|(id, _)| *id == socket.id) {
+                return Ok(Self {
+                    state: state.clone(),
+                });
+            }
+
+            let state = Arc::new(State {
+                subscribers: Mutex::new(Subscribers {
+                    next_id: 0,
+                    list: Vec::new(),
+                })?,
+            });
+            notifiers.try_reserve(1).map_err(|_| error(sys::nng_errno_enum::NNG_ENOMEM))?;
+
+            let data = Arc::as_ptr(&state) as *mut c_void;
+            for event in PipeEvent::ALL.iter() {
+                let result = unsafe {
+                    sys::nng_pipe_notify(socket, event.into_raw(), Some(pipe_callback), data)
+                };
+
+                if result != 0 {
+                    for event in PipeEvent::ALL.iter() {
+                        unsafe {
+                            sys::nng_pipe_notify(socket, event.into_raw(), None, ptr::null_mut());
+                        }
+                    }
+                    return Err(error(result));
+                }
+            }
+
+            notifiers.push((socket.id, state.clone()));
+            Ok(Self {
+                state
+            })
+        })
+    }
+
+    ///Registers `callback` to be invoked on every pipe event.
+    ///
+    ///Callback is removed once returned [Subscription] is dropped.
+    pub fn subscribe<F: Fn(Pipe, PipeEvent) + Send + Sync + 'static>(&self, callback: F) -> Subscription {
+        let id = self.state.subscribers.lock().add(None, Arc::new(callback));
+
+        Subscription {
+            state: self.state.clone(),
+            id,
+        }
+    }
+
+    //Registers `callback` to be invoked on events of pipes, accepted by `listener`, until it is closed.
+    pub(crate) fn subscribe_listener<F: Fn(Pipe, PipeEvent) + Send + Sync + 'static>(&self, listener: sys::nng_listener, callback: F) {
+        self.state.subscribers.lock().add(Some(listener.id), Arc::new(callback));
+    }
+
+    ///Creates stream of pipe events.
+    ///
+    ///Events are buffered without limit until consumed, therefore stream should be polled
+    ///regularly or dropped once no longer needed.
+    pub fn events(&self) -> Result<PipeEvents, ErrorCode> {
+        let queue = Queue::new()?;
+
+        let producer = queue.clone();
+        let subscription = self.subscribe(move |pipe, event| Queue::push(&producer, (pipe, event)));
+
+        Ok(PipeEvents {
+            queue,
+            _subscription: subscription,
+        })
+    }
+
+    ///Creates [Monitor] of connections.
+    ///
+    ///Only connections, established after monitor is created, are reported with their information.
+    pub fn monitor(&self) -> Result<Monitor, ErrorCode> {
+        let queue = Queue::new()?;
+        let peers = Mutex::new(Vec::<Peer>::new())?;
+
+        let producer = queue.clone();
+        let subscription = self.subscribe(move |pipe, event| {
+            let event = match event {
+                PipeEvent::AddPre => return,
+                PipeEvent::AddPost => {
+                    //Information is gathered now, as it is no longer available once pipe is removed
+                    let peer = Peer::new(pipe);
+                    peers.lock().push(peer.clone());
+                    ConnectionEvent::Connected(peer)
+                },
+                PipeEvent::RemPost => {
+                    let peer = {
+                        let mut peers = peers.lock();
+                        match peers.iter().position(|peer| peer.pipe == pipe) {
+                            Some(idx) => peers.swap_remove(idx),
+                            None => return,
+                        }
+                    };
+                    ConnectionEvent::Disconnected(peer)
+                },
+            };
+            Queue::push(&producer, event);
+        });
+
+        Ok(Monitor {
+            queue,
+            _subscription: subscription,
+        })
+    }
+
+    #[inline]
+    ///Returns number of current subscribers
+    pub fn subscribers(&self) -> usize {
+        self.state.subscribers.lock().list.len()
+    }
+}
+
+impl fmt::Debug for PipeNotifier {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("PipeNotifier").field("subscribers", &self.subscribers()).finish()
+    }
+}
+
+#[must_use = "Dropping subscription removes subscriber"]
+///Subscription to pipe events, removing subscriber on drop
+pub struct Subscription {
+    state: Arc<State>,
+    id: usize,
+}
+
+impl Subscription {
+    #[inline]
+    ///Keeps subscriber until socket is closed, consuming subscription.
+    pub fn forget(self) {
+        let this = mem::ManuallyDrop::new(self);
+        drop(unsafe {
+            ptr::read(&this.state)
+        });
+    }
+}
+
+impl Drop for Subscription {
+    #[inline]
+    fn drop(&mut self) {
+        self.state.unsubscribe(self.id);
+    }
+}
+
+impl fmt::Debug for Subscription {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Subscription").field("id", &self.id).finish()
+    }
+}
+
+struct Queue<T> {
+    events: VecDeque<T>,
+    waker: Option<task::Waker>,
+}
+
+impl<T> Queue<T> {
+    #[inline]
+    fn new() -> Result<Arc<Mutex<Self>>, ErrorCode> {
+        Ok(Arc::new(Mutex::new(Self {
+            events: VecDeque::new(),
+            waker: None,
+        })?))
+    }
+
+    fn push(queue: &Mutex<Self>, event: T) {
+        let mut queue = queue.lock();
+        queue.events.push_back(event);
+        if let Some(waker) = queue.waker.take() {
+            drop(queue);
+            waker.wake();
+        }
+    }
+
+    fn poll_next(queue: &Mutex<Self>, ctx: &mut task::Context<'_>) -> task::Poll<T> {
+        let mut queue = queue.lock();
+        match queue.events.pop_front() {
+            Some(event) => task::Poll::Ready(event),
+            None => {
+                match queue.waker.as_ref() {
+                    Some(waker) if waker.will_wake(ctx.waker()) => (),
+                    _ => queue.waker = Some(ctx.waker().clone()),
+                }
+                task::Poll::Pending
+            }
+        }
+    }
+}
+
+///Stream of pipe events, created via [PipeNotifier::events]
+///
+///Stream never ends, as notifier has no way to know when socket is closed.
+pub struct PipeEvents {
+    queue: Arc<Mutex<Queue<(Pipe, PipeEvent)>>>,
+    _subscription: Subscription,
+}
+
+impl PipeEvents {
+    #[inline]
+    ///Returns next event, if available, without waiting
+    pub fn try_next(&self) -> Option<(Pipe, PipeEvent)> {
+        self.queue.lock().events.pop_front()
+    }
+
+    ///Polls for next event, registering waker from `ctx` if none is available
+    pub fn poll_next(&self, ctx: &mut task::Context<'_>) -> task::Poll<(Pipe, PipeEvent)> {
+        Queue::poll_next(&self.queue, ctx)
+    }
+
+    #[inline(always)]
+    ///Returns future, resolving into next event
+    pub fn next(&self) -> NextEvent<'_> {
+        NextEvent {
+            events: self,
+        }
+    }
+}
+
+#[cfg(feature = "futures")]
+impl futures_core::Stream for PipeEvents {
+    type Item = (Pipe, PipeEvent);
+
+    #[inline(always)]
+    fn poll_next(self: Pin<&mut Self>, ctx: &mut task::Context<'_>) -> task::Poll<Option<Self::Item>> {
+        PipeEvents::poll_next(&self, ctx).map(Some)
+    }
+}
+
+impl fmt::Debug for PipeEvents {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("PipeEvents").field("pending", &self.queue.lock().events.len()).finish()
+    }
+}
+
+#[must_use = "Future does nothing unless polled"]
+///Future returned by [PipeEvents::next]
+pub struct NextEvent<'a> {
+    events: &'a PipeEvents,
+}
+
+impl Future for NextEvent<'_> {
+    type Output = (Pipe, PipeEvent);
+
+    #[inline(always)]
+    fn poll(self: Pin<&mut Self>, ctx: &mut task::Context<'_>) -> task::Poll<Self::Output> {
+        self.events.poll_next(ctx)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+///Peer of the connection, reported by [Monitor]
+pub struct Peer {
+    ///Pipe of the connection, which is no longer valid once peer is disconnected
+    pub pipe: Pipe,
+    ///Remote address of the peer, if transport provides it
+    pub remote_addr: Option<Address>,
+    ///Whether connection has been established by dialer, rather than by listener
+    pub is_dialer: bool,
+}
+
+impl Peer {
+    #[inline]
+    fn new(pipe: Pipe) -> Self {
+        Self {
+            pipe,
+            remote_addr: pipe.get_prop::<RemoteAddr>().ok().map(|addr| addr.0),
+            is_dialer: pipe.is_dialer(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+///Connection event, reported by [Monitor]
+pub enum ConnectionEvent {
+    ///Peer is connected
+    Connected(Peer),
+    ///Peer is disconnected
+    Disconnected(Peer),
+}
+
+impl ConnectionEvent {
+    #[inline(always)]
+    ///Returns peer of the event
+    pub fn peer(&self) -> &Peer {
+        match self {
+            Self::Connected(peer) => peer,
+            Self::Disconnected(peer) => peer,
+        }
+    }
+}
+
+///Stream of connection events, created via [Socket::monitor] or [PipeNotifier::monitor]
+///
+///Unlike [PipeEvents], it only reports established connections, providing information about peer
+///on disconnect as well.
+///
+///Events are buffered without limit until consumed, while stream never ends, as notifier has no
+///way to know when socket is closed.
+pub struct Monitor {
+    queue: Arc<Mutex<Queue<ConnectionEvent>>>,
+    _subscription: Subscription,
+}
+
+impl Monitor {
+    #[inline]
+    ///Returns next event, if available, without waiting
+    pub fn try_next(&self) -> Option<ConnectionEvent> {
+        self.queue.lock().events.pop_front()
+    }
+
+    #[inline]
+    ///Polls for next event, registering waker from `ctx` if none is available
+    pub fn poll_next(&self, ctx: &mut task::Context<'_>) -> task::Poll<ConnectionEvent> {
+        Queue::poll_next(&self.queue, ctx)
+    }
+
+    #[inline(always)]
+    ///Returns future, resolving into next event
+    pub fn next(&self) -> NextConnectionEvent<'_> {
+        NextConnectionEvent {
+            monitor: self,
+        }
+    }
+}
+
+#[cfg(feature = "futures")]
+impl futures_core::Stream for Monitor {
+    type Item = ConnectionEvent;
+
+    #[inline(always)]
+    fn poll_next(self: Pin<&mut Self>, ctx: &mut task::Context<'_>) -> task::Poll<Option<Self::Item>> {
+        Monitor::poll_next(&self, ctx).map(Some)
+    }
+}
+
+impl fmt::Debug for Monitor {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Monitor").field("pending", &self.queue.lock().events.len()).finish()
+    }
+}
+
+#[must_use = "Future does nothing unless polled"]
+///Future returned by [Monitor::next]
+pub struct NextConnectionEvent<'a> {
+    monitor: &'a Monitor,
+}
+
+impl Future for NextConnectionEvent<'_> {
+    type Output = ConnectionEvent;
+
+    #[inline(always)]
+    fn poll(self: Pin<&mut Self>, ctx: &mut task::Context<'_>) -> task::Poll<Self::Output> {
+        self.monitor.poll_next(ctx)
+    }
+}
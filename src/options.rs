@@ -1,11 +1,20 @@
 //! Options
 
 use crate::sys;
-use crate::socket::Socket;
+use crate::socket::{Socket, Protocol};
+use crate::msg::Message;
+use crate::stream::Stream;
+use crate::pipe::Pipe;
+use crate::context::Context;
 use crate::error::{error, ErrorCode};
 
 use core::{fmt, time};
-use core::convert::TryInto;
+use core::convert::{TryFrom, TryInto};
+use core::ffi::CStr;
+use core::net::{SocketAddr, SocketAddrV4, SocketAddrV6, Ipv4Addr, Ipv6Addr};
+
+use alloc::string::String;
+use alloc::vec::Vec;
 
 ///Property interface
 pub trait Property<T>: Sized {
@@ -26,6 +35,14 @@ impl<T> Options<T> for () {
     }
 }
 
+impl<T, A: Options<T>, B: Options<T>> Options<T> for (A, B) {
+    #[inline]
+    fn apply(&self, target: &T) -> Result<(), ErrorCode> {
+        self.0.apply(target)?;
+        self.1.apply(target)
+    }
+}
+
 macro_rules! set_bytes_option {
     ($socket:expr, $name:expr, $bytes:expr) => {
         unsafe {
@@ -61,6 +78,17 @@ macro_rules! set_int_option {
     }
 }
 
+macro_rules! set_bool_option {
+    ($socket:expr, $name:expr, $value:expr) => {
+        unsafe {
+            match sys::nng_socket_set_bool($socket, $name.as_ptr() as _, $value) {
+                0 => Ok(()),
+                code => Err(error(code)),
+            }
+        }
+    }
+}
+
 macro_rules! set_size_t_option {
     ($socket:expr, $name:expr, $num:expr) => {
         unsafe {
@@ -86,6 +114,43 @@ macro_rules! set_duration_option {
     }
 }
 
+macro_rules! set_ctx_bytes_option {
+    ($ctx:expr, $name:expr, $bytes:expr) => {
+        unsafe {
+            let bytes = $bytes;
+            match sys::nng_ctx_set($ctx, $name.as_ptr() as _, bytes.as_ptr() as _, bytes.len()) {
+                0 => Ok(()),
+                code => Err(error(code)),
+            }
+        }
+    }
+}
+
+macro_rules! set_ctx_bool_option {
+    ($ctx:expr, $name:expr, $value:expr) => {
+        unsafe {
+            match sys::nng_ctx_set_bool($ctx, $name.as_ptr() as _, $value) {
+                0 => Ok(()),
+                code => Err(error(code)),
+            }
+        }
+    }
+}
+
+macro_rules! set_ctx_duration_option {
+    ($ctx:expr, $name:expr, $duration:expr) => {
+        match $duration.as_millis().try_into() {
+            Ok(duration) => unsafe {
+                match sys::nng_ctx_set_ms($ctx, $name.as_ptr() as _, duration) {
+                    0 => Ok(()),
+                    code => Err(error(code)),
+                }
+            },
+            Err(_) => Err(error(sys::nng_errno_enum::NNG_EINVAL)),
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 ///Req protocol options
 pub struct Req {
@@ -111,6 +176,18 @@ impl Options<Socket> for Req {
     }
 }
 
+impl Options<Context> for Req {
+    #[inline]
+    fn apply(&self, target: &Context) -> Result<(), ErrorCode> {
+        //Resend tick is property of the socket only
+        if let Some(resend_time) = self.resend_time {
+            set_ctx_duration_option!(target.0, sys::NNG_OPT_REQ_RESENDTIME, resend_time)?;
+        }
+
+        Ok(())
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 ///Topic to subscribe to for sub protocol.
 pub struct Subscribe<'a>(pub &'a [u8]);
@@ -121,6 +198,12 @@ impl Options<Socket> for Subscribe<'_> {
     }
 }
 
+impl Options<Context> for Subscribe<'_> {
+    fn apply(&self, target: &Context) -> Result<(), ErrorCode> {
+        set_ctx_bytes_option!(target.0, sys::NNG_OPT_SUB_SUBSCRIBE, self.0)
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 ///Topic to unsubscribe from for sub protocol.
 pub struct Unsubscribe<'a>(pub &'a [u8]);
@@ -131,10 +214,51 @@ impl Options<Socket> for Unsubscribe<'_> {
     }
 }
 
-#[derive(Copy, Clone, Debug)]
+impl Options<Context> for Unsubscribe<'_> {
+    fn apply(&self, target: &Context) -> Result<(), ErrorCode> {
+        set_ctx_bytes_option!(target.0, sys::NNG_OPT_SUB_UNSUBSCRIBE, self.0)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+///Whether sub protocol drops the oldest message, instead of the newest one, when receive buffer is full.
+///
+///Defaults to `true`, which suits consumers, interested only in the latest data (i.e. telemetry).
+pub struct SubPreferNew(pub bool);
+
+impl Options<Socket> for SubPreferNew {
+    fn apply(&self, target: &Socket) -> Result<(), ErrorCode> {
+        set_bool_option!(**target, sys::NNG_OPT_SUB_PREFNEW, self.0)
+    }
+}
+
+impl Options<Context> for SubPreferNew {
+    fn apply(&self, target: &Context) -> Result<(), ErrorCode> {
+        set_ctx_bool_option!(target.0, sys::NNG_OPT_SUB_PREFNEW, self.0)
+    }
+}
+
+impl Property<Socket> for SubPreferNew {
+    fn get(target: &Socket) -> Result<Self, ErrorCode> {
+        let mut value = false;
+        let result = unsafe {
+            sys::nng_socket_get_bool(**target, sys::NNG_OPT_SUB_PREFNEW.as_ptr() as _, &mut value)
+        };
+
+        match result {
+            0 => Ok(Self(value)),
+            code => Err(error(code))
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 ///Max number of hops message can make to reach peer
 ///
-///Usually defaults to 8
+///Usually defaults to 8. Allowed values are from 1 to 15.
+///
+///Applicable to pair1 sockets, where messages with hop count exceeding this limit are dropped,
+///as well as to devices forwarding req/rep and survey protocols.
 pub struct MaxTtl(pub u8);
 
 impl Options<Socket> for MaxTtl {
@@ -143,6 +267,43 @@ impl Options<Socket> for MaxTtl {
     }
 }
 
+impl Property<Socket> for MaxTtl {
+    fn get(target: &Socket) -> Result<Self, ErrorCode> {
+        let mut value = 0;
+        let result = unsafe {
+            sys::nng_socket_get_int(**target, sys::NNG_OPT_MAXTTL.as_ptr() as _, &mut value)
+        };
+
+        match result {
+            0 => Ok(Self(value as _)),
+            code => Err(error(code))
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+///Number of hops message received by pair1 socket has made.
+///
+///Stored by pair1 protocol in the message header.
+pub struct HopCount(pub u8);
+
+impl HopCount {
+    #[inline(always)]
+    ///Returns number of hops message can still make before being dropped by peer with `max_ttl`
+    pub const fn remaining(&self, max_ttl: MaxTtl) -> u8 {
+        max_ttl.0.saturating_sub(self.0)
+    }
+}
+
+impl Property<Message> for HopCount {
+    fn get(target: &Message) -> Result<Self, ErrorCode> {
+        match target.header() {
+            [0, 0, 0, hops] => Ok(Self(*hops)),
+            _ => Err(error(sys::nng_errno_enum::NNG_EPROTO)),
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 ///Reconnect options
 pub struct Reconnect {
@@ -169,7 +330,7 @@ impl Options<Socket> for Reconnect {
     }
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 ///Sets internal receive buffer to this amount of messages
 ///
 ///Allowed values are from 0 to 8192.
@@ -181,6 +342,20 @@ impl Options<Socket> for RecvBuf {
     }
 }
 
+impl Property<Socket> for RecvBuf {
+    fn get(target: &Socket) -> Result<Self, ErrorCode> {
+        let mut value = 0;
+        let result = unsafe {
+            sys::nng_socket_get_int(**target, sys::NNG_OPT_RECVBUF.as_ptr() as _, &mut value)
+        };
+
+        match result {
+            0 => Ok(Self(value as _)),
+            code => Err(error(code))
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 ///Limits size of message that socket can receive
 ///
@@ -205,7 +380,13 @@ impl Options<Socket> for RecvTimeout {
     }
 }
 
-#[derive(Copy, Clone, Debug)]
+impl Options<Context> for RecvTimeout {
+    fn apply(&self, target: &Context) -> Result<(), ErrorCode> {
+        set_ctx_duration_option!(target.0, sys::NNG_OPT_RECVTIMEO, self.0)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 ///Sets internal send buffer to this amount of messages
 ///
 ///Allowed values are from 0 to 8192.
@@ -217,6 +398,20 @@ impl Options<Socket> for SendBuf {
     }
 }
 
+impl Property<Socket> for SendBuf {
+    fn get(target: &Socket) -> Result<Self, ErrorCode> {
+        let mut value = 0;
+        let result = unsafe {
+            sys::nng_socket_get_int(**target, sys::NNG_OPT_SENDBUF.as_ptr() as _, &mut value)
+        };
+
+        match result {
+            0 => Ok(Self(value as _)),
+            code => Err(error(code))
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 ///Sets timeout on message send.
 ///
@@ -229,6 +424,30 @@ impl Options<Socket> for SendTimeout {
     }
 }
 
+impl Options<Context> for SendTimeout {
+    fn apply(&self, target: &Context) -> Result<(), ErrorCode> {
+        set_ctx_duration_option!(target.0, sys::NNG_OPT_SENDTIMEO, self.0)
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+///Sets duration of survey for surveyor protocol.
+///
+///Responses arriving after survey expired are discarded.
+pub struct SurveyTime(pub time::Duration);
+
+impl Options<Socket> for SurveyTime {
+    fn apply(&self, target: &Socket) -> Result<(), ErrorCode> {
+        set_duration_option!(**target, sys::NNG_OPT_SURVEYOR_SURVEYTIME, self.0)
+    }
+}
+
+impl Options<Context> for SurveyTime {
+    fn apply(&self, target: &Context) -> Result<(), ErrorCode> {
+        set_ctx_duration_option!(target.0, sys::NNG_OPT_SURVEYOR_SURVEYTIME, self.0)
+    }
+}
+
 #[derive(Copy, Clone, Eq)]
 ///Socket name, limited to 63 characters.
 ///
@@ -352,11 +571,11 @@ impl fmt::Display for SocketName {
 ///This tells protocol of the peer
 pub struct PeerName(pub(crate) SocketName);
 
-impl Property<Socket> for PeerName {
-    fn get(target: &Socket) -> Result<Self, ErrorCode> {
+impl PeerName {
+    fn get_raw(socket: sys::nng_socket) -> Result<Self, ErrorCode> {
         let mut buf = [0; 64];
         let result = unsafe {
-            sys::nng_socket_get(**target, sys::NNG_OPT_PEERNAME.as_ptr() as _, buf.as_mut_ptr() as _, &mut buf.len())
+            sys::nng_socket_get(socket, sys::NNG_OPT_PEERNAME.as_ptr() as _, buf.as_mut_ptr() as _, &mut buf.len())
         };
 
         match result {
@@ -366,6 +585,21 @@ impl Property<Socket> for PeerName {
     }
 }
 
+impl Property<Socket> for PeerName {
+    #[inline]
+    fn get(target: &Socket) -> Result<Self, ErrorCode> {
+        Self::get_raw(**target)
+    }
+}
+
+impl Property<Pipe> for PeerName {
+    #[inline]
+    ///Gets peer name of the socket, pipe belongs to
+    fn get(target: &Pipe) -> Result<Self, ErrorCode> {
+        Self::get_raw(target.socket())
+    }
+}
+
 impl PartialEq<SocketName> for PeerName {
     #[inline]
     fn eq(&self, other: &SocketName) -> bool {
@@ -428,3 +662,484 @@ impl fmt::Display for PeerName {
         }
     }
 }
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+#[repr(transparent)]
+///Protocol name of the socket, limited to 63 characters.
+pub struct ProtocolName(pub(crate) SocketName);
+
+impl ProtocolName {
+    #[inline(always)]
+    ///Returns string, if raw bytes are valid unicode
+    pub fn as_str(&self) -> Option<&str> {
+        self.0.as_str()
+    }
+}
+
+impl Property<Socket> for ProtocolName {
+    fn get(target: &Socket) -> Result<Self, ErrorCode> {
+        let mut buf = [0; 64];
+        let result = unsafe {
+            sys::nng_socket_get(**target, sys::NNG_OPT_PROTONAME.as_ptr() as _, buf.as_mut_ptr() as _, &mut buf.len())
+        };
+
+        match result {
+            0 => Ok(Self(SocketName(buf))),
+            code => Err(error(code))
+        }
+    }
+}
+
+impl PartialEq<str> for ProtocolName {
+    #[inline]
+    fn eq(&self, other: &str) -> bool {
+        self.0.as_bytes() == other.as_bytes()
+    }
+}
+
+impl PartialEq<&str> for ProtocolName {
+    #[inline]
+    fn eq(&self, other: &&str) -> bool {
+        self.0.as_bytes() == other.as_bytes()
+    }
+}
+
+impl fmt::Debug for ProtocolName {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut fmt = fmt.debug_tuple("ProtocolName");
+        match self.0.as_str() {
+            Some(name) => fmt.field(&name).finish(),
+            None => fmt.field(&self.0).finish(),
+        }
+    }
+}
+
+impl fmt::Display for ProtocolName {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, fmt)
+    }
+}
+
+macro_rules! get_protocol_id {
+    ($socket:expr, $name:expr) => {{
+        let mut value = 0;
+        let result = unsafe {
+            sys::nng_socket_get_int($socket, $name.as_ptr() as _, &mut value)
+        };
+
+        match result {
+            0 => Ok(Self(value as _)),
+            code => Err(error(code))
+        }
+    }};
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+///Protocol number of the socket
+pub struct ProtocolId(pub u16);
+
+impl ProtocolId {
+    #[inline(always)]
+    ///Returns protocol, if it is known
+    pub const fn protocol(&self) -> Option<Protocol> {
+        Protocol::from_id(self.0)
+    }
+}
+
+impl Property<Socket> for ProtocolId {
+    #[inline]
+    fn get(target: &Socket) -> Result<Self, ErrorCode> {
+        get_protocol_id!(**target, sys::NNG_OPT_PROTO)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+///Protocol number of the socket's peer
+pub struct PeerId(pub u16);
+
+impl PeerId {
+    #[inline(always)]
+    ///Returns protocol, if it is known
+    pub const fn protocol(&self) -> Option<Protocol> {
+        Protocol::from_id(self.0)
+    }
+}
+
+impl Property<Socket> for PeerId {
+    #[inline]
+    fn get(target: &Socket) -> Result<Self, ErrorCode> {
+        get_protocol_id!(**target, sys::NNG_OPT_PEER)
+    }
+}
+
+impl Property<Pipe> for PeerId {
+    #[inline]
+    ///Gets peer protocol of the socket, pipe belongs to
+    fn get(target: &Pipe) -> Result<Self, ErrorCode> {
+        get_protocol_id!(target.socket(), sys::NNG_OPT_PEER)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+///Whether socket is in raw mode
+pub struct Raw(pub bool);
+
+impl Property<Socket> for Raw {
+    fn get(target: &Socket) -> Result<Self, ErrorCode> {
+        let mut value = false;
+        let result = unsafe {
+            sys::nng_socket_get_bool(**target, sys::NNG_OPT_RAW.as_ptr() as _, &mut value)
+        };
+
+        match result {
+            0 => Ok(Self(value)),
+            code => Err(error(code))
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+///Whether pair1 socket is in polyamorous mode, accepting multiple peers
+///
+///Mode is chosen on creation via [Socket::pair1_poly].
+///Any other socket reports `false`.
+pub struct Pair1Poly(pub bool);
+
+impl Property<Socket> for Pair1Poly {
+    fn get(target: &Socket) -> Result<Self, ErrorCode> {
+        let mut value = false;
+        let result = unsafe {
+            sys::nng_socket_get_bool(**target, sys::NNG_OPT_PAIR1_POLY.as_ptr() as _, &mut value)
+        };
+
+        match result {
+            0 => Ok(Self(value)),
+            //Option is only known to polyamorous socket
+            sys::nng_errno_enum::NNG_ENOTSUP => Ok(Self(false)),
+            code => Err(error(code))
+        }
+    }
+}
+
+fn get_socket_int(target: &Socket, name: &[u8]) -> Result<i32, ErrorCode> {
+    let mut value = 0;
+    let result = unsafe {
+        sys::nng_socket_get_int(**target, name.as_ptr() as _, &mut value)
+    };
+
+    match result {
+        0 => Ok(value),
+        code => Err(error(code))
+    }
+}
+
+//Returns `None` for infinite duration
+fn get_socket_duration(target: &Socket, name: &[u8]) -> Result<Option<time::Duration>, ErrorCode> {
+    let mut value = 0;
+    let result = unsafe {
+        sys::nng_socket_get_ms(**target, name.as_ptr() as _, &mut value)
+    };
+
+    match result {
+        0 => Ok(TryInto::<u64>::try_into(value).ok().map(time::Duration::from_millis)),
+        code => Err(error(code))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+///Snapshot of socket's readable options, intended for diagnostics
+///
+///Options, that are not supported by socket's protocol, are set to `None`.
+pub struct SocketOptions {
+    ///Socket name
+    pub name: SocketName,
+    ///Protocol name
+    pub protocol: ProtocolName,
+    ///Protocol name of the peer
+    pub peer: PeerName,
+    ///Whether socket is in raw mode
+    pub raw: bool,
+    ///Receive buffer size in messages
+    pub recv_buf: u16,
+    ///Send buffer size in messages
+    pub send_buf: u16,
+    ///Receive timeout, `None` if infinite
+    pub recv_timeout: Option<time::Duration>,
+    ///Send timeout, `None` if infinite
+    pub send_timeout: Option<time::Duration>,
+    ///Maximum size of received message, where 0 means no limit
+    pub recv_max_size: usize,
+    ///Maximum number of hops message can make
+    pub max_ttl: Option<u8>,
+    ///File descriptor, that becomes readable when message can be received
+    pub recv_fd: Option<i32>,
+    ///File descriptor, that becomes readable when message can be sent
+    pub send_fd: Option<i32>,
+}
+
+impl Property<Socket> for SocketOptions {
+    fn get(target: &Socket) -> Result<Self, ErrorCode> {
+        let mut recv_max_size = 0;
+        let result = unsafe {
+            sys::nng_socket_get_size(**target, sys::NNG_OPT_RECVMAXSZ.as_ptr() as _, &mut recv_max_size)
+        };
+        if result != 0 {
+            return Err(error(result));
+        }
+
+        Ok(Self {
+            name: SocketName::get(target)?,
+            protocol: ProtocolName::get(target)?,
+            peer: PeerName::get(target)?,
+            raw: Raw::get(target)?.0,
+            recv_buf: RecvBuf::get(target)?.0,
+            send_buf: SendBuf::get(target)?.0,
+            recv_timeout: get_socket_duration(target, sys::NNG_OPT_RECVTIMEO)?,
+            send_timeout: get_socket_duration(target, sys::NNG_OPT_SENDTIMEO)?,
+            recv_max_size,
+            max_ttl: MaxTtl::get(target).ok().map(|ttl| ttl.0),
+            recv_fd: get_socket_int(target, sys::NNG_OPT_RECVFD).ok(),
+            send_fd: get_socket_int(target, sys::NNG_OPT_SENDFD).ok(),
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+///Address of the transport endpoint
+pub enum Address {
+    ///Address is not known
+    Unspecified,
+    ///Name of in-process endpoint
+    Inproc(String),
+    ///Path to IPC socket
+    Ipc(String),
+    ///Name of abstract unix socket
+    Abstract(Vec<u8>),
+    ///IP address and port
+    Inet(SocketAddr),
+    ///ZeroTier address, not supported by this library
+    ZeroTier,
+}
+
+impl Address {
+    pub(crate) fn from_raw(addr: &sys::nng_sockaddr) -> Self {
+        #[inline]
+        fn c_string(name: &[core::ffi::c_char]) -> String {
+            let name = unsafe {
+                core::slice::from_raw_parts(name.as_ptr() as *const u8, name.len())
+            };
+            let len = name.iter().position(|byt| *byt == 0).unwrap_or(name.len());
+            String::from_utf8_lossy(&name[..len]).into_owned()
+        }
+
+        unsafe {
+            match addr.s_family as _ {
+                sys::nng_sockaddr_family::NNG_AF_INPROC => Self::Inproc(c_string(&addr.s_inproc.sa_name)),
+                sys::nng_sockaddr_family::NNG_AF_IPC => Self::Ipc(c_string(&addr.s_ipc.sa_path)),
+                sys::nng_sockaddr_family::NNG_AF_ABSTRACT => {
+                    let name = &addr.s_abstract.sa_name;
+                    let len = core::cmp::min(addr.s_abstract.sa_len as usize, name.len());
+                    Self::Abstract(name[..len].to_vec())
+                },
+                //Address and port are stored in network byte order
+                sys::nng_sockaddr_family::NNG_AF_INET => {
+                    let ip = Ipv4Addr::from(addr.s_in.sa_addr.to_ne_bytes());
+                    Self::Inet(SocketAddrV4::new(ip, u16::from_be(addr.s_in.sa_port)).into())
+                },
+                sys::nng_sockaddr_family::NNG_AF_INET6 => {
+                    let ip = Ipv6Addr::from(addr.s_in6.sa_addr);
+                    Self::Inet(SocketAddrV6::new(ip, u16::from_be(addr.s_in6.sa_port), 0, addr.s_in6.sa_scope).into())
+                },
+                sys::nng_sockaddr_family::NNG_AF_ZT => Self::ZeroTier,
+                _ => Self::Unspecified,
+            }
+        }
+    }
+}
+
+impl Address {
+    #[inline]
+    ///Returns IP address and port, if this is address of TCP based transport
+    pub fn socket_addr(&self) -> Option<SocketAddr> {
+        match self {
+            Self::Inet(addr) => Some(*addr),
+            _ => None,
+        }
+    }
+}
+
+impl From<SocketAddr> for Address {
+    #[inline(always)]
+    fn from(addr: SocketAddr) -> Self {
+        Self::Inet(addr)
+    }
+}
+
+impl From<SocketAddrV4> for Address {
+    #[inline(always)]
+    fn from(addr: SocketAddrV4) -> Self {
+        Self::Inet(addr.into())
+    }
+}
+
+impl From<SocketAddrV6> for Address {
+    #[inline(always)]
+    fn from(addr: SocketAddrV6) -> Self {
+        Self::Inet(addr.into())
+    }
+}
+
+impl TryFrom<Address> for SocketAddr {
+    type Error = Address;
+
+    #[inline]
+    ///Converts IP address, returning other kinds of address back
+    fn try_from(addr: Address) -> Result<Self, Self::Error> {
+        match addr {
+            Address::Inet(addr) => Ok(addr),
+            addr => Err(addr),
+        }
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unspecified => fmt.write_str("<unspecified>"),
+            Self::Inproc(name) => fmt.write_fmt(format_args!("inproc://{}", name)),
+            Self::Ipc(path) => fmt.write_fmt(format_args!("ipc://{}", path)),
+            Self::Abstract(name) => fmt.write_fmt(format_args!("abstract://{}", String::from_utf8_lossy(name))),
+            Self::Inet(addr) => fmt.write_fmt(format_args!("tcp://{}", addr)),
+            Self::ZeroTier => fmt.write_str("zt://<unsupported>"),
+        }
+    }
+}
+
+fn get_addr<F: FnOnce(*mut sys::nng_sockaddr) -> core::ffi::c_int>(get: F) -> Result<Address, ErrorCode> {
+    let mut addr = unsafe {
+        core::mem::zeroed::<sys::nng_sockaddr>()
+    };
+
+    match get(&mut addr) {
+        0 => Ok(Address::from_raw(&addr)),
+        code => Err(error(code)),
+    }
+}
+
+fn get_string<F: FnOnce(*mut *mut core::ffi::c_char) -> core::ffi::c_int>(get: F) -> Result<String, ErrorCode> {
+    let mut value = core::ptr::null_mut();
+
+    match get(&mut value) {
+        0 if value.is_null() => Err(error(sys::nng_errno_enum::NNG_ENOENT)),
+        0 => unsafe {
+            let string = CStr::from_ptr(value).to_string_lossy().into_owned();
+            sys::nng_strfree(value);
+            Ok(string)
+        },
+        code => Err(error(code))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+///Address of the remote peer
+pub struct RemoteAddr(pub Address);
+
+impl Property<Stream> for RemoteAddr {
+    #[inline]
+    fn get(target: &Stream) -> Result<Self, ErrorCode> {
+        get_addr(|addr| unsafe {
+            sys::nng_stream_get_addr(target.as_ptr(), sys::NNG_OPT_REMADDR.as_ptr() as _, addr)
+        }).map(Self)
+    }
+}
+
+impl Property<Pipe> for RemoteAddr {
+    #[inline]
+    fn get(target: &Pipe) -> Result<Self, ErrorCode> {
+        get_addr(|addr| unsafe {
+            sys::nng_pipe_get_addr(target.0, sys::NNG_OPT_REMADDR.as_ptr() as _, addr)
+        }).map(Self)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+///Local address of the connection
+pub struct LocalAddr(pub Address);
+
+impl Property<Stream> for LocalAddr {
+    #[inline]
+    fn get(target: &Stream) -> Result<Self, ErrorCode> {
+        get_addr(|addr| unsafe {
+            sys::nng_stream_get_addr(target.as_ptr(), sys::NNG_OPT_LOCADDR.as_ptr() as _, addr)
+        }).map(Self)
+    }
+}
+
+impl Property<Pipe> for LocalAddr {
+    #[inline]
+    fn get(target: &Pipe) -> Result<Self, ErrorCode> {
+        get_addr(|addr| unsafe {
+            sys::nng_pipe_get_addr(target.0, sys::NNG_OPT_LOCADDR.as_ptr() as _, addr)
+        }).map(Self)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+///Whether TLS peer has been verified
+///
+///Only available on TLS connections.
+pub struct TlsVerified(pub bool);
+
+impl Property<Stream> for TlsVerified {
+    fn get(target: &Stream) -> Result<Self, ErrorCode> {
+        let mut value = false;
+        let result = unsafe {
+            sys::nng_stream_get_bool(target.as_ptr(), sys::NNG_OPT_TLS_VERIFIED.as_ptr() as _, &mut value)
+        };
+
+        match result {
+            0 => Ok(Self(value)),
+            code => Err(error(code))
+        }
+    }
+}
+
+impl Property<Pipe> for TlsVerified {
+    fn get(target: &Pipe) -> Result<Self, ErrorCode> {
+        let mut value = false;
+        let result = unsafe {
+            sys::nng_pipe_get_bool(target.0, sys::NNG_OPT_TLS_VERIFIED.as_ptr() as _, &mut value)
+        };
+
+        match result {
+            0 => Ok(Self(value)),
+            code => Err(error(code))
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+///Common name of TLS peer's certificate
+///
+///Only available on TLS connections where peer presented certificate.
+pub struct TlsPeerCn(pub String);
+
+impl Property<Stream> for TlsPeerCn {
+    #[inline]
+    fn get(target: &Stream) -> Result<Self, ErrorCode> {
+        get_string(|value| unsafe {
+            sys::nng_stream_get_string(target.as_ptr(), sys::NNG_OPT_TLS_PEER_CN.as_ptr() as _, value)
+        }).map(Self)
+    }
+}
+
+impl Property<Pipe> for TlsPeerCn {
+    #[inline]
+    fn get(target: &Pipe) -> Result<Self, ErrorCode> {
+        get_string(|value| unsafe {
+            sys::nng_pipe_get_string(target.0, sys::NNG_OPT_TLS_PEER_CN.as_ptr() as _, value)
+        }).map(Self)
+    }
+}
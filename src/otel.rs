@@ -0,0 +1,225 @@
+//!OpenTelemetry instrumentation
+//!
+//![Traced] wraps socket, recording span for every send and receive operation with following attributes:
+//!
+//!- `nng.socket.id` - Socket's id;
+//!- `nng.protocol` - Socket's protocol name;
+//!- `messaging.message.body.size` - Size of message body in bytes, excluding [metadata](crate::metadata) region;
+//!- `network.peer.address` - Address of the peer, if known (i.e. for received messages).
+//!
+//!Trace context is propagated within message via [TraceContext], making span of the receiver child of the sender's span.
+//!
+//!Requires feature `otel`
+
+use crate::ErrorCode;
+use crate::correlation::TraceContext;
+use crate::metadata;
+use crate::msg::Message;
+use crate::options::{ProtocolName, RemoteAddr};
+use crate::socket::Socket;
+
+use core::fmt;
+use alloc::borrow::Cow;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use std::time::SystemTime;
+
+use opentelemetry::{global, Context, KeyValue};
+use opentelemetry::trace::{Span, SpanContext, SpanId, SpanKind, Status, TraceContextExt, TraceFlags, TraceId, TraceState, Tracer};
+
+///Name of the tracer used by [Traced::new]
+pub const TRACER_NAME: &str = "nng-c";
+
+///Extracts parent context from the trace context, stamped onto `msg`
+///
+///Trace context region is removed from the message.
+pub fn extract_context(msg: &mut Message) -> Option<Context> {
+    let trace = TraceContext::extract(msg)?;
+    let span = SpanContext::new(
+        TraceId::from(trace.trace_id),
+        SpanId::from(trace.span_id),
+        TraceFlags::new(trace.flags),
+        true,
+        TraceState::default(),
+    );
+    Some(Context::new().with_remote_span_context(span))
+}
+
+///Stamps trace context of the span, active in `context`, onto `msg`
+///
+///Does nothing if there is no valid span in context.
+pub fn inject_context(context: &Context, msg: &mut Message) -> Result<(), ErrorCode> {
+    stamp_span(context.span().span_context(), msg)
+}
+
+fn stamp_span(span: &SpanContext, msg: &mut Message) -> Result<(), ErrorCode> {
+    if !span.is_valid() {
+        return Ok(());
+    }
+
+    TraceContext {
+        trace_id: u128::from_be_bytes(span.trace_id().to_bytes()),
+        span_id: u64::from_be_bytes(span.span_id().to_bytes()),
+        flags: span.trace_flags().to_u8(),
+    }.stamp(msg)
+}
+
+///Socket wrapper, recording span for every operation
+pub struct Traced<'a> {
+    socket: &'a Socket,
+    tracer: global::BoxedTracer,
+    attributes: [KeyValue; 2],
+}
+
+impl<'a> Traced<'a> {
+    #[inline]
+    ///Wraps `socket`, using global tracer named [TRACER_NAME]
+    pub fn new(socket: &'a Socket) -> Self {
+        Self::with_tracer(socket, global::tracer(TRACER_NAME))
+    }
+
+    ///Wraps `socket`, using specified `tracer`
+    pub fn with_tracer(socket: &'a Socket, tracer: global::BoxedTracer) -> Self {
+        let protocol = match socket.get_prop::<ProtocolName>() {
+            Ok(protocol) => Cow::Owned(protocol.to_string()),
+            Err(_) => Cow::Borrowed("unknown"),
+        };
+
+        Self {
+            socket,
+            tracer,
+            attributes: [
+                KeyValue::new("nng.socket.id", socket.0.id as i64),
+                KeyValue::new("nng.protocol", protocol),
+            ],
+        }
+    }
+
+    #[inline(always)]
+    ///Returns underlying socket
+    pub fn socket(&self) -> &'a Socket {
+        self.socket
+    }
+
+    fn attributes(&self, msg: &Message) -> Vec<KeyValue> {
+        let mut attributes = Vec::with_capacity(self.attributes.len() + 2);
+        attributes.extend_from_slice(&self.attributes);
+        attributes.push(KeyValue::new("messaging.message.body.size", (msg.len() - metadata::size(msg)) as i64));
+        if let Some(Ok(RemoteAddr(addr))) = msg.pipe().map(|pipe| pipe.get_prop::<RemoteAddr>()) {
+            attributes.push(KeyValue::new("network.peer.address", addr.to_string()));
+        }
+        attributes
+    }
+
+    //Starts send span as child of `parent`, stamping its context onto `msg`
+    fn start_send(&self, parent: &Context, msg: &mut Message) -> Result<global::BoxedSpan, ErrorCode> {
+        let mut span = self.tracer.span_builder("nng.send")
+                                  .with_kind(SpanKind::Producer)
+                                  .with_attributes(self.attributes(msg))
+                                  .start_with_context(&self.tracer, parent);
+        if let Err(error) = stamp_span(span.span_context(), msg) {
+            span.set_status(Status::error(error.to_string()));
+            span.end();
+            return Err(error);
+        }
+        Ok(span)
+    }
+
+    //Records receive span, started at `started`, returning context with it
+    fn finish_recv(&self, started: SystemTime, mut msg: Message) -> (Message, Context) {
+        let parent = extract_context(&mut msg).unwrap_or_default();
+        let mut span = self.tracer.span_builder("nng.recv")
+                                  .with_kind(SpanKind::Consumer)
+                                  .with_start_time(started)
+                                  .with_attributes(self.attributes(&msg))
+                                  .start_with_context(&self.tracer, &parent);
+        span.end();
+        (msg, parent.with_span(span))
+    }
+
+    fn fail_recv(&self, started: SystemTime, error: &ErrorCode) {
+        let mut span = self.tracer.span_builder("nng.recv")
+                                  .with_kind(SpanKind::Consumer)
+                                  .with_start_time(started)
+                                  .with_attributes(self.attributes.to_vec())
+                                  .start(&self.tracer);
+        span.set_status(Status::error(error.to_string()));
+        span.end();
+    }
+
+    ///Sends message within span, that is child of `parent` context.
+    ///
+    ///On error, message is returned with trace context stamped.
+    pub fn send_msg(&self, parent: &Context, mut msg: Message) -> Result<(), (Message, ErrorCode)> {
+        let mut span = match self.start_send(parent, &mut msg) {
+            Ok(span) => span,
+            Err(error) => return Err((msg, error)),
+        };
+
+        let result = self.socket.send_msg(msg).map_err(|(msg, error)| (msg, ErrorCode::from(error)));
+        if let Err((_, error)) = &result {
+            span.set_status(Status::error(error.to_string()));
+        }
+        span.end();
+        result
+    }
+
+    ///Sends message asynchronously within span, that is child of `parent` context.
+    ///
+    ///Message is dropped on failure.
+    pub async fn send_msg_async(&self, parent: &Context, mut msg: Message) -> Result<(), ErrorCode> {
+        let mut span = self.start_send(parent, &mut msg)?;
+
+        let result = match self.socket.send_msg_async(msg) {
+            Ok(fut) => fut.await.map_err(|(_, error)| error),
+            Err(error) => Err(error),
+        };
+        if let Err(error) = &result {
+            span.set_status(Status::error(error.to_string()));
+        }
+        span.end();
+        result
+    }
+
+    ///Receives message, recording span that is child of the sender's span, if message has trace context.
+    ///
+    ///Returns message with context, containing receive span, to be used as parent for message processing.
+    pub fn recv_msg(&self) -> Result<(Message, Context), ErrorCode> {
+        let started = SystemTime::now();
+        match self.socket.recv_msg() {
+            Ok(msg) => Ok(self.finish_recv(started, msg)),
+            Err(error) => {
+                let error = error.into();
+                self.fail_recv(started, &error);
+                Err(error)
+            }
+        }
+    }
+
+    ///Receives message asynchronously, recording span that is child of the sender's span, if message has trace context.
+    ///
+    ///Returns message with context, containing receive span, to be used as parent for message processing.
+    pub async fn recv_msg_async(&self) -> Result<Option<(Message, Context)>, ErrorCode> {
+        let started = SystemTime::now();
+        let result = match self.socket.recv_msg_async() {
+            Ok(fut) => fut.await,
+            Err(error) => Err(error),
+        };
+
+        match result {
+            Ok(Some(msg)) => Ok(Some(self.finish_recv(started, msg))),
+            Ok(None) => Ok(None),
+            Err(error) => {
+                self.fail_recv(started, &error);
+                Err(error)
+            }
+        }
+    }
+}
+
+impl fmt::Debug for Traced<'_> {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Traced").field("socket", &self.socket).finish()
+    }
+}
@@ -0,0 +1,327 @@
+//!Persistent outbox
+//!
+//![Outbox] wraps sending socket, spooling messages that cannot be sent into file and replaying
+//!them once connection with peer is established again, so that messages are not lost across
+//!peer restarts or even restarts of the application itself.
+//!
+//!Messages are sent without waiting, therefore socket should have no send buffer (default for
+//!most protocols), otherwise messages are queued within socket's buffer and lost if socket is closed.
+//!
+//!Spooled messages are appended to the file, which is only rewritten once some of them are replayed.
+//!
+//!Requires feature `std`
+
+use crate::ErrorCode;
+use crate::msg::Message;
+use crate::notify::{PipeEvent, PipeNotifier, Subscription};
+use crate::socket::Socket;
+use crate::sys;
+
+use core::fmt;
+
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread;
+
+fn io_error(error: std::io::Error) -> ErrorCode {
+    match error.raw_os_error() {
+        Some(code) => crate::error::error(sys::nng_errno_enum::NNG_ESYSERR | code),
+        None => crate::error::error(sys::nng_errno_enum::NNG_EINTERNAL),
+    }
+}
+
+struct Spool {
+    queue: VecDeque<Message>,
+    //Whether background replay is in progress
+    replaying: bool,
+    //Whether new connection has been established since last replay
+    reconnected: bool,
+    //Whether outbox is closed, stopping replay thread
+    closed: bool,
+}
+
+struct State {
+    socket: sys::nng_socket,
+    path: PathBuf,
+    spool: Mutex<Spool>,
+    wakeup: Condvar,
+}
+
+impl State {
+    #[inline]
+    fn lock(&self) -> MutexGuard<'_, Spool> {
+        match self.spool.lock() {
+            Ok(spool) => spool,
+            Err(error) => error.into_inner(),
+        }
+    }
+
+    //Sends spooled messages in order without waiting, stopping at first failure
+    fn replay(&self, spool: &mut Spool) -> usize {
+        let mut sent = 0;
+        while let Some(msg) = spool.queue.pop_front() {
+            let result = unsafe {
+                sys::nng_sendmsg(self.socket, msg.as_ptr(), sys::NNG_FLAG_NONBLOCK)
+            };
+
+            if result == 0 {
+                core::mem::forget(msg);
+                sent += 1;
+            } else {
+                spool.queue.push_front(msg);
+                break;
+            }
+        }
+        sent
+    }
+
+    //Sends spooled messages in order, waiting for each to be sent, until spool is empty or send fails
+    fn replay_blocking(&self) {
+        let mut sent = 0usize;
+        loop {
+            let msg = {
+                let mut spool = self.lock();
+                match spool.queue.pop_front() {
+                    Some(msg) => msg,
+                    None => {
+                        spool.replaying = false;
+                        let _ = self.persist(&spool);
+                        return;
+                    }
+                }
+            };
+
+            let result = unsafe {
+                sys::nng_sendmsg(self.socket, msg.as_ptr(), 0)
+            };
+
+            if result == 0 {
+                core::mem::forget(msg);
+                sent += 1;
+            } else {
+                let mut spool = self.lock();
+                spool.queue.push_front(msg);
+                spool.replaying = false;
+                if sent > 0 {
+                    let _ = self.persist(&spool);
+                }
+                return;
+            }
+        }
+    }
+
+    //Replays spool on every new connection, until outbox is closed
+    fn run(&self) {
+        loop {
+            {
+                let mut spool = self.lock();
+                while !spool.closed && !spool.reconnected {
+                    spool = match self.wakeup.wait(spool) {
+                        Ok(spool) => spool,
+                        Err(error) => error.into_inner(),
+                    };
+                }
+                if spool.closed {
+                    return;
+                }
+
+                spool.reconnected = false;
+                if spool.queue.is_empty() || spool.replaying {
+                    continue;
+                }
+                spool.replaying = true;
+            }
+
+            //New pipe is not ready to send yet, so replay waits for it to become ready
+            self.replay_blocking();
+        }
+    }
+
+    //Rewrites spool file with current content
+    fn persist(&self, spool: &Spool) -> Result<(), ErrorCode> {
+        if spool.queue.is_empty() {
+            return match std::fs::remove_file(&self.path) {
+                Ok(()) => Ok(()),
+                Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(()),
+                Err(error) => Err(io_error(error)),
+            };
+        }
+
+        let mut content = Vec::new();
+        for msg in spool.queue.iter() {
+            content.extend_from_slice(&msg.to_wire()?);
+        }
+
+        let mut tmp = self.path.clone().into_os_string();
+        tmp.push(".tmp");
+        std::fs::write(&tmp, content).map_err(io_error)?;
+        std::fs::rename(&tmp, &self.path).map_err(io_error)
+    }
+
+    //Appends `msg` to the end of spool file
+    fn append(&self, msg: &Message) -> Result<(), ErrorCode> {
+        let mut file = std::fs::OpenOptions::new().create(true).append(true).open(&self.path).map_err(io_error)?;
+        let wire = msg.to_wire()?;
+        file.write_all(&wire).map_err(io_error)
+    }
+}
+
+///Socket wrapper, persisting messages that failed to be sent
+///
+///Spooled messages are replayed by background thread once new connection is established, or on next send.
+///Message may be sent twice, if application stops before replay is complete.
+///
+///Connections are tracked via socket's [PipeNotifier], hence outbox can be used together with other subscribers.
+pub struct Outbox {
+    socket: Socket,
+    state: Arc<State>,
+    worker: Option<thread::JoinHandle<()>>,
+    _subscription: Subscription,
+}
+
+impl Outbox {
+    ///Creates new outbox for `socket`, using file at `path` as spool.
+    ///
+    ///Messages, that were spooled previously, are loaded from the file and replayed once socket is connected.
+    pub fn open<P: AsRef<Path>>(socket: Socket, path: P) -> Result<Self, ErrorCode> {
+        let path = path.as_ref().to_path_buf();
+        let mut spool = VecDeque::new();
+        match std::fs::read(&path) {
+            Ok(content) => {
+                let mut content = content.as_slice();
+                while !content.is_empty() {
+                    let (msg, rest) = Message::split_wire(content)?;
+                    spool.push_back(msg);
+                    content = rest;
+                }
+            },
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => (),
+            Err(error) => return Err(io_error(error)),
+        }
+
+        let state = Arc::new(State {
+            socket: *socket,
+            path,
+            spool: Mutex::new(Spool {
+                queue: spool,
+                replaying: false,
+                reconnected: false,
+                closed: false,
+            }),
+            wakeup: Condvar::new(),
+        });
+
+        let subscription = {
+            let state = state.clone();
+            PipeNotifier::install(&socket)?.subscribe(move |_, event| if event == PipeEvent::AddPost {
+                let mut spool = state.lock();
+                if !spool.queue.is_empty() {
+                    spool.reconnected = true;
+                    state.wakeup.notify_one();
+                }
+            })
+        };
+
+        let worker = {
+            let state = state.clone();
+            thread::Builder::new().name("nng-c-outbox".into()).spawn(move || state.run()).map_err(io_error)?
+        };
+
+        Ok(Self {
+            socket,
+            state,
+            worker: Some(worker),
+            _subscription: subscription,
+        })
+    }
+
+    #[inline(always)]
+    ///Returns underlying socket
+    pub fn socket(&self) -> &Socket {
+        &self.socket
+    }
+
+    #[inline]
+    ///Returns number of spooled messages
+    pub fn len(&self) -> usize {
+        self.state.lock().queue.len()
+    }
+
+    #[inline]
+    ///Returns whether there are no spooled messages
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    ///Sends `msg`, spooling it if it cannot be sent immediately.
+    ///
+    ///Previously spooled messages are sent first, preserving order of messages.
+    ///Returns error only if message cannot be persisted.
+    pub fn send(&self, msg: Message) -> Result<(), ErrorCode> {
+        let mut spool = self.state.lock();
+        let sent = if spool.replaying || spool.queue.is_empty() {
+            0
+        } else {
+            self.state.replay(&mut spool)
+        };
+
+        if !spool.replaying && spool.queue.is_empty() {
+            match self.socket.try_send_msg(msg) {
+                Ok(()) => return if sent > 0 {
+                    self.state.persist(&spool)
+                } else {
+                    Ok(())
+                },
+                Err((msg, _)) => spool.queue.push_back(msg),
+            }
+        } else {
+            spool.queue.push_back(msg);
+        }
+
+        if sent > 0 {
+            self.state.persist(&spool)
+        } else if let Some(msg) = spool.queue.back() {
+            self.state.append(msg)
+        } else {
+            Ok(())
+        }
+    }
+
+    ///Attempts to send spooled messages without waiting, returning number of sent messages
+    ///
+    ///Does nothing if replay is already in progress.
+    pub fn flush(&self) -> Result<usize, ErrorCode> {
+        let mut spool = self.state.lock();
+        if spool.replaying || spool.queue.is_empty() {
+            return Ok(0);
+        }
+
+        let sent = self.state.replay(&mut spool);
+        if sent > 0 {
+            self.state.persist(&spool)?;
+        }
+        Ok(sent)
+    }
+}
+
+impl Drop for Outbox {
+    fn drop(&mut self) {
+        self.state.lock().closed = true;
+        self.state.wakeup.notify_one();
+        //Interrupts replay, which may be waiting for the send
+        self.socket.close();
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+impl fmt::Debug for Outbox {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Outbox").field("socket", &self.socket).field("path", &self.state.path).finish()
+    }
+}
@@ -0,0 +1,111 @@
+//!Pipe module
+//!
+//!Pipe is single connection of the socket, established either by listener or dialer.
+//!
+//!Pipe, message arrived on, is available via [Message::pipe](crate::Message::pipe), allowing to
+//!learn about the peer through [properties](Pipe::get_prop):
+//!
+//!- [RemoteAddr](crate::options::RemoteAddr) and [LocalAddr](crate::options::LocalAddr) of the connection;
+//!- [PeerName](crate::options::PeerName) and [PeerId](crate::options::PeerId) of the protocol;
+//!- [TlsVerified](crate::options::TlsVerified) and [TlsPeerCn](crate::options::TlsPeerCn) of TLS connections.
+
+use crate::ErrorCode;
+use crate::error::error;
+use crate::options::Property;
+use crate::sys;
+
+use core::{fmt, hash};
+use core::ffi::c_int;
+
+#[derive(Copy, Clone)]
+#[repr(transparent)]
+///Connection of the socket
+///
+///This is only handle, which doesn't own connection, hence it may become invalid once
+///connection is closed, in which case all operations fail.
+pub struct Pipe(pub(crate) sys::nng_pipe);
+
+impl Pipe {
+    #[inline(always)]
+    ///Returns pipe's identifier, which is positive for valid pipe
+    pub fn id(&self) -> i32 {
+        unsafe {
+            sys::nng_pipe_id(self.0)
+        }
+    }
+
+    #[inline(always)]
+    pub(crate) fn socket(&self) -> sys::nng_socket {
+        unsafe {
+            sys::nng_pipe_socket(self.0)
+        }
+    }
+
+    #[inline]
+    ///Returns identifier of the socket, pipe belongs to
+    pub fn socket_id(&self) -> c_int {
+        unsafe {
+            sys::nng_socket_id(self.socket())
+        }
+    }
+
+    #[inline]
+    ///Returns whether pipe has been established by listener
+    pub fn is_listener(&self) -> bool {
+        unsafe {
+            sys::nng_pipe_listener(self.0).id > 0
+        }
+    }
+
+    #[inline]
+    ///Returns whether pipe has been established by dialer
+    pub fn is_dialer(&self) -> bool {
+        unsafe {
+            sys::nng_pipe_dialer(self.0).id > 0
+        }
+    }
+
+    #[inline(always)]
+    ///Gets property of the pipe
+    ///
+    ///Refer to [options](crate::options) for available properties (i.e. [RemoteAddr](crate::options::RemoteAddr))
+    pub fn get_prop<T: Property<Self>>(&self) -> Result<T, ErrorCode> {
+        T::get(self)
+    }
+
+    #[inline]
+    ///Closes pipe, disconnecting the peer
+    pub fn close(&self) -> Result<(), ErrorCode> {
+        let result = unsafe {
+            sys::nng_pipe_close(self.0)
+        };
+
+        match result {
+            0 => Ok(()),
+            code => Err(error(code)),
+        }
+    }
+}
+
+impl PartialEq for Pipe {
+    #[inline(always)]
+    fn eq(&self, other: &Self) -> bool {
+        self.0.id == other.0.id
+    }
+}
+
+impl Eq for Pipe {}
+
+impl hash::Hash for Pipe {
+    #[inline(always)]
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.0.id.hash(state)
+    }
+}
+
+impl fmt::Debug for Pipe {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_fmt(format_args!("Pipe(id={})", self.0.id))
+    }
+}
@@ -0,0 +1,170 @@
+//!Worker pool for rep0 sockets
+//!
+//![WorkerPool] keeps multiple contexts with outstanding asynchronous receives on the same socket,
+//!similarly to nng's async demo, so that requests are not queued behind replies in flight.
+//!Single task drives all workers, calling handler as soon as any of them receives request and
+//!sending reply on the context, request came from.
+//!
+//!Handler is called on the task, driving pool, hence to process requests on multiple threads, run
+//!separate pool on each of them.
+//!
+//!## Usage
+//!
+//!```rust
+//!use nng_c::{Message, Socket};
+//!use nng_c::pool::WorkerPool;
+//!use nng_c::utils::block_on;
+//!
+//!const ADDR: &str = "inproc://pool-example\0";
+//!
+//!let server = Socket::rep0().expect("create server");
+//!server.listen(ADDR.into()).expect("listen");
+//!let client = Socket::req0().expect("create client");
+//!client.connect(ADDR.into()).expect("connect");
+//!
+//!std::thread::scope(|scope| {
+//!    scope.spawn(|| {
+//!        let mut pool = WorkerPool::new(&server, 8).expect("create pool");
+//!        block_on(pool.serve(|req| req)).expect("run executor")
+//!    });
+//!
+//!    let mut req = Message::new().expect("create message");
+//!    req.append(b"ping").expect("append");
+//!    let resp = block_on(client.request(req)).expect("run executor").expect("get response");
+//!    assert_eq!(resp.body(), b"ping");
+//!
+//!    server.close();
+//!});
+//!```
+
+use crate::{ErrorCode, NngError};
+use crate::context::Context;
+use crate::error::error;
+use crate::fallible::try_vec;
+use crate::msg::Message;
+use crate::socket::{Socket, FutureReq, FutureResp};
+use crate::sys;
+
+use core::{fmt, future, task};
+use core::future::Future;
+use core::pin::Pin;
+
+use alloc::vec::Vec;
+
+enum Step {
+    Idle,
+    Recv(FutureResp),
+    Send(FutureReq),
+    Done,
+}
+
+struct Worker<'a> {
+    step: Step,
+    ctx: &'a Context,
+}
+
+impl Worker<'_> {
+    //Drives worker until it waits for operation, returning `Ready` once socket is closed or on error
+    fn poll<F: FnMut(Message) -> Message>(&mut self, handler: &mut F, ctx: &mut task::Context<'_>) -> task::Poll<Result<(), ErrorCode>> {
+        loop {
+            match &mut self.step {
+                Step::Idle => match self.ctx.recv_msg_async() {
+                    Ok(fut) => self.step = Step::Recv(fut),
+                    Err(error) => return self.complete(error),
+                },
+                Step::Recv(fut) => match Pin::new(fut).poll(ctx) {
+                    task::Poll::Pending => return task::Poll::Pending,
+                    task::Poll::Ready(Ok(Some(req))) => match self.ctx.send_msg_async(handler(req)) {
+                        Ok(fut) => self.step = Step::Send(fut),
+                        Err(error) => return self.complete(error),
+                    },
+                    task::Poll::Ready(Ok(None)) => return self.complete(error(sys::nng_errno_enum::NNG_EINTERNAL)),
+                    task::Poll::Ready(Err(error)) => return self.complete(error),
+                },
+                Step::Send(fut) => match Pin::new(fut).poll(ctx) {
+                    task::Poll::Pending => return task::Poll::Pending,
+                    task::Poll::Ready(Ok(())) => self.step = Step::Idle,
+                    task::Poll::Ready(Err((_, error))) => return self.complete(error),
+                },
+                Step::Done => return task::Poll::Ready(Ok(())),
+            }
+        }
+    }
+
+    #[inline]
+    fn complete(&mut self, error: ErrorCode) -> task::Poll<Result<(), ErrorCode>> {
+        self.step = Step::Done;
+        if error.is_closed() {
+            task::Poll::Ready(Ok(()))
+        } else {
+            task::Poll::Ready(Err(error))
+        }
+    }
+}
+
+///Pool of contexts, serving requests on rep0 socket concurrently.
+pub struct WorkerPool {
+    workers: Vec<Context>,
+}
+
+impl WorkerPool {
+    ///Creates pool of `size` workers on `socket`, which must be rep0 socket.
+    ///
+    ///Returns `NNG_EINVAL` if `size` is zero.
+    pub fn new(socket: &Socket, size: usize) -> Result<Self, ErrorCode> {
+        if size == 0 {
+            return Err(error(sys::nng_errno_enum::NNG_EINVAL));
+        }
+
+        let mut workers = try_vec(size)?;
+        for _ in 0..size {
+            workers.push(Context::new(socket)?);
+        }
+
+        Ok(Self {
+            workers
+        })
+    }
+
+    #[inline(always)]
+    ///Returns number of workers
+    pub fn size(&self) -> usize {
+        self.workers.len()
+    }
+
+    ///Serves requests, replying to each with result of `handler`.
+    ///
+    ///Completes with `Ok` once socket is closed, otherwise with first unexpected error, cancelling
+    ///operations of all workers.
+    pub async fn serve<F: FnMut(Message) -> Message>(&mut self, mut handler: F) -> Result<(), ErrorCode> {
+        let mut workers = try_vec(self.workers.len())?;
+        workers.extend(self.workers.iter().map(|ctx| Worker {
+            step: Step::Idle,
+            ctx,
+        }));
+
+        future::poll_fn(|ctx| {
+            let mut is_done = true;
+            for worker in workers.iter_mut() {
+                match worker.poll(&mut handler, ctx) {
+                    task::Poll::Pending => is_done = false,
+                    task::Poll::Ready(Ok(())) => (),
+                    task::Poll::Ready(Err(error)) => return task::Poll::Ready(Err(error)),
+                }
+            }
+
+            if is_done {
+                task::Poll::Ready(Ok(()))
+            } else {
+                task::Poll::Pending
+            }
+        }).await
+    }
+}
+
+impl fmt::Debug for WorkerPool {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("WorkerPool").field("workers", &self.workers).finish()
+    }
+}
@@ -0,0 +1,144 @@
+//!Postcard messages
+//!
+//!Encodes values using [postcard](https://crates.io/crates/postcard) format straight into message
+//!body and decodes them back.
+//!
+//!Unlike [JSON](crate::json), format is compact and requires only `alloc`, so that `no_std`
+//!embedded peers and `std` services can exchange the same typed messages.
+//!Format is not self-describing, hence both peers must agree on the type of the message.
+//!
+//![Socket] and [Context] get `send_postcard` and `recv_postcard` methods, reporting which step failed via [PostcardError].
+//!
+//!Requires feature `postcard`
+//!
+//!## Usage
+//!
+//!```rust
+//!use nng_c::Socket;
+//!
+//!#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+//!struct Reading {
+//!    sensor: u16,
+//!    value: i32,
+//!}
+//!
+//!const ADDR: &str = "inproc://postcard-example\0";
+//!
+//!let server = Socket::pair0().expect("create server");
+//!server.listen(ADDR.into()).expect("listen");
+//!let client = Socket::pair0().expect("create client");
+//!client.connect(ADDR.into()).expect("connect");
+//!
+//!let reading = Reading { sensor: 1, value: -20 };
+//!client.send_postcard(&reading).expect("send");
+//!assert_eq!(server.recv_postcard::<Reading>().expect("receive"), reading);
+//!```
+
+use crate::ErrorCode;
+use crate::context::Context;
+use crate::error::error;
+use crate::msg::Message;
+use crate::socket::Socket;
+use crate::sys;
+
+use core::fmt;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+#[derive(Debug)]
+///Error of sending or receiving postcard message
+pub enum PostcardError {
+    ///Socket failed to send or receive message
+    Socket(ErrorCode),
+    ///Value cannot be encoded
+    Encode(::postcard::Error),
+    ///Message body is not valid encoding of expected type
+    Decode(::postcard::Error),
+}
+
+impl PostcardError {
+    #[inline(always)]
+    ///Returns whether error is caused by content of the message, rather than socket failure
+    pub const fn is_codec(&self) -> bool {
+        !matches!(self, Self::Socket(_))
+    }
+}
+
+impl From<PostcardError> for ErrorCode {
+    #[inline]
+    fn from(postcard: PostcardError) -> Self {
+        match postcard {
+            PostcardError::Socket(code) => code,
+            PostcardError::Encode(_) => error(sys::nng_errno_enum::NNG_EINVAL),
+            PostcardError::Decode(_) => error(sys::nng_errno_enum::NNG_EPROTO),
+        }
+    }
+}
+
+impl fmt::Display for PostcardError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Socket(code) => fmt.write_fmt(format_args!("socket failure: {}", code)),
+            Self::Encode(error) => fmt.write_fmt(format_args!("unable to encode postcard: {}", error)),
+            Self::Decode(error) => fmt.write_fmt(format_args!("unable to decode postcard message body: {}", error)),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PostcardError {}
+
+///Creates message with `value` encoded as postcard body
+pub fn encode<T: Serialize + ?Sized>(value: &T) -> Result<Message, PostcardError> {
+    let mut msg = match Message::new() {
+        Some(msg) => msg,
+        None => return Err(PostcardError::Socket(error(sys::nng_errno_enum::NNG_ENOMEM))),
+    };
+    let body = ::postcard::to_allocvec(value).map_err(PostcardError::Encode)?;
+    msg.append(&body).map_err(PostcardError::Socket)?;
+    Ok(msg)
+}
+
+///Decodes body of the `msg` as postcard.
+///
+///Trailing bytes are rejected, as they indicate that peer sent message of different type.
+pub fn decode<T: DeserializeOwned>(msg: &Message) -> Result<T, PostcardError> {
+    match ::postcard::take_from_bytes(msg.body()) {
+        Ok((value, [])) => Ok(value),
+        Ok(_) => Err(PostcardError::Decode(::postcard::Error::DeserializeBadEncoding)),
+        Err(error) => Err(PostcardError::Decode(error)),
+    }
+}
+
+impl Socket {
+    #[inline]
+    ///Sends `value` encoded as postcard
+    pub fn send_postcard<T: Serialize + ?Sized>(&self, value: &T) -> Result<(), PostcardError> {
+        let msg = encode(value)?;
+        self.send_msg(msg).map_err(|(_, error)| PostcardError::Socket(error.into()))
+    }
+
+    #[inline]
+    ///Receives message, decoding its body as postcard
+    pub fn recv_postcard<T: DeserializeOwned>(&self) -> Result<T, PostcardError> {
+        let msg = self.recv_msg().map_err(|error| PostcardError::Socket(error.into()))?;
+        decode(&msg)
+    }
+}
+
+impl Context {
+    #[inline]
+    ///Sends `value` encoded as postcard
+    pub fn send_postcard<T: Serialize + ?Sized>(&self, value: &T) -> Result<(), PostcardError> {
+        let msg = encode(value)?;
+        self.send_msg(msg).map_err(|(_, error)| PostcardError::Socket(error))
+    }
+
+    #[inline]
+    ///Receives message, decoding its body as postcard
+    pub fn recv_postcard<T: DeserializeOwned>(&self) -> Result<T, PostcardError> {
+        let msg = self.recv_msg().map_err(PostcardError::Socket)?;
+        decode(&msg)
+    }
+}
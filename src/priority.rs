@@ -0,0 +1,196 @@
+//!Prioritized sending
+//!
+//!nng queues outgoing messages in strict FIFO order, so urgent message has to wait for all bulk
+//!traffic queued before it.
+//!
+//![Prioritized] keeps separate lane per [Priority] in front of the socket, always sending
+//!message from the highest non-empty lane first, while messages within the same lane retain their order.
+//!
+//!Messages are handed to the socket without waiting, hence socket's own send buffer should be small
+//!(default for most protocols), otherwise messages are queued by nng in FIFO order regardless of priority.
+//!
+//!## Usage
+//!
+//!```rust
+//!use nng_c::{Message, Socket};
+//!use nng_c::priority::{Priority, Prioritized};
+//!
+//!let socket = Socket::pair0().expect("create socket");
+//!let queue = Prioritized::new(&socket).expect("create queue");
+//!
+//!let mut msg = Message::new().expect("create message");
+//!msg.append(b"bulk").expect("append");
+//!queue.send(msg, Priority::Low).expect("enqueue");
+//!
+//!let mut msg = Message::new().expect("create message");
+//!msg.append(b"urgent").expect("append");
+//!queue.send(msg, Priority::High).expect("enqueue");
+//!
+//!//No peer is connected, so both messages are waiting, urgent one first
+//!assert_eq!(queue.len(), 2);
+//!```
+
+use crate::ErrorCode;
+use crate::error::NngError;
+use crate::msg::Message;
+use crate::socket::Socket;
+use crate::utils::sync::Mutex;
+
+use core::fmt;
+
+use alloc::collections::VecDeque;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+///Priority of the message
+pub enum Priority {
+    ///Sent before any other message
+    High,
+    ///Default priority
+    Normal,
+    ///Bulk traffic, sent only when there are no other messages
+    Low,
+}
+
+impl Priority {
+    const LANES: usize = 3;
+
+    #[inline(always)]
+    const fn lane(self) -> usize {
+        self as usize
+    }
+}
+
+impl Default for Priority {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::Normal
+    }
+}
+
+type Lanes = [VecDeque<Message>; Priority::LANES];
+
+struct Queue(Lanes);
+
+impl Queue {
+    #[inline]
+    fn pop(&mut self) -> Option<(Message, usize)> {
+        self.0.iter_mut().enumerate().find_map(|(lane, queue)| queue.pop_front().map(|msg| (msg, lane)))
+    }
+}
+
+///Sending socket with priority lanes
+pub struct Prioritized<'a> {
+    socket: &'a Socket,
+    lanes: Mutex<Queue>,
+}
+
+impl<'a> Prioritized<'a> {
+    #[inline]
+    ///Creates new instance over `socket`
+    ///
+    ///Returns error if unable to allocate lock.
+    pub fn new(socket: &'a Socket) -> Result<Self, ErrorCode> {
+        Ok(Self {
+            socket,
+            lanes: Mutex::new(Queue(Default::default()))?,
+        })
+    }
+
+    #[inline(always)]
+    ///Returns underlying socket
+    pub fn socket(&self) -> &'a Socket {
+        self.socket
+    }
+
+    #[inline]
+    ///Returns total number of queued messages
+    pub fn len(&self) -> usize {
+        self.lanes.lock().0.iter().map(VecDeque::len).sum()
+    }
+
+    #[inline]
+    ///Returns whether there are no queued messages
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[inline]
+    ///Returns number of messages queued with `priority`
+    pub fn queued(&self, priority: Priority) -> usize {
+        self.lanes.lock().0[priority.lane()].len()
+    }
+
+    #[inline]
+    ///Queues `msg` with `priority` without sending it
+    pub fn enqueue(&self, msg: Message, priority: Priority) {
+        self.lanes.lock().0[priority.lane()].push_back(msg);
+    }
+
+    #[inline]
+    ///Queues `msg` with `priority`, then sends as many queued messages as possible without waiting.
+    ///
+    ///Refer to [flush](Self::flush) for details.
+    pub fn send(&self, msg: Message, priority: Priority) -> Result<usize, ErrorCode> {
+        self.enqueue(msg, priority);
+        self.flush()
+    }
+
+    ///Sends queued messages in order of priority without waiting, returning number of sent messages.
+    ///
+    ///Stops once socket cannot accept message, leaving it at the front of its lane.
+    ///Returns error if socket fails for any other reason than lack of space.
+    pub fn flush(&self) -> Result<usize, ErrorCode> {
+        let mut lanes = self.lanes.lock();
+        let mut sent = 0;
+        while let Some((msg, lane)) = lanes.pop() {
+            match self.socket.try_send_msg(msg) {
+                Ok(()) => sent += 1,
+                Err((msg, error)) => {
+                    lanes.0[lane].push_front(msg);
+                    return if error.is_would_block() {
+                        Ok(sent)
+                    } else {
+                        Err(error.into())
+                    };
+                }
+            }
+        }
+
+        Ok(sent)
+    }
+
+    ///Sends single message with the highest priority, waiting for socket to accept it.
+    ///
+    ///Lock is not held while waiting, allowing other threads to enqueue messages, which makes it suitable
+    ///for dedicated sending thread.
+    ///
+    ///Returns priority of sent message or `None` if there is no queued message.
+    ///On error, message is returned at the front of its lane.
+    pub fn send_next(&self) -> Result<Option<Priority>, ErrorCode> {
+        const PRIORITIES: [Priority; Priority::LANES] = [Priority::High, Priority::Normal, Priority::Low];
+
+        let (msg, lane) = match self.lanes.lock().pop() {
+            Some(next) => next,
+            None => return Ok(None),
+        };
+
+        match self.socket.send_msg(msg) {
+            Ok(()) => Ok(Some(PRIORITIES[lane])),
+            Err((msg, error)) => {
+                self.lanes.lock().0[lane].push_front(msg);
+                Err(error.into())
+            }
+        }
+    }
+}
+
+impl fmt::Debug for Prioritized<'_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let lanes = self.lanes.lock();
+        fmt.debug_struct("Prioritized").field("socket", &self.socket)
+                                       .field("high", &lanes.0[Priority::High.lane()].len())
+                                       .field("normal", &lanes.0[Priority::Normal.lane()].len())
+                                       .field("low", &lanes.0[Priority::Low.lane()].len())
+                                       .finish()
+    }
+}
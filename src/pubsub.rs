@@ -0,0 +1,738 @@
+//!Publish/subscribe helpers
+//!
+//!nng's pub/sub protocol has no notion of topic: subscriber simply filters messages which body
+//!starts with one of subscribed prefixes.
+//!
+//!Publisher is expected to write topic in front of payload via [message], while subscriber can
+//!use [Topics] to subscribe and split received body back into topic and payload.
+//!Alternatively, [framed] messages terminate topic with [TOPIC_DELIMITER], so that topic
+//!`weather` never matches `weather.eu`. Such messages are published via [Socket::publish],
+//!received via [Socket::subscribe_topic] and split by [Message::split_topic].
+//!
+//![subscribe_all] and [unsubscribe_all] change multiple subscriptions at once, reverting them if any topic fails.
+//!
+//![Subscriber] keeps track of socket's subscriptions, exposing current set.
+//!Subscriptions of sub0 socket are local to the socket and filter messages of every pipe, hence
+//!they stay in effect after reconnect without being re-applied.
+//!It also supports wildcard [Pattern]s, subscribing socket to their literal prefix and filtering
+//!the rest locally.
+//!
+//![LastValueCache] relays messages from publishers to subscribers, recording the most recent
+//!message of each topic. New subscribers do not receive automatic replay of cached messages:
+//!late joiner requests current state as snapshot over separate req0/rep0 socket pair instead.
+
+use crate::ErrorCode;
+use crate::error::error;
+use crate::fallible::try_vec;
+use crate::msg::Message;
+use crate::options::{Options, Subscribe, Unsubscribe};
+use crate::socket::Socket;
+use crate::utils::sync::Mutex;
+use crate::sys;
+
+use core::fmt;
+
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+///Creates message with `topic` followed by `payload`, to be sent by pub0 socket
+pub fn message(topic: &[u8], payload: &[u8]) -> Result<Message, ErrorCode> {
+    let mut msg = match Message::new() {
+        Some(msg) => msg,
+        None => return Err(error(sys::nng_errno_enum::NNG_ENOMEM)),
+    };
+    msg.reserve(topic.len() + payload.len())?;
+    msg.append(topic)?;
+    msg.append(payload)?;
+    Ok(msg)
+}
+
+///Delimiter, terminating topic of the framed message
+pub const TOPIC_DELIMITER: u8 = 0;
+
+#[inline]
+fn check_topic(topic: &[u8]) -> Result<(), ErrorCode> {
+    if topic.contains(&TOPIC_DELIMITER) {
+        Err(error(sys::nng_errno_enum::NNG_EINVAL))
+    } else {
+        Ok(())
+    }
+}
+
+///Creates framed message with `topic`, terminated by [TOPIC_DELIMITER], followed by `payload`
+///
+///Unlike plain prefix, framed topic is never mistaken for another topic, starting with it.
+///Returns `NNG_EINVAL` if `topic` contains delimiter.
+pub fn framed(topic: &[u8], payload: &[u8]) -> Result<Message, ErrorCode> {
+    check_topic(topic)?;
+    let mut msg = match Message::new() {
+        Some(msg) => msg,
+        None => return Err(error(sys::nng_errno_enum::NNG_ENOMEM)),
+    };
+    msg.reserve(topic.len() + 1 + payload.len())?;
+    msg.append(topic)?;
+    msg.append(&[TOPIC_DELIMITER])?;
+    msg.append(payload)?;
+    Ok(msg)
+}
+
+///Returns subscription prefix, matching framed messages of exactly `topic`
+///
+///Returns `NNG_EINVAL` if `topic` contains delimiter.
+pub fn framed_prefix(topic: &[u8]) -> Result<Vec<u8>, ErrorCode> {
+    check_topic(topic)?;
+    let mut prefix = try_vec(topic.len() + 1)?;
+    prefix.extend_from_slice(topic);
+    prefix.push(TOPIC_DELIMITER);
+    Ok(prefix)
+}
+
+#[inline]
+///Splits body of framed message into topic and payload
+///
+///Returns `None` if `body` has no delimiter.
+pub fn split_framed(body: &[u8]) -> Option<(&[u8], &[u8])> {
+    body.iter().position(|byte| *byte == TOPIC_DELIMITER).map(|idx| (&body[..idx], &body[idx + 1..]))
+}
+
+#[derive(Clone, Debug, Default)]
+///Set of topics, subscriber is interested in
+///
+///Can be applied to sub0 socket as options to subscribe to all topics at once.
+pub struct Topics {
+    topics: Vec<Vec<u8>>,
+}
+
+impl Topics {
+    #[inline]
+    ///Creates empty set
+    pub const fn new() -> Self {
+        Self {
+            topics: Vec::new(),
+        }
+    }
+
+    #[inline]
+    ///Adds `topic` to the set, if not present yet
+    pub fn topic(mut self, topic: &[u8]) -> Self {
+        self.add(topic);
+        self
+    }
+
+    ///Adds `topic` to the set, returning `false` if it is already present
+    pub fn add(&mut self, topic: &[u8]) -> bool {
+        if self.contains(topic) {
+            false
+        } else {
+            self.topics.push(topic.to_vec());
+            true
+        }
+    }
+
+    ///Removes `topic` from the set, returning `false` if it is not present
+    pub fn remove(&mut self, topic: &[u8]) -> bool {
+        match self.topics.iter().position(|existing| existing == topic) {
+            Some(idx) => {
+                self.topics.swap_remove(idx);
+                true
+            },
+            None => false,
+        }
+    }
+
+    #[inline]
+    ///Returns whether `topic` is present in the set
+    pub fn contains(&self, topic: &[u8]) -> bool {
+        self.topics.iter().any(|existing| existing == topic)
+    }
+
+    #[inline]
+    ///Returns iterator over topics
+    pub fn iter(&self) -> impl Iterator<Item = &[u8]> {
+        self.topics.iter().map(|topic| topic.as_slice())
+    }
+
+    ///Splits `body` into matched topic and payload.
+    ///
+    ///If multiple topics match, the longest one is selected.
+    ///Returns `None` if no topic matches, which is only possible if socket is subscribed to
+    ///topics not present in this set.
+    pub fn split<'a>(&'a self, body: &'a [u8]) -> Option<(&'a [u8], &'a [u8])> {
+        self.topics.iter()
+                   .filter(|topic| body.starts_with(topic))
+                   .max_by_key(|topic| topic.len())
+                   .map(|topic| (topic.as_slice(), &body[topic.len()..]))
+    }
+}
+
+impl Options<Socket> for Topics {
+    #[inline]
+    fn apply(&self, target: &Socket) -> Result<(), ErrorCode> {
+        for topic in self.iter() {
+            Subscribe(topic).apply(target)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+///Error of changing subscriptions in batch, identifying topic which failed
+pub struct TopicError {
+    ///Index of the topic in the batch
+    pub index: usize,
+    ///Error of the topic
+    pub error: ErrorCode,
+}
+
+impl fmt::Display for TopicError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_fmt(format_args!("topic #{} failed: {}", self.index, self.error))
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TopicError {}
+
+///Subscribes sub0 socket or context to all `topics`
+///
+///If any topic fails, subscriptions made by this call are reverted before returning error.
+///Note that nng does not count subscriptions, hence reverting also removes topic, that was
+///subscribed before this call.
+pub fn subscribe_all<T>(target: &T, topics: &[&[u8]]) -> Result<(), TopicError> where for<'a> Subscribe<'a>: Options<T>, for<'a> Unsubscribe<'a>: Options<T> {
+    for (index, &topic) in topics.iter().enumerate() {
+        if let Err(error) = Subscribe(topic).apply(target) {
+            for &topic in topics[..index].iter() {
+                let _ = Unsubscribe(topic).apply(target);
+            }
+            return Err(TopicError {
+                index,
+                error,
+            });
+        }
+    }
+    Ok(())
+}
+
+///Unsubscribes sub0 socket or context from all `topics`
+///
+///If any topic fails (i.e. it is not subscribed), topics unsubscribed by this call are subscribed
+///again before returning error.
+pub fn unsubscribe_all<T>(target: &T, topics: &[&[u8]]) -> Result<(), TopicError> where for<'a> Subscribe<'a>: Options<T>, for<'a> Unsubscribe<'a>: Options<T> {
+    for (index, &topic) in topics.iter().enumerate() {
+        if let Err(error) = Unsubscribe(topic).apply(target) {
+            for &topic in topics[..index].iter() {
+                let _ = Subscribe(topic).apply(target);
+            }
+            return Err(TopicError {
+                index,
+                error,
+            });
+        }
+    }
+    Ok(())
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+///Topic pattern with wildcards
+///
+///Pattern consists of segments, delimited by separator (`.` by default):
+///
+///- `*` matches exactly one non-empty segment;
+///- `#` matches any remaining content, hence it must be the last segment;
+///- Any other segment matches itself.
+///
+///Like sub0 subscriptions, pattern matches beginning of the message body, with the last literal
+///segment matched as prefix. I.e. `weather.*.temp` matches `weather.eu.temp:25`, splitting it into
+///topic `weather.eu.temp` and payload `:25`.
+///
+///sub0 only supports prefix matching, therefore socket is subscribed to the [prefix](Self::prefix)
+///preceding the first wildcard, while the rest of the pattern is matched locally.
+pub struct Pattern {
+    pattern: Vec<u8>,
+    separator: u8,
+}
+
+impl Pattern {
+    ///Wildcard matching single segment
+    pub const SEGMENT: &'static [u8] = b"*";
+    ///Wildcard matching remaining content
+    pub const REST: &'static [u8] = b"#";
+
+    #[inline]
+    ///Creates new pattern with `.` as separator
+    pub fn new(pattern: &[u8]) -> Self {
+        Self {
+            pattern: pattern.to_vec(),
+            separator: b'.',
+        }
+    }
+
+    #[inline]
+    ///Sets separator of segments
+    pub fn with_separator(mut self, separator: u8) -> Self {
+        self.separator = separator;
+        self
+    }
+
+    #[inline(always)]
+    ///Returns pattern as bytes
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.pattern
+    }
+
+    #[inline]
+    fn segments(&self) -> impl Iterator<Item = &[u8]> {
+        let separator = self.separator;
+        self.pattern.split(move |byte| *byte == separator)
+    }
+
+    ///Returns literal prefix of the pattern, preceding the first wildcard
+    pub fn prefix(&self) -> &[u8] {
+        let mut len = 0;
+        for segment in self.segments() {
+            if segment == Self::SEGMENT || segment == Self::REST {
+                return &self.pattern[..len];
+            }
+            len += segment.len() + 1;
+        }
+        &self.pattern
+    }
+
+    #[inline]
+    ///Returns whether pattern has any wildcard
+    pub fn is_wildcard(&self) -> bool {
+        self.prefix().len() != self.pattern.len()
+    }
+
+    ///Splits `body` into matched topic and payload, returning `None` if pattern does not match
+    pub fn split<'a>(&self, body: &'a [u8]) -> Option<(&'a [u8], &'a [u8])> {
+        let mut segments = self.segments().peekable();
+        let mut pos = 0;
+        while let Some(segment) = segments.next() {
+            let is_last = segments.peek().is_none();
+            let rest = &body[pos..];
+            if segment == Self::REST {
+                return if is_last {
+                    Some((body, &[]))
+                } else {
+                    None
+                };
+            }
+
+            let len = if segment == Self::SEGMENT {
+                match rest.iter().position(|byte| *byte == self.separator).unwrap_or(rest.len()) {
+                    0 => return None,
+                    len => len,
+                }
+            } else if rest.starts_with(segment) {
+                segment.len()
+            } else {
+                return None;
+            };
+
+            pos += len;
+            if !is_last {
+                //Separator must follow non-last segment
+                if body.get(pos) != Some(&self.separator) {
+                    return None;
+                }
+                pos += 1;
+            }
+        }
+
+        Some(body.split_at(pos))
+    }
+
+    #[inline]
+    ///Returns whether pattern matches beginning of the `body`
+    pub fn matches(&self, body: &[u8]) -> bool {
+        self.split(body).is_some()
+    }
+}
+
+fn set_topic(socket: sys::nng_socket, name: &[u8], topic: &[u8]) -> Result<(), ErrorCode> {
+    let result = unsafe {
+        sys::nng_socket_set(socket, name.as_ptr() as _, topic.as_ptr() as _, topic.len())
+    };
+
+    match result {
+        0 => Ok(()),
+        code => Err(error(code)),
+    }
+}
+
+struct State {
+    topics: Topics,
+    patterns: Vec<Pattern>,
+}
+
+impl State {
+    //Returns whether `topic` is subscribed on socket, either directly or as prefix of pattern
+    fn is_subscribed(&self, topic: &[u8]) -> bool {
+        self.topics.contains(topic) || self.patterns.iter().any(|pattern| pattern.prefix() == topic)
+    }
+
+    fn accepts(&self, body: &[u8]) -> bool {
+        self.topics.iter().any(|topic| body.starts_with(topic)) || self.patterns.iter().any(|pattern| pattern.matches(body))
+    }
+}
+
+fn subscribe_state(socket: sys::nng_socket, state: &State) -> Result<(), ErrorCode> {
+    for topic in state.topics.iter().chain(state.patterns.iter().map(Pattern::prefix)) {
+        set_topic(socket, sys::NNG_OPT_SUB_SUBSCRIBE, topic)?;
+    }
+    Ok(())
+}
+
+///sub0 socket wrapper, remembering its subscriptions
+///
+///Subscriptions are meant to be managed exclusively via wrapper, otherwise they are not tracked.
+///They persist across reconnects, as sub0 filters messages locally regardless of pipe.
+///
+///Besides plain topics, subscriber supports wildcard [patterns](Pattern), which are matched locally
+///by [recv_msg](Self::recv_msg).
+///
+///## Usage
+///
+///```rust
+///use nng_c::Socket;
+///use nng_c::pubsub::{Pattern, Subscriber};
+///
+///let socket = Socket::sub0().expect("create socket");
+///let subscriber = Subscriber::new(&socket).expect("create subscriber");
+///
+///subscriber.subscribe(b"weather.").expect("subscribe");
+///assert!(subscriber.topics().contains(b"weather."));
+///
+///subscriber.subscribe_pattern(Pattern::new(b"news.*.sports")).expect("subscribe");
+///assert!(subscriber.accepts(b"news.eu.sports:score"));
+///assert!(!subscriber.accepts(b"news.eu.politics:vote"));
+///```
+pub struct Subscriber<'a> {
+    socket: &'a Socket,
+    state: Mutex<State>,
+}
+
+impl<'a> Subscriber<'a> {
+    #[inline]
+    ///Creates new wrapper over `socket` without subscriptions
+    ///
+    ///Returns error if unable to allocate lock.
+    pub fn new(socket: &'a Socket) -> Result<Self, ErrorCode> {
+        Ok(Self {
+            socket,
+            state: Mutex::new(State {
+                topics: Topics::new(),
+                patterns: Vec::new(),
+            })?,
+        })
+    }
+
+    #[inline(always)]
+    ///Returns underlying socket
+    pub fn socket(&self) -> &'a Socket {
+        self.socket
+    }
+
+    #[inline]
+    ///Returns current set of subscribed topics, excluding patterns
+    pub fn topics(&self) -> Topics {
+        self.state.lock().topics.clone()
+    }
+
+    #[inline]
+    ///Returns current set of subscribed patterns
+    pub fn patterns(&self) -> Vec<Pattern> {
+        self.state.lock().patterns.clone()
+    }
+
+    ///Subscribes to `topic`, returning `false` if already subscribed
+    pub fn subscribe(&self, topic: &[u8]) -> Result<bool, ErrorCode> {
+        let mut state = self.state.lock();
+        set_topic(**self.socket, sys::NNG_OPT_SUB_SUBSCRIBE, topic)?;
+        Ok(state.topics.add(topic))
+    }
+
+    ///Unsubscribes from `topic`, returning `false` if not subscribed
+    pub fn unsubscribe(&self, topic: &[u8]) -> Result<bool, ErrorCode> {
+        let mut state = self.state.lock();
+        if !state.topics.remove(topic) {
+            return Ok(false);
+        }
+        if !state.is_subscribed(topic) {
+            if let Err(error) = set_topic(**self.socket, sys::NNG_OPT_SUB_UNSUBSCRIBE, topic) {
+                state.topics.add(topic);
+                return Err(error);
+            }
+        }
+        Ok(true)
+    }
+
+    ///Subscribes to `pattern`, returning `false` if already subscribed
+    pub fn subscribe_pattern(&self, pattern: Pattern) -> Result<bool, ErrorCode> {
+        let mut state = self.state.lock();
+        if state.patterns.contains(&pattern) {
+            return Ok(false);
+        }
+        set_topic(**self.socket, sys::NNG_OPT_SUB_SUBSCRIBE, pattern.prefix())?;
+        state.patterns.push(pattern);
+        Ok(true)
+    }
+
+    ///Unsubscribes from `pattern`, returning `false` if not subscribed
+    pub fn unsubscribe_pattern(&self, pattern: &Pattern) -> Result<bool, ErrorCode> {
+        let mut state = self.state.lock();
+        let pattern = match state.patterns.iter().position(|existing| existing == pattern) {
+            Some(idx) => state.patterns.swap_remove(idx),
+            None => return Ok(false),
+        };
+        if !state.is_subscribed(pattern.prefix()) {
+            if let Err(error) = set_topic(**self.socket, sys::NNG_OPT_SUB_UNSUBSCRIBE, pattern.prefix()) {
+                state.patterns.push(pattern);
+                return Err(error);
+            }
+        }
+        Ok(true)
+    }
+
+    #[inline]
+    ///Returns whether message `body` matches any subscribed topic or pattern
+    pub fn accepts(&self, body: &[u8]) -> bool {
+        self.state.lock().accepts(body)
+    }
+
+    ///Receives message, matching subscribed topic or pattern.
+    ///
+    ///Messages which only match prefix of a pattern are discarded, hence receive timeout applies to
+    ///every attempt rather than whole operation.
+    pub fn recv_msg(&self) -> Result<Message, ErrorCode> {
+        loop {
+            let msg = self.socket.recv_msg()?;
+            if self.accepts(msg.body()) {
+                break Ok(msg);
+            }
+        }
+    }
+
+    #[inline]
+    ///Re-applies all subscriptions to the socket
+    pub fn resubscribe(&self) -> Result<(), ErrorCode> {
+        subscribe_state(**self.socket, &self.state.lock())
+    }
+}
+
+impl fmt::Debug for Subscriber<'_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.state.lock();
+        fmt.debug_struct("Subscriber").field("socket", &self.socket)
+                                      .field("topics", &state.topics)
+                                      .field("patterns", &state.patterns)
+                                      .finish()
+    }
+}
+
+//Topic with body of its last message
+type Cache = Mutex<Vec<(Vec<u8>, Vec<u8>)>>;
+
+#[derive(Clone)]
+///Last value cache relay
+///
+///Relay forwards messages from publishers to subscribers, recording the most recent message of
+///each topic, which is served as snapshot on request of newly connected subscribers.
+///Topic of the message is the longest of configured [Topics], matching its body, while messages
+///matching no topic are forwarded without being cached.
+///
+///Relay is meant to be run as device between raw sockets:
+///
+///- backend sub0 socket, connected to publishers;
+///- frontend pub0 socket, listening for subscribers.
+///
+///pub0 cannot send message to single subscriber, hence late subscriber requests current state
+///over separate rep0 socket, served by [serve_snapshots](Self::serve_snapshots), after connecting to
+///the frontend (see [request_snapshot](Self::request_snapshot)).
+///Subscriber should connect first, so that no update is missed between snapshot and live stream,
+///at the cost of possibly receiving the same value twice.
+///
+///Relay is cheap to clone, with all clones sharing the same cache.
+///
+///## Usage
+///
+///```rust
+///use nng_c::Socket;
+///use nng_c::pubsub::{LastValueCache, Topics};
+///
+///let backend = Socket::sub0_raw().expect("create backend");
+///backend.listen("inproc://lvc-example-publishers".into()).expect("listen");
+///let frontend = Socket::pub0_raw().expect("create frontend");
+///frontend.listen("inproc://lvc-example-subscribers".into()).expect("listen");
+///let snapshots = Socket::rep0().expect("create snapshot socket");
+///snapshots.listen("inproc://lvc-example-snapshots".into()).expect("listen");
+///
+///let cache = LastValueCache::new(Topics::new().topic(b"weather.eu").topic(b"weather.us")).expect("create cache");
+///
+///std::thread::scope(|scope| {
+///    let relay = scope.spawn(|| cache.run(&backend, &frontend));
+///    let server = scope.spawn(|| cache.serve_snapshots(&snapshots));
+///
+///    let client = Socket::req0().expect("create client");
+///    client.connect("inproc://lvc-example-snapshots".into()).expect("connect");
+///    let state = LastValueCache::request_snapshot(&client, b"weather.").expect("request snapshot");
+///    assert!(state.is_empty());
+///
+///    //Relay stops once socket is closed
+///    backend.close();
+///    snapshots.close();
+///    relay.join().expect("join relay");
+///    server.join().expect("join server");
+///});
+///```
+pub struct LastValueCache {
+    topics: Arc<Topics>,
+    cache: Arc<Cache>,
+}
+
+impl LastValueCache {
+    #[inline]
+    ///Creates new relay, caching messages of `topics`
+    ///
+    ///Returns error if unable to allocate lock.
+    pub fn new(topics: Topics) -> Result<Self, ErrorCode> {
+        Ok(Self {
+            topics: Arc::new(topics),
+            cache: Arc::new(Mutex::new(Vec::new())?),
+        })
+    }
+
+    #[inline(always)]
+    ///Returns topics of cached messages
+    pub fn topics(&self) -> &Topics {
+        &self.topics
+    }
+
+    #[inline]
+    ///Returns number of cached messages
+    pub fn len(&self) -> usize {
+        self.cache.lock().len()
+    }
+
+    #[inline]
+    ///Returns whether there are no cached messages
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    ///Returns body of the last message of `topic`, if any
+    pub fn get(&self, topic: &[u8]) -> Option<Vec<u8>> {
+        self.cache.lock().iter().find(|(cached, _)| cached == topic).map(|(_, body)| body.clone())
+    }
+
+    ///Records `msg` as the last message of its topic, returning `false` if it matches no topic
+    pub fn record(&self, msg: &Message) -> bool {
+        let body = msg.body();
+        let topic = match self.topics.split(body) {
+            Some((topic, _)) => topic,
+            None => return false,
+        };
+
+        let mut cache = self.cache.lock();
+        match cache.iter_mut().find(|(cached, _)| cached == topic) {
+            Some((_, last)) => {
+                last.clear();
+                last.extend_from_slice(body);
+            },
+            None => cache.push((topic.to_vec(), body.to_vec())),
+        }
+        true
+    }
+
+    #[inline]
+    ///Removes all cached messages
+    pub fn clear(&self) {
+        self.cache.lock().clear();
+    }
+
+    ///Creates snapshot of cached messages, whose body starts with `prefix`.
+    ///
+    ///Snapshot is concatenation of [wire](Message::to_wire) frames, one per cached message.
+    pub fn snapshot(&self, prefix: &[u8]) -> Result<Message, ErrorCode> {
+        let mut snapshot = match Message::new() {
+            Some(msg) => msg,
+            None => return Err(error(sys::nng_errno_enum::NNG_ENOMEM)),
+        };
+        for (_, body) in self.cache.lock().iter().filter(|(_, body)| body.starts_with(prefix)) {
+            let mut msg = match Message::new() {
+                Some(msg) => msg,
+                None => return Err(error(sys::nng_errno_enum::NNG_ENOMEM)),
+            };
+            msg.append(body)?;
+            snapshot.append(&msg.try_to_wire()?)?;
+        }
+        Ok(snapshot)
+    }
+
+    ///Replies to snapshot requests received over rep0 `socket`.
+    ///
+    ///Body of request is prefix of messages to include, with empty body requesting whole cache.
+    ///
+    ///Runs until socket fails (i.e. it is closed), returning its error.
+    pub fn serve_snapshots(&self, socket: &Socket) -> ErrorCode {
+        loop {
+            let request = match socket.recv_msg() {
+                Ok(request) => request,
+                Err(error) => break error.into(),
+            };
+            let reply = match self.snapshot(request.body()) {
+                Ok(reply) => reply,
+                Err(error) => break error,
+            };
+            if let Err((_, error)) = socket.send_msg(reply) {
+                break error.into();
+            }
+        }
+    }
+
+    ///Requests snapshot of messages, starting with `prefix`, over req0 `socket` connected to [serve_snapshots](Self::serve_snapshots)
+    ///
+    ///Returns cached messages in no particular order.
+    pub fn request_snapshot(socket: &Socket, prefix: &[u8]) -> Result<Vec<Message>, ErrorCode> {
+        let mut request = match Message::new() {
+            Some(msg) => msg,
+            None => return Err(error(sys::nng_errno_enum::NNG_ENOMEM)),
+        };
+        request.append(prefix)?;
+        socket.send_msg(request).map_err(|(_, error)| error)?;
+
+        let reply = socket.recv_msg()?;
+        let mut frames = reply.body();
+        let mut result = Vec::new();
+        while !frames.is_empty() {
+            let (msg, rest) = Message::split_wire(frames)?;
+            result.push(msg);
+            frames = rest;
+        }
+        Ok(result)
+    }
+
+    ///Forwards messages from `backend` to `frontend`, recording them in cache.
+    ///
+    ///Runs until either socket fails (i.e. it is closed), returning its error.
+    pub fn run(&self, backend: &Socket, frontend: &Socket) -> ErrorCode {
+        loop {
+            let msg = match backend.recv_msg() {
+                Ok(msg) => msg,
+                Err(error) => break error.into(),
+            };
+            self.record(&msg);
+            if let Err((_, error)) = frontend.send_msg(msg) {
+                break error.into();
+            }
+        }
+    }
+}
+
+impl fmt::Debug for LastValueCache {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("LastValueCache").field("topics", &self.topics).field("cached", &self.len()).finish()
+    }
+}
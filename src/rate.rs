@@ -0,0 +1,216 @@
+//!Rate limiting of outgoing messages
+//!
+//![RateLimited] wraps socket, limiting number of messages sent per second using token bucket:
+//!bucket holds up to `burst` tokens, refilled at configured rate, and each message consumes single token.
+//!
+//!Non-blocking sends fail with would-block error when there is no token, while blocking and
+//!asynchronous sends are delayed until token becomes available.
+
+use crate::ErrorCode;
+use crate::error::error;
+use crate::aio::Sleep;
+use crate::msg::Message;
+use crate::socket::{Buf, Socket};
+use crate::sys;
+
+use core::cell::UnsafeCell;
+use core::sync::atomic::{AtomicBool, Ordering};
+use core::{fmt, time};
+
+//Tokens are tracked in thousandths to refill them every millisecond without losing precision
+const TOKEN: u64 = 1000;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+///Rate limit configuration
+pub struct RateLimit {
+    rate: u32,
+    burst: u32,
+}
+
+impl RateLimit {
+    #[inline]
+    ///Creates limit of `rate` messages per second, allowing burst of the same number of messages.
+    ///
+    ///Zero `rate` is treated as 1.
+    pub const fn new(rate: u32) -> Self {
+        let rate = if rate == 0 { 1 } else { rate };
+        Self {
+            rate,
+            burst: rate,
+        }
+    }
+
+    #[inline]
+    ///Sets maximum number of messages that can be sent at once, after period of inactivity.
+    ///
+    ///Zero `burst` is treated as 1.
+    pub const fn burst(mut self, burst: u32) -> Self {
+        self.burst = if burst == 0 { 1 } else { burst };
+        self
+    }
+}
+
+struct Bucket {
+    tokens: u64,
+    last_refill: sys::nng_time,
+}
+
+///Token bucket
+pub struct TokenBucket {
+    limit: RateLimit,
+    lock: AtomicBool,
+    state: UnsafeCell<Bucket>,
+}
+
+unsafe impl Send for TokenBucket {}
+unsafe impl Sync for TokenBucket {}
+
+impl TokenBucket {
+    ///Creates new bucket, filled up to `burst`
+    pub fn new(limit: RateLimit) -> Self {
+        Self {
+            limit,
+            lock: AtomicBool::new(false),
+            state: UnsafeCell::new(Bucket {
+                tokens: limit.burst as u64 * TOKEN,
+                last_refill: unsafe {
+                    sys::nng_clock()
+                },
+            }),
+        }
+    }
+
+    #[inline(always)]
+    ///Returns limit of the bucket
+    pub fn limit(&self) -> RateLimit {
+        self.limit
+    }
+
+    ///Attempts to take single token.
+    ///
+    ///If there is no token available, returns time until next token.
+    pub fn try_acquire(&self) -> Result<(), time::Duration> {
+        while self.lock.compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed).is_err() {
+            core::hint::spin_loop();
+        }
+
+        let state = unsafe {
+            &mut *self.state.get()
+        };
+        let now = unsafe {
+            sys::nng_clock()
+        };
+        let elapsed = now.saturating_sub(state.last_refill);
+        state.last_refill = now;
+        let capacity = self.limit.burst as u64 * TOKEN;
+        state.tokens = core::cmp::min(capacity, state.tokens.saturating_add(elapsed.saturating_mul(self.limit.rate as u64)));
+
+        let result = if state.tokens >= TOKEN {
+            state.tokens -= TOKEN;
+            Ok(())
+        } else {
+            let missing = TOKEN - state.tokens;
+            let rate = self.limit.rate as u64;
+            Err(time::Duration::from_millis(missing.div_ceil(rate)))
+        };
+
+        self.lock.store(false, Ordering::Release);
+        result
+    }
+
+    ///Waits until token is available, taking it.
+    pub fn acquire(&self) {
+        while let Err(wait) = self.try_acquire() {
+            unsafe {
+                sys::nng_msleep(wait.as_millis() as _)
+            }
+        }
+    }
+
+    ///Asynchronously waits until token is available, taking it.
+    pub async fn acquire_async(&self) -> Result<(), ErrorCode> {
+        while let Err(wait) = self.try_acquire() {
+            Sleep::new(wait)?.await?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for TokenBucket {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("TokenBucket").field("limit", &self.limit).finish()
+    }
+}
+
+///Socket wrapper, limiting rate of sending
+pub struct RateLimited<'a> {
+    socket: &'a Socket,
+    bucket: TokenBucket,
+}
+
+impl<'a> RateLimited<'a> {
+    #[inline]
+    ///Wraps `socket` with specified `limit`
+    pub fn new(socket: &'a Socket, limit: RateLimit) -> Self {
+        Self {
+            socket,
+            bucket: TokenBucket::new(limit),
+        }
+    }
+
+    #[inline(always)]
+    ///Returns underlying socket
+    pub fn socket(&self) -> &'a Socket {
+        self.socket
+    }
+
+    #[inline(always)]
+    ///Returns token bucket used to limit rate
+    pub fn bucket(&self) -> &TokenBucket {
+        &self.bucket
+    }
+
+    ///Sends bytes if rate limit allows it, otherwise returns would-block error.
+    pub fn try_send(&self, msg: Buf<'_>) -> Result<(), ErrorCode> {
+        match self.bucket.try_acquire() {
+            Ok(()) => self.socket.send(msg).map_err(Into::into),
+            Err(_) => Err(error(sys::nng_errno_enum::NNG_EAGAIN)),
+        }
+    }
+
+    ///Sends bytes, waiting for rate limit to allow it.
+    pub fn send(&self, msg: Buf<'_>) -> Result<(), ErrorCode> {
+        self.bucket.acquire();
+        self.socket.send(msg).map_err(Into::into)
+    }
+
+    ///Sends message if rate limit allows it, otherwise returns would-block error.
+    pub fn try_send_msg(&self, msg: Message) -> Result<(), (Message, ErrorCode)> {
+        match self.bucket.try_acquire() {
+            Ok(()) => self.socket.send_msg(msg).map_err(|(msg, error)| (msg, error.into())),
+            Err(_) => Err((msg, error(sys::nng_errno_enum::NNG_EAGAIN))),
+        }
+    }
+
+    ///Sends message, waiting for rate limit to allow it.
+    pub fn send_msg(&self, msg: Message) -> Result<(), (Message, ErrorCode)> {
+        self.bucket.acquire();
+        self.socket.send_msg(msg).map_err(|(msg, error)| (msg, error.into()))
+    }
+
+    ///Sends message asynchronously, waiting for rate limit to allow it.
+    ///
+    ///Message is dropped on failure.
+    pub async fn send_msg_async(&self, msg: Message) -> Result<(), ErrorCode> {
+        self.bucket.acquire_async().await?;
+        self.socket.send_msg_async(msg)?.await.map_err(|(_, error)| error)
+    }
+}
+
+impl fmt::Debug for RateLimited<'_> {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("RateLimited").field("socket", &self.socket).field("bucket", &self.bucket).finish()
+    }
+}
@@ -0,0 +1,175 @@
+//!Raw protocol helpers
+//!
+//!Raw sockets leave protocol headers to the user, which is necessary for writing custom routers and devices.
+//!
+//!## Req/Rep header
+//!
+//!Header of req0/rep0 message is a stack of 32-bit big-endian entries, called backtrace.
+//!
+//!- Each device that forwards request pushes ID of the pipe it received request on at the start of the stack;
+//!- Last entry is request ID, which has most significant bit set.
+//!
+//!When reply travels back, each device pops first entry to select pipe to forward reply to.
+//!
+//!Raw rep0 socket routes reply using its header, hence reply must carry backtrace of the request,
+//!which [ReplyTo] captures.
+
+use crate::ErrorCode;
+use crate::error::error;
+use crate::msg::Message;
+use crate::pipe::Pipe;
+use crate::sys;
+
+use core::iter::FusedIterator;
+
+use alloc::vec::Vec;
+
+///Bit set on request ID, marking the end of backtrace
+pub const REQUEST_ID_BIT: u32 = 0x8000_0000;
+
+const ENTRY_SIZE: usize = core::mem::size_of::<u32>();
+
+#[derive(Clone)]
+///Iterator over backtrace entries of req/rep message header.
+///
+///Yields raw entries, including request ID as last element.
+///Trailing bytes that do not form complete entry are ignored.
+pub struct Backtrace<'a> {
+    header: &'a [u8],
+}
+
+impl<'a> Backtrace<'a> {
+    #[inline(always)]
+    ///Creates iterator over raw `header` bytes
+    pub const fn new(header: &'a [u8]) -> Self {
+        Self {
+            header
+        }
+    }
+}
+
+impl Iterator for Backtrace<'_> {
+    type Item = u32;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.header.len() < ENTRY_SIZE {
+            return None;
+        }
+
+        let (entry, rest) = self.header.split_at(ENTRY_SIZE);
+        self.header = rest;
+        Some(u32::from_be_bytes([entry[0], entry[1], entry[2], entry[3]]))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.header.len() / ENTRY_SIZE;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for Backtrace<'_> {}
+impl FusedIterator for Backtrace<'_> {}
+
+#[inline]
+///Returns iterator over backtrace of the `msg` header
+pub fn backtrace(msg: &Message) -> Backtrace<'_> {
+    Backtrace::new(msg.header())
+}
+
+///Returns request ID of the message, if header has valid backtrace.
+///
+///Returned value has [REQUEST_ID_BIT] cleared.
+pub fn request_id(msg: &Message) -> Option<u32> {
+    backtrace(msg).find(|entry| entry & REQUEST_ID_BIT != 0).map(|id| id & !REQUEST_ID_BIT)
+}
+
+#[inline]
+///Returns iterator over pipe IDs (hops) that request travelled through, starting with the most recent.
+pub fn hops(msg: &Message) -> impl Iterator<Item = u32> + '_ {
+    backtrace(msg).take_while(|entry| entry & REQUEST_ID_BIT == 0)
+}
+
+///Replaces header of the message with request ID.
+///
+///This is necessary when sending request via raw req0 socket.
+pub fn set_request_id(msg: &mut Message, id: u32) -> Result<(), ErrorCode> {
+    msg.header_clear();
+    msg.header_append_u32(id | REQUEST_ID_BIT)
+}
+
+#[inline]
+///Pushes pipe ID at the start of the backtrace.
+pub fn push_hop(msg: &mut Message, pipe_id: u32) -> Result<(), ErrorCode> {
+    msg.header_insert_u32(pipe_id & !REQUEST_ID_BIT)
+}
+
+///Pops pipe ID from the start of the backtrace.
+///
+///Returns `None`, if first entry is request ID or there is no entry, leaving header unchanged.
+pub fn pop_hop(msg: &mut Message) -> Option<u32> {
+    match backtrace(msg).next() {
+        Some(entry) if entry & REQUEST_ID_BIT == 0 => msg.header_pop_front_u32(),
+        _ => None,
+    }
+}
+
+#[derive(Clone, Debug)]
+///Return address of the request, received via raw rep0 socket.
+///
+///Captures backtrace and pipe of the request, so that reply can be sent after request is consumed.
+pub struct ReplyTo {
+    header: Vec<u8>,
+    pipe: Option<Pipe>,
+}
+
+impl ReplyTo {
+    ///Captures return address of the `req`
+    ///
+    ///Returns `None` if header has no valid backtrace.
+    pub fn new(req: &Message) -> Option<Self> {
+        let len = backtrace(req).position(|entry| entry & REQUEST_ID_BIT != 0)? + 1;
+        Some(Self {
+            header: req.header()[..len * ENTRY_SIZE].to_vec(),
+            pipe: req.pipe(),
+        })
+    }
+
+    #[inline]
+    ///Returns request ID with [REQUEST_ID_BIT] cleared
+    pub fn request_id(&self) -> u32 {
+        match Backtrace::new(&self.header).last() {
+            Some(id) => id & !REQUEST_ID_BIT,
+            //Backtrace always ends with request ID
+            None => unreachable!(),
+        }
+    }
+
+    #[inline(always)]
+    ///Returns pipe, request was received from
+    pub fn pipe(&self) -> Option<Pipe> {
+        self.pipe
+    }
+
+    ///Addresses `reply`, replacing its header with backtrace of the request.
+    pub fn address(&self, reply: &mut Message) -> Result<(), ErrorCode> {
+        reply.header_clear();
+        reply.header_append(&self.header)?;
+        if let Some(pipe) = self.pipe.as_ref() {
+            reply.set_pipe(pipe);
+        }
+        Ok(())
+    }
+
+    #[inline]
+    ///Creates empty reply, addressed to the request.
+    pub fn reply(&self) -> Result<Message, ErrorCode> {
+        let mut reply = match Message::new() {
+            Some(reply) => reply,
+            None => return Err(error(sys::nng_errno_enum::NNG_ENOMEM)),
+        };
+        self.address(&mut reply)?;
+        Ok(reply)
+    }
+}
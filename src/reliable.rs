@@ -0,0 +1,258 @@
+//!At-least-once delivery
+//!
+//!nng's pair protocol is fire-and-forget: message is lost if connection breaks while it is in flight.
+//!
+//![Channel] builds acknowledged delivery on top of pair socket:
+//!
+//!- Each message is prefixed with session id of the sender and sequence number (both u32 in network byte order);
+//!- Receiver answers each message with acknowledgment, containing the same session id and sequence number with most significant bit set;
+//!- Sender keeps copy of every message until acknowledged, retransmitting it once retransmission time is elapsed;
+//!- Receiver discards duplicates, produced by retransmission.
+//!
+//!Session id is chosen randomly for each [Channel], so that sequence numbers of restarted peer,
+//!starting from zero again, are not mistaken for duplicates.
+//!
+//!Both peers must use [Channel] as sequence number is part of the message body.
+
+use crate::ErrorCode;
+use crate::error::error;
+use crate::msg::Message;
+use crate::socket::Socket;
+use crate::sys;
+
+use core::{fmt, time};
+
+use alloc::collections::{BTreeSet, VecDeque};
+
+const ACK_BIT: u32 = 0x8000_0000;
+const SEQ_MASK: u32 = !ACK_BIT;
+
+#[inline(always)]
+fn now() -> sys::nng_time {
+    unsafe {
+        sys::nng_clock()
+    }
+}
+
+struct Pending {
+    seq: u32,
+    msg: Message,
+    sent_at: sys::nng_time,
+}
+
+///Channel with acknowledged delivery over pair socket
+///
+///Channel has no background activity: acknowledgments are processed and retransmission is performed
+///only while channel is used via [recv](Self::recv), [retransmit](Self::retransmit) or [flush](Self::flush).
+pub struct Channel<'a> {
+    socket: &'a Socket,
+    retransmit_time: sys::nng_time,
+    session: u32,
+    next_seq: u32,
+    pending: VecDeque<Pending>,
+    //Session of the peer, sequence numbers below belong to
+    peer_session: Option<u32>,
+    //Every sequence number below is received
+    next_expected: u32,
+    //Sequence numbers above `next_expected`, that are already received
+    received: BTreeSet<u32>,
+    inbox: VecDeque<Message>,
+}
+
+impl<'a> Channel<'a> {
+    ///Default time after which unacknowledged message is sent again
+    pub const DEFAULT_RETRANSMIT_TIME: time::Duration = time::Duration::from_secs(1);
+
+    #[inline]
+    ///Creates new channel over `socket`, which should be pair socket
+    pub fn new(socket: &'a Socket) -> Self {
+        Self {
+            socket,
+            retransmit_time: Self::DEFAULT_RETRANSMIT_TIME.as_millis() as _,
+            session: unsafe {
+                sys::nng_random()
+            },
+            next_seq: 0,
+            pending: VecDeque::new(),
+            peer_session: None,
+            next_expected: 0,
+            received: BTreeSet::new(),
+            inbox: VecDeque::new(),
+        }
+    }
+
+    #[inline]
+    ///Sets time after which unacknowledged message is sent again
+    pub fn with_retransmit_time(mut self, time: time::Duration) -> Self {
+        self.retransmit_time = time.as_millis() as _;
+        self
+    }
+
+    #[inline(always)]
+    ///Returns underlying socket
+    pub fn socket(&self) -> &'a Socket {
+        self.socket
+    }
+
+    #[inline(always)]
+    ///Returns number of messages, waiting for acknowledgment
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    ///Sends `msg`, returning its sequence number.
+    ///
+    ///Message is retained until acknowledged by peer.
+    ///Message is considered sent even if socket cannot send it without waiting, in which case it shall be retransmitted.
+    ///Error is returned only if message cannot be prepared for sending.
+    pub fn send(&mut self, mut msg: Message) -> Result<u32, ErrorCode> {
+        let seq = self.next_seq;
+        msg.insert_u32(seq)?;
+        msg.insert_u32(self.session)?;
+        let copy = match msg.dup() {
+            Some(copy) => copy,
+            None => return Err(error(sys::nng_errno_enum::NNG_ENOMEM)),
+        };
+
+        self.next_seq = (self.next_seq + 1) & SEQ_MASK;
+        let _ = self.socket.try_send_msg(copy);
+        self.pending.push_back(Pending {
+            seq,
+            msg,
+            sent_at: now(),
+        });
+        Ok(seq)
+    }
+
+    ///Sends again every message, which is not acknowledged within retransmission time.
+    ///
+    ///Returns number of messages sent.
+    pub fn retransmit(&mut self) -> Result<usize, ErrorCode> {
+        let now = now();
+        let mut sent = 0;
+        for pending in self.pending.iter_mut() {
+            if now.saturating_sub(pending.sent_at) < self.retransmit_time {
+                continue;
+            }
+
+            let copy = match pending.msg.dup() {
+                Some(copy) => copy,
+                None => return Err(error(sys::nng_errno_enum::NNG_ENOMEM)),
+            };
+            pending.sent_at = now;
+            if self.socket.try_send_msg(copy).is_ok() {
+                sent += 1;
+            }
+        }
+        Ok(sent)
+    }
+
+    fn acknowledge(&mut self, seq: u32) {
+        if let Some(idx) = self.pending.iter().position(|pending| pending.seq == seq) {
+            self.pending.remove(idx);
+        }
+    }
+
+    //Returns whether message with `seq` is received for the first time
+    fn mark_received(&mut self, seq: u32) -> bool {
+        if seq.wrapping_sub(self.next_expected) & SEQ_MASK >= ACK_BIT >> 1 {
+            //Behind `next_expected`, hence already received
+            return false;
+        }
+
+        if seq == self.next_expected {
+            self.next_expected = (self.next_expected + 1) & SEQ_MASK;
+            while self.received.remove(&self.next_expected) {
+                self.next_expected = (self.next_expected + 1) & SEQ_MASK;
+            }
+            true
+        } else {
+            self.received.insert(seq)
+        }
+    }
+
+    //Processes incoming message, returning it if it is new data message
+    fn handle(&mut self, mut msg: Message) -> Result<Option<Message>, ErrorCode> {
+        let (session, seq) = match (msg.pop_front_u32(), msg.pop_front_u32()) {
+            (Some(session), Some(seq)) => (session, seq),
+            _ => return Err(error(sys::nng_errno_enum::NNG_EPROTO)),
+        };
+
+        if seq & ACK_BIT == ACK_BIT {
+            //Acknowledgment of previous channel on the same socket is ignored
+            if session == self.session {
+                self.acknowledge(seq & SEQ_MASK);
+            }
+            return Ok(None);
+        }
+
+        let mut ack = match Message::new() {
+            Some(ack) => ack,
+            None => return Err(error(sys::nng_errno_enum::NNG_ENOMEM)),
+        };
+        ack.append_u32(session)?;
+        ack.append_u32(seq | ACK_BIT)?;
+        //Lost acknowledgment results in retransmission, which is acknowledged again
+        let _ = self.socket.try_send_msg(ack);
+
+        //Peer is restarted, so its sequence starts over
+        if self.peer_session != Some(session) {
+            self.peer_session = Some(session);
+            self.next_expected = 0;
+            self.received.clear();
+        }
+
+        if self.mark_received(seq) {
+            Ok(Some(msg))
+        } else {
+            Ok(None)
+        }
+    }
+
+    ///Receives next message, acknowledging it.
+    ///
+    ///While waiting, acknowledgments from peer are processed and retransmission is performed.
+    ///Waits up to socket's [RecvTimeout](crate::options::RecvTimeout) for each incoming message.
+    pub fn recv(&mut self) -> Result<Message, ErrorCode> {
+        if let Some(msg) = self.inbox.pop_front() {
+            return Ok(msg);
+        }
+
+        loop {
+            self.retransmit()?;
+            let msg = self.socket.recv_msg()?;
+            if let Some(msg) = self.handle(msg)? {
+                return Ok(msg);
+            }
+        }
+    }
+
+    ///Waits until all sent messages are acknowledged, retransmitting them as necessary.
+    ///
+    ///Messages received from peer meanwhile are retained to be returned by [recv](Self::recv).
+    ///Returns timed out error, if messages are not acknowledged within `timeout`
+    pub fn flush(&mut self, timeout: time::Duration) -> Result<(), ErrorCode> {
+        let started = now();
+        let timeout = timeout.as_millis() as sys::nng_time;
+        while !self.pending.is_empty() {
+            self.retransmit()?;
+            match self.socket.try_recv_msg()? {
+                Some(msg) => if let Some(msg) = self.handle(msg)? {
+                    self.inbox.push_back(msg);
+                },
+                None if now().saturating_sub(started) >= timeout => return Err(error(sys::nng_errno_enum::NNG_ETIMEDOUT)),
+                None => unsafe {
+                    sys::nng_msleep(1)
+                },
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Channel<'_> {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Channel").field("socket", &self.socket).field("pending", &self.pending.len()).finish()
+    }
+}
@@ -0,0 +1,91 @@
+//!Pluggable endpoint resolution
+//!
+//![Resolver] maps name of the service to urls of its endpoints, so that discovery mechanism
+//!(static configuration, consul, etcd or mDNS via `discovery` module) is chosen by application,
+//!rather than being baked into every caller.
+//!
+//!Resolved endpoints are consumed by [connect_all](crate::Socket::connect_all) and
+//![Balancer](crate::balance::Balancer::add_resolved).
+//!
+//!## Usage
+//!
+//!```rust
+//!use nng_c::Socket;
+//!use nng_c::resolve::{Resolver, StaticResolver};
+//!
+//!let resolver = StaticResolver::new().endpoint("orders", "inproc://orders-1")
+//!                                    .endpoint("orders", "inproc://orders-2");
+//!assert_eq!(resolver.resolve("orders").expect("resolve").len(), 2);
+//!
+//!let client = Socket::req0().expect("create client");
+//!let connected = client.connect_all(&resolver, "orders").expect("connect");
+//!assert_eq!(connected, 2);
+//!```
+
+use crate::ErrorCode;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+///Resolver of service endpoints
+pub trait Resolver {
+    ///Returns urls of the `service` endpoints
+    ///
+    ///Unknown service should be reported as empty list, while error is reserved for failure of
+    ///the resolution itself.
+    fn resolve(&self, service: &str) -> Result<Vec<String>, ErrorCode>;
+}
+
+impl<F: Fn(&str) -> Result<Vec<String>, ErrorCode>> Resolver for F {
+    #[inline(always)]
+    fn resolve(&self, service: &str) -> Result<Vec<String>, ErrorCode> {
+        (self)(service)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+///Resolver with fixed list of endpoints per service
+pub struct StaticResolver {
+    endpoints: Vec<(String, String)>,
+}
+
+impl StaticResolver {
+    #[inline(always)]
+    ///Creates new instance without endpoints
+    pub const fn new() -> Self {
+        Self {
+            endpoints: Vec::new(),
+        }
+    }
+
+    #[inline]
+    ///Adds endpoint `url` of the `service`
+    pub fn endpoint(mut self, service: &str, url: &str) -> Self {
+        self.add(service, url);
+        self
+    }
+
+    ///Adds endpoint `url` of the `service`, returning `false` if it is already present
+    pub fn add(&mut self, service: &str, url: &str) -> bool {
+        if self.endpoints.iter().any(|(existing, existing_url)| existing == service && existing_url == url) {
+            false
+        } else {
+            self.endpoints.push((service.into(), url.into()));
+            true
+        }
+    }
+
+    ///Removes all endpoints of the `service`, returning number of removed endpoints
+    pub fn remove(&mut self, service: &str) -> usize {
+        let len = self.endpoints.len();
+        self.endpoints.retain(|(existing, _)| existing != service);
+        len - self.endpoints.len()
+    }
+}
+
+impl Resolver for StaticResolver {
+    #[inline]
+    fn resolve(&self, service: &str) -> Result<Vec<String>, ErrorCode> {
+        Ok(self.endpoints.iter().filter(|(existing, _)| existing == service).map(|(_, url)| url.clone()).collect())
+    }
+}
@@ -0,0 +1,248 @@
+//!Graceful shutdown on Ctrl-C
+//!
+//![Shutdown] collects sockets, servers and arbitrary teardown hooks of the application, running
+//!them once SIGINT or SIGTERM (Ctrl-C or Ctrl-Break on Windows) is received.
+//!
+//!Hooks run in reverse order of registration, similarly to destructors, so that components
+//!registered later (i.e. servers using sockets) are stopped before components they depend on.
+//!
+//!nng discards messages still queued by socket once it is closed, hence socket is given time to drain
+//!before closing: it is closed once [linger](Shutdown::set_linger) period, started when shutdown is triggered, elapses.
+//!Socket keeps operating during this period, so that replies to requests being processed can be delivered.
+//!Closing socket aborts pending operations on it, hence loops, like
+//![Respondent::serve](crate::survey::Respondent::serve), return and let their threads finish.
+//!Same applies to devices (e.g. [LastValueCache](crate::pubsub::LastValueCache)), which stop once their sockets are closed.
+//!
+//!Requires feature `signal`
+//!
+//!## Usage
+//!
+//!```rust,no_run
+//!use nng_c::Socket;
+//!use nng_c::shutdown::Shutdown;
+//!
+//!use std::sync::Arc;
+//!
+//!let shutdown = Shutdown::new();
+//!shutdown.install().expect("install signal handler");
+//!
+//!let server = Arc::new(Socket::rep0().expect("create server"));
+//!server.listen("tcp://127.0.0.1:5555\0".into()).expect("listen");
+//!shutdown.socket(server.clone());
+//!
+//!let worker = std::thread::spawn(move || while let Ok(msg) = server.recv_msg() {
+//!    let _ = server.send_msg(msg);
+//!});
+//!
+//!//Blocks until Ctrl-C is pressed and socket is closed
+//!shutdown.wait();
+//!worker.join().expect("join worker");
+//!```
+
+use crate::ErrorCode;
+use crate::error::error;
+use crate::socket::Socket;
+use crate::sys;
+
+use core::{fmt, time};
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, Weak};
+use std::time::Instant;
+
+type Hook = Box<dyn FnOnce() + Send>;
+
+///Default time given to sockets to drain before closing
+pub const DEFAULT_LINGER: time::Duration = time::Duration::from_millis(100);
+
+struct State {
+    hooks: Vec<Hook>,
+    linger: time::Duration,
+    //Set once shutdown is triggered
+    linger_until: Option<Instant>,
+    triggered: bool,
+    done: bool,
+}
+
+struct Inner {
+    state: Mutex<State>,
+    done: Condvar,
+}
+
+impl Inner {
+    #[inline]
+    fn lock(&self) -> MutexGuard<'_, State> {
+        match self.state.lock() {
+            Ok(state) => state,
+            Err(error) => error.into_inner(),
+        }
+    }
+
+    //Waits until linger period of triggered shutdown elapses
+    fn linger(&self) {
+        let linger_until = self.lock().linger_until;
+        if let Some(remaining) = linger_until.and_then(|linger_until| linger_until.checked_duration_since(Instant::now())) {
+            std::thread::sleep(remaining);
+        }
+    }
+}
+
+#[derive(Clone)]
+///Registry of components to tear down on shutdown
+///
+///Cloned instances share the same registry.
+pub struct Shutdown {
+    inner: Arc<Inner>,
+}
+
+impl Shutdown {
+    ///Creates new registry without components
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                state: Mutex::new(State {
+                    hooks: Vec::new(),
+                    linger: DEFAULT_LINGER,
+                    linger_until: None,
+                    triggered: false,
+                    done: false,
+                }),
+                done: Condvar::new(),
+            })
+        }
+    }
+
+    ///Installs process signal handler, triggering shutdown on SIGINT and SIGTERM.
+    ///
+    ///Only single handler can be installed per process, hence second call fails with `NNG_EBUSY`.
+    pub fn install(&self) -> Result<(), ErrorCode> {
+        let this = self.clone();
+        match ctrlc::set_handler(move || {
+            this.trigger();
+        }) {
+            Ok(()) => Ok(()),
+            Err(ctrlc::Error::MultipleHandlers) => Err(error(sys::nng_errno_enum::NNG_EBUSY)),
+            Err(_) => Err(error(sys::nng_errno_enum::NNG_ENOTSUP)),
+        }
+    }
+
+    ///Registers `hook` to run on shutdown.
+    ///
+    ///If shutdown is already triggered, `hook` runs immediately.
+    pub fn on_shutdown<F: FnOnce() + Send + 'static>(&self, hook: F) {
+        let mut state = self.inner.lock();
+        if state.triggered {
+            drop(state);
+            hook();
+        } else {
+            state.hooks.push(Box::new(hook));
+        }
+    }
+
+    #[inline]
+    ///Sets time given to sockets to drain before closing, defaulting to [DEFAULT_LINGER].
+    ///
+    ///Has no effect once shutdown is triggered.
+    pub fn set_linger(&self, linger: time::Duration) {
+        self.inner.lock().linger = linger;
+    }
+
+    ///Registers `socket` to be closed on shutdown, once linger period elapses.
+    pub fn socket(&self, socket: Arc<Socket>) {
+        let inner = Arc::downgrade(&self.inner);
+        self.on_shutdown(move || {
+            if let Some(inner) = Weak::upgrade(&inner) {
+                inner.linger();
+            }
+            socket.close();
+        })
+    }
+
+    #[inline]
+    ///Registers sockets of the device to be closed on shutdown, stopping device.
+    ///
+    ///`frontend` is closed first, once linger period elapses.
+    pub fn device(&self, backend: Arc<Socket>, frontend: Arc<Socket>) {
+        self.socket(backend);
+        self.socket(frontend);
+    }
+
+    #[cfg(feature = "http")]
+    #[inline]
+    ///Registers HTTP `server` to be stopped on shutdown
+    pub fn http_server(&self, server: Arc<crate::http::Server>) {
+        self.on_shutdown(move || server.stop())
+    }
+
+    ///Triggers shutdown, running all registered hooks.
+    ///
+    ///Returns `false` if shutdown is already triggered, in which case nothing is done.
+    pub fn trigger(&self) -> bool {
+        let hooks = {
+            let mut state = self.inner.lock();
+            if state.triggered {
+                return false;
+            }
+            state.triggered = true;
+            state.linger_until = Instant::now().checked_add(state.linger);
+            core::mem::take(&mut state.hooks)
+        };
+
+        for hook in hooks.into_iter().rev() {
+            hook();
+        }
+
+        self.inner.lock().done = true;
+        self.inner.done.notify_all();
+        true
+    }
+
+    #[inline]
+    ///Returns whether shutdown is triggered
+    pub fn is_triggered(&self) -> bool {
+        self.inner.lock().triggered
+    }
+
+    ///Waits until shutdown is triggered and all hooks complete
+    pub fn wait(&self) {
+        let mut state = self.inner.lock();
+        while !state.done {
+            state = match self.inner.done.wait(state) {
+                Ok(state) => state,
+                Err(error) => error.into_inner(),
+            };
+        }
+    }
+
+    ///Waits up to `timeout` until shutdown is triggered and all hooks complete.
+    ///
+    ///Returns `true` if shutdown is complete.
+    pub fn wait_timeout(&self, timeout: time::Duration) -> bool {
+        let state = self.inner.lock();
+        let (state, _) = match self.inner.done.wait_timeout_while(state, timeout, |state| !state.done) {
+            Ok(result) => result,
+            Err(error) => error.into_inner(),
+        };
+        state.done
+    }
+}
+
+impl Default for Shutdown {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for Shutdown {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.inner.lock();
+        fmt.debug_struct("Shutdown").field("hooks", &state.hooks.len())
+                                    .field("linger", &state.linger)
+                                    .field("triggered", &state.triggered)
+                                    .field("done", &state.done)
+                                    .finish()
+    }
+}
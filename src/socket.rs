@@ -1,19 +1,42 @@
 //!Socket module
 use crate::ErrorCode;
-use crate::error::error;
+use crate::error::{error, NngError, Op, OpError};
+#[cfg(debug_assertions)]
+use crate::error::{misuse, Misuse};
 use crate::msg::Message;
+use crate::url::check_scheme;
+use crate::fallible::try_vec;
 use crate::aio::Aio;
 use crate::sys;
 use crate::str::String;
-use crate::options::{Options, Property};
+use crate::options::{Options, Property, PeerName, ProtocolName, Raw, SocketOptions, Subscribe, Unsubscribe};
+use crate::pipe::Pipe;
+use crate::notify::{Monitor, PipeEvent, PipeNotifier};
+use crate::resolve::Resolver;
+use crate::endpoints::Endpoints;
+use crate::context::{Context, FutureRequest};
+use crate::pubsub;
 
 use core::pin::Pin;
+use core::convert::TryFrom;
 use core::ffi::c_int;
 use core::future::Future;
-use core::{mem, fmt, ops, ptr, task, marker, slice};
+use core::{mem, fmt, ops, ptr, task, marker, slice, time};
+use core::net::IpAddr;
+#[cfg(feature = "counters")]
+use core::sync::atomic::AtomicUsize;
+#[cfg(debug_assertions)]
+use core::sync::atomic::AtomicBool;
+#[cfg(any(feature = "counters", debug_assertions))]
+use core::sync::atomic::Ordering;
 
 use alloc::vec::Vec;
 
+#[cold]
+fn url_error(op: Op, error: ErrorCode, url: &String<'_>) -> OpError {
+    OpError::new(op, error).with_url(&alloc::string::String::from_utf8_lossy(url.as_bytes()))
+}
+
 type InitFn = unsafe extern "C" fn(msg: *mut sys::nng_socket) -> core::ffi::c_int;
 
 ///Wrapper over slice of bytes.
@@ -110,6 +133,7 @@ impl<'a> From<&'a mut [mem::MaybeUninit<u8>]> for BufMut<'a> {
 ///Connect options
 pub struct ConnectOptions<T> {
     flags: c_int,
+    local_addr: Option<IpAddr>,
     dialer: T
 }
 
@@ -118,6 +142,7 @@ impl ConnectOptions<()> {
     pub const fn new() -> Self {
         Self {
             flags: 0,
+            local_addr: None,
             dialer: ()
         }
     }
@@ -138,14 +163,408 @@ impl<T> ConnectOptions<T> {
     pub const fn with_dialer<R: Options<Dialer>>(&self, dialer: R) -> ConnectOptions<R> {
         ConnectOptions {
             flags: self.flags,
+            local_addr: self.local_addr,
             dialer
         }
     }
+
+    ///Sets local address, outgoing connection shall originate from.
+    ///
+    ///Only supported by TCP based transports, connection via other transports fails.
+    pub const fn with_local_address(mut self, addr: IpAddr) -> Self {
+        self.local_addr = Some(addr);
+        self
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize), serde(rename_all = "lowercase"))]
+///Socket protocol
+pub enum Protocol {
+    ///Version 0 pair
+    Pair0,
+    ///Version 1 pair
+    Pair1,
+    ///Version 0 publisher
+    Pub0,
+    ///Version 0 subscriber
+    Sub0,
+    ///Version 0 requester
+    Req0,
+    ///Version 0 replier
+    Rep0,
+    ///Version 0 surveyor
+    Surveyor0,
+    ///Version 0 respondent
+    Respondent0,
+    ///Version 0 pusher
+    Push0,
+    ///Version 0 puller
+    Pull0,
+}
+
+impl Protocol {
+    ///Creates new socket of this protocol
+    ///
+    ///If `raw` is `true`, then creates socket in raw mode
+    pub fn open(self, raw: bool) -> Result<Socket, ErrorCode> {
+        match (self, raw) {
+            (Self::Pair0, false) => Socket::pair0(),
+            (Self::Pair0, true) => Socket::pair0_raw(),
+            (Self::Pair1, false) => Socket::pair1(),
+            (Self::Pair1, true) => Socket::pair1_raw(),
+            (Self::Pub0, false) => Socket::pub0(),
+            (Self::Pub0, true) => Socket::pub0_raw(),
+            (Self::Sub0, false) => Socket::sub0(),
+            (Self::Sub0, true) => Socket::sub0_raw(),
+            (Self::Req0, false) => Socket::req0(),
+            (Self::Req0, true) => Socket::req0_raw(),
+            (Self::Rep0, false) => Socket::rep0(),
+            (Self::Rep0, true) => Socket::rep0_raw(),
+            (Self::Surveyor0, false) => Socket::surveyor0(),
+            (Self::Surveyor0, true) => Socket::surveyor0_raw(),
+            (Self::Respondent0, false) => Socket::respondent0(),
+            (Self::Respondent0, true) => Socket::respondent0_raw(),
+            (Self::Push0, false) => Socket::push0(),
+            (Self::Push0, true) => Socket::push0_raw(),
+            (Self::Pull0, false) => Socket::pull0(),
+            (Self::Pull0, true) => Socket::pull0_raw(),
+        }
+    }
+
+    ///Returns name of the protocol, as reported by nng
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Pair0 => "pair",
+            Self::Pair1 => "pair1",
+            Self::Pub0 => "pub",
+            Self::Sub0 => "sub",
+            Self::Req0 => "req",
+            Self::Rep0 => "rep",
+            Self::Surveyor0 => "surveyor",
+            Self::Respondent0 => "respondent",
+            Self::Push0 => "push",
+            Self::Pull0 => "pull",
+        }
+    }
+
+    ///Returns number of the protocol, as reported by nng
+    ///
+    ///Upper 12 bits identify protocol family, while lower 4 bits identify its role.
+    pub const fn id(self) -> u16 {
+        match self {
+            Self::Pair0 => 0x10,
+            Self::Pair1 => 0x11,
+            Self::Pub0 => 0x20,
+            Self::Sub0 => 0x21,
+            Self::Req0 => 0x30,
+            Self::Rep0 => 0x31,
+            Self::Push0 => 0x50,
+            Self::Pull0 => 0x51,
+            Self::Surveyor0 => 0x62,
+            Self::Respondent0 => 0x63,
+        }
+    }
+
+    ///Returns protocol with specified number, if it is known
+    pub const fn from_id(id: u16) -> Option<Self> {
+        match id {
+            0x10 => Some(Self::Pair0),
+            0x11 => Some(Self::Pair1),
+            0x20 => Some(Self::Pub0),
+            0x21 => Some(Self::Sub0),
+            0x30 => Some(Self::Req0),
+            0x31 => Some(Self::Rep0),
+            0x50 => Some(Self::Push0),
+            0x51 => Some(Self::Pull0),
+            0x62 => Some(Self::Surveyor0),
+            0x63 => Some(Self::Respondent0),
+            _ => None,
+        }
+    }
+
+    ///Returns protocol of the peer, this protocol communicates with
+    pub const fn peer(self) -> Self {
+        match self {
+            Self::Pair0 => Self::Pair0,
+            Self::Pair1 => Self::Pair1,
+            Self::Pub0 => Self::Sub0,
+            Self::Sub0 => Self::Pub0,
+            Self::Req0 => Self::Rep0,
+            Self::Rep0 => Self::Req0,
+            Self::Surveyor0 => Self::Respondent0,
+            Self::Respondent0 => Self::Surveyor0,
+            Self::Push0 => Self::Pull0,
+            Self::Pull0 => Self::Push0,
+        }
+    }
+}
+
+impl fmt::Display for Protocol {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str(self.name())
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+///Error of [Socket::check_peer]
+pub enum PeerError {
+    ///Unable to read peer protocol of the socket
+    Property(ErrorCode),
+    ///Socket communicates with peers of different protocol
+    Mismatch {
+        ///Expected peer protocol
+        expected: Protocol,
+        ///Peer protocol of the socket
+        actual: PeerName,
+    },
+}
+
+impl fmt::Display for PeerError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Property(code) => fmt.write_fmt(format_args!("unable to get peer protocol: {}", code)),
+            Self::Mismatch { expected, actual } => fmt.write_fmt(format_args!("expected socket for '{}' peer, but it communicates with '{}'", expected, actual)),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PeerError {}
+
+#[cfg(feature = "counters")]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+///Snapshot of socket's counters
+///
+///Only synchronous operations performed directly via [Socket] are accounted for.
+pub struct Counters {
+    ///Number of successfully sent messages
+    pub msgs_sent: usize,
+    ///Number of bytes of successfully sent messages' bodies
+    pub bytes_sent: usize,
+    ///Number of successfully received messages
+    pub msgs_recv: usize,
+    ///Number of bytes of successfully received messages' bodies
+    pub bytes_recv: usize,
+    ///Number of failed send operations, excluding attempts when message cannot be sent immediately
+    pub send_failures: usize,
+    ///Number of failed receive operations, excluding attempts when no message is available
+    pub recv_failures: usize,
+}
+
+#[cfg(feature = "counters")]
+struct AtomicCounters {
+    msgs_sent: AtomicUsize,
+    bytes_sent: AtomicUsize,
+    msgs_recv: AtomicUsize,
+    bytes_recv: AtomicUsize,
+    send_failures: AtomicUsize,
+    recv_failures: AtomicUsize,
+}
+
+#[cfg(feature = "counters")]
+impl AtomicCounters {
+    #[inline(always)]
+    const fn new() -> Self {
+        Self {
+            msgs_sent: AtomicUsize::new(0),
+            bytes_sent: AtomicUsize::new(0),
+            msgs_recv: AtomicUsize::new(0),
+            bytes_recv: AtomicUsize::new(0),
+            send_failures: AtomicUsize::new(0),
+            recv_failures: AtomicUsize::new(0),
+        }
+    }
+
+    #[inline(always)]
+    fn sent(&self, size: usize) {
+        self.msgs_sent.fetch_add(1, Ordering::Relaxed);
+        self.bytes_sent.fetch_add(size, Ordering::Relaxed);
+    }
+
+    #[inline(always)]
+    fn send_failed(&self) {
+        self.send_failures.fetch_add(1, Ordering::Relaxed);
+    }
+
+    #[inline(always)]
+    fn received(&self, size: usize) {
+        self.msgs_recv.fetch_add(1, Ordering::Relaxed);
+        self.bytes_recv.fetch_add(size, Ordering::Relaxed);
+    }
+
+    #[inline(always)]
+    fn recv_failed(&self, error: &ErrorCode) {
+        if !error.is_would_block() {
+            self.recv_failures.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn get(&self) -> Counters {
+        Counters {
+            msgs_sent: self.msgs_sent.load(Ordering::Relaxed),
+            bytes_sent: self.bytes_sent.load(Ordering::Relaxed),
+            msgs_recv: self.msgs_recv.load(Ordering::Relaxed),
+            bytes_recv: self.bytes_recv.load(Ordering::Relaxed),
+            send_failures: self.send_failures.load(Ordering::Relaxed),
+            recv_failures: self.recv_failures.load(Ordering::Relaxed),
+        }
+    }
+
+    fn reset(&self) {
+        self.msgs_sent.store(0, Ordering::Relaxed);
+        self.bytes_sent.store(0, Ordering::Relaxed);
+        self.msgs_recv.store(0, Ordering::Relaxed);
+        self.bytes_recv.store(0, Ordering::Relaxed);
+        self.send_failures.store(0, Ordering::Relaxed);
+        self.recv_failures.store(0, Ordering::Relaxed);
+    }
+}
+
+//No-op counters when feature is disabled
+#[cfg(not(feature = "counters"))]
+struct AtomicCounters;
+
+#[cfg(not(feature = "counters"))]
+impl AtomicCounters {
+    #[inline(always)]
+    const fn new() -> Self {
+        Self
+    }
+
+    #[inline(always)]
+    fn sent(&self, _: usize) {
+    }
+
+    #[inline(always)]
+    fn send_failed(&self) {
+    }
+
+    #[inline(always)]
+    fn received(&self, _: usize) {
+    }
+
+    #[inline(always)]
+    fn recv_failed(&self, _: &ErrorCode) {
+    }
+}
+
+#[cfg(debug_assertions)]
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Role {
+    Other,
+    SendOnly,
+    RecvOnly,
+    Rep,
+}
+
+//Tracks state of the protocol in debug builds, to report obviously invalid sequences of
+//synchronous operations with descriptive error.
+//Asynchronous operations only update state, as they cannot return message back.
+#[cfg(debug_assertions)]
+struct ProtocolState {
+    role: Role,
+    //Request is received on rep0
+    pending: AtomicBool,
+}
+
+#[cfg(debug_assertions)]
+impl ProtocolState {
+    fn new(socket: sys::nng_socket) -> Self {
+        let mut proto = 0;
+        let mut raw = false;
+        let role = unsafe {
+            match (sys::nng_socket_get_int(socket, sys::NNG_OPT_PROTO.as_ptr() as _, &mut proto), sys::nng_socket_get_bool(socket, sys::NNG_OPT_RAW.as_ptr() as _, &mut raw)) {
+                //Raw sockets leave protocol state to application
+                (0, 0) if !raw => match proto {
+                    //pub0 and push0
+                    0x20 | 0x50 => Role::SendOnly,
+                    //sub0 and pull0
+                    0x21 | 0x51 => Role::RecvOnly,
+                    0x31 => Role::Rep,
+                    _ => Role::Other,
+                },
+                _ => Role::Other,
+            }
+        };
+
+        Self {
+            role,
+            pending: AtomicBool::new(false),
+        }
+    }
+
+    #[inline(always)]
+    fn check_send(&self) -> Result<(), ErrorCode> {
+        match self.role {
+            Role::RecvOnly => Err(misuse(Misuse::SendOnRecvOnly)),
+            Role::Rep if !self.pending.load(Ordering::Acquire) => Err(misuse(Misuse::NoPendingRequest)),
+            _ => Ok(()),
+        }
+    }
+
+    #[inline(always)]
+    fn sent(&self) {
+        if self.role == Role::Rep {
+            self.pending.store(false, Ordering::Release);
+        }
+    }
+
+    #[inline(always)]
+    fn check_recv(&self) -> Result<(), ErrorCode> {
+        match self.role {
+            Role::SendOnly => Err(misuse(Misuse::RecvOnSendOnly)),
+            _ => Ok(()),
+        }
+    }
+
+    #[inline(always)]
+    fn received(&self) {
+        if self.role == Role::Rep {
+            self.pending.store(true, Ordering::Release);
+        }
+    }
+}
+
+//No-op state in release builds
+#[cfg(not(debug_assertions))]
+struct ProtocolState;
+
+#[cfg(not(debug_assertions))]
+impl ProtocolState {
+    #[inline(always)]
+    const fn new(_: sys::nng_socket) -> Self {
+        Self
+    }
+
+    #[inline(always)]
+    fn check_send(&self) -> Result<(), ErrorCode> {
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn sent(&self) {
+    }
+
+    #[inline(always)]
+    fn check_recv(&self) -> Result<(), ErrorCode> {
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn received(&self) {
+    }
 }
 
-#[repr(transparent)]
+#[cfg_attr(all(not(feature = "counters"), not(debug_assertions)), repr(transparent))]
 ///Generic socket type
-pub struct Socket(pub(crate) sys::nng_socket);
+///
+///In debug builds, socket detects obviously invalid use of its protocol (i.e. receiving on pub0,
+///sending on sub0 or replying on rep0 without receiving request) and reports it with descriptive
+///error, instead of bare `NNG_ENOTSUP` or `NNG_ESTATE`.
+///Sending new request on req0 is valid, as it abandons previous one.
+pub struct Socket(pub(crate) sys::nng_socket, AtomicCounters, ProtocolState);
 
 impl Socket {
     #[inline(always)]
@@ -159,7 +578,7 @@ impl Socket {
         };
 
         if result == 0 {
-            Ok(Self(socket))
+            Ok(Self(socket, AtomicCounters::new(), ProtocolState::new(socket)))
         } else {
             Err(error(result))
         }
@@ -178,6 +597,14 @@ impl Socket {
         Self::with(sys::nng_pair1_open)
     }
 
+    #[inline(always)]
+    ///Creates new version 1 pair socket in polyamorous mode
+    ///
+    ///Polyamorous socket accepts multiple peers, replying to particular peer via [send_msg_to](Self::send_msg_to).
+    pub fn pair1_poly() -> Result<Self, ErrorCode> {
+        Self::with(sys::nng_pair1_open_poly)
+    }
+
     #[inline(always)]
     ///Creates new version 0 publisher socket
     pub fn pub0() -> Result<Self, ErrorCode> {
@@ -202,6 +629,138 @@ impl Socket {
         Self::with(sys::nng_rep0_open)
     }
 
+    #[inline(always)]
+    ///Creates new version 0 surveyor socket
+    pub fn surveyor0() -> Result<Self, ErrorCode> {
+        Self::with(sys::nng_surveyor0_open)
+    }
+
+    #[inline(always)]
+    ///Creates new version 0 respondent socket
+    pub fn respondent0() -> Result<Self, ErrorCode> {
+        Self::with(sys::nng_respondent0_open)
+    }
+
+    #[inline(always)]
+    ///Creates new version 0 push socket
+    pub fn push0() -> Result<Self, ErrorCode> {
+        Self::with(sys::nng_push0_open)
+    }
+
+    #[inline(always)]
+    ///Creates new version 0 pull socket
+    pub fn pull0() -> Result<Self, ErrorCode> {
+        Self::with(sys::nng_pull0_open)
+    }
+
+    #[inline(always)]
+    ///Creates new version 0 pair socket in raw mode
+    ///
+    ///Raw sockets do not perform protocol processing, leaving message headers to the user.
+    pub fn pair0_raw() -> Result<Self, ErrorCode> {
+        Self::with(sys::nng_pair0_open_raw)
+    }
+
+    #[inline(always)]
+    ///Creates new version 1 pair socket in raw mode
+    ///
+    ///Raw sockets do not perform protocol processing, leaving message headers to the user.
+    pub fn pair1_raw() -> Result<Self, ErrorCode> {
+        Self::with(sys::nng_pair1_open_raw)
+    }
+
+    #[inline(always)]
+    ///Creates new version 0 publisher socket in raw mode
+    ///
+    ///Raw sockets do not perform protocol processing, leaving message headers to the user.
+    pub fn pub0_raw() -> Result<Self, ErrorCode> {
+        Self::with(sys::nng_pub0_open_raw)
+    }
+
+    #[inline(always)]
+    ///Creates new version 0 subscriber socket in raw mode
+    ///
+    ///Raw sockets do not perform protocol processing, leaving message headers to the user.
+    pub fn sub0_raw() -> Result<Self, ErrorCode> {
+        Self::with(sys::nng_sub0_open_raw)
+    }
+
+    #[inline(always)]
+    ///Creates new version 0 request socket in raw mode
+    ///
+    ///Raw sockets do not perform protocol processing, leaving message headers to the user.
+    pub fn req0_raw() -> Result<Self, ErrorCode> {
+        Self::with(sys::nng_req0_open_raw)
+    }
+
+    #[inline(always)]
+    ///Creates new version 0 reply socket in raw mode
+    ///
+    ///Raw sockets do not perform protocol processing, leaving message headers to the user.
+    pub fn rep0_raw() -> Result<Self, ErrorCode> {
+        Self::with(sys::nng_rep0_open_raw)
+    }
+
+    #[inline(always)]
+    ///Creates new version 0 surveyor socket in raw mode
+    ///
+    ///Raw sockets do not perform protocol processing, leaving message headers to the user.
+    pub fn surveyor0_raw() -> Result<Self, ErrorCode> {
+        Self::with(sys::nng_surveyor0_open_raw)
+    }
+
+    #[inline(always)]
+    ///Creates new version 0 respondent socket in raw mode
+    ///
+    ///Raw sockets do not perform protocol processing, leaving message headers to the user.
+    pub fn respondent0_raw() -> Result<Self, ErrorCode> {
+        Self::with(sys::nng_respondent0_open_raw)
+    }
+
+    #[inline(always)]
+    ///Creates new version 0 push socket in raw mode
+    ///
+    ///Raw sockets do not perform protocol processing, leaving message headers to the user.
+    pub fn push0_raw() -> Result<Self, ErrorCode> {
+        Self::with(sys::nng_push0_open_raw)
+    }
+
+    #[inline(always)]
+    ///Creates new version 0 pull socket in raw mode
+    ///
+    ///Raw sockets do not perform protocol processing, leaving message headers to the user.
+    pub fn pull0_raw() -> Result<Self, ErrorCode> {
+        Self::with(sys::nng_pull0_open_raw)
+    }
+
+    #[inline(always)]
+    ///Creates instance from raw `socket`, taking ownership over it.
+    ///
+    ///## Safety
+    ///
+    ///`socket` must be valid and not owned by anyone else, as it will be closed on `Drop`.
+    pub unsafe fn from_raw(socket: sys::nng_socket) -> Self {
+        Self(socket, AtomicCounters::new(), ProtocolState::new(socket))
+    }
+
+    #[inline]
+    ///Relinquishes ownership over socket, returning raw handle without closing it.
+    ///
+    ///It is user responsibility to close socket afterwards.
+    pub fn into_raw(self) -> sys::nng_socket {
+        let socket = self.0;
+        mem::forget(self);
+        socket
+    }
+
+    #[inline]
+    ///Leaks socket, making it live until the end of program.
+    ///
+    ///Socket can still be closed explicitly via [close](Self::close)
+    pub fn leak(self) -> &'static Self {
+        alloc::boxed::Box::leak(alloc::boxed::Box::new(self))
+    }
+
     #[inline(always)]
     ///Closes socket.
     ///
@@ -225,14 +784,29 @@ impl Socket {
     ///Allows to provide custom options to initialize listener with.
     ///Mostly useful to set optional TLS config
     pub fn listen_with<T: Options<Listener>>(&self, url: String<'_>, options: &T) -> Result<(), ErrorCode> {
-        let listener = Listener::new(self, url)?;
-        options.apply(&listener)?;
-        listener.start()?;
+        match self.add_listener(url, options) {
+            Ok(_) => Ok(()),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    ///Binds socket to the specified `url`, starting to listen for incoming messages.
+    ///
+    ///Unlike [listen_with](Self::listen_with), returns id of created listener, while error carries url and listener id.
+    pub fn add_listener<T: Options<Listener>>(&self, url: String<'_>, options: &T) -> Result<u32, OpError> {
+        let listener = match Listener::new(self, &url) {
+            Ok(listener) => listener,
+            Err(error) => return Err(url_error(Op::Listen, error, &url)),
+        };
+        if let Err(error) = options.apply(&listener).and_then(|_| listener.start()) {
+            return Err(url_error(Op::Listen, error, &url).with_endpoint(listener.0.id));
+        }
 
         //Listener will be assigned to the socket and can be closed by it
+        let id = listener.0.id;
         mem::forget(listener);
 
-        Ok(())
+        Ok(id)
     }
 
     #[inline]
@@ -244,14 +818,201 @@ impl Socket {
     #[inline]
     ///Connects to the remote peer via `url`, with custom options settings
     pub fn connect_with<T: Options<Dialer>>(&self, url: String<'_>, options: ConnectOptions<T>) -> Result<(), ErrorCode> {
-        let dialer = Dialer::new(self, url)?;
-        options.dialer.apply(&dialer)?;
-        dialer.start(options.flags)?;
+        match self.add_dialer(url, options) {
+            Ok(_) => Ok(()),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    ///Connects to all endpoints of the `service`, returned by `resolver`.
+    ///
+    ///Connections are established in background, as some endpoints may be temporary unavailable.
+    ///Returns number of endpoints or error if there are none.
+    pub fn connect_all<R: Resolver + ?Sized>(&self, resolver: &R, service: &str) -> Result<usize, ErrorCode> {
+        let urls = resolver.resolve(service)?;
+        if urls.is_empty() {
+            return Err(error(sys::nng_errno_enum::NNG_ENOENT));
+        }
+
+        for url in urls.iter() {
+            self.connect_with(url.as_str().into(), ConnectOptions::new().with_async())?;
+        }
+        Ok(urls.len())
+    }
+
+    ///Connects to the remote peer via `url`, retrying while peer refuses connection, until `timeout` elapses.
+    ///
+    ///This is useful on startup, when peer may not be listening yet.
+    ///Returns last error, if unable to connect before deadline.
+    pub fn connect_until(&self, url: String<'_>, timeout: time::Duration) -> Result<(), ErrorCode> {
+        const RETRY_INTERVAL: sys::nng_duration = 10;
+
+        let deadline = unsafe {
+            sys::nng_clock()
+        }.saturating_add(timeout.as_millis() as _);
+
+        loop {
+            let dialer = Dialer::new(self, &url)?;
+            let error = match dialer.start(0) {
+                Ok(()) => {
+                    //Dialer will be assigned to the socket and can be closed by it
+                    mem::forget(dialer);
+                    return Ok(());
+                },
+                Err(error) => error,
+            };
+
+            let now = unsafe {
+                sys::nng_clock()
+            };
+            if error.raw_code() != sys::nng_errno_enum::NNG_ECONNREFUSED || now >= deadline {
+                return Err(error);
+            }
+
+            let wait = core::cmp::min(deadline - now, RETRY_INTERVAL as _);
+            unsafe {
+                sys::nng_msleep(wait as _);
+            }
+        }
+    }
+
+    ///Connects to the remote peer via `url`, with custom options settings
+    ///
+    ///Unlike [connect_with](Self::connect_with), returns id of created dialer, while error carries url and dialer id.
+    pub fn add_dialer<T: Options<Dialer>>(&self, url: String<'_>, options: ConnectOptions<T>) -> Result<u32, OpError> {
+        let dialer = match Dialer::new(self, &url) {
+            Ok(dialer) => dialer,
+            Err(error) => return Err(url_error(Op::Dial, error, &url)),
+        };
+        let result = match options.local_addr {
+            Some(addr) => dialer.set_local_addr(addr),
+            None => Ok(()),
+        };
+        if let Err(error) = result.and_then(|_| options.dialer.apply(&dialer)).and_then(|_| dialer.start(options.flags)) {
+            return Err(url_error(Op::Dial, error, &url).with_endpoint(dialer.0.id));
+        }
 
         //Dialer will be assigned to the socket and can be closed by it
+        let id = dialer.0.id;
         mem::forget(dialer);
 
-        Ok(())
+        Ok(id)
+    }
+
+    #[inline(always)]
+    ///Creates new [Context] on the socket, allowing to process multiple requests concurrently
+    ///
+    ///Refer to [Context::new]
+    pub fn context(&self) -> Result<Context, ErrorCode> {
+        Context::new(self)
+    }
+
+    #[inline]
+    ///Sends request `msg`, returning future resolving to the matching reply.
+    ///
+    ///Request is sent on its own [Context], so multiple requests can be in flight at the same time.
+    ///Socket must be req0 socket, and its options, like resend interval, apply to every request.
+    pub fn request(&self, msg: Message) -> FutureRequest<'static> {
+        FutureRequest::new(self, msg)
+    }
+
+    ///Serves requests on rep0 socket, replying to each with result of `handler`.
+    ///
+    ///Blocks until socket is closed, which is clean shutdown, returning `Ok`.
+    ///Otherwise returns first unexpected error (i.e. timeout, if receive timeout is set on the socket).
+    pub fn serve<F: FnMut(Message) -> Message>(&self, mut handler: F) -> Result<(), ErrorCode> {
+        loop {
+            let req = match self.recv_msg() {
+                Ok(req) => req,
+                Err(error) if error.is_closed() => break Ok(()),
+                Err(error) => break Err(error.into()),
+            };
+
+            match self.send_msg(handler(req)) {
+                Ok(()) => (),
+                Err((_, error)) if error.is_closed() => break Ok(()),
+                Err((_, error)) => break Err(error.into()),
+            }
+        }
+    }
+
+    ///Asynchronously serves requests on rep0 socket, replying to each with result of `handler`.
+    ///
+    ///Requests are received on its own [Context], so multiple loops can be run on the same socket to
+    ///serve requests concurrently.
+    ///
+    ///Completes with `Ok` once socket is closed, otherwise with first unexpected error.
+    pub async fn serve_async<F: FnMut(Message) -> Message>(&self, mut handler: F) -> Result<(), ErrorCode> {
+        let ctx = match self.context() {
+            Ok(ctx) => ctx,
+            Err(error) if error.is_closed() => return Ok(()),
+            Err(error) => return Err(error),
+        };
+        loop {
+            let req = match ctx.recv_msg_async()?.await {
+                Ok(Some(req)) => req,
+                Ok(None) => break Err(error(sys::nng_errno_enum::NNG_EINTERNAL)),
+                Err(error) if error.is_closed() => break Ok(()),
+                Err(error) => break Err(error),
+            };
+
+            match ctx.send_msg_async(handler(req))?.await {
+                Ok(()) => (),
+                Err((_, error)) if error.is_closed() => break Ok(()),
+                Err((_, error)) => break Err(error),
+            }
+        }
+    }
+
+    ///Subscribes sub0 socket to the `topic`, returning guard, that unsubscribes on drop.
+    ///
+    ///nng does not count subscriptions, hence dropping any guard of the same topic unsubscribes
+    ///socket from it.
+    pub fn subscribe(&self, topic: &[u8]) -> Result<Subscription<'_>, ErrorCode> {
+        let mut owned = try_vec(topic.len())?;
+        owned.extend_from_slice(topic);
+        self.subscribe_owned(owned)
+    }
+
+    #[inline]
+    ///Subscribes sub0 socket to messages with exactly `topic`, published via [publish](Self::publish)
+    ///
+    ///Refer to [subscribe](Self::subscribe)
+    pub fn subscribe_topic(&self, topic: &[u8]) -> Result<Subscription<'_>, ErrorCode> {
+        self.subscribe_owned(pubsub::framed_prefix(topic)?)
+    }
+
+    fn subscribe_owned(&self, topic: Vec<u8>) -> Result<Subscription<'_>, ErrorCode> {
+        self.set_opt(Subscribe(&topic))?;
+        Ok(Subscription {
+            socket: self,
+            topic,
+        })
+    }
+
+    #[inline]
+    ///Publishes `payload` under `topic` on pub0 socket.
+    ///
+    ///Message is [framed](pubsub::framed), hence it is received only by subscribers of exactly
+    ///`topic` (i.e. via [subscribe_topic](Self::subscribe_topic)).
+    pub fn publish(&self, topic: &[u8], payload: &[u8]) -> Result<(), ErrorCode> {
+        let msg = pubsub::framed(topic, payload)?;
+        self.send_msg(msg).map_err(|(_, error)| error.into())
+    }
+
+    #[inline]
+    ///Creates [Monitor] of socket's connections.
+    ///
+    ///Monitor subscribes to socket's [PipeNotifier], hence any number of monitors can be created
+    ///alongside other subscribers.
+    pub fn monitor(&self) -> Result<Monitor, ErrorCode> {
+        PipeNotifier::install(self)?.monitor()
+    }
+
+    #[inline(always)]
+    ///Creates [Endpoints] manager, allowing to add and remove listeners and dialers at runtime
+    pub fn endpoints(&self) -> Endpoints<'_> {
+        Endpoints::new(self)
     }
 
     #[inline(always)]
@@ -268,7 +1029,30 @@ impl Socket {
         T::get(self)
     }
 
+    ///Checks that socket communicates with peers of `expected` protocol.
+    ///
+    ///nng rejects connections between incompatible protocols, so this is useful to validate
+    ///socket, created elsewhere, before connecting it to the known peer.
+    pub fn check_peer(&self, expected: Protocol) -> Result<(), PeerError> {
+        let actual = self.get_prop::<PeerName>().map_err(PeerError::Property)?;
+        if actual == expected.name() {
+            Ok(())
+        } else {
+            Err(PeerError::Mismatch {
+                expected,
+                actual,
+            })
+        }
+    }
+
+    #[inline(always)]
+    ///Returns snapshot of all readable options, intended for diagnostics
+    pub fn dump_options(&self) -> Result<SocketOptions, ErrorCode> {
+        self.get_prop()
+    }
+
     fn recv_inner<'a, const FLAGS: c_int>(&self, out: BufMut<'a>) -> Result<&'a [u8], ErrorCode> {
+        self.2.check_recv()?;
         let mut size = out.size;
         let result = unsafe {
             sys::nng_recv(**self, out.ptr as _, &mut size, FLAGS)
@@ -276,12 +1060,18 @@ impl Socket {
 
         match result {
             0 => {
+                self.1.received(size);
+                self.2.received();
                 let out = unsafe {
                     slice::from_raw_parts(out.ptr, size)
                 };
                 Ok(out)
             },
-            code => Err(error(code)),
+            code => {
+                let error = error(code);
+                self.1.recv_failed(&error);
+                Err(error)
+            },
         }
     }
 
@@ -295,8 +1085,8 @@ impl Socket {
     ///
     ///Returns [would block](https://docs.rs/error-code/3.2.0/error_code/struct.ErrorCode.html#method.is_would_block)
     ///error if no message is available.
-    pub fn try_recv<'a>(&self, out: impl Into<BufMut<'a>>) -> Result<&'a [u8], ErrorCode> {
-        self.recv_inner::<{sys::NNG_FLAG_NONBLOCK}>(out.into())
+    pub fn try_recv<'a>(&self, out: impl Into<BufMut<'a>>) -> Result<&'a [u8], OpError> {
+        self.recv_inner::<{sys::NNG_FLAG_NONBLOCK}>(out.into()).map_err(|error| OpError::new(Op::Recv, error))
     }
 
     #[inline(always)]
@@ -305,22 +1095,32 @@ impl Socket {
     ///If underlying protocol doesn't support receiving messages, this shall return error always
     ///
     ///Returns written bytes on success
-    pub fn recv<'a>(&self, out: impl Into<BufMut<'a>>) -> Result<&'a [u8], ErrorCode> {
-        self.recv_inner::<0>(out.into())
+    pub fn recv<'a>(&self, out: impl Into<BufMut<'a>>) -> Result<&'a [u8], OpError> {
+        self.recv_inner::<0>(out.into()).map_err(|error| OpError::new(Op::Recv, error))
     }
 
     ///Receives pending message, waiting forever if none is available.
     ///
     ///If underlying protocol doesn't support receiving messages, this shall return error always
     fn recv_msg_inner<const FLAGS: c_int>(&self) -> Result<Message, ErrorCode> {
+        self.2.check_recv()?;
         let mut msg = ptr::null_mut();
         let result = unsafe {
             sys::nng_recvmsg(**self, &mut msg, FLAGS)
         };
 
         match ptr::NonNull::new(msg) {
-            Some(ptr) => Ok(Message(ptr)),
-            None => Err(error(result)),
+            Some(ptr) => {
+                let msg = Message(ptr);
+                self.1.received(msg.len());
+                self.2.received();
+                Ok(msg)
+            },
+            None => {
+                let error = error(result);
+                self.1.recv_failed(&error);
+                Err(error)
+            },
         }
     }
 
@@ -328,8 +1128,8 @@ impl Socket {
     ///Receives pending message, waiting forever if none is available.
     ///
     ///If underlying protocol doesn't support receiving messages, this shall return error always
-    pub fn recv_msg(&self) -> Result<Message, ErrorCode> {
-        self.recv_msg_inner::<0>()
+    pub fn recv_msg(&self) -> Result<Message, OpError> {
+        self.recv_msg_inner::<0>().map_err(|error| OpError::new(Op::Recv, error))
     }
 
     #[inline]
@@ -338,11 +1138,11 @@ impl Socket {
     ///If underlying protocol doesn't support receiving messages, this shall return error always
     ///
     ///Returns None if no message is available.
-    pub fn try_recv_msg(&self) -> Result<Option<Message>, ErrorCode> {
+    pub fn try_recv_msg(&self) -> Result<Option<Message>, OpError> {
         match self.recv_msg_inner::<{sys::NNG_FLAG_NONBLOCK}>() {
             Ok(msg) => Ok(Some(msg)),
             Err(error) if error.is_would_block() => Ok(None),
-            Err(error) => Err(error)
+            Err(error) => Err(OpError::new(Op::Recv, error))
         }
     }
 
@@ -352,40 +1152,154 @@ impl Socket {
         FutureResp::new(self)
     }
 
+    ///Receives pending message, waiting up to `timeout` if none is available.
+    ///
+    ///Returns None if no message is received within `timeout`.
+    fn recv_msg_timeout(&self, timeout: time::Duration) -> Result<Option<Message>, ErrorCode> {
+        self.2.check_recv()?;
+        let timeout = match i32::try_from(timeout.as_millis()) {
+            Ok(timeout) => timeout,
+            Err(_) => return Err(error(sys::nng_errno_enum::NNG_EINVAL)),
+        };
+        let mut aio = Aio::new()?;
+        unsafe {
+            sys::nng_aio_set_timeout(aio.as_ptr(), timeout);
+            sys::nng_recv_aio(**self, aio.as_ptr());
+            sys::nng_aio_wait(aio.as_ptr());
+        }
+
+        match aio.get_msg() {
+            Ok(Some(msg)) => {
+                self.1.received(msg.len());
+                self.2.received();
+                Ok(Some(msg))
+            },
+            Ok(None) => Ok(None),
+            Err(error) if error.is_timed_out() => Ok(None),
+            Err(error) => {
+                self.1.recv_failed(&error);
+                Err(error)
+            }
+        }
+    }
+
+    #[inline(always)]
+    ///Creates iterator over messages that are immediately available, without waiting.
+    ///
+    ///Iterator ends once there is no pending message or after first error.
+    ///Useful to process messages in batches or to flush socket on shutdown.
+    pub fn drain(&self) -> Drain<'_> {
+        Drain {
+            socket: Some(self),
+        }
+    }
+
+    #[inline(always)]
+    ///Creates iterator over messages, waiting up to `idle` for each message.
+    ///
+    ///Iterator ends once no message is received within `idle` or after first error.
+    ///
+    ///Yields `NNG_EINVAL` error if `idle` in milliseconds doesn't fit into `i32`.
+    pub fn iter_timeout(&self, idle: time::Duration) -> IterTimeout<'_> {
+        IterTimeout {
+            socket: Some(self),
+            idle,
+        }
+    }
+
     #[inline]
     ///Encodes bytes into message and send it over the socket.
     ///
     ///Internally message shall be encoded and sent over
-    pub fn send(&self, msg: Buf<'_>) -> Result<(), ErrorCode> {
+    pub fn send(&self, msg: Buf<'_>) -> Result<(), OpError> {
+        if let Err(error) = self.2.check_send() {
+            return Err(OpError::new(Op::Send, error));
+        }
         let result = unsafe {
             sys::nng_send(**self, msg.ptr as _, msg.size, 0)
         };
 
         match result {
-            0 => Ok(()),
-            code => Err(error(code)),
+            0 => {
+                self.1.sent(msg.size);
+                self.2.sent();
+                Ok(())
+            },
+            code => {
+                self.1.send_failed();
+                Err(OpError::new(Op::Send, error(code)))
+            },
         }
     }
 
-    #[inline]
-    ///Sends message over the socket.
-    ///
-    ///If successful takes ownership of message.
-    ///Otherwise returns message with error code.
-    pub fn send_msg(&self, msg: Message) -> Result<(), (Message, ErrorCode)> {
+    fn send_msg_inner<const FLAGS: c_int>(&self, msg: Message) -> Result<(), (Message, OpError)> {
+        if let Err(error) = self.2.check_send() {
+            return Err((msg, OpError::new(Op::Send, error)));
+        }
+        let size = msg.len();
         let result = unsafe {
-            sys::nng_sendmsg(**self, msg.as_ptr(), 0)
+            sys::nng_sendmsg(**self, msg.as_ptr(), FLAGS)
         };
 
         match result {
             0 => {
                 mem::forget(msg);
+                self.1.sent(size);
+                self.2.sent();
                 Ok(())
             },
-            code => Err((msg, error(code))),
+            code => {
+                let error = error(code);
+                if !error.is_would_block() {
+                    self.1.send_failed();
+                }
+                Err((msg, OpError::new(Op::Send, error)))
+            },
+        }
+    }
+
+    #[inline]
+    ///Sends message over the socket.
+    ///
+    ///If successful takes ownership of message.
+    ///Otherwise returns message with error code.
+    pub fn send_msg(&self, msg: Message) -> Result<(), (Message, OpError)> {
+        self.send_msg_inner::<0>(msg)
+    }
+
+    #[inline]
+    ///Sends message to the specific `pipe` of the socket.
+    ///
+    ///Pipe is only honored by protocols that route messages by pipe, such as polyamorous pair1
+    ///([pair1_poly](Self::pair1_poly)), which drops message if pipe is no longer connected.
+    ///Other protocols ignore it, i.e. pub0 still delivers message to all subscribers.
+    ///
+    ///If successful takes ownership of message.
+    ///Otherwise returns message with error code.
+    pub fn send_msg_to(&self, pipe: &Pipe, mut msg: Message) -> Result<(), (Message, OpError)> {
+        msg.set_pipe(pipe);
+        self.send_msg(msg)
+    }
+
+    ///Sends `msg` to the pipe, `request` was received from.
+    ///
+    ///This is the way to reply to particular peer of polyamorous pair1 socket, refer to [send_msg_to](Self::send_msg_to).
+    ///Returns `NNG_EINVAL` if `request` has no pipe (i.e. it was not received from socket).
+    pub fn reply_to(&self, request: &Message, msg: Message) -> Result<(), (Message, OpError)> {
+        match request.pipe() {
+            Some(pipe) => self.send_msg_to(&pipe, msg),
+            None => Err((msg, OpError::new(Op::Send, error(sys::nng_errno_enum::NNG_EINVAL)))),
         }
     }
 
+    #[inline]
+    ///Attempts to send message over the socket without waiting.
+    ///
+    ///If message cannot be sent immediately, returns it with would-block error.
+    pub fn try_send_msg(&self, msg: Message) -> Result<(), (Message, OpError)> {
+        self.send_msg_inner::<{sys::NNG_FLAG_NONBLOCK}>(msg)
+    }
+
     #[inline]
     ///Sends message over the socket asynchronously.
     ///
@@ -394,19 +1308,52 @@ impl Socket {
     pub fn send_msg_async(&self, msg: Message) -> Result<FutureReq, ErrorCode> {
         FutureReq::new(self, msg)
     }
+
+    #[cfg(feature = "counters")]
+    #[inline]
+    ///Returns snapshot of socket's counters
+    pub fn counters(&self) -> Counters {
+        self.1.get()
+    }
+
+    #[cfg(feature = "counters")]
+    #[inline]
+    ///Resets socket's counters to zero
+    pub fn reset_counters(&self) {
+        self.1.reset()
+    }
 }
 
 impl fmt::Debug for Socket {
-    #[inline(always)]
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
-        fmt.write_fmt(format_args!("Socket(id={})", self.0.id))
+        //Properties are not available once socket is closed
+        match (self.get_prop::<ProtocolName>(), self.get_prop::<Raw>()) {
+            (Ok(protocol), Ok(Raw(raw))) => fmt.write_fmt(format_args!("Socket(id={}, protocol={}, raw={})", self.0.id, protocol, raw)),
+            _ => fmt.write_fmt(format_args!("Socket(id={})", self.0.id)),
+        }
+    }
+}
+
+impl fmt::Display for Socket {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.get_prop::<ProtocolName>() {
+            Ok(protocol) => fmt.write_fmt(format_args!("{}#{}", protocol, self.0.id))?,
+            Err(_) => fmt.write_fmt(format_args!("<closed>#{}", self.0.id))?,
+        }
+
+        match self.get_prop::<Raw>() {
+            Ok(Raw(true)) => fmt.write_str("(raw)"),
+            _ => Ok(()),
+        }
     }
 }
 
 impl Drop for Socket {
-    #[inline(always)]
+    #[inline]
     fn drop(&mut self) {
         self.close();
+        //Once socket is closed, nng no longer invokes pipe callbacks
+        crate::notify::release(self.0);
     }
 }
 
@@ -426,30 +1373,180 @@ impl ops::DerefMut for Socket {
     }
 }
 
+#[derive(Debug)]
+///Iterator over immediately available messages, created via [Socket::drain]
+pub struct Drain<'a> {
+    socket: Option<&'a Socket>,
+}
+
+impl Iterator for Drain<'_> {
+    type Item = Result<Message, ErrorCode>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let socket = self.socket?;
+        match socket.try_recv_msg() {
+            Ok(Some(msg)) => Some(Ok(msg)),
+            Ok(None) => {
+                self.socket = None;
+                None
+            },
+            Err(error) => {
+                self.socket = None;
+                Some(Err(error.into()))
+            }
+        }
+    }
+}
+
+impl core::iter::FusedIterator for Drain<'_> {}
+
+#[derive(Debug)]
+///Iterator over messages, ending after idle period, created via [Socket::iter_timeout]
+pub struct IterTimeout<'a> {
+    socket: Option<&'a Socket>,
+    idle: time::Duration,
+}
+
+impl Iterator for IterTimeout<'_> {
+    type Item = Result<Message, ErrorCode>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let socket = self.socket?;
+        match socket.recv_msg_timeout(self.idle) {
+            Ok(Some(msg)) => Some(Ok(msg)),
+            Ok(None) => {
+                self.socket = None;
+                None
+            },
+            Err(error) => {
+                self.socket = None;
+                Some(Err(error))
+            }
+        }
+    }
+}
+
+impl core::iter::FusedIterator for IterTimeout<'_> {}
+
+///Subscription of the sub0 socket to the topic, created via [Socket::subscribe]
+///
+///Socket is unsubscribed from the topic once guard is dropped.
+pub struct Subscription<'a> {
+    socket: &'a Socket,
+    topic: Vec<u8>,
+}
+
+impl Subscription<'_> {
+    #[inline(always)]
+    ///Returns subscribed topic
+    pub fn topic(&self) -> &[u8] {
+        &self.topic
+    }
+
+    #[inline]
+    ///Unsubscribes from the topic, returning error if it fails
+    pub fn unsubscribe(self) -> Result<(), ErrorCode> {
+        let mut this = mem::ManuallyDrop::new(self);
+        let topic = mem::take(&mut this.topic);
+        this.socket.set_opt(Unsubscribe(&topic))
+    }
+
+    #[inline]
+    ///Keeps socket subscribed to the topic after guard is gone
+    pub fn forget(self) {
+        let mut this = mem::ManuallyDrop::new(self);
+        drop(mem::take(&mut this.topic));
+    }
+}
+
+impl Drop for Subscription<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        let _ = self.socket.set_opt(Unsubscribe(&self.topic));
+    }
+}
+
+impl fmt::Debug for Subscription<'_> {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Subscription").field("socket", self.socket).field("topic", &self.topic).finish()
+    }
+}
+
 ///Futures that resolves into message
 pub struct FutureResp {
     aio: Aio,
+    started: sys::nng_time,
 }
 
 impl FutureResp {
+    #[inline]
     ///Creates new future to retrieve message from the socket
     pub fn new(socket: &Socket) -> Result<Self, ErrorCode> {
+        //Outcome is unknown, hence request is assumed to be received
+        socket.2.received();
+        Self::start(|aio| unsafe {
+            sys::nng_recv_aio(**socket, aio)
+        })
+    }
+
+    pub(crate) fn start<F: FnOnce(*mut sys::nng_aio)>(submit: F) -> Result<Self, ErrorCode> {
         let aio = Aio::new()?;
-        unsafe {
-            sys::nng_recv_aio(**socket, aio.as_ptr())
-        }
+        let started = unsafe {
+            sys::nng_clock()
+        };
+        submit(aio.as_ptr());
 
         Ok(Self {
-            aio
+            aio,
+            started,
         })
     }
 
+    #[cfg(feature = "std")]
+    #[inline(always)]
+    pub(crate) fn aio_ptr(&self) -> *mut sys::nng_aio {
+        self.aio.as_ptr()
+    }
+
+    #[inline(always)]
+    ///Returns whether operation is still in progress
+    pub fn is_in_flight(&self) -> bool {
+        self.aio.is_busy()
+    }
+
+    #[inline(always)]
+    ///Returns whether operation is complete, meaning future is ready to yield its result
+    pub fn is_finished(&self) -> bool {
+        self.aio.is_ready()
+    }
+
+    #[inline]
+    ///Returns time elapsed since operation was submitted
+    pub fn elapsed(&self) -> time::Duration {
+        let now = unsafe {
+            sys::nng_clock()
+        };
+        time::Duration::from_millis(now.saturating_sub(self.started) as _)
+    }
+
     ///Sets future for cancelling
     pub fn cancel(&self) {
         unsafe {
             sys::nng_aio_cancel(self.aio.as_ptr())
         }
     }
+
+    ///Aborts future, completing it with specified `error`
+    ///
+    ///Unlike [cancel](Self::cancel), this allows to distinguish reason of failure, for example to
+    ///signal shutdown with application defined error code.
+    ///Has no effect if operation is already complete.
+    pub fn abort(&self, error: ErrorCode) {
+        unsafe {
+            sys::nng_aio_abort(self.aio.as_ptr(), error.raw_code())
+        }
+    }
 }
 
 impl Future for FutureResp {
@@ -469,31 +1566,82 @@ impl Future for FutureResp {
 ///Futures that awaits message to be sent
 pub struct FutureReq {
     aio: Aio,
+    started: sys::nng_time,
 }
 
 impl FutureReq {
+    #[inline]
     ///Creates new future taking ownership over `msg`
     pub fn new(socket: &Socket, msg: Message) -> Result<Self, ErrorCode> {
+        socket.2.sent();
+        Self::start(msg, |aio| unsafe {
+            sys::nng_send_aio(**socket, aio)
+        })
+    }
+
+    pub(crate) fn start<F: FnOnce(*mut sys::nng_aio)>(msg: Message, submit: F) -> Result<Self, ErrorCode> {
         let aio = Aio::new()?;
         unsafe {
             sys::nng_aio_set_msg(aio.as_ptr(), msg.as_ptr());
-            sys::nng_send_aio(**socket, aio.as_ptr())
         }
+        let started = unsafe {
+            sys::nng_clock()
+        };
+        submit(aio.as_ptr());
 
         //AIO takes ownership of the message
         mem::forget(msg);
 
         Ok(Self {
-            aio
+            aio,
+            started,
         })
     }
 
+    #[cfg(feature = "std")]
+    #[inline(always)]
+    pub(crate) fn aio_ptr(&self) -> *mut sys::nng_aio {
+        self.aio.as_ptr()
+    }
+
+    #[inline(always)]
+    ///Returns whether operation is still in progress
+    pub fn is_in_flight(&self) -> bool {
+        self.aio.is_busy()
+    }
+
+    #[inline(always)]
+    ///Returns whether operation is complete, meaning future is ready to yield its result
+    pub fn is_finished(&self) -> bool {
+        self.aio.is_ready()
+    }
+
+    #[inline]
+    ///Returns time elapsed since operation was submitted
+    pub fn elapsed(&self) -> time::Duration {
+        let now = unsafe {
+            sys::nng_clock()
+        };
+        time::Duration::from_millis(now.saturating_sub(self.started) as _)
+    }
+
     ///Sets future for cancelling
     pub fn cancel(&self) {
         unsafe {
             sys::nng_aio_cancel(self.aio.as_ptr())
         }
     }
+
+    ///Aborts future, completing it with specified `error`
+    ///
+    ///Unlike [cancel](Self::cancel), this allows to distinguish reason of failure, for example to
+    ///signal shutdown with application defined error code.
+    ///Has no effect if operation is already complete.
+    pub fn abort(&self, error: ErrorCode) {
+        unsafe {
+            sys::nng_aio_abort(self.aio.as_ptr(), error.raw_code())
+        }
+    }
 }
 
 impl Future for FutureReq {
@@ -511,10 +1659,11 @@ impl Future for FutureReq {
 }
 
 ///Socket listener
-pub struct Listener(pub(crate) sys::nng_listener);
+pub struct Listener(pub(crate) sys::nng_listener, pub(crate) sys::nng_socket);
 
 impl Listener {
-    pub(crate) fn new(socket: &Socket, url: String<'_>) -> Result<Self, ErrorCode> {
+    pub(crate) fn new(socket: &Socket, url: &String<'_>) -> Result<Self, ErrorCode> {
+        check_scheme(url.as_bytes())?;
         let url = url.as_ptr();
         let mut this = sys::nng_listener {
             id: 0
@@ -525,7 +1674,7 @@ impl Listener {
         };
 
         match result {
-            0 => Ok(Self(this)),
+            0 => Ok(Self(this, **socket)),
             code => Err(error(code))
         }
     }
@@ -540,6 +1689,76 @@ impl Listener {
             code => Err(error(code))
         }
     }
+
+    ///Sets `filter` to decide whether to accept connection, before any message is exchanged.
+    ///
+    ///Rejected connections are closed right away.
+    ///
+    ///Filter only applies to connections accepted by this listener. Multiple filters can be set,
+    ///in which case connection is accepted only if every filter accepts it.
+    ///Filter is subscribed to socket's [PipeNotifier], hence it can be used together with other subscribers.
+    pub fn set_accept_filter(&self, filter: fn(&Pipe) -> bool) -> Result<(), ErrorCode> {
+        let notifier = PipeNotifier::install_raw(self.1)?;
+        notifier.subscribe_listener(self.0, move |pipe, event| if event == PipeEvent::AddPre && !(filter)(&pipe) {
+            let _ = pipe.close();
+        });
+        Ok(())
+    }
+
+    ///Limits number of simultaneously connected pipes, accepted by this listener, to `limit`.
+    ///
+    ///Connections in excess of the limit are closed right away, before any message is exchanged.
+    ///
+    ///Limit is subscribed to socket's [PipeNotifier], hence it can be used together with other subscribers.
+    pub fn set_max_connections(&self, limit: usize) -> Result<(), ErrorCode> {
+        let notifier = PipeNotifier::install_raw(self.1)?;
+        //Ids of pipes accepted by listener
+        let pipes = crate::utils::sync::Mutex::new(Vec::<u32>::new())?;
+        notifier.subscribe_listener(self.0, move |pipe, event| match event {
+            PipeEvent::AddPre => {
+                let mut pipes = pipes.lock();
+                if pipes.len() < limit {
+                    pipes.push(pipe.0.id);
+                } else {
+                    drop(pipes);
+                    let _ = pipe.close();
+                }
+            },
+            //Also delivered for pipes, rejected on AddPre
+            PipeEvent::RemPost => {
+                let mut pipes = pipes.lock();
+                if let Some(idx) = pipes.iter().position(|id| *id == pipe.0.id) {
+                    pipes.swap_remove(idx);
+                }
+            },
+            PipeEvent::AddPost => (),
+        });
+        Ok(())
+    }
+}
+
+///Options to set accept filter on [Listener]
+///
+///Refer to [Listener::set_accept_filter] for details.
+pub struct AcceptFilter(pub fn(&Pipe) -> bool);
+
+impl Options<Listener> for AcceptFilter {
+    #[inline]
+    fn apply(&self, target: &Listener) -> Result<(), ErrorCode> {
+        target.set_accept_filter(self.0)
+    }
+}
+
+///Options to limit number of connections accepted by [Listener]
+///
+///Refer to [Listener::set_max_connections] for details.
+pub struct MaxConnections(pub usize);
+
+impl Options<Listener> for MaxConnections {
+    #[inline]
+    fn apply(&self, target: &Listener) -> Result<(), ErrorCode> {
+        target.set_max_connections(self.0)
+    }
 }
 
 impl Drop for Listener {
@@ -548,6 +1767,7 @@ impl Drop for Listener {
         unsafe {
             sys::nng_listener_close(self.0);
         }
+        crate::notify::release_listener(self.1, self.0);
     }
 }
 
@@ -555,7 +1775,8 @@ impl Drop for Listener {
 pub struct Dialer(pub(crate) sys::nng_dialer);
 
 impl Dialer {
-    pub(crate) fn new(socket: &Socket, url: String<'_>) -> Result<Self, ErrorCode> {
+    pub(crate) fn new(socket: &Socket, url: &String<'_>) -> Result<Self, ErrorCode> {
+        check_scheme(url.as_bytes())?;
         let url = url.as_ptr();
         let mut this = sys::nng_dialer {
             id: 0
@@ -571,6 +1792,39 @@ impl Dialer {
         }
     }
 
+    fn set_local_addr(&self, addr: IpAddr) -> Result<(), ErrorCode> {
+        let mut raw = unsafe {
+            mem::zeroed::<sys::nng_sockaddr>()
+        };
+        //Port is chosen by OS, as nng doesn't allow to bind dialer to specific port
+        match addr {
+            IpAddr::V4(addr) => {
+                raw.s_in = sys::nng_sockaddr_in {
+                    sa_family: sys::nng_sockaddr_family::NNG_AF_INET as _,
+                    sa_port: 0,
+                    sa_addr: u32::from_ne_bytes(addr.octets()),
+                };
+            },
+            IpAddr::V6(addr) => {
+                raw.s_in6 = sys::nng_sockaddr_in6 {
+                    sa_family: sys::nng_sockaddr_family::NNG_AF_INET6 as _,
+                    sa_port: 0,
+                    sa_addr: addr.octets(),
+                    sa_scope: 0,
+                };
+            },
+        }
+
+        let result = unsafe {
+            sys::nng_dialer_set_addr(self.0, sys::NNG_OPT_LOCADDR.as_ptr() as _, &raw)
+        };
+
+        match result {
+            0 => Ok(()),
+            code => Err(error(code))
+        }
+    }
+
     pub(crate) fn start(&self, flags: c_int) -> Result<(), ErrorCode> {
         let result = unsafe {
             sys::nng_dialer_start(self.0, flags)
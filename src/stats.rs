@@ -0,0 +1,755 @@
+//!Statistics module
+//!
+//!Provides access to nng's statistics tree, as well as helpers built on top of it.
+//!
+//!## Queue depth
+//!
+//!nng does not expose occupancy of socket's queues, therefore [QueueDepth] estimates it by comparing
+//!socket's [counters](crate::socket::Counters) with number of messages transferred by socket's pipes.
+//!
+//!Keep in mind following limitations:
+//!
+//!- Only synchronous operations performed directly via [Socket] are counted by socket, hence asynchronous
+//!  or context operations make estimate meaningless;
+//!- Only `tcp`, `ipc` and `tls` transports count messages, hence socket with `inproc` pipes is rejected;
+//!- Statistics of pipe are discarded once pipe is closed;
+//!- Protocol may send or drop messages on its own (e.g. req0 re-sending request).
+//!
+//!As such, it is only suitable for long living connections where socket is used via synchronous API only.
+//!
+//!## Slow consumers
+//!
+//!pub0 socket never blocks, silently dropping messages for subscribers which cannot keep up.
+//![SlowConsumers] detects such subscribers by comparing number of messages sent via socket with
+//!number of messages written by each pipe, relying on the same statistics as [QueueDepth].
+//!As pub0 sends every message to all subscribers, number of messages written by the fastest subscriber is used
+//!whenever it is greater.
+//!
+//!Keep in mind following limitations:
+//!
+//!- Messages sent asynchronously (e.g. via [send_msg_async](Socket::send_msg_async)) are not counted by socket,
+//!  hence they are only accounted for by the fastest subscriber. As such, slow subscriber is detected only
+//!  as long as there is other subscriber keeping up, while sole subscriber of such publisher is never reported;
+//!- Pipes of `inproc` transport are not counted, hence they are never considered slow.
+//!
+//!## Sampling
+//!
+//![Sampler] takes snapshot every interval, yielding [Sample] with previous and current snapshots,
+//!so that rates can be derived from counters:
+//!
+//!```rust,no_run
+//!use nng_c::Socket;
+//!use nng_c::stats;
+//!
+//!use core::time;
+//!
+//!let socket = Socket::pair1().expect("create socket");
+//!let mut sampler = stats::sampler(time::Duration::from_secs(1)).expect("create sampler");
+//!loop {
+//!    let sample = sampler.wait().expect("take sample");
+//!    let rate = sample.rate(|snapshot| snapshot.socket(&socket)?.child("tx_msgs"));
+//!    println!("msgs/sec: {:?}", rate);
+//!}
+//!```
+
+use crate::ErrorCode;
+use crate::error::error;
+use crate::options::{Address, RecvBuf, RemoteAddr, SendBuf};
+use crate::pipe::Pipe;
+use crate::socket::Socket;
+use crate::sys;
+
+use crate::aio::Sleep;
+
+use core::ffi::CStr;
+use core::ptr::NonNull;
+use core::{fmt, marker, mem, time};
+
+use alloc::vec::Vec;
+
+#[inline(always)]
+fn to_str<'a>(ptr: *const core::ffi::c_char) -> &'a str {
+    if ptr.is_null() {
+        return "";
+    }
+
+    let value = unsafe {
+        CStr::from_ptr(ptr)
+    };
+    value.to_str().unwrap_or_default()
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+///Kind of statistic
+pub enum Kind {
+    ///Group of statistics, identified by its [id](Stat::id)
+    Scope,
+    ///Value that can go up and down
+    Level,
+    ///Value that only increases
+    Counter,
+    ///String
+    String,
+    ///Boolean
+    Boolean,
+    ///Identifier of object (e.g. socket)
+    Id,
+}
+
+///Snapshot of all statistics
+///
+///Requires `stats` feature, otherwise nng does not collect any statistics.
+pub struct Snapshot(NonNull<sys::nng_stat>);
+
+unsafe impl Send for Snapshot {}
+unsafe impl Sync for Snapshot {}
+
+impl Snapshot {
+    ///Takes snapshot of current statistics
+    pub fn get() -> Result<Self, ErrorCode> {
+        let mut ptr = core::ptr::null_mut();
+        let result = unsafe {
+            sys::nng_stats_get(&mut ptr)
+        };
+
+        match result {
+            0 => match NonNull::new(ptr) {
+                Some(ptr) => Ok(Self(ptr)),
+                None => Err(error(sys::nng_errno_enum::NNG_ENOMEM)),
+            },
+            code => Err(error(code)),
+        }
+    }
+
+    #[inline(always)]
+    ///Returns root of the statistics tree
+    pub fn root(&self) -> Stat<'_> {
+        Stat::new(self.0)
+    }
+
+    #[inline]
+    ///Returns scope of `socket` statistics, if any
+    pub fn socket(&self, socket: &Socket) -> Option<Stat<'_>> {
+        let ptr = unsafe {
+            sys::nng_stat_find_socket(self.0.as_ptr(), **socket)
+        };
+        NonNull::new(ptr).map(Stat::new)
+    }
+
+    ///Returns iterator over scopes of pipes statistics, belonging to the `socket`
+    pub fn pipes<'a>(&'a self, socket: &Socket) -> impl Iterator<Item = Stat<'a>> + 'a {
+        let id = unsafe {
+            sys::nng_socket_id(**socket)
+        };
+        self.root().children().filter(move |stat| {
+            stat.kind() == Kind::Scope && stat.name() == "pipe" && stat.child("socket").map(|socket| socket.id()) == Some(id)
+        })
+    }
+}
+
+impl fmt::Debug for Snapshot {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_fmt(format_args!("Snapshot({:p})", self.0))
+    }
+}
+
+impl Drop for Snapshot {
+    #[inline(always)]
+    fn drop(&mut self) {
+        unsafe {
+            sys::nng_stats_free(self.0.as_ptr())
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+///Single statistic within [Snapshot]
+pub struct Stat<'a> {
+    ptr: NonNull<sys::nng_stat>,
+    _snapshot: marker::PhantomData<&'a Snapshot>,
+}
+
+impl<'a> Stat<'a> {
+    #[inline(always)]
+    fn new(ptr: NonNull<sys::nng_stat>) -> Self {
+        Self {
+            ptr,
+            _snapshot: marker::PhantomData,
+        }
+    }
+
+    #[inline]
+    ///Returns name of the statistic
+    pub fn name(&self) -> &'a str {
+        to_str(unsafe {
+            sys::nng_stat_name(self.ptr.as_ptr())
+        })
+    }
+
+    #[inline]
+    ///Returns description of the statistic
+    pub fn description(&self) -> &'a str {
+        to_str(unsafe {
+            sys::nng_stat_desc(self.ptr.as_ptr())
+        })
+    }
+
+    ///Returns kind of the statistic
+    pub fn kind(&self) -> Kind {
+        let kind = unsafe {
+            sys::nng_stat_type(self.ptr.as_ptr())
+        };
+
+        match kind {
+            sys::nng_stat_type_enum::NNG_STAT_SCOPE => Kind::Scope,
+            sys::nng_stat_type_enum::NNG_STAT_LEVEL => Kind::Level,
+            sys::nng_stat_type_enum::NNG_STAT_COUNTER => Kind::Counter,
+            sys::nng_stat_type_enum::NNG_STAT_STRING => Kind::String,
+            sys::nng_stat_type_enum::NNG_STAT_BOOLEAN => Kind::Boolean,
+            _ => Kind::Id,
+        }
+    }
+
+    #[inline]
+    ///Returns value of [Counter](Kind::Counter) or [Level](Kind::Level)
+    pub fn value(&self) -> u64 {
+        unsafe {
+            sys::nng_stat_value(self.ptr.as_ptr())
+        }
+    }
+
+    #[inline]
+    ///Returns identifier of [Scope](Kind::Scope) or [Id](Kind::Id)
+    pub fn id(&self) -> i32 {
+        //nng has no dedicated getter for identifiers, reporting them via nng_stat_value
+        self.value() as i32
+    }
+
+    #[inline]
+    ///Returns value of [Boolean](Kind::Boolean)
+    pub fn boolean(&self) -> bool {
+        unsafe {
+            sys::nng_stat_bool(self.ptr.as_ptr())
+        }
+    }
+
+    #[inline]
+    ///Returns value of [String](Kind::String), or empty string for any other kind
+    pub fn string(&self) -> &'a str {
+        to_str(unsafe {
+            sys::nng_stat_string(self.ptr.as_ptr())
+        })
+    }
+
+    #[inline]
+    ///Returns iterator over direct children of the statistic
+    pub fn children(&self) -> Children<'a> {
+        Children {
+            next: NonNull::new(unsafe {
+                sys::nng_stat_child(self.ptr.as_ptr())
+            }),
+            _snapshot: marker::PhantomData,
+        }
+    }
+
+    #[inline]
+    ///Returns direct child with specified `name`
+    pub fn child(&self, name: &str) -> Option<Self> {
+        self.children().find(|stat| stat.name() == name)
+    }
+}
+
+impl fmt::Debug for Stat<'_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut out = fmt.debug_struct("Stat");
+        out.field("name", &self.name());
+        match self.kind() {
+            Kind::Scope | Kind::Id => out.field("id", &self.id()),
+            Kind::Level | Kind::Counter => out.field("value", &self.value()),
+            Kind::String => out.field("value", &self.string()),
+            Kind::Boolean => out.field("value", &self.boolean()),
+        };
+        out.finish()
+    }
+}
+
+///Iterator over children of [Stat]
+pub struct Children<'a> {
+    next: Option<NonNull<sys::nng_stat>>,
+    _snapshot: marker::PhantomData<&'a Snapshot>,
+}
+
+impl<'a> Iterator for Children<'a> {
+    type Item = Stat<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let stat = self.next?;
+        self.next = NonNull::new(unsafe {
+            sys::nng_stat_next(stat.as_ptr())
+        });
+        Some(Stat::new(stat))
+    }
+}
+
+#[inline]
+///Creates [Sampler], taking snapshot of statistics every `interval`
+pub fn sampler(interval: time::Duration) -> Result<Sampler, ErrorCode> {
+    Sampler::new(interval)
+}
+
+#[inline(always)]
+fn clock() -> sys::nng_time {
+    unsafe {
+        sys::nng_clock()
+    }
+}
+
+///Periodic sampler of statistics
+///
+///Every sample is taken `interval` after previous one, regardless of time spent processing it, unless it takes longer than `interval`.
+pub struct Sampler {
+    interval: time::Duration,
+    previous: Option<Snapshot>,
+    current: Snapshot,
+    time: sys::nng_time,
+}
+
+impl Sampler {
+    ///Creates new sampler, taking initial snapshot right away
+    pub fn new(interval: time::Duration) -> Result<Self, ErrorCode> {
+        Ok(Self {
+            interval,
+            previous: None,
+            current: Snapshot::get()?,
+            time: clock(),
+        })
+    }
+
+    #[inline(always)]
+    fn remaining(&self) -> time::Duration {
+        let elapsed = clock().saturating_sub(self.time);
+        self.interval.saturating_sub(time::Duration::from_millis(elapsed as _))
+    }
+
+    fn sample(&mut self) -> Result<Sample<'_>, ErrorCode> {
+        let snapshot = Snapshot::get()?;
+        let now = clock();
+        let elapsed = time::Duration::from_millis(now.saturating_sub(self.time) as _);
+        self.time = now;
+
+        let previous = mem::replace(&mut self.current, snapshot);
+        Ok(Sample {
+            previous: self.previous.insert(previous),
+            current: &self.current,
+            elapsed,
+        })
+    }
+
+    ///Blocks until next sample is due, taking it.
+    pub fn wait(&mut self) -> Result<Sample<'_>, ErrorCode> {
+        let remaining = self.remaining();
+        if !remaining.is_zero() {
+            unsafe {
+                sys::nng_msleep(remaining.as_millis() as _);
+            }
+        }
+        self.sample()
+    }
+
+    ///Awaits next sample, taking it.
+    pub async fn next(&mut self) -> Result<Sample<'_>, ErrorCode> {
+        let remaining = self.remaining();
+        if !remaining.is_zero() {
+            Sleep::new(remaining)?.await?;
+        }
+        self.sample()
+    }
+}
+
+impl fmt::Debug for Sampler {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Sampler").field("interval", &self.interval).finish()
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+///Pair of consecutive snapshots, taken by [Sampler]
+pub struct Sample<'a> {
+    previous: &'a Snapshot,
+    current: &'a Snapshot,
+    elapsed: time::Duration,
+}
+
+impl<'a> Sample<'a> {
+    #[inline(always)]
+    ///Returns snapshot, taken by previous sample
+    pub fn previous(&self) -> &'a Snapshot {
+        self.previous
+    }
+
+    #[inline(always)]
+    ///Returns snapshot, taken by this sample
+    pub fn current(&self) -> &'a Snapshot {
+        self.current
+    }
+
+    #[inline(always)]
+    ///Returns time elapsed since previous sample
+    pub fn elapsed(&self) -> time::Duration {
+        self.elapsed
+    }
+
+    ///Returns change of the statistic, found via `select`, since previous sample.
+    ///
+    ///Statistic missing from previous snapshot (e.g. of new pipe) is considered to start from 0.
+    ///Decrease of [Level](Kind::Level) is reported as 0.
+    ///
+    ///Returns `None` if statistic is missing from current snapshot.
+    pub fn delta<F: for<'s> Fn(&'s Snapshot) -> Option<Stat<'s>>>(&self, select: F) -> Option<u64> {
+        let current = select(self.current)?.value();
+        let previous = select(self.previous).map_or(0, |stat| stat.value());
+        Some(current.saturating_sub(previous))
+    }
+
+    ///Returns rate per second of the statistic, found via `select`, since previous sample.
+    ///
+    ///Refer to [delta](Self::delta) for details.
+    pub fn rate<F: for<'s> Fn(&'s Snapshot) -> Option<Stat<'s>>>(&self, select: F) -> Option<f64> {
+        let delta = self.delta(select)?;
+        let elapsed = self.elapsed.as_secs_f64();
+        if elapsed > 0.0 {
+            Some(delta as f64 / elapsed)
+        } else {
+            None
+        }
+    }
+}
+
+#[inline]
+fn is_inproc(id: i32) -> bool {
+    let pipe = Pipe(sys::nng_pipe { id: id as _ });
+    matches!(pipe.get_prop(), Ok(RemoteAddr(Address::Inproc(_))))
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+///Estimated number of messages in socket's queues
+///
+///Estimate is meaningful only for socket used via synchronous API.
+///Refer to [module](self) documentation for limitations.
+pub struct QueueDepth {
+    ///Number of messages sent via socket, but not yet written by pipes
+    pub send: usize,
+    ///Number of messages read by pipes, but not yet received via socket
+    pub recv: usize,
+}
+
+impl QueueDepth {
+    ///Estimates queue depth of the `socket`
+    ///
+    ///Returns `NNG_ENOTSUP` if socket has `inproc` pipes, which do not count messages.
+    pub fn of(socket: &Socket) -> Result<Self, ErrorCode> {
+        let snapshot = Snapshot::get()?;
+        let mut tx_msgs = 0u64;
+        let mut rx_msgs = 0u64;
+        for pipe in snapshot.pipes(socket) {
+            if matches!(pipe.child("id"), Some(id) if is_inproc(id.id())) {
+                return Err(error(sys::nng_errno_enum::NNG_ENOTSUP));
+            }
+            tx_msgs += pipe.child("tx_msgs").map_or(0, |stat| stat.value());
+            rx_msgs += pipe.child("rx_msgs").map_or(0, |stat| stat.value());
+        }
+
+        let counters = socket.counters();
+        Ok(Self {
+            send: (counters.msgs_sent as u64).saturating_sub(tx_msgs) as usize,
+            recv: rx_msgs.saturating_sub(counters.msgs_recv as u64) as usize,
+        })
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+///Socket's queue
+pub enum Queue {
+    ///Send queue, limited by [SendBuf]
+    Send,
+    ///Receive queue, limited by [RecvBuf]
+    Recv,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+///Event emitted when queue crosses watermark
+pub struct WatermarkEvent {
+    ///Queue that crossed watermark
+    pub queue: Queue,
+    ///Whether queue is above high watermark now.
+    ///
+    ///Otherwise it went below low watermark.
+    pub high: bool,
+    ///Current queue depth
+    pub depth: usize,
+    ///Configured queue capacity
+    pub capacity: usize,
+}
+
+///Queue watermark monitor
+///
+///Watermarks are specified as percentage of queue capacity.
+///Once queue depth reaches high watermark, callback is invoked and it will not be invoked again
+///until queue depth goes down to low watermark.
+///
+///Monitor has no background activity, requiring user to [check](Self::check) socket periodically.
+pub struct Watermarks<F> {
+    high: u8,
+    low: u8,
+    send_high: bool,
+    recv_high: bool,
+    callback: F,
+}
+
+impl<F: FnMut(WatermarkEvent)> Watermarks<F> {
+    #[inline]
+    ///Creates new monitor with default high watermark of 80% and low watermark of 50%
+    pub const fn new(callback: F) -> Self {
+        Self {
+            high: 80,
+            low: 50,
+            send_high: false,
+            recv_high: false,
+            callback,
+        }
+    }
+
+    #[inline]
+    ///Sets watermarks as percentage of capacity, limited to 100.
+    ///
+    ///`low` is limited by `high`
+    pub const fn with_watermarks(mut self, high: u8, low: u8) -> Self {
+        self.high = if high > 100 { 100 } else { high };
+        self.low = if low > self.high { self.high } else { low };
+        self
+    }
+
+    ///Checks queues of the `socket`, invoking callback if any watermark is crossed.
+    ///
+    ///Returns current queue depth.
+    pub fn check(&mut self, socket: &Socket) -> Result<QueueDepth, ErrorCode> {
+        let depth = QueueDepth::of(socket)?;
+        let SendBuf(send_capacity) = socket.get_prop()?;
+        let RecvBuf(recv_capacity) = socket.get_prop()?;
+
+        self.check_queue(Queue::Send, depth.send, send_capacity as usize);
+        self.check_queue(Queue::Recv, depth.recv, recv_capacity as usize);
+        Ok(depth)
+    }
+
+    fn check_queue(&mut self, queue: Queue, depth: usize, capacity: usize) {
+        let is_high = match queue {
+            Queue::Send => &mut self.send_high,
+            Queue::Recv => &mut self.recv_high,
+        };
+        //Unbuffered queue is considered full with single message
+        let high = core::cmp::max(1, capacity * self.high as usize / 100);
+        let low = capacity * self.low as usize / 100;
+
+        let crossed = if *is_high {
+            depth <= low
+        } else {
+            depth >= high
+        };
+
+        if crossed {
+            *is_high = !*is_high;
+            (self.callback)(WatermarkEvent {
+                queue,
+                high: *is_high,
+                depth,
+                capacity,
+            });
+        }
+    }
+}
+
+impl<F> fmt::Debug for Watermarks<F> {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Watermarks").field("high", &self.high).field("low", &self.low).finish()
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+///Action taken once slow consumer is detected
+pub enum SlowConsumerAction {
+    ///Only invoke callback
+    Notify,
+    ///Invoke callback and close pipe of the consumer
+    Close,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+///Event emitted when consumer persistently fails to keep up
+pub struct SlowConsumerEvent {
+    ///Pipe of the consumer
+    pub pipe: Pipe,
+    ///Number of messages sent via socket, but not written by pipe since it is tracked.
+    ///
+    ///These messages are either queued or dropped.
+    pub backlog: u64,
+    ///Number of consecutive checks during which consumer failed to keep up
+    pub strikes: u32,
+    ///Whether pipe has been closed
+    pub closed: bool,
+}
+
+struct TrackedPipe {
+    id: i32,
+    tx_msgs: u64,
+    backlog: u64,
+    strikes: u32,
+    reported: bool,
+}
+
+///Slow consumer monitor
+///
+///On every [check](Self::check), consumer which wrote fewer messages than were sent via socket
+///(or written by the fastest consumer, if greater) since previous check, while having backlog of at least `max_backlog` messages, receives strike.
+///Once consumer accumulates configured number of consecutive strikes, callback is invoked and
+///configured [action](SlowConsumerAction) is taken. Consumer is reported only once until it
+///catches up again.
+///
+///Monitor has no background activity, requiring user to [check](Self::check) socket periodically.
+///Refer to [module](self) documentation for limitations.
+pub struct SlowConsumers<F> {
+    max_backlog: u64,
+    strikes: u32,
+    action: SlowConsumerAction,
+    msgs_sent: u64,
+    pipes: Vec<TrackedPipe>,
+    callback: F,
+}
+
+impl<F: FnMut(SlowConsumerEvent)> SlowConsumers<F> {
+    #[inline]
+    ///Creates new monitor, reporting consumers with backlog of 100 messages for 3 consecutive checks
+    pub const fn new(callback: F) -> Self {
+        Self {
+            max_backlog: 100,
+            strikes: 3,
+            action: SlowConsumerAction::Notify,
+            msgs_sent: 0,
+            pipes: Vec::new(),
+            callback,
+        }
+    }
+
+    #[inline]
+    ///Sets minimal backlog of slow consumer
+    pub const fn with_max_backlog(mut self, max_backlog: u64) -> Self {
+        self.max_backlog = max_backlog;
+        self
+    }
+
+    #[inline]
+    ///Sets number of consecutive strikes before consumer is reported, treating zero as 1
+    pub const fn with_strikes(mut self, strikes: u32) -> Self {
+        self.strikes = if strikes == 0 { 1 } else { strikes };
+        self
+    }
+
+    #[inline]
+    ///Sets action to take once slow consumer is detected
+    pub const fn with_action(mut self, action: SlowConsumerAction) -> Self {
+        self.action = action;
+        self
+    }
+
+    ///Checks pipes of the `socket`, invoking callback for every detected slow consumer.
+    ///
+    ///Returns number of pipes currently considered slow, including already reported ones.
+    pub fn check(&mut self, socket: &Socket) -> Result<usize, ErrorCode> {
+        let snapshot = Snapshot::get()?;
+        let msgs_sent = socket.counters().msgs_sent as u64;
+        //Counters might have been reset
+        let sent = msgs_sent.saturating_sub(self.msgs_sent);
+        self.msgs_sent = msgs_sent;
+
+        let mut stats = Vec::with_capacity(self.pipes.len());
+        for stat in snapshot.pipes(socket) {
+            if let Some(id) = stat.child("id") {
+                stats.push((id.id(), stat.child("tx_msgs").map_or(0, |stat| stat.value())));
+            }
+        }
+        //Every message is sent to all consumers, so the fastest one accounts for messages not counted by socket
+        let sent = stats.iter().filter_map(|(id, tx_msgs)| {
+            self.pipes.iter().find(|pipe| pipe.id == *id).map(|pipe| tx_msgs.saturating_sub(pipe.tx_msgs))
+        }).fold(sent, core::cmp::max);
+
+        let mut pipes = Vec::with_capacity(self.pipes.len());
+        let mut slow = 0;
+        for (id, tx_msgs) in stats {
+            let mut tracked = match self.pipes.iter().position(|pipe| pipe.id == id) {
+                Some(idx) => self.pipes.swap_remove(idx),
+                //New pipe starts tracking from the current state
+                None => {
+                    if is_inproc(id) {
+                        continue;
+                    }
+                    pipes.push(TrackedPipe {
+                        id,
+                        tx_msgs,
+                        backlog: 0,
+                        strikes: 0,
+                        reported: false,
+                    });
+                    continue;
+                }
+            };
+
+            let written = tx_msgs.saturating_sub(tracked.tx_msgs);
+            tracked.tx_msgs = tx_msgs;
+            tracked.backlog = (tracked.backlog + sent).saturating_sub(written);
+
+            if written < sent && tracked.backlog >= self.max_backlog {
+                tracked.strikes = tracked.strikes.saturating_add(1);
+            } else if written >= sent {
+                tracked.strikes = 0;
+                tracked.reported = false;
+            }
+
+            if tracked.strikes >= self.strikes {
+                slow += 1;
+                if !tracked.reported {
+                    tracked.reported = true;
+                    let pipe = Pipe(sys::nng_pipe { id: id as _ });
+                    let closed = match self.action {
+                        SlowConsumerAction::Notify => false,
+                        SlowConsumerAction::Close => pipe.close().is_ok(),
+                    };
+                    (self.callback)(SlowConsumerEvent {
+                        pipe,
+                        backlog: tracked.backlog,
+                        strikes: tracked.strikes,
+                        closed,
+                    });
+                }
+            }
+            pipes.push(tracked);
+        }
+
+        //Pipes missing from snapshot are gone
+        self.pipes = pipes;
+        Ok(slow)
+    }
+}
+
+impl<F> fmt::Debug for SlowConsumers<F> {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("SlowConsumers").field("max_backlog", &self.max_backlog)
+                                         .field("strikes", &self.strikes)
+                                         .field("action", &self.action)
+                                         .field("pipes", &self.pipes.len())
+                                         .finish()
+    }
+}
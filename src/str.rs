@@ -1,6 +1,9 @@
 //! C String wrapper
 use core::{fmt, mem};
 
+use crate::ErrorCode;
+use crate::fallible::try_vec;
+
 use alloc::vec::Vec;
 
 ///String's static buffer size
@@ -33,7 +36,7 @@ impl<'a> String<'a> {
     ///
     ///Returns None if input has no NULL character at the end
     pub const fn try_new_c(string: &'a [u8]) -> Option<Self> {
-        if string[string.len() - 1] == 0 {
+        if !string.is_empty() && string[string.len() - 1] == 0 {
             Some(Self {
                 state: State::Slice(string)
             })
@@ -47,7 +50,7 @@ impl<'a> String<'a> {
     ///
     ///Panics if input has no NULL character at the end
     pub const fn new_c(string: &'a [u8]) -> Self {
-        if string[string.len() - 1] == 0 {
+        if !string.is_empty() && string[string.len() - 1] == 0 {
             Self {
                 state: State::Slice(string)
             }
@@ -60,14 +63,13 @@ impl<'a> String<'a> {
     ///
     ///If `string` ends with null character, then it slice will be used as it is otherwise
     ///it shall create buffer to store `string` with null terminating character appended
+    ///
+    ///Aborts on allocation failure, use [try_new](Self::try_new) to handle it.
     pub fn new(string: &'a [u8]) -> Self {
         let state = if let Some(this) = Self::try_new_c(string) {
             this.state
         } else if string.len() < STATIC_SIZE {
-            let mut buffer = [0u8; STATIC_SIZE];
-            buffer[..string.len()].copy_from_slice(string);
-            buffer[string.len()] = 0;
-            State::Static(buffer)
+            Self::new_static(string)
         } else {
             let mut buffer = Vec::with_capacity(string.len().saturating_add(1));
             buffer.extend_from_slice(string);
@@ -80,6 +82,34 @@ impl<'a> String<'a> {
         }
     }
 
+    ///Creates new String, same as [new](Self::new).
+    ///
+    ///Returns `NNG_ENOMEM` if unable to allocate buffer.
+    pub fn try_new(string: &'a [u8]) -> Result<Self, ErrorCode> {
+        let state = if let Some(this) = Self::try_new_c(string) {
+            this.state
+        } else if string.len() < STATIC_SIZE {
+            Self::new_static(string)
+        } else {
+            let mut buffer = try_vec(string.len().saturating_add(1))?;
+            buffer.extend_from_slice(string);
+            buffer.push(0);
+            State::Heap(buffer)
+        };
+
+        Ok(Self {
+            state
+        })
+    }
+
+    #[inline(always)]
+    fn new_static(string: &[u8]) -> State<'a> {
+        let mut buffer = [0u8; STATIC_SIZE];
+        buffer[..string.len()].copy_from_slice(string);
+        buffer[string.len()] = 0;
+        State::Static(buffer)
+    }
+
     ///Returns pointer to the underlying buffer
     pub fn as_ptr(&self) -> *const u8 {
         match &self.state {
@@ -136,6 +166,13 @@ impl PartialEq<str> for String<'_> {
     }
 }
 
+impl PartialEq<&str> for String<'_> {
+    #[inline(always)]
+    fn eq(&self, other: &&str) -> bool {
+        PartialEq::eq(self.as_bytes(), other.as_bytes())
+    }
+}
+
 impl<'a> From<&'a [u8]> for String<'a> {
     #[inline]
     fn from(value: &'a [u8]) -> Self {
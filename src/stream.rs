@@ -0,0 +1,401 @@
+//!Byte stream module
+//!
+//!Streams provide raw connection (i.e. TCP, IPC or TLS) without any messaging protocol on top of it.
+//!
+//!All operations are asynchronous and take ownership of buffers, which are returned or dropped
+//!once operation is complete.
+
+use crate::ErrorCode;
+use crate::error::error;
+use crate::aio::Aio;
+use crate::str::String;
+use crate::url::check_scheme;
+use crate::options::{Options, Property};
+use crate::sys;
+
+use core::pin::Pin;
+use core::future::Future;
+use core::ptr::NonNull;
+use core::{fmt, mem, task};
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+///Maximum number of buffers in single vectored operation
+pub const MAX_IOV: usize = 8;
+
+///Byte stream
+pub struct Stream(NonNull<sys::nng_stream>);
+
+unsafe impl Send for Stream {}
+unsafe impl Sync for Stream {}
+
+impl Stream {
+    #[inline]
+    ///Sends `buf` over the stream.
+    ///
+    ///Refer to [send_vectored](Self::send_vectored) for details.
+    pub fn send<B: AsRef<[u8]> + 'static>(&self, buf: B) -> Result<FutureSend<B>, ErrorCode> {
+        self.send_vectored(vec![buf])
+    }
+
+    ///Sends `bufs` over the stream as single contiguous sequence of bytes, without concatenating them.
+    ///
+    ///Up to [MAX_IOV] buffers can be sent at once, otherwise returns error.
+    ///
+    ///Future resolves into number of bytes sent, which may be less than total size of buffers.
+    ///It is user responsibility to send remaining bytes.
+    pub fn send_vectored<B: AsRef<[u8]> + 'static>(&self, bufs: Vec<B>) -> Result<FutureSend<B>, ErrorCode> {
+        if bufs.len() > MAX_IOV {
+            return Err(error(sys::nng_errno_enum::NNG_EINVAL));
+        }
+
+        let aio = Aio::new()?;
+        let mut iov = [sys::nng_iov {
+            iov_buf: core::ptr::null_mut(),
+            iov_len: 0,
+        }; MAX_IOV];
+        for (iov, buf) in iov.iter_mut().zip(bufs.iter()) {
+            let buf = buf.as_ref();
+            iov.iov_buf = buf.as_ptr() as _;
+            iov.iov_len = buf.len();
+        }
+        aio.set_iov(&iov[..bufs.len()])?;
+
+        //Buffers are heap allocated and owned by future, so they remain valid even if future is leaked
+        unsafe {
+            sys::nng_stream_send(self.0.as_ptr(), aio.as_ptr());
+        }
+
+        Ok(FutureSend {
+            aio,
+            _bufs: bufs,
+        })
+    }
+
+    ///Receives up to `size` bytes from the stream.
+    ///
+    ///Future resolves into buffer with received bytes, which may be less than `size`
+    pub fn recv(&self, size: usize) -> Result<FutureRecv, ErrorCode> {
+        let aio = Aio::new()?;
+        let mut buf = Vec::with_capacity(size);
+        let iov = sys::nng_iov {
+            iov_buf: buf.as_mut_ptr() as _,
+            iov_len: buf.capacity(),
+        };
+        aio.set_iov(&[iov])?;
+
+        unsafe {
+            sys::nng_stream_recv(self.0.as_ptr(), aio.as_ptr());
+        }
+
+        Ok(FutureRecv {
+            aio,
+            buf,
+        })
+    }
+
+    #[inline]
+    ///Closes stream, aborting any pending operation
+    pub fn close(&self) {
+        unsafe {
+            sys::nng_stream_close(self.0.as_ptr())
+        }
+    }
+
+    #[inline(always)]
+    ///Gets property of the stream
+    ///
+    ///Refer to [options](crate::options) for available properties (i.e. [RemoteAddr](crate::options::RemoteAddr))
+    pub fn get_prop<T: Property<Self>>(&self) -> Result<T, ErrorCode> {
+        T::get(self)
+    }
+
+    #[inline(always)]
+    pub(crate) fn as_ptr(&self) -> *mut sys::nng_stream {
+        self.0.as_ptr()
+    }
+}
+
+impl fmt::Debug for Stream {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_fmt(format_args!("Stream({:p})", self.0))
+    }
+}
+
+impl Drop for Stream {
+    #[inline(always)]
+    fn drop(&mut self) {
+        unsafe {
+            sys::nng_stream_free(self.0.as_ptr())
+        }
+    }
+}
+
+///Future that sends buffers over the stream
+pub struct FutureSend<B> {
+    aio: Aio,
+    _bufs: Vec<B>,
+}
+
+impl<B> FutureSend<B> {
+    #[inline]
+    ///Sets future for cancelling
+    pub fn cancel(&self) {
+        unsafe {
+            sys::nng_aio_cancel(self.aio.as_ptr())
+        }
+    }
+}
+
+impl<B> Future for FutureSend<B> {
+    type Output = Result<usize, ErrorCode>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut task::Context<'_>) -> task::Poll<Self::Output> {
+        if self.aio.is_ready() {
+            task::Poll::Ready(self.aio.get_result().map(|_| self.aio.get_count()))
+        } else {
+            self.aio.register_waker(ctx.waker());
+            task::Poll::Pending
+        }
+    }
+}
+
+///Future that receives bytes from the stream
+pub struct FutureRecv {
+    aio: Aio,
+    buf: Vec<u8>,
+}
+
+impl FutureRecv {
+    #[inline]
+    ///Sets future for cancelling
+    pub fn cancel(&self) {
+        unsafe {
+            sys::nng_aio_cancel(self.aio.as_ptr())
+        }
+    }
+}
+
+impl Future for FutureRecv {
+    type Output = Result<Vec<u8>, ErrorCode>;
+
+    fn poll(mut self: Pin<&mut Self>, ctx: &mut task::Context<'_>) -> task::Poll<Self::Output> {
+        let this = self.as_mut().get_mut();
+        if this.aio.is_ready() {
+            task::Poll::Ready(this.aio.get_result().map(|_| {
+                let mut buf = mem::take(&mut this.buf);
+                let count = this.aio.get_count();
+                debug_assert!(count <= buf.capacity());
+                unsafe {
+                    buf.set_len(count);
+                }
+                buf
+            }))
+        } else {
+            this.aio.register_waker(ctx.waker());
+            task::Poll::Pending
+        }
+    }
+}
+
+///Future that resolves into connected stream
+pub struct FutureStream {
+    aio: Aio,
+}
+
+impl FutureStream {
+    #[inline]
+    ///Sets future for cancelling
+    pub fn cancel(&self) {
+        unsafe {
+            sys::nng_aio_cancel(self.aio.as_ptr())
+        }
+    }
+}
+
+impl Future for FutureStream {
+    type Output = Result<Stream, ErrorCode>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut task::Context<'_>) -> task::Poll<Self::Output> {
+        if self.aio.is_ready() {
+            let result = self.aio.get_result().and_then(|_| match NonNull::new(self.aio.take_output(0) as *mut sys::nng_stream) {
+                Some(stream) => Ok(Stream(stream)),
+                None => Err(error(sys::nng_errno_enum::NNG_EINTERNAL)),
+            });
+            task::Poll::Ready(result)
+        } else {
+            self.aio.register_waker(ctx.waker());
+            task::Poll::Pending
+        }
+    }
+}
+
+impl Drop for FutureStream {
+    fn drop(&mut self) {
+        self.aio.stop();
+        //Make sure connection is not leaked if future is dropped before being polled
+        if self.aio.get_result().is_ok() {
+            if let Some(stream) = NonNull::new(self.aio.take_output(0) as *mut sys::nng_stream) {
+                drop(Stream(stream));
+            }
+        }
+    }
+}
+
+///Stream dialer, establishing connections to the remote peer
+pub struct Dialer(pub(crate) NonNull<sys::nng_stream_dialer>);
+
+unsafe impl Send for Dialer {}
+unsafe impl Sync for Dialer {}
+
+impl Dialer {
+    ///Creates new dialer to connect to the `url`
+    pub fn new(url: String<'_>) -> Result<Self, ErrorCode> {
+        check_scheme(url.as_bytes())?;
+        let mut ptr = core::ptr::null_mut();
+        let result = unsafe {
+            sys::nng_stream_dialer_alloc(&mut ptr, url.as_ptr() as _)
+        };
+
+        match result {
+            0 => match NonNull::new(ptr) {
+                Some(ptr) => Ok(Self(ptr)),
+                None => Err(error(sys::nng_errno_enum::NNG_ENOMEM)),
+            },
+            code => Err(error(code)),
+        }
+    }
+
+    #[inline(always)]
+    ///Sets options on the dialer
+    pub fn set_opt<T: Options<Self>>(&self, opts: T) -> Result<(), ErrorCode> {
+        opts.apply(self)
+    }
+
+    ///Starts connecting to the remote peer
+    pub fn dial(&self) -> Result<FutureStream, ErrorCode> {
+        let aio = Aio::new()?;
+        unsafe {
+            sys::nng_stream_dialer_dial(self.0.as_ptr(), aio.as_ptr());
+        }
+
+        Ok(FutureStream {
+            aio
+        })
+    }
+
+    #[inline]
+    ///Closes dialer, aborting any pending connection
+    pub fn close(&self) {
+        unsafe {
+            sys::nng_stream_dialer_close(self.0.as_ptr())
+        }
+    }
+}
+
+impl fmt::Debug for Dialer {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_fmt(format_args!("Dialer({:p})", self.0))
+    }
+}
+
+impl Drop for Dialer {
+    #[inline(always)]
+    fn drop(&mut self) {
+        unsafe {
+            sys::nng_stream_dialer_free(self.0.as_ptr())
+        }
+    }
+}
+
+///Stream listener, accepting connections from remote peers
+pub struct Listener(pub(crate) NonNull<sys::nng_stream_listener>);
+
+unsafe impl Send for Listener {}
+unsafe impl Sync for Listener {}
+
+impl Listener {
+    ///Creates new listener for the `url`
+    ///
+    ///Listener must be started via [listen](Self::listen) before accepting connections
+    pub fn new(url: String<'_>) -> Result<Self, ErrorCode> {
+        check_scheme(url.as_bytes())?;
+        let mut ptr = core::ptr::null_mut();
+        let result = unsafe {
+            sys::nng_stream_listener_alloc(&mut ptr, url.as_ptr() as _)
+        };
+
+        match result {
+            0 => match NonNull::new(ptr) {
+                Some(ptr) => Ok(Self(ptr)),
+                None => Err(error(sys::nng_errno_enum::NNG_ENOMEM)),
+            },
+            code => Err(error(code)),
+        }
+    }
+
+    #[inline(always)]
+    ///Sets options on the listener
+    pub fn set_opt<T: Options<Self>>(&self, opts: T) -> Result<(), ErrorCode> {
+        opts.apply(self)
+    }
+
+    ///Binds listener to its address, starting to listen for incoming connections
+    pub fn listen(&self) -> Result<(), ErrorCode> {
+        let result = unsafe {
+            sys::nng_stream_listener_listen(self.0.as_ptr())
+        };
+
+        match result {
+            0 => Ok(()),
+            code => Err(error(code)),
+        }
+    }
+
+    ///Creates future to accept next incoming connection
+    pub fn accept_async(&self) -> Result<FutureStream, ErrorCode> {
+        let aio = Aio::new()?;
+        unsafe {
+            sys::nng_stream_listener_accept(self.0.as_ptr(), aio.as_ptr());
+        }
+
+        Ok(FutureStream {
+            aio
+        })
+    }
+
+    ///Accepts next incoming connection
+    ///
+    ///Returns error once listener is closed, allowing to write accept loop as
+    ///`while let Ok(stream) = listener.accept().await`
+    pub async fn accept(&self) -> Result<Stream, ErrorCode> {
+        self.accept_async()?.await
+    }
+
+    #[inline]
+    ///Closes listener, aborting any pending accept
+    pub fn close(&self) {
+        unsafe {
+            sys::nng_stream_listener_close(self.0.as_ptr())
+        }
+    }
+}
+
+impl fmt::Debug for Listener {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_fmt(format_args!("Listener({:p})", self.0))
+    }
+}
+
+impl Drop for Listener {
+    #[inline(always)]
+    fn drop(&mut self) {
+        unsafe {
+            sys::nng_stream_listener_free(self.0.as_ptr())
+        }
+    }
+}
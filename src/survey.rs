@@ -0,0 +1,231 @@
+//!Survey protocol helpers
+
+use crate::{ErrorCode, NngError};
+use crate::error::error;
+use crate::msg::Message;
+use crate::socket::Socket;
+use crate::context::Context;
+use crate::options::SurveyTime;
+use crate::sys;
+
+use core::{fmt, time};
+
+#[inline]
+fn is_survey_gone(error: &ErrorCode) -> bool {
+    let code = error.raw_code();
+    code == sys::nng_errno_enum::NNG_ESTATE || code == sys::nng_errno_enum::NNG_ECANCELED || code == sys::nng_errno_enum::NNG_ETIMEDOUT
+}
+
+///Asynchronous helper to answer surveys on respondent0 socket.
+///
+///Each instance uses its own context, so multiple respondents can be run on the same socket.
+pub struct Respondent {
+    ctx: Context,
+}
+
+impl Respondent {
+    #[inline]
+    ///Creates new respondent on `socket`, which must be respondent0 socket.
+    pub fn new(socket: &Socket) -> Result<Self, ErrorCode> {
+        Context::new(socket).map(|ctx| Self {
+            ctx
+        })
+    }
+
+    ///Awaits next survey.
+    pub async fn next(&mut self) -> Result<Survey<'_>, ErrorCode> {
+        match self.ctx.recv_msg_async()?.await? {
+            Some(msg) => Ok(Survey {
+                msg,
+                ctx: &self.ctx,
+            }),
+            None => Err(error(sys::nng_errno_enum::NNG_EINTERNAL)),
+        }
+    }
+
+    ///Runs loop answering surveys with `handler`, until underlying socket is closed.
+    ///
+    ///Handler can return `None` to ignore the survey.
+    ///Answers that cannot be delivered because survey is no longer active are dropped.
+    ///
+    ///Returns `Ok` when socket is closed, otherwise first unexpected error.
+    pub async fn serve<F: FnMut(Message) -> Option<Message>>(&mut self, mut handler: F) -> Result<(), ErrorCode> {
+        loop {
+            let survey = match self.next().await {
+                Ok(survey) => survey,
+                Err(error) if error.is_closed() => break Ok(()),
+                Err(error) => break Err(error),
+            };
+
+            let Survey { msg, ctx } = survey;
+            if let Some(answer) = handler(msg) {
+                match ctx.send_msg_async(answer)?.await {
+                    Ok(()) => (),
+                    Err((_, error)) if is_survey_gone(&error) => (),
+                    Err((_, error)) if error.is_closed() => break Ok(()),
+                    Err((_, error)) => break Err(error),
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Debug for Respondent {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Respondent").field("ctx", &self.ctx).finish()
+    }
+}
+
+///Survey received by [Respondent]
+pub struct Survey<'a> {
+    msg: Message,
+    ctx: &'a Context,
+}
+
+impl Survey<'_> {
+    #[inline(always)]
+    ///Access survey's message
+    pub fn message(&self) -> &Message {
+        &self.msg
+    }
+
+    ///Sends `answer` to the survey.
+    ///
+    ///Returns `true` if answer is sent, or `false` if it is dropped because survey is no longer active.
+    pub async fn answer(self, answer: Message) -> Result<bool, ErrorCode> {
+        match self.ctx.send_msg_async(answer)?.await {
+            Ok(()) => Ok(true),
+            Err((_, error)) if is_survey_gone(&error) => Ok(false),
+            Err((_, error)) => Err(error),
+        }
+    }
+}
+
+impl fmt::Debug for Survey<'_> {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Survey").field("msg", &self.msg).finish()
+    }
+}
+
+///Helper to send surveys on surveyor0 socket and collect responses.
+///
+///Each instance uses its own context, so multiple surveys can be run on the same socket concurrently.
+pub struct Surveyor {
+    ctx: Context,
+}
+
+impl Surveyor {
+    #[inline]
+    ///Creates new surveyor on `socket`, which must be surveyor0 socket.
+    ///
+    ///Survey deadline is inherited from socket's [SurveyTime]
+    pub fn new(socket: &Socket) -> Result<Self, ErrorCode> {
+        Context::new(socket).map(|ctx| Self {
+            ctx
+        })
+    }
+
+    #[inline]
+    ///Sets deadline of surveys, overriding socket's [SurveyTime]
+    pub fn set_deadline(&self, deadline: time::Duration) -> Result<(), ErrorCode> {
+        self.ctx.set_opt(SurveyTime(deadline))
+    }
+
+    ///Sends survey, returning its responses.
+    ///
+    ///Starting new survey cancels previous one.
+    pub fn survey(&mut self, msg: Message) -> Result<Responses<'_>, ErrorCode> {
+        self.ctx.send_msg(msg).map_err(|(_, error)| error)?;
+        Ok(Responses {
+            ctx: &self.ctx,
+            done: false,
+        })
+    }
+
+    ///Asynchronously sends survey, returning its responses.
+    ///
+    ///Starting new survey cancels previous one.
+    pub async fn survey_async(&mut self, msg: Message) -> Result<Responses<'_>, ErrorCode> {
+        self.ctx.send_msg_async(msg)?.await.map_err(|(_, error)| error)?;
+        Ok(Responses {
+            ctx: &self.ctx,
+            done: false,
+        })
+    }
+}
+
+impl fmt::Debug for Surveyor {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Surveyor").field("ctx", &self.ctx).finish()
+    }
+}
+
+///Responses to the survey, sent by [Surveyor]
+///
+///Responses are received until survey deadline expires, which ends them instead of reporting error.
+///Any other error is reported once, after which responses end as well.
+///
+///Iterator blocks waiting for next response, while [next_async](Self::next_async) awaits it.
+pub struct Responses<'a> {
+    ctx: &'a Context,
+    done: bool,
+}
+
+impl Responses<'_> {
+    #[inline]
+    fn complete(&mut self, result: Result<Option<Message>, ErrorCode>) -> Result<Option<Message>, ErrorCode> {
+        match result {
+            Ok(Some(msg)) => Ok(Some(msg)),
+            Ok(None) => Err(error(sys::nng_errno_enum::NNG_EINTERNAL)),
+            Err(error) => {
+                self.done = true;
+                match is_survey_gone(&error) {
+                    true => Ok(None),
+                    false => Err(error),
+                }
+            },
+        }
+    }
+
+    ///Awaits next response, returning `None` once survey is over.
+    pub async fn next_async(&mut self) -> Result<Option<Message>, ErrorCode> {
+        if self.done {
+            return Ok(None);
+        }
+
+        let result = match self.ctx.recv_msg_async() {
+            Ok(fut) => fut.await,
+            Err(error) => Err(error),
+        };
+        self.complete(result)
+    }
+
+    #[inline(always)]
+    ///Returns whether survey is over
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+}
+
+impl Iterator for Responses<'_> {
+    type Item = Result<Message, ErrorCode>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let result = self.ctx.recv_msg().map(Some);
+        self.complete(result).transpose()
+    }
+}
+
+impl fmt::Debug for Responses<'_> {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Responses").field("ctx", self.ctx).field("done", &self.done).finish()
+    }
+}
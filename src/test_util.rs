@@ -0,0 +1,264 @@
+//!Test support utilities
+//!
+//!Provides unique endpoint addresses to avoid collisions between concurrently running tests
+//!and helpers to wait for connection instead of sleeping for arbitrary duration.
+
+use crate::ErrorCode;
+use crate::error::error;
+use crate::notify::{PipeEvent, PipeNotifier, Subscription};
+use crate::options;
+use crate::socket::{Protocol, Socket};
+use crate::sys;
+use crate::utils::ipc::IpcPath;
+use crate::utils::sync::Mutex;
+
+use core::sync::atomic::{AtomicUsize, Ordering};
+use core::time;
+
+use alloc::format;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use std::thread;
+use std::time::Instant;
+
+static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+#[inline]
+fn unique_id() -> usize {
+    COUNTER.fetch_add(1, Ordering::Relaxed)
+}
+
+///Generates unique inproc URL, using `name` as part of address
+pub fn inproc_url(name: &str) -> String {
+    format!("inproc://nng-c-{}-{}-{}", std::process::id(), unique_id(), name)
+}
+
+///Generates unique IPC URL, using `name` as part of address
+///
+///On unix systems socket file is placed within temporary directory, with `name` truncated to fit
+///socket path limit, as per [IpcPath](crate::utils::ipc::IpcPath).
+pub fn ipc_url(name: &str) -> String {
+    let path = if cfg!(unix) {
+        IpcPath::file(name)
+    } else {
+        IpcPath::new(name)
+    };
+    path.into_url()
+}
+
+///Generates TCP URL with currently available port on loopback interface
+///
+///Port is allocated by OS and released immediately, so there is small chance that it will be
+///taken by someone else before it is used.
+pub fn tcp_url() -> Result<String, ErrorCode> {
+    let listener = std::net::TcpListener::bind(("127.0.0.1", 0))?;
+    let addr = listener.local_addr()?;
+    Ok(format!("tcp://{}", addr))
+}
+
+///Polls `predicate` until it returns `true` or `timeout` expires.
+///
+///Returns last result of `predicate`
+pub fn wait_until<F: FnMut() -> bool>(timeout: time::Duration, mut predicate: F) -> bool {
+    const POLL_INTERVAL: time::Duration = time::Duration::from_millis(1);
+    let start = Instant::now();
+
+    loop {
+        if predicate() {
+            break true;
+        } else if start.elapsed() >= timeout {
+            break predicate();
+        }
+
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+#[derive(Clone, Debug)]
+///Tracker of number of connections (pipes) of the socket.
+///
+///Subscribes to socket's [PipeNotifier], hence it can be used together with other subscribers.
+///
+///Pipes that have been established before installation are not accounted for, hence it should
+///be installed before starting to listen or connect.
+pub struct PipeCounter {
+    //Ids of attached pipes
+    pipes: Arc<Mutex<Vec<i32>>>,
+    _subscription: Arc<Subscription>,
+}
+
+impl PipeCounter {
+    ///Installs counter on the `socket`
+    pub fn install(socket: &Socket) -> Result<Self, ErrorCode> {
+        let notifier = PipeNotifier::install(socket)?;
+        let pipes = Arc::new(Mutex::new(Vec::new())?);
+
+        let tracked = pipes.clone();
+        let subscription = notifier.subscribe(move |pipe, event| match event {
+            PipeEvent::AddPost => tracked.lock().push(pipe.id()),
+            //Also delivered for pipes, rejected before being attached, which are not counted
+            PipeEvent::RemPost => {
+                let mut pipes = tracked.lock();
+                if let Some(idx) = pipes.iter().position(|id| *id == pipe.id()) {
+                    pipes.swap_remove(idx);
+                }
+            },
+            PipeEvent::AddPre => (),
+        });
+
+        Ok(Self {
+            pipes,
+            _subscription: Arc::new(subscription),
+        })
+    }
+
+    #[inline]
+    ///Returns current number of connections
+    pub fn count(&self) -> usize {
+        self.pipes.lock().len()
+    }
+
+    #[inline]
+    ///Waits until socket has at least `count` connections
+    ///
+    ///Returns timeout error if `timeout` expires before that.
+    pub fn wait_connected(&self, count: usize, timeout: time::Duration) -> Result<(), ErrorCode> {
+        match wait_until(timeout, || self.count() >= count) {
+            true => Ok(()),
+            false => Err(error(sys::nng_errno_enum::NNG_ETIMEDOUT)),
+        }
+    }
+
+    #[inline]
+    ///Waits until socket has no connections
+    ///
+    ///Returns timeout error if `timeout` expires before that.
+    pub fn wait_disconnected(&self, timeout: time::Duration) -> Result<(), ErrorCode> {
+        match wait_until(timeout, || self.count() == 0) {
+            true => Ok(()),
+            false => Err(error(sys::nng_errno_enum::NNG_ETIMEDOUT)),
+        }
+    }
+}
+
+const LINK_TIMEOUT: time::Duration = time::Duration::from_secs(5);
+
+fn relay(from: &Socket, to: &Socket, latency: time::Duration) {
+    while let Ok(msg) = from.recv_msg() {
+        thread::sleep(latency);
+        if to.send_msg(msg).is_err() {
+            break;
+        }
+    }
+}
+
+struct Relay {
+    sockets: Arc<(Socket, Socket)>,
+    threads: [Option<thread::JoinHandle<()>>; 2],
+}
+
+impl Relay {
+    //Forwards messages between `listener` url and new url via raw sockets, returning new url
+    fn new(proto: Protocol, listener: &str, latency: time::Duration) -> Result<(Self, String), ErrorCode> {
+        let url = inproc_url("relay");
+        let sockets = Arc::new((proto.peer().open(true)?, proto.open(true)?));
+        sockets.0.connect(listener.into())?;
+        sockets.1.listen(url.as_str().into())?;
+
+        let forward = sockets.clone();
+        let backward = sockets.clone();
+        let threads = [
+            Some(thread::spawn(move || relay(&forward.0, &forward.1, latency))),
+            Some(thread::spawn(move || relay(&backward.1, &backward.0, latency))),
+        ];
+
+        Ok((Self { sockets, threads }, url))
+    }
+}
+
+impl Drop for Relay {
+    fn drop(&mut self) {
+        self.sockets.0.close();
+        self.sockets.1.close();
+        for thread in self.threads.iter_mut() {
+            if let Some(thread) = thread.take() {
+                let _ = thread.join();
+            }
+        }
+    }
+}
+
+///Pair of sockets connected over unique inproc address
+///
+///`sub0` socket is subscribed to all topics.
+pub struct LinkedPair {
+    ///Socket of requested protocol, listening for connection
+    pub listener: Socket,
+    ///Socket of peer protocol, connected to the listener
+    pub dialer: Socket,
+    //Must be dropped after sockets
+    relay: Option<Relay>,
+}
+
+impl LinkedPair {
+    fn new(proto: Protocol, latency: Option<time::Duration>) -> Result<Self, ErrorCode> {
+        let url = inproc_url("linked");
+        let listener = proto.open(false)?;
+        if proto == Protocol::Sub0 {
+            listener.set_opt(options::Subscribe(&[]))?;
+        }
+        let counter = PipeCounter::install(&listener)?;
+        listener.listen(url.as_str().into())?;
+
+        let (relay, url) = match latency {
+            Some(latency) => {
+                let (relay, url) = Relay::new(proto, &url, latency)?;
+                (Some(relay), url)
+            },
+            None => (None, url),
+        };
+
+        let dialer = proto.peer().open(false)?;
+        if proto.peer() == Protocol::Sub0 {
+            dialer.set_opt(options::Subscribe(&[]))?;
+        }
+        let dialer_counter = PipeCounter::install(&dialer)?;
+        dialer.connect(url.as_str().into())?;
+
+        counter.wait_connected(1, LINK_TIMEOUT)?;
+        dialer_counter.wait_connected(1, LINK_TIMEOUT)?;
+
+        Ok(Self {
+            listener,
+            dialer,
+            relay,
+        })
+    }
+
+    #[inline]
+    ///Returns whether messages are delivered with injected latency
+    pub fn has_latency(&self) -> bool {
+        self.relay.is_some()
+    }
+}
+
+#[inline]
+///Creates pair of connected sockets, with first socket using `proto` and second using its peer protocol.
+///
+///Function returns only once both sockets are connected, hence it is safe to send messages right away.
+///
+///Both sockets have [PipeCounter] installed.
+pub fn linked_pair(proto: Protocol) -> Result<LinkedPair, ErrorCode> {
+    LinkedPair::new(proto, None)
+}
+
+#[inline]
+///Creates pair of connected sockets, similarly to [linked_pair], delivering messages with injected `latency`.
+///
+///Messages are forwarded between sockets via relay, delaying each message by `latency`.
+///Relay handles one message at a time, so every message is delayed by at least `latency`.
+pub fn linked_pair_with_latency(proto: Protocol, latency: time::Duration) -> Result<LinkedPair, ErrorCode> {
+    LinkedPair::new(proto, Some(latency))
+}
@@ -1,6 +1,7 @@
 //!TLS module
 
 use crate::socket;
+use crate::stream;
 use crate::str::String;
 use crate::options::Options;
 use crate::defs::MAX_HOSTNAME_LEN;
@@ -46,7 +47,8 @@ pub struct OwnCert<'a> {
 }
 
 ///Authentication mode
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize), serde(rename_all = "lowercase"))]
 #[repr(i32)]
 pub enum Auth {
     ///No authentication of the TLS peer is performed. This is the default for TLS servers, which most typically do not authenticate their clients.
@@ -167,6 +169,37 @@ impl Config {
         }
     }
 
+    ///Loads CA certificate chain and optional revocation list from file at `path`
+    pub fn ca_file(&self, path: String<'_>) -> Result<(), ErrorCode> {
+        let result = unsafe {
+            sys::nng_tls_config_ca_file(self.0.as_ptr(), path.as_ptr() as _)
+        };
+
+        match result {
+            0 => Ok(()),
+            code => Err(error(code)),
+        }
+    }
+
+    ///Loads local certificate and its private key from file at `path`
+    ///
+    ///File must contain both PEM encoded certificate and key.
+    ///Optional `pass` is used to decrypt private key.
+    pub fn cert_key_file(&self, path: String<'_>, pass: Option<String<'_>>) -> Result<(), ErrorCode> {
+        let pass = match pass.as_ref() {
+            Some(pass) => pass.as_ptr(),
+            None => ptr::null()
+        };
+        let result = unsafe {
+            sys::nng_tls_config_cert_key_file(self.0.as_ptr(), path.as_ptr() as _, pass as _)
+        };
+
+        match result {
+            0 => Ok(()),
+            code => Err(error(code)),
+        }
+    }
+
     ///Sets local certificate used in TLS handshake
     pub fn own_cert(&self, cert: &OwnCert<'_>) -> Result<(), ErrorCode> {
         let pass = match cert.pass.as_ref() {
@@ -232,3 +265,31 @@ impl Options<socket::Dialer> for Config {
         }
     }
 }
+
+impl Options<stream::Dialer> for Config {
+    #[inline]
+    fn apply(&self, target: &stream::Dialer) -> Result<(), ErrorCode> {
+        let result = unsafe {
+            sys::nng_stream_dialer_set_ptr(target.0.as_ptr(), sys::NNG_OPT_TLS_CONFIG.as_ptr() as _, self.0.as_ptr() as _)
+        };
+
+        match result {
+            0 => Ok(()),
+            code => Err(error(code)),
+        }
+    }
+}
+
+impl Options<stream::Listener> for Config {
+    #[inline]
+    fn apply(&self, target: &stream::Listener) -> Result<(), ErrorCode> {
+        let result = unsafe {
+            sys::nng_stream_listener_set_ptr(target.0.as_ptr(), sys::NNG_OPT_TLS_CONFIG.as_ptr() as _, self.0.as_ptr() as _)
+        };
+
+        match result {
+            0 => Ok(()),
+            code => Err(error(code)),
+        }
+    }
+}
@@ -0,0 +1,195 @@
+//![tower](https://crates.io/crates/tower) integration
+//!
+//![Client] exposes req0 socket as `Service<Message>`, while [Server] drives any
+//!`Service<Message>` with requests received on rep0 socket, so that timeouts, retries,
+//!load-shedding and other middleware of tower ecosystem apply to nng RPC.
+//!
+//!Both use their own [Context] per request, hence multiple requests can be in flight on the same socket.
+//!
+//!Requires feature `tower`
+//!
+//!## Usage
+//!
+//!```rust
+//!use nng_c::{Message, ErrorCode, Socket};
+//!use nng_c::tower::{Client, Server};
+//!use nng_c::utils::block_on;
+//!use tower_service::Service;
+//!
+//!use core::{future, task};
+//!
+//!struct Echo;
+//!
+//!impl Service<Message> for Echo {
+//!    type Response = Message;
+//!    type Error = ErrorCode;
+//!    type Future = future::Ready<Result<Message, ErrorCode>>;
+//!
+//!    fn poll_ready(&mut self, _: &mut task::Context<'_>) -> task::Poll<Result<(), ErrorCode>> {
+//!        task::Poll::Ready(Ok(()))
+//!    }
+//!
+//!    fn call(&mut self, req: Message) -> Self::Future {
+//!        future::ready(Ok(req))
+//!    }
+//!}
+//!
+//!const ADDR: &str = "inproc://tower-example\0";
+//!
+//!let server = Socket::rep0().expect("create server");
+//!server.listen(ADDR.into()).expect("listen");
+//!let client = Socket::req0().expect("create client");
+//!client.connect(ADDR.into()).expect("connect");
+//!
+//!std::thread::scope(|scope| {
+//!    scope.spawn(|| {
+//!        let mut worker = Server::new(&server).expect("create context");
+//!        block_on(worker.serve(Echo)).expect("run executor")
+//!    });
+//!
+//!    let mut req = Message::new().expect("create message");
+//!    req.append(b"ping").expect("append");
+//!    let resp = block_on(Client::new(&client).call(req)).expect("run executor").expect("get response");
+//!    assert_eq!(resp.body(), b"ping");
+//!
+//!    server.close();
+//!});
+//!```
+
+use crate::{ErrorCode, NngError};
+use crate::context::{Context, FutureRequest};
+use crate::error::error;
+use crate::msg::Message;
+use crate::socket::Socket;
+use crate::sys;
+
+use core::{fmt, future, task};
+
+use tower_service::Service;
+
+#[derive(Clone, Copy)]
+///Service sending requests over req0 socket
+///
+///Each request is sent on its own context, awaiting matching reply.
+///Socket options, like resend interval, apply to every request.
+pub struct Client<'a> {
+    socket: &'a Socket,
+}
+
+impl<'a> Client<'a> {
+    #[inline(always)]
+    ///Creates new client on `socket`, which must be req0 socket.
+    pub const fn new(socket: &'a Socket) -> Self {
+        Self {
+            socket
+        }
+    }
+
+    #[inline(always)]
+    ///Returns underlying socket
+    pub fn socket(&self) -> &'a Socket {
+        self.socket
+    }
+}
+
+impl fmt::Debug for Client<'_> {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Client").field("socket", &self.socket).finish()
+    }
+}
+
+impl Service<Message> for Client<'_> {
+    type Response = Message;
+    type Error = ErrorCode;
+    type Future = ResponseFuture;
+
+    #[inline(always)]
+    fn poll_ready(&mut self, _: &mut task::Context<'_>) -> task::Poll<Result<(), Self::Error>> {
+        task::Poll::Ready(Ok(()))
+    }
+
+    #[inline(always)]
+    fn call(&mut self, req: Message) -> Self::Future {
+        self.socket.request(req)
+    }
+}
+
+///Future of the [Client] response
+///
+///Dropping it cancels request.
+pub type ResponseFuture = FutureRequest<'static>;
+
+#[derive(Debug)]
+///Error of running [Server]
+pub enum ServeError<E> {
+    ///Socket failed to receive request or send reply
+    Socket(ErrorCode),
+    ///Service failed to become ready
+    Service(E),
+}
+
+impl<E: fmt::Display> fmt::Display for ServeError<E> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Socket(code) => fmt.write_fmt(format_args!("socket failure: {}", code)),
+            Self::Service(error) => fmt.write_fmt(format_args!("service is not ready: {}", error)),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: std::error::Error> std::error::Error for ServeError<E> {}
+
+///Asynchronous server, handling requests on rep0 socket with tower `Service`.
+///
+///Each instance uses its own context, so multiple servers can be run on the same socket to process requests concurrently.
+pub struct Server {
+    ctx: Context,
+}
+
+impl Server {
+    #[inline]
+    ///Creates new server on `socket`, which must be rep0 socket.
+    pub fn new(socket: &Socket) -> Result<Self, ErrorCode> {
+        Context::new(socket).map(|ctx| Self {
+            ctx
+        })
+    }
+
+    ///Runs loop handling requests with `service`, until underlying socket is closed.
+    ///
+    ///Request, which `service` fails to handle, is left without reply, to be retried or timed
+    ///out by the client.
+    ///
+    ///Returns `Ok` when socket is closed, otherwise first unexpected error.
+    pub async fn serve<S: Service<Message, Response = Message>>(&mut self, mut service: S) -> Result<(), ServeError<S::Error>> {
+        loop {
+            let req = match self.ctx.recv_msg_async().map_err(ServeError::Socket)?.await {
+                Ok(Some(req)) => req,
+                Ok(None) => break Err(ServeError::Socket(error(sys::nng_errno_enum::NNG_EINTERNAL))),
+                Err(error) if error.is_closed() => break Ok(()),
+                Err(error) => break Err(ServeError::Socket(error)),
+            };
+
+            future::poll_fn(|ctx| service.poll_ready(ctx)).await.map_err(ServeError::Service)?;
+            let resp = match service.call(req).await {
+                Ok(resp) => resp,
+                Err(_) => continue,
+            };
+
+            match self.ctx.send_msg_async(resp).map_err(ServeError::Socket)?.await {
+                Ok(()) => (),
+                Err((_, error)) if error.is_closed() => break Ok(()),
+                Err((_, error)) => break Err(ServeError::Socket(error)),
+            }
+        }
+    }
+}
+
+impl fmt::Debug for Server {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Server").field("ctx", &self.ctx).finish()
+    }
+}
@@ -0,0 +1,304 @@
+//!File transfer over push/pull
+//!
+//![Sender] streams any `Read` source as chunked messages over push0 socket, while [Receiver]
+//!reassembles them from pull0 socket into any `Write` destination.
+//!
+//!Each transfer consists of:
+//!
+//!- Start message, carrying random transfer ID and total size, if known;
+//!- Chunk messages, carrying transfer ID, offset and data;
+//!- End message, carrying transfer ID, total size and CRC-32 of the content.
+//!
+//!Receiver verifies that chunks are contiguous and that size and checksum match, failing with
+//!protocol error otherwise.
+//!
+//!push0 distributes messages across all connected pullers, therefore transfer requires exactly
+//!single puller to be connected.
+//!
+//!Requires feature `std`
+//!
+//!## Usage
+//!
+//!```rust
+//!use nng_c::Socket;
+//!use nng_c::transfer::{Receiver, Sender};
+//!
+//!const ADDR: &str = "inproc://transfer-example\0";
+//!
+//!let pull = Socket::pull0().expect("create pull socket");
+//!pull.listen(ADDR.into()).expect("listen");
+//!let push = Socket::push0().expect("create push socket");
+//!push.connect(ADDR.into()).expect("connect");
+//!
+//!let content = vec![42u8; 100_000];
+//!std::thread::scope(|scope| {
+//!    scope.spawn(|| Sender::new(&push).send(content.as_slice(), Some(content.len() as u64), |_| ()).expect("send"));
+//!
+//!    let mut received = Vec::new();
+//!    let size = Receiver::new(&pull).receive(&mut received, |progress| {
+//!        println!("Received {} out of {:?} bytes", progress.transferred, progress.total);
+//!    }).expect("receive");
+//!    assert_eq!(size, content.len() as u64);
+//!    assert_eq!(received, content);
+//!});
+//!```
+
+use crate::ErrorCode;
+use crate::checksum::Crc32;
+use crate::error::error;
+use crate::msg::Message;
+use crate::socket::Socket;
+use crate::sys;
+
+use core::fmt;
+
+use alloc::vec;
+use std::io::{Read, Write};
+use std::path::Path;
+
+const START: u8 = 0;
+const CHUNK: u8 = 1;
+const END: u8 = 2;
+
+//Marks unknown total size
+const UNKNOWN_SIZE: u64 = u64::MAX;
+
+#[inline(always)]
+fn protocol_error() -> ErrorCode {
+    error(sys::nng_errno_enum::NNG_EPROTO)
+}
+
+#[inline(always)]
+fn new_message() -> Result<Message, ErrorCode> {
+    match Message::new() {
+        Some(msg) => Ok(msg),
+        None => Err(error(sys::nng_errno_enum::NNG_ENOMEM)),
+    }
+}
+
+#[inline]
+fn send(socket: &Socket, msg: Message) -> Result<(), ErrorCode> {
+    socket.send_msg(msg).map_err(|(_, error)| error.into())
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+///Progress of the transfer
+pub struct Progress {
+    ///Number of bytes transferred so far
+    pub transferred: u64,
+    ///Total number of bytes, if known
+    pub total: Option<u64>,
+}
+
+///Sending side of the transfer over push0 socket
+pub struct Sender<'a> {
+    socket: &'a Socket,
+    chunk_size: usize,
+}
+
+impl<'a> Sender<'a> {
+    ///Default size of chunk
+    pub const DEFAULT_CHUNK_SIZE: usize = 64 * 1024;
+
+    #[inline]
+    ///Creates new sender over `socket`, which should be push0 socket
+    pub fn new(socket: &'a Socket) -> Self {
+        Self {
+            socket,
+            chunk_size: Self::DEFAULT_CHUNK_SIZE,
+        }
+    }
+
+    #[inline]
+    ///Sets maximum size of the chunk, sent in single message.
+    ///
+    ///Zero `size` is treated as 1.
+    pub fn with_chunk_size(mut self, size: usize) -> Self {
+        self.chunk_size = core::cmp::max(size, 1);
+        self
+    }
+
+    ///Sends content of `reader`, invoking `progress` after every chunk.
+    ///
+    ///`total` is size of content, if known, which is only used to report progress.
+    ///
+    ///Returns number of bytes sent.
+    pub fn send<R: Read, P: FnMut(Progress)>(&self, mut reader: R, total: Option<u64>, mut progress: P) -> Result<u64, ErrorCode> {
+        let id = unsafe {
+            (sys::nng_random() as u64) << 32 | sys::nng_random() as u64
+        };
+
+        let mut msg = new_message()?;
+        msg.append(&[START])?;
+        msg.append_u64(id)?;
+        msg.append_u64(total.unwrap_or(UNKNOWN_SIZE))?;
+        send(self.socket, msg)?;
+
+        let mut crc = Crc32::new();
+        let mut transferred = 0u64;
+        let mut buffer = vec![0u8; self.chunk_size];
+        loop {
+            let size = match reader.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(size) => size,
+                Err(error) if error.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error.into()),
+            };
+            let chunk = &buffer[..size];
+            crc.update(chunk);
+
+            let mut msg = new_message()?;
+            msg.reserve(1 + 16 + size)?;
+            msg.append(&[CHUNK])?;
+            msg.append_u64(id)?;
+            msg.append_u64(transferred)?;
+            msg.append(chunk)?;
+            send(self.socket, msg)?;
+
+            transferred += size as u64;
+            progress(Progress {
+                transferred,
+                total,
+            });
+        }
+
+        let mut msg = new_message()?;
+        msg.append(&[END])?;
+        msg.append_u64(id)?;
+        msg.append_u64(transferred)?;
+        msg.append_u32(crc.finish())?;
+        send(self.socket, msg)?;
+
+        Ok(transferred)
+    }
+
+    ///Sends content of the file at `path`, invoking `progress` after every chunk.
+    ///
+    ///Returns number of bytes sent.
+    pub fn send_file<P: AsRef<Path>, F: FnMut(Progress)>(&self, path: P, progress: F) -> Result<u64, ErrorCode> {
+        let file = std::fs::File::open(path)?;
+        let total = file.metadata()?.len();
+        self.send(std::io::BufReader::new(file), Some(total), progress)
+    }
+}
+
+impl fmt::Debug for Sender<'_> {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Sender").field("socket", &self.socket).field("chunk_size", &self.chunk_size).finish()
+    }
+}
+
+//Splits `header` from the front of message body
+fn split_header(body: &[u8]) -> Result<(u8, u64, &[u8]), ErrorCode> {
+    if body.len() < 9 {
+        return Err(protocol_error());
+    }
+
+    let mut id = [0u8; 8];
+    id.copy_from_slice(&body[1..9]);
+    Ok((body[0], u64::from_be_bytes(id), &body[9..]))
+}
+
+#[inline]
+fn read_u64(bytes: &[u8]) -> Result<u64, ErrorCode> {
+    let mut value = [0u8; 8];
+    match bytes.get(..8) {
+        Some(bytes) => value.copy_from_slice(bytes),
+        None => return Err(protocol_error()),
+    }
+    Ok(u64::from_be_bytes(value))
+}
+
+///Receiving side of the transfer over pull0 socket
+pub struct Receiver<'a> {
+    socket: &'a Socket,
+}
+
+impl<'a> Receiver<'a> {
+    #[inline]
+    ///Creates new receiver over `socket`, which should be pull0 socket
+    pub fn new(socket: &'a Socket) -> Self {
+        Self {
+            socket,
+        }
+    }
+
+    ///Receives single transfer into `writer`, invoking `progress` after every chunk.
+    ///
+    ///Messages preceding start of the transfer are discarded.
+    ///Returns number of bytes received, or protocol error if transfer is incomplete or corrupted.
+    ///
+    ///Use [RecvTimeout](crate::options::RecvTimeout) to avoid waiting forever if sender is gone.
+    pub fn receive<W: Write, P: FnMut(Progress)>(&self, mut writer: W, mut progress: P) -> Result<u64, ErrorCode> {
+        let (id, total) = loop {
+            let msg = self.socket.recv_msg()?;
+            if let Ok((START, id, rest)) = split_header(msg.body()) {
+                let total = match read_u64(rest)? {
+                    UNKNOWN_SIZE => None,
+                    total => Some(total),
+                };
+                break (id, total);
+            }
+        };
+
+        let mut crc = Crc32::new();
+        let mut transferred = 0u64;
+        loop {
+            let msg = self.socket.recv_msg()?;
+            let (kind, msg_id, rest) = split_header(msg.body())?;
+            if msg_id != id {
+                return Err(protocol_error());
+            }
+
+            match kind {
+                CHUNK => {
+                    if read_u64(rest)? != transferred {
+                        return Err(protocol_error());
+                    }
+                    let chunk = &rest[8..];
+                    writer.write_all(chunk)?;
+                    crc.update(chunk);
+                    transferred += chunk.len() as u64;
+                    progress(Progress {
+                        transferred,
+                        total,
+                    });
+                },
+                END => {
+                    let size = read_u64(rest)?;
+                    let checksum = match rest.get(8..12) {
+                        Some(checksum) => u32::from_be_bytes([checksum[0], checksum[1], checksum[2], checksum[3]]),
+                        None => return Err(protocol_error()),
+                    };
+                    if size != transferred || checksum != crc.finish() {
+                        return Err(protocol_error());
+                    }
+                    writer.flush()?;
+                    break Ok(transferred);
+                },
+                _ => break Err(protocol_error()),
+            }
+        }
+    }
+
+    ///Receives single transfer into file at `path`, invoking `progress` after every chunk.
+    ///
+    ///File is created or truncated. If transfer fails, partially written file is removed.
+    pub fn receive_file<P: AsRef<Path>, F: FnMut(Progress)>(&self, path: P, progress: F) -> Result<u64, ErrorCode> {
+        let path = path.as_ref();
+        let file = std::fs::File::create(path)?;
+        let result = self.receive(std::io::BufWriter::new(file), progress);
+        if result.is_err() {
+            let _ = std::fs::remove_file(path);
+        }
+        result
+    }
+}
+
+impl fmt::Debug for Receiver<'_> {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Receiver").field("socket", &self.socket).finish()
+    }
+}
@@ -0,0 +1,355 @@
+//!URL utilities
+//!
+//!nng reports URL with transport, that is not compiled in, as bare `NNG_ENOTSUP`.
+//!Transports `tls+tcp`, `ws` and `wss` are optional and depend on cargo features, so this module
+//!checks URL scheme upfront to report which feature is missing.
+//!
+//![Url] allows to construct correctly formatted URLs, that can be passed to the socket without extra allocation.
+//!
+//!## Usage
+//!
+//!```rust
+//!use nng_c::Socket;
+//!use nng_c::url::{Scheme, Url};
+//!
+//!let url = Url::inproc("url-example").expect("create url");
+//!assert_eq!(url.scheme(), Scheme::Inproc);
+//!assert_eq!(url.as_str(), "inproc://url-example");
+//!
+//!let server = Socket::rep0().expect("create server");
+//!server.listen((&url).into()).expect("listen");
+//!
+//!assert_eq!(Url::tcp("localhost", 8080).expect("create url").as_str(), "tcp://localhost:8080");
+//!assert_eq!(Url::ws("localhost", 8080, "api").expect("create url").as_str(), "ws://localhost:8080/api");
+//!```
+use crate::ErrorCode;
+use crate::error::{error, missing_feature, MissingFeature};
+use crate::sys;
+
+use core::fmt;
+use core::net::SocketAddr;
+
+use alloc::string::String;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+///Transport of the URL
+pub enum Scheme {
+    ///In-process transport
+    Inproc,
+    ///Inter-process transport over UNIX domain sockets or Windows named pipes
+    Ipc,
+    ///Inter-process transport over abstract UNIX domain sockets (Linux only)
+    Abstract,
+    ///TCP transport
+    Tcp,
+    ///TLS over TCP transport. Requires `tls` feature
+    Tls,
+    ///Websocket transport. Requires `websocket` feature
+    Ws,
+    ///Websocket over TLS transport. Requires `websocket` and `tls` features
+    Wss,
+}
+
+impl Scheme {
+    #[inline]
+    ///Returns textual name of the scheme, as used in URL
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Inproc => "inproc",
+            Self::Ipc => "ipc",
+            Self::Abstract => "abstract",
+            Self::Tcp => "tcp",
+            Self::Tls => "tls+tcp",
+            Self::Ws => "ws",
+            Self::Wss => "wss",
+        }
+    }
+
+    #[inline]
+    ///Returns whether transport is compiled in
+    pub const fn is_enabled(&self) -> bool {
+        match self {
+            Self::Inproc | Self::Ipc | Self::Abstract | Self::Tcp => true,
+            Self::Tls => cfg!(feature = "tls"),
+            Self::Ws => cfg!(feature = "websocket"),
+            Self::Wss => cfg!(all(feature = "websocket", feature = "tls")),
+        }
+    }
+
+    ///Determines scheme of the `url`
+    ///
+    ///Address family suffix of IP based transports (i.e. `tcp4://` or `ws6://`) is accepted.
+    ///Returns `None` if `url` has no known scheme.
+    pub fn from_url(url: &[u8]) -> Option<Self> {
+        const SCHEMES: [Scheme; 7] = [Scheme::Inproc, Scheme::Ipc, Scheme::Abstract, Scheme::Tcp, Scheme::Tls, Scheme::Ws, Scheme::Wss];
+
+        SCHEMES.iter().find(|scheme| scheme.matches(url)).copied()
+    }
+
+    fn matches(&self, url: &[u8]) -> bool {
+        let rest = match url.strip_prefix(self.as_str().as_bytes()) {
+            Some(rest) => rest,
+            None => return false,
+        };
+        //IP transports accept optional address family suffix
+        let rest = match (self, rest.first()) {
+            (Self::Tcp | Self::Tls | Self::Ws | Self::Wss, Some(b'4' | b'6')) => &rest[1..],
+            _ => rest,
+        };
+        rest.starts_with(b"://")
+    }
+}
+
+impl fmt::Display for Scheme {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str(self.as_str())
+    }
+}
+
+///Checks that transport of the `url` is enabled.
+///
+///Returns `NNG_ENOTSUP` error, describing missing cargo feature, if `url` uses `tls+tcp`, `ws` or `wss` transport, but it is not compiled in.
+///Any other URL is accepted as it is, leaving its validation to nng.
+pub fn check_scheme(url: &[u8]) -> Result<(), ErrorCode> {
+    let scheme = match Scheme::from_url(url) {
+        Some(scheme) => scheme,
+        None => return Ok(()),
+    };
+
+    if scheme.is_enabled() {
+        return Ok(());
+    }
+
+    Err(missing_feature(match scheme {
+        Scheme::Ws => MissingFeature::Websocket,
+        Scheme::Wss => MissingFeature::WebsocketTls,
+        _ => MissingFeature::Tls,
+    }))
+}
+
+//Host of IP based transport
+struct Host<'a>(&'a str);
+
+impl fmt::Display for Host<'_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let host = self.0;
+        //nng treats empty host as wildcard, while `*` is only legacy nanomsg alias for it
+        if host.is_empty() || host == "*" {
+            return Ok(());
+        }
+
+        let host = host.strip_prefix('[').and_then(|host| host.strip_suffix(']')).unwrap_or(host);
+        if host.contains(':') {
+            //Zone id is passed to resolver as it is, so percent encoded delimiter is not understood
+            match host.split_once("%25") {
+                Some((addr, zone)) => fmt.write_fmt(format_args!("[{}%{}]", addr, zone)),
+                None => fmt.write_fmt(format_args!("[{}]", host)),
+            }
+        } else {
+            fmt.write_str(host)
+        }
+    }
+}
+
+//Path of websocket transport
+struct Path<'a>(Option<&'a str>);
+
+impl fmt::Display for Path<'_> {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(path) => fmt.write_fmt(format_args!("/{}", path.trim_start_matches('/'))),
+            None => Ok(()),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+///Null terminated URL of the endpoint
+///
+///Converts into [String](crate::str::String) without allocation, to be used with `listen` or `connect`.
+pub struct Url {
+    scheme: Scheme,
+    //Always ends with zero char
+    buf: String,
+}
+
+impl Url {
+    fn with_address(scheme: Scheme, address: fmt::Arguments<'_>) -> Result<Self, ErrorCode> {
+        let mut buf = String::new();
+        let _ = fmt::write(&mut buf, format_args!("{}://{}", scheme, address));
+        if buf.contains('\0') {
+            return Err(error(sys::nng_errno_enum::NNG_EADDRINVAL));
+        }
+        buf.push('\0');
+        Ok(Self {
+            scheme,
+            buf,
+        })
+    }
+
+    #[inline]
+    ///Creates URL with `scheme` and raw `address`, that follows `scheme://`
+    ///
+    ///Returns `NNG_EADDRINVAL` if `address` contains zero char.
+    pub fn new(scheme: Scheme, address: &str) -> Result<Self, ErrorCode> {
+        Self::with_address(scheme, format_args!("{}", address))
+    }
+
+    ///Parses `url`, verifying it has known scheme.
+    ///
+    ///Returns `NNG_EADDRINVAL` if scheme is not known or `url` contains zero char anywhere except its end.
+    pub fn parse(url: &str) -> Result<Self, ErrorCode> {
+        let url = url.strip_suffix('\0').unwrap_or(url);
+        if url.contains('\0') {
+            return Err(error(sys::nng_errno_enum::NNG_EADDRINVAL));
+        }
+
+        match Scheme::from_url(url.as_bytes()) {
+            Some(scheme) => {
+                let mut buf = String::with_capacity(url.len().saturating_add(1));
+                buf.push_str(url);
+                buf.push('\0');
+                Ok(Self {
+                    scheme,
+                    buf,
+                })
+            },
+            None => Err(error(sys::nng_errno_enum::NNG_EADDRINVAL)),
+        }
+    }
+
+    #[inline]
+    ///Creates `inproc://name` URL
+    pub fn inproc(name: &str) -> Result<Self, ErrorCode> {
+        Self::new(Scheme::Inproc, name)
+    }
+
+    #[inline]
+    ///Creates `ipc://path` URL
+    pub fn ipc(path: &str) -> Result<Self, ErrorCode> {
+        Self::new(Scheme::Ipc, path)
+    }
+
+    #[inline]
+    ///Creates `abstract://name` URL
+    pub fn abstract_socket(name: &str) -> Result<Self, ErrorCode> {
+        Self::new(Scheme::Abstract, name)
+    }
+
+    #[inline]
+    fn ip(scheme: Scheme, host: &str, port: u16, path: Option<&str>) -> Result<Self, ErrorCode> {
+        Self::with_address(scheme, format_args!("{}:{}{}", Host(host), port, Path(path)))
+    }
+
+    #[inline]
+    ///Creates `tcp://host:port` URL
+    ///
+    ///IPv6 `host` is enclosed in brackets, if necessary, while empty or `*` `host` binds to all interfaces.
+    ///
+    ///Returns `NNG_EADDRINVAL` if `host` contains zero char.
+    pub fn tcp(host: &str, port: u16) -> Result<Self, ErrorCode> {
+        Self::ip(Scheme::Tcp, host, port, None)
+    }
+
+    #[inline]
+    ///Creates `tls+tcp://host:port` URL
+    ///
+    ///`host` is formatted as in [tcp](Self::tcp)
+    pub fn tls(host: &str, port: u16) -> Result<Self, ErrorCode> {
+        Self::ip(Scheme::Tls, host, port, None)
+    }
+
+    #[inline]
+    ///Creates `ws://host:port/path` URL
+    ///
+    ///`host` is formatted as in [tcp](Self::tcp)
+    pub fn ws(host: &str, port: u16, path: &str) -> Result<Self, ErrorCode> {
+        Self::ip(Scheme::Ws, host, port, Some(path))
+    }
+
+    #[inline]
+    ///Creates `wss://host:port/path` URL
+    ///
+    ///`host` is formatted as in [tcp](Self::tcp)
+    pub fn wss(host: &str, port: u16, path: &str) -> Result<Self, ErrorCode> {
+        Self::ip(Scheme::Wss, host, port, Some(path))
+    }
+
+    ///Creates URL of IP based `scheme` with `addr`
+    ///
+    ///Scope id of IPv6 address is preserved as zone id. Websocket URLs get root path.
+    ///
+    ///Returns `NNG_EADDRINVAL` if `scheme` is not IP based.
+    pub fn from_socket_addr(scheme: Scheme, addr: SocketAddr) -> Result<Self, ErrorCode> {
+        let path = match scheme {
+            Scheme::Tcp | Scheme::Tls => None,
+            Scheme::Ws | Scheme::Wss => Some(""),
+            _ => return Err(error(sys::nng_errno_enum::NNG_EADDRINVAL)),
+        };
+
+        let path = Path(path);
+        match addr {
+            SocketAddr::V4(addr) => Self::with_address(scheme, format_args!("{}{}", addr, path)),
+            SocketAddr::V6(addr) if addr.scope_id() != 0 => Self::with_address(scheme, format_args!("[{}%{}]:{}{}", addr.ip(), addr.scope_id(), addr.port(), path)),
+            SocketAddr::V6(addr) => Self::with_address(scheme, format_args!("[{}]:{}{}", addr.ip(), addr.port(), path)),
+        }
+    }
+
+    ///Creates URL of IP based `scheme`, binding to all interfaces on `port`
+    ///
+    ///Returns `NNG_EADDRINVAL` if `scheme` is not IP based.
+    pub fn wildcard(scheme: Scheme, port: u16) -> Result<Self, ErrorCode> {
+        match scheme {
+            Scheme::Tcp | Scheme::Tls => Self::ip(scheme, "", port, None),
+            Scheme::Ws | Scheme::Wss => Self::ip(scheme, "", port, Some("")),
+            _ => Err(error(sys::nng_errno_enum::NNG_EADDRINVAL)),
+        }
+    }
+
+    #[inline(always)]
+    ///Returns scheme of the URL
+    pub fn scheme(&self) -> Scheme {
+        self.scheme
+    }
+
+    #[inline(always)]
+    ///Returns URL without zero char
+    pub fn as_str(&self) -> &str {
+        &self.buf[..self.buf.len() - 1]
+    }
+
+    #[inline(always)]
+    ///Returns URL with zero char at the end
+    pub fn as_bytes_with_nul(&self) -> &[u8] {
+        self.buf.as_bytes()
+    }
+
+    #[inline(always)]
+    ///Checks that transport of the URL is enabled, as per [check_scheme]
+    pub fn check(&self) -> Result<(), ErrorCode> {
+        check_scheme(self.as_str().as_bytes())
+    }
+}
+
+impl fmt::Debug for Url {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), fmt)
+    }
+}
+
+impl fmt::Display for Url {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str(self.as_str())
+    }
+}
+
+impl<'a> From<&'a Url> for crate::str::String<'a> {
+    #[inline(always)]
+    fn from(url: &'a Url) -> Self {
+        Self::new_c(url.as_bytes_with_nul())
+    }
+}
@@ -5,7 +5,20 @@ use nng_c_sys::nng_system_logger;
 use nng_c_sys::nng_null_logger;
 use nng_c_sys::nng_log_set_logger;
 
-#[derive(Copy, Clone)]
+pub mod uri;
+pub mod sync;
+pub mod thread;
+pub use thread::spawn;
+pub mod executor;
+pub use executor::block_on;
+#[cfg(feature = "spin")]
+pub use executor::{spin_on, spin_on_with};
+#[cfg(feature = "std")]
+pub mod bench;
+#[cfg(feature = "std")]
+pub mod ipc;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 #[repr(i32)]
 ///NNG logging level
 pub enum Level {
@@ -34,6 +47,20 @@ impl Level {
     }
 }
 
+impl Level {
+    #[inline]
+    fn from_raw(level: nng_c_sys::nng_log_level::Type) -> Option<Self> {
+        match level {
+            nng_c_sys::nng_log_level::NNG_LOG_ERR => Some(Self::Error),
+            nng_c_sys::nng_log_level::NNG_LOG_WARN => Some(Self::Warn),
+            nng_c_sys::nng_log_level::NNG_LOG_NOTICE => Some(Self::Info),
+            nng_c_sys::nng_log_level::NNG_LOG_INFO => Some(Self::Debug),
+            nng_c_sys::nng_log_level::NNG_LOG_DEBUG => Some(Self::Trace),
+            _ => None,
+        }
+    }
+}
+
 impl Default for Level {
     #[inline(always)]
     fn default() -> Self {
@@ -50,7 +77,8 @@ pub fn disable_logging() {
 
 ///Enables logging using standard facilities of nng.
 ///
-///Specifically it will use syslog on POSIX compliant systems while other systems will use stderr
+///Specifically it will use syslog on POSIX compliant systems while other systems will use stderr.
+///Use [set_log_facility] to select syslog facility.
 pub fn enable_logging(level: Level) {
     unsafe {
         nng_log_set_level(level as _);
@@ -151,3 +179,244 @@ pub fn enable_tracing(level: Level) {
         nng_log_set_logger(Some(nng_rust_tracing_logger));
     }
 }
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+///NNG logging facility, corresponding to syslog facility
+pub enum Facility {
+    ///User level messages
+    User,
+    ///System daemon messages
+    Daemon,
+    ///Security and authentication messages
+    Auth,
+    ///Local use facility from 0 to 7
+    Local(u8),
+    ///Unknown facility
+    Other(i32),
+}
+
+impl Facility {
+    #[inline]
+    fn from_raw(facility: nng_c_sys::nng_log_facility::Type) -> Self {
+        match facility {
+            nng_c_sys::nng_log_facility::NNG_LOG_USER => Self::User,
+            nng_c_sys::nng_log_facility::NNG_LOG_DAEMON => Self::Daemon,
+            nng_c_sys::nng_log_facility::NNG_LOG_AUTH => Self::Auth,
+            nng_c_sys::nng_log_facility::NNG_LOG_LOCAL0..=nng_c_sys::nng_log_facility::NNG_LOG_LOCAL7 => Self::Local((facility - nng_c_sys::nng_log_facility::NNG_LOG_LOCAL0) as u8),
+            other => Self::Other(other),
+        }
+    }
+
+    #[inline]
+    fn into_raw(self) -> nng_c_sys::nng_log_facility::Type {
+        match self {
+            Self::User => nng_c_sys::nng_log_facility::NNG_LOG_USER,
+            Self::Daemon => nng_c_sys::nng_log_facility::NNG_LOG_DAEMON,
+            Self::Auth => nng_c_sys::nng_log_facility::NNG_LOG_AUTH,
+            Self::Local(idx) => nng_c_sys::nng_log_facility::NNG_LOG_LOCAL0 + core::cmp::min(idx, 7) as nng_c_sys::nng_log_facility::Type,
+            Self::Other(other) => other,
+        }
+    }
+}
+
+///Sets facility of nng log messages, which is [User](Facility::User) by default.
+///
+///Facility is used by system logger, installed via [enable_logging], to direct messages to the proper syslog facility.
+///[Local](Facility::Local) index is limited to 7.
+pub fn set_log_facility(facility: Facility) {
+    unsafe {
+        nng_c_sys::nng_log_set_facility(facility.into_raw());
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+///Log event emitted by NNG
+pub struct LogEvent<'a> {
+    ///Level of the message
+    pub level: Level,
+    ///Facility of the message
+    pub facility: Facility,
+    ///Message identifier, describing subsystem that emitted message (e.g. `NNG-PIPEADD`)
+    pub msg_id: Option<&'a str>,
+    ///Message text
+    pub msg: &'a str,
+}
+
+static LOG_CALLBACK: core::sync::atomic::AtomicPtr<()> = core::sync::atomic::AtomicPtr::new(core::ptr::null_mut());
+
+///Enables logging, delivering every message as [LogEvent] to the `callback`
+///
+///Callback is global and replaced by subsequent call.
+///It may be invoked from any thread, including nng's internal threads, so it should not block.
+///
+///Note that messages are only logged if C strings are valid utf-8
+pub fn enable_event_logging(level: Level, callback: fn(&LogEvent<'_>)) {
+    use core::ffi::CStr;
+    use core::sync::atomic::Ordering;
+
+    unsafe extern "C" fn nng_rust_event_logger(level: nng_c_sys::nng_log_level::Type, facility: nng_c_sys::nng_log_facility::Type, msg_id: *const core::ffi::c_char, msg: *const core::ffi::c_char) {
+        let callback = LOG_CALLBACK.load(Ordering::Acquire);
+        if callback.is_null() || msg.is_null() {
+            return;
+        }
+        let callback: fn(&LogEvent<'_>) = core::mem::transmute(callback);
+
+        let level = match Level::from_raw(level) {
+            Some(level) => level,
+            None => return,
+        };
+
+        let msg = match CStr::from_ptr(msg).to_str() {
+            Ok(msg) => msg,
+            Err(_) => return,
+        };
+
+        let msg_id = if msg_id.is_null() {
+            None
+        } else {
+            CStr::from_ptr(msg_id).to_str().ok()
+        };
+
+        callback(&LogEvent {
+            level,
+            facility: Facility::from_raw(facility),
+            msg_id,
+            msg,
+        });
+    }
+
+    LOG_CALLBACK.store(callback as *mut (), Ordering::Release);
+    unsafe {
+        nng_log_set_level(level as _);
+        nng_log_set_logger(Some(nng_rust_event_logger));
+    }
+}
+
+#[cfg(feature = "std")]
+///Default size of log file after which it is rotated, used by [enable_file_logging]
+pub const DEFAULT_LOG_FILE_SIZE: u64 = 10 * 1024 * 1024;
+
+#[cfg(feature = "std")]
+struct LogFile {
+    path: std::path::PathBuf,
+    file: std::fs::File,
+    size: u64,
+    max_size: u64,
+}
+
+#[cfg(feature = "std")]
+impl LogFile {
+    fn open(path: std::path::PathBuf, max_size: u64) -> std::io::Result<Self> {
+        let file = std::fs::OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            path,
+            file,
+            size,
+            max_size,
+        })
+    }
+
+    fn rotate(&mut self) -> std::io::Result<()> {
+        let mut backup = self.path.clone().into_os_string();
+        backup.push(".1");
+        std::fs::rename(&self.path, backup)?;
+        self.file = std::fs::OpenOptions::new().create(true).write(true).truncate(true).open(&self.path)?;
+        self.size = 0;
+        Ok(())
+    }
+
+    fn write(&mut self, line: &[u8]) -> std::io::Result<()> {
+        use std::io::Write;
+
+        if self.size > 0 && self.size + line.len() as u64 > self.max_size {
+            self.rotate()?;
+        }
+        self.file.write_all(line)?;
+        self.size += line.len() as u64;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+static LOG_FILE: std::sync::Mutex<Option<LogFile>> = std::sync::Mutex::new(None);
+
+#[cfg(feature = "std")]
+///Enables logging into file at `path`, rotating it once it reaches [DEFAULT_LOG_FILE_SIZE]
+///
+///Requires feature `std`
+///
+///Refer to [enable_rotating_file_logging] for details.
+pub fn enable_file_logging<P: AsRef<std::path::Path>>(path: P, level: Level) -> std::io::Result<()> {
+    enable_rotating_file_logging(path, level, DEFAULT_LOG_FILE_SIZE)
+}
+
+#[cfg(feature = "std")]
+///Enables logging into file at `path`, rotating it once it reaches `max_size` bytes
+///
+///Requires feature `std`
+///
+///Each message is written as single line, prefixed with UNIX timestamp in milliseconds precision and level.
+///On rotation, current file is renamed by appending `.1` to its name, replacing previous one, and new file is started.
+///
+///Messages are appended to the existing file, if any.
+///Failures to write messages are ignored.
+pub fn enable_rotating_file_logging<P: AsRef<std::path::Path>>(path: P, level: Level, max_size: u64) -> std::io::Result<()> {
+    use core::ffi::CStr;
+    use std::io::Write;
+
+    unsafe extern "C" fn nng_rust_file_logger(level: nng_c_sys::nng_log_level::Type, _: nng_c_sys::nng_log_facility::Type, msg_id: *const core::ffi::c_char, msg: *const core::ffi::c_char) {
+        const NNG: &str = "NNG";
+
+        if msg.is_null() {
+            return;
+        }
+
+        let level = match Level::from_raw(level) {
+            Some(Level::Error) => "ERROR",
+            Some(Level::Warn) => "WARN",
+            Some(Level::Info) => "INFO",
+            Some(Level::Debug) => "DEBUG",
+            Some(Level::Trace) => "TRACE",
+            None => return,
+        };
+
+        let msg = match CStr::from_ptr(msg).to_str() {
+            Ok(msg) => msg,
+            Err(_) => return,
+        };
+
+        let nng_tag = if msg_id.is_null() {
+            NNG
+        } else {
+            match CStr::from_ptr(msg_id).to_str() {
+                Ok(msg) => msg,
+                Err(_) => NNG,
+            }
+        };
+
+        let timestamp = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default();
+        let mut line = std::vec::Vec::with_capacity(msg.len() + 64);
+        let _ = writeln!(line, "{}.{:03} {} {}: {}", timestamp.as_secs(), timestamp.subsec_millis(), level, nng_tag, msg);
+
+        let mut log_file = match LOG_FILE.lock() {
+            Ok(log_file) => log_file,
+            Err(error) => error.into_inner(),
+        };
+        if let Some(log_file) = log_file.as_mut() {
+            let _ = log_file.write(&line);
+        }
+    }
+
+    let log_file = LogFile::open(path.as_ref().to_path_buf(), max_size)?;
+    match LOG_FILE.lock() {
+        Ok(mut current) => *current = Some(log_file),
+        Err(error) => *error.into_inner() = Some(log_file),
+    }
+
+    unsafe {
+        nng_log_set_level(level as _);
+        nng_log_set_logger(Some(nng_rust_file_logger));
+    }
+    Ok(())
+}
@@ -0,0 +1,227 @@
+//!Round-trip benchmarking
+//!
+//!Measures latency and throughput of ping/pong exchange between two connected sockets.
+//!
+//!Both client and server sides are driven by the calling thread (or task), so benchmark measures
+//!cost of transport and nng itself, without any contention with user's threads.
+//!Sockets must use protocols that allow to both send and receive (i.e. pair or req/rep).
+//!
+//!## Usage
+//!
+//!```rust
+//!use nng_c::Socket;
+//!use nng_c::utils::bench;
+//!
+//!const ADDR: &str = "inproc://bench_example\0";
+//!
+//!let server = Socket::rep0().expect("create server");
+//!server.listen(ADDR.into()).expect("listen");
+//!let client = Socket::req0().expect("create client");
+//!client.connect(ADDR.into()).expect("connect");
+//!
+//!let report = bench::round_trip(&client, &server, &bench::Config::new().messages(100)).expect("run benchmark");
+//!assert_eq!(report.messages, 100);
+//!println!("{}", report);
+//!```
+
+use crate::ErrorCode;
+use crate::error::error;
+use crate::msg::Message;
+use crate::socket::Socket;
+use crate::sys;
+
+use core::{fmt, time};
+
+use alloc::vec::Vec;
+
+use std::time::Instant;
+
+#[derive(Copy, Clone, Debug)]
+///Benchmark configuration
+pub struct Config {
+    messages: usize,
+    warmup: usize,
+    size: usize,
+}
+
+impl Config {
+    #[inline]
+    ///Creates default configuration: 1000 messages of 64 bytes with 100 warmup messages
+    pub const fn new() -> Self {
+        Self {
+            messages: 1000,
+            warmup: 100,
+            size: 64,
+        }
+    }
+
+    #[inline]
+    ///Sets number of measured round trips
+    pub const fn messages(mut self, messages: usize) -> Self {
+        self.messages = messages;
+        self
+    }
+
+    #[inline]
+    ///Sets number of round trips to perform before measuring
+    pub const fn warmup(mut self, warmup: usize) -> Self {
+        self.warmup = warmup;
+        self
+    }
+
+    #[inline]
+    ///Sets size of message's body in bytes
+    pub const fn size(mut self, size: usize) -> Self {
+        self.size = size;
+        self
+    }
+
+    fn message(&self) -> Result<Message, ErrorCode> {
+        let mut msg = match Message::new() {
+            Some(msg) => msg,
+            None => return Err(error(sys::nng_errno_enum::NNG_ENOMEM)),
+        };
+        let body = (0..self.size).map(|idx| idx as u8).collect::<Vec<_>>();
+        msg.append(&body)?;
+        Ok(msg)
+    }
+}
+
+impl Default for Config {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Clone, Debug)]
+///Benchmark results
+pub struct Report {
+    ///Number of measured round trips
+    pub messages: usize,
+    ///Size of message's body in bytes
+    pub size: usize,
+    ///Total time of measured round trips
+    pub elapsed: time::Duration,
+    ///Minimal round trip time
+    pub min: time::Duration,
+    ///Maximum round trip time
+    pub max: time::Duration,
+    ///Average round trip time
+    pub mean: time::Duration,
+    ///Median round trip time
+    pub p50: time::Duration,
+    ///99th percentile of round trip time
+    pub p99: time::Duration,
+}
+
+impl Report {
+    fn new(size: usize, mut samples: Vec<time::Duration>) -> Self {
+        samples.sort_unstable();
+        let elapsed = samples.iter().sum::<time::Duration>();
+        let percentile = |pct: usize| match samples.len() {
+            0 => time::Duration::ZERO,
+            len => samples[(len - 1) * pct / 100],
+        };
+
+        Self {
+            messages: samples.len(),
+            size,
+            elapsed,
+            min: samples.first().copied().unwrap_or_default(),
+            max: samples.last().copied().unwrap_or_default(),
+            mean: match samples.len() {
+                0 => time::Duration::ZERO,
+                len => elapsed / len as u32,
+            },
+            p50: percentile(50),
+            p99: percentile(99),
+        }
+    }
+
+    ///Returns number of round trips per second
+    pub fn round_trips_per_sec(&self) -> f64 {
+        match self.elapsed.as_secs_f64() {
+            elapsed if elapsed > 0.0 => self.messages as f64 / elapsed,
+            _ => 0.0,
+        }
+    }
+
+    #[inline]
+    ///Returns number of transferred bytes per second, accounting for both directions
+    pub fn bytes_per_sec(&self) -> f64 {
+        self.round_trips_per_sec() * (self.size * 2) as f64
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_fmt(format_args!(
+            "{} round trips of {} bytes in {:?}: min={:?} mean={:?} p50={:?} p99={:?} max={:?} ({:.0} msg/s, {:.0} B/s)",
+            self.messages, self.size, self.elapsed,
+            self.min, self.mean, self.p50, self.p99, self.max,
+            self.round_trips_per_sec(), self.bytes_per_sec()
+        ))
+    }
+}
+
+#[inline]
+fn round_trip_once(client: &Socket, server: &Socket, msg: Message) -> Result<Message, ErrorCode> {
+    client.send_msg(msg).map_err(|(_, error)| error.code())?;
+    let msg = server.recv_msg()?;
+    server.send_msg(msg).map_err(|(_, error)| error.code())?;
+    client.recv_msg().map_err(Into::into)
+}
+
+///Runs benchmark, using blocking operations.
+///
+///`client` sends message to the `server` which is sent back as it is.
+pub fn round_trip(client: &Socket, server: &Socket, config: &Config) -> Result<Report, ErrorCode> {
+    let mut msg = config.message()?;
+    for _ in 0..config.warmup {
+        msg = round_trip_once(client, server, msg)?;
+    }
+
+    let mut samples = Vec::with_capacity(config.messages);
+    for _ in 0..config.messages {
+        let start = Instant::now();
+        msg = round_trip_once(client, server, msg)?;
+        samples.push(start.elapsed());
+    }
+
+    Ok(Report::new(config.size, samples))
+}
+
+#[inline]
+async fn recv_async(socket: &Socket) -> Result<Message, ErrorCode> {
+    match socket.recv_msg_async()?.await? {
+        Some(msg) => Ok(msg),
+        None => Err(error(sys::nng_errno_enum::NNG_EINTERNAL)),
+    }
+}
+
+async fn round_trip_once_async(client: &Socket, server: &Socket, msg: Message) -> Result<Message, ErrorCode> {
+    client.send_msg_async(msg)?.await.map_err(|(_, error)| error)?;
+    let msg = recv_async(server).await?;
+    server.send_msg_async(msg)?.await.map_err(|(_, error)| error)?;
+    recv_async(client).await
+}
+
+///Runs benchmark, using asynchronous operations.
+///
+///`client` sends message to the `server` which is sent back as it is.
+pub async fn round_trip_async(client: &Socket, server: &Socket, config: &Config) -> Result<Report, ErrorCode> {
+    let mut msg = config.message()?;
+    for _ in 0..config.warmup {
+        msg = round_trip_once_async(client, server, msg).await?;
+    }
+
+    let mut samples = Vec::with_capacity(config.messages);
+    for _ in 0..config.messages {
+        let start = Instant::now();
+        msg = round_trip_once_async(client, server, msg).await?;
+        samples.push(start.elapsed());
+    }
+
+    Ok(Report::new(config.size, samples))
+}
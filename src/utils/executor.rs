@@ -0,0 +1,182 @@
+//!Minimal executor
+//!
+//!Allows synchronous applications to await futures of this crate without adopting full async runtime.
+//!Current thread is blocked on nng's condition variable until future is woken, hence it is available without `std`.
+//!
+//!For targets without threads, feature `spin` provides `spin_on`, which busy-polls future instead.
+//!
+//!## Usage
+//!
+//!```rust
+//!use nng_c::utils::block_on;
+//!
+//!let result = block_on(async { 1 + 1 }).expect("create executor");
+//!assert_eq!(result, 2);
+//!```
+
+use crate::ErrorCode;
+use crate::error::error;
+use crate::sys;
+
+use core::{ptr, task};
+use core::cell::UnsafeCell;
+use core::future::Future;
+
+use alloc::sync::Arc;
+use alloc::task::Wake;
+
+struct Parker {
+    mutex: ptr::NonNull<sys::nng_mtx>,
+    cond: ptr::NonNull<sys::nng_cv>,
+    //Guarded by mutex
+    notified: UnsafeCell<bool>,
+}
+
+unsafe impl Send for Parker {}
+unsafe impl Sync for Parker {}
+
+impl Parker {
+    fn new() -> Result<Self, ErrorCode> {
+        let mut mutex = ptr::null_mut();
+        let result = unsafe {
+            sys::nng_mtx_alloc(&mut mutex)
+        };
+        let mutex = match ptr::NonNull::new(mutex) {
+            Some(mutex) if result == 0 => mutex,
+            _ => return Err(error(result)),
+        };
+
+        let mut cond = ptr::null_mut();
+        let result = unsafe {
+            sys::nng_cv_alloc(&mut cond, mutex.as_ptr())
+        };
+        match ptr::NonNull::new(cond) {
+            Some(cond) if result == 0 => Ok(Self {
+                mutex,
+                cond,
+                notified: UnsafeCell::new(false),
+            }),
+            _ => {
+                unsafe {
+                    sys::nng_mtx_free(mutex.as_ptr());
+                }
+                Err(error(result))
+            }
+        }
+    }
+
+    //Waits for notification, consuming it
+    fn park(&self) {
+        unsafe {
+            sys::nng_mtx_lock(self.mutex.as_ptr());
+            while !*self.notified.get() {
+                sys::nng_cv_wait(self.cond.as_ptr());
+            }
+            *self.notified.get() = false;
+            sys::nng_mtx_unlock(self.mutex.as_ptr());
+        }
+    }
+
+    fn unpark(&self) {
+        unsafe {
+            sys::nng_mtx_lock(self.mutex.as_ptr());
+            *self.notified.get() = true;
+            sys::nng_cv_wake1(self.cond.as_ptr());
+            sys::nng_mtx_unlock(self.mutex.as_ptr());
+        }
+    }
+}
+
+impl Drop for Parker {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe {
+            sys::nng_cv_free(self.cond.as_ptr());
+            sys::nng_mtx_free(self.mutex.as_ptr());
+        }
+    }
+}
+
+impl Wake for Parker {
+    #[inline(always)]
+    fn wake(self: Arc<Self>) {
+        self.unpark();
+    }
+
+    #[inline(always)]
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.unpark();
+    }
+}
+
+///Runs `fut` to completion on the current thread, blocking it while future is pending.
+///
+///Returns error if unable to allocate synchronization primitives.
+pub fn block_on<F: Future>(fut: F) -> Result<F::Output, ErrorCode> {
+    let parker = Arc::new(Parker::new()?);
+    let waker = task::Waker::from(parker.clone());
+    let mut ctx = task::Context::from_waker(&waker);
+
+    let mut fut = core::pin::pin!(fut);
+
+    loop {
+        match fut.as_mut().poll(&mut ctx) {
+            task::Poll::Ready(result) => break Ok(result),
+            task::Poll::Pending => parker.park(),
+        }
+    }
+}
+
+#[cfg(feature = "spin")]
+mod noop {
+    use core::{ptr, task};
+
+    static VTABLE: task::RawWakerVTable = task::RawWakerVTable::new(clone, action, action, action);
+
+    fn clone(_: *const ()) -> task::RawWaker {
+        task::RawWaker::new(ptr::null(), &VTABLE)
+    }
+
+    fn action(_: *const ()) {
+    }
+
+    #[inline(always)]
+    pub fn waker() -> task::Waker {
+        unsafe {
+            task::Waker::from_raw(clone(ptr::null()))
+        }
+    }
+}
+
+#[cfg(feature = "spin")]
+#[inline(always)]
+///Runs `fut` to completion by busy-polling it, spinning between polls.
+///
+///Refer to [spin_on_with] for details.
+///
+///Requires feature `spin`
+pub fn spin_on<F: Future>(fut: F) -> F::Output {
+    spin_on_with(fut, core::hint::spin_loop)
+}
+
+#[cfg(feature = "spin")]
+///Runs `fut` to completion by busy-polling it, calling `wait` between polls.
+///
+///Wake ups are ignored, as future is polled again right after `wait` returns, so that no thread
+///parking is required. `wait` is hook to yield to the platform (i.e. wait for interrupt or sleep),
+///reducing CPU usage.
+///
+///Requires feature `spin`
+pub fn spin_on_with<F: Future, W: FnMut()>(fut: F, mut wait: W) -> F::Output {
+    let waker = noop::waker();
+    let mut ctx = task::Context::from_waker(&waker);
+
+    let mut fut = core::pin::pin!(fut);
+
+    loop {
+        match fut.as_mut().poll(&mut ctx) {
+            task::Poll::Ready(result) => break result,
+            task::Poll::Pending => wait(),
+        }
+    }
+}
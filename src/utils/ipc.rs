@@ -0,0 +1,163 @@
+//!IPC endpoint paths
+//!
+//!Generates unique IPC endpoints, suitable for the current platform:
+//!
+//!- Linux uses abstract namespace, which requires no file and is never stale;
+//!- Other unix systems use socket file within temporary directory;
+//!- Windows uses named pipe.
+//!
+//!Unix socket paths are limited to about 100 bytes, hence name is truncated to fit, while
+//!uniqueness is guaranteed by process id and counter.
+//!
+//!Requires feature `std`
+//!
+//!## Usage
+//!
+//!```rust
+//!use nng_c::Socket;
+//!use nng_c::utils::ipc::IpcPath;
+//!
+//!let path = IpcPath::new("example");
+//!let server = Socket::pair0().expect("create socket");
+//!server.listen(path.url().into()).expect("listen");
+//!```
+
+use core::fmt;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use alloc::format;
+use alloc::string::String;
+use std::path::{Path, PathBuf};
+
+//sockaddr_un.sun_path is 108 bytes on Linux and 104 on BSD, including terminating zero
+const MAX_PATH_LEN: usize = 103;
+
+static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+fn file_name(name: &str, max_len: usize) -> String {
+    let prefix = format!("nng-c-{}-{}-", std::process::id(), COUNTER.fetch_add(1, Ordering::Relaxed));
+    let mut name_len = core::cmp::min(name.len(), max_len.saturating_sub(prefix.len()));
+    while !name.is_char_boundary(name_len) {
+        name_len -= 1;
+    }
+    format!("{}{}", prefix, &name[..name_len])
+}
+
+///Unique IPC endpoint
+///
+///If endpoint is backed by socket file, it is removed on drop.
+pub struct IpcPath {
+    url: String,
+    file: Option<PathBuf>,
+}
+
+impl IpcPath {
+    ///Generates unique endpoint, using `name` as part of its address
+    pub fn new(name: &str) -> Self {
+        if cfg!(target_os = "linux") {
+            Self {
+                url: format!("abstract://{}", file_name(name, MAX_PATH_LEN - 1)),
+                file: None,
+            }
+        } else if cfg!(windows) {
+            Self {
+                url: format!("ipc://{}", file_name(name, MAX_PATH_LEN)),
+                file: None,
+            }
+        } else {
+            Self::file(name)
+        }
+    }
+
+    ///Generates unique endpoint backed by socket file within temporary directory, using `name` as part of its address
+    ///
+    ///If temporary directory path is too long to fit, falls back to `/tmp`.
+    pub fn file(name: &str) -> Self {
+        let mut dir = std::env::temp_dir();
+        //Leave space for separator and at least prefix of file name
+        if dir.as_os_str().len() + 32 > MAX_PATH_LEN {
+            dir = PathBuf::from("/tmp");
+        }
+        let file = dir.join(file_name(name, MAX_PATH_LEN - dir.as_os_str().len() - 1));
+        Self {
+            url: format!("ipc://{}", file.display()),
+            file: Some(file),
+        }
+    }
+
+    #[inline(always)]
+    ///Returns URL of the endpoint
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    #[inline(always)]
+    ///Returns path to socket file, if endpoint is backed by file
+    pub fn path(&self) -> Option<&Path> {
+        self.file.as_deref()
+    }
+
+    #[inline]
+    ///Consumes self, returning URL without removing socket file
+    pub fn into_url(mut self) -> String {
+        self.file = None;
+        core::mem::take(&mut self.url)
+    }
+}
+
+impl Drop for IpcPath {
+    fn drop(&mut self) {
+        if let Some(file) = self.file.as_ref() {
+            let _ = std::fs::remove_file(file);
+        }
+    }
+}
+
+impl fmt::Debug for IpcPath {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("IpcPath").field("url", &self.url).finish()
+    }
+}
+
+impl fmt::Display for IpcPath {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str(&self.url)
+    }
+}
+
+///Removes stale socket file at `path`, left by process that did not shut down cleanly
+///
+///File is considered stale if nobody accepts connections on it.
+///Returns `true` if file is removed.
+///
+///Does nothing on non-unix systems.
+pub fn remove_stale(path: &Path) -> std::io::Result<bool> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::FileTypeExt;
+
+        match std::fs::symlink_metadata(path) {
+            Ok(meta) if meta.file_type().is_socket() => (),
+            Ok(_) => return Ok(false),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+            Err(error) => return Err(error),
+        }
+
+        match std::os::unix::net::UnixStream::connect(path) {
+            Ok(_) => Ok(false),
+            Err(error) if error.kind() == std::io::ErrorKind::ConnectionRefused => {
+                std::fs::remove_file(path)?;
+                Ok(true)
+            },
+            Err(error) => Err(error),
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = path;
+        Ok(false)
+    }
+}
@@ -0,0 +1,246 @@
+//!Synchronization primitives of nng
+//!
+//!Thin wrappers over `nng_mtx` and `nng_cv`, available without `std`, using the same
+//!threading implementation as nng itself.
+//!
+//!## Usage
+//!
+//!```rust
+//!use nng_c::utils::sync::{Condvar, Mutex};
+//!
+//!let ready = Mutex::new(false).expect("create mutex");
+//!let cond = Condvar::new(&ready).expect("create condvar");
+//!
+//!*ready.lock() = true;
+//!cond.notify_all();
+//!
+//!let guard = ready.lock();
+//!let guard = cond.wait_while(guard, |ready| !*ready);
+//!assert!(*guard);
+//!```
+
+use crate::ErrorCode;
+use crate::error::error;
+use crate::sys;
+
+use core::{fmt, ops, ptr, time};
+use core::cell::UnsafeCell;
+
+///Mutual exclusion lock, protecting `T`
+pub struct Mutex<T> {
+    raw: ptr::NonNull<sys::nng_mtx>,
+    value: UnsafeCell<T>,
+}
+
+unsafe impl<T: Send> Send for Mutex<T> {}
+unsafe impl<T: Send> Sync for Mutex<T> {}
+
+impl<T> Mutex<T> {
+    ///Creates new mutex protecting `value`
+    ///
+    ///Returns error if unable to allocate mutex.
+    pub fn new(value: T) -> Result<Self, ErrorCode> {
+        let mut raw = ptr::null_mut();
+        let result = unsafe {
+            sys::nng_mtx_alloc(&mut raw)
+        };
+
+        match ptr::NonNull::new(raw) {
+            Some(raw) if result == 0 => Ok(Self {
+                raw,
+                value: UnsafeCell::new(value),
+            }),
+            _ => Err(error(result)),
+        }
+    }
+
+    #[inline]
+    ///Acquires lock, waiting for it to be released if necessary.
+    ///
+    ///Lock is not recursive, locking it twice from the same thread results in deadlock.
+    pub fn lock(&self) -> MutexGuard<'_, T> {
+        unsafe {
+            sys::nng_mtx_lock(self.raw.as_ptr());
+        }
+
+        MutexGuard {
+            mutex: self,
+        }
+    }
+
+    #[inline(always)]
+    ///Returns mutable reference to the value, which requires no locking as access is exclusive.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.value.get_mut()
+    }
+
+    #[inline]
+    ///Consumes mutex, returning value
+    pub fn into_inner(self) -> T {
+        let this = core::mem::ManuallyDrop::new(self);
+        unsafe {
+            sys::nng_mtx_free(this.raw.as_ptr());
+            ptr::read(this.value.get())
+        }
+    }
+}
+
+impl<T> Drop for Mutex<T> {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe {
+            sys::nng_mtx_free(self.raw.as_ptr());
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Mutex<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Mutex").field("value", &*self.lock()).finish()
+    }
+}
+
+///Guard of the locked [Mutex], releasing lock on drop
+pub struct MutexGuard<'a, T> {
+    mutex: &'a Mutex<T>,
+}
+
+impl<T> ops::Deref for MutexGuard<'_, T> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        unsafe {
+            &*self.mutex.value.get()
+        }
+    }
+}
+
+impl<T> ops::DerefMut for MutexGuard<'_, T> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe {
+            &mut *self.mutex.value.get()
+        }
+    }
+}
+
+impl<T> Drop for MutexGuard<'_, T> {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe {
+            sys::nng_mtx_unlock(self.mutex.raw.as_ptr());
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for MutexGuard<'_, T> {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, fmt)
+    }
+}
+
+///Condition variable, bound to particular [Mutex]
+///
+///As with any condition variable, wake ups may be spurious, therefore condition must be checked
+///after each wait (or use [wait_while](Self::wait_while)).
+pub struct Condvar<'a, T> {
+    raw: ptr::NonNull<sys::nng_cv>,
+    mutex: &'a Mutex<T>,
+}
+
+unsafe impl<T: Send> Send for Condvar<'_, T> {}
+unsafe impl<T: Send> Sync for Condvar<'_, T> {}
+
+impl<'a, T> Condvar<'a, T> {
+    ///Creates new condition variable for use with `mutex`
+    ///
+    ///Returns error if unable to allocate condition variable.
+    pub fn new(mutex: &'a Mutex<T>) -> Result<Self, ErrorCode> {
+        let mut raw = ptr::null_mut();
+        let result = unsafe {
+            sys::nng_cv_alloc(&mut raw, mutex.raw.as_ptr())
+        };
+
+        match ptr::NonNull::new(raw) {
+            Some(raw) if result == 0 => Ok(Self {
+                raw,
+                mutex,
+            }),
+            _ => Err(error(result)),
+        }
+    }
+
+    #[inline(always)]
+    fn assert_guard(&self, guard: &MutexGuard<'_, T>) {
+        assert!(ptr::eq(self.mutex, guard.mutex), "Condvar is used with guard of different mutex");
+    }
+
+    ///Releases lock and waits for notification, re-acquiring lock before returning.
+    ///
+    ///Panics if `guard` belongs to different mutex.
+    pub fn wait<'g>(&self, guard: MutexGuard<'g, T>) -> MutexGuard<'g, T> {
+        self.assert_guard(&guard);
+        unsafe {
+            sys::nng_cv_wait(self.raw.as_ptr());
+        }
+        guard
+    }
+
+    ///Waits while `condition` returns `true`.
+    ///
+    ///Panics if `guard` belongs to different mutex.
+    pub fn wait_while<'g, F: FnMut(&mut T) -> bool>(&self, mut guard: MutexGuard<'g, T>, mut condition: F) -> MutexGuard<'g, T> {
+        while condition(&mut *guard) {
+            guard = self.wait(guard);
+        }
+        guard
+    }
+
+    ///Releases lock and waits for notification up to `timeout`, re-acquiring lock before returning.
+    ///
+    ///Returns guard with `true` if timeout elapsed.
+    ///
+    ///Panics if `guard` belongs to different mutex.
+    pub fn wait_timeout<'g>(&self, guard: MutexGuard<'g, T>, timeout: time::Duration) -> (MutexGuard<'g, T>, bool) {
+        self.assert_guard(&guard);
+        let result = unsafe {
+            let deadline = sys::nng_clock().saturating_add(timeout.as_millis() as _);
+            sys::nng_cv_until(self.raw.as_ptr(), deadline)
+        };
+        (guard, result == sys::nng_errno_enum::NNG_ETIMEDOUT)
+    }
+
+    #[inline]
+    ///Wakes up single waiting thread
+    pub fn notify_one(&self) {
+        unsafe {
+            sys::nng_cv_wake1(self.raw.as_ptr());
+        }
+    }
+
+    #[inline]
+    ///Wakes up all waiting threads
+    pub fn notify_all(&self) {
+        unsafe {
+            sys::nng_cv_wake(self.raw.as_ptr());
+        }
+    }
+}
+
+impl<T> Drop for Condvar<'_, T> {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe {
+            sys::nng_cv_free(self.raw.as_ptr());
+        }
+    }
+}
+
+impl<T> fmt::Debug for Condvar<'_, T> {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str("Condvar")
+    }
+}
@@ -0,0 +1,126 @@
+//!Threads of nng
+//!
+//!Allows to run background tasks (i.e. receive loop) using nng's own portable threads,
+//!without relying on `std`.
+//!
+//!## Usage
+//!
+//!```rust
+//!use nng_c::utils::thread;
+//!
+//!let handle = thread::spawn(|| 1 + 1).expect("spawn thread");
+//!assert_eq!(handle.join(), 2);
+//!```
+
+use crate::ErrorCode;
+use crate::error::error;
+use crate::fallible::try_box;
+use crate::str::String;
+use crate::sys;
+
+use core::{fmt, ptr};
+use core::ffi::c_void;
+
+use alloc::boxed::Box;
+
+struct Packet<F, R> {
+    func: Option<F>,
+    result: Option<R>,
+}
+
+unsafe extern "C" fn thread_entry<F: FnOnce() -> R, R>(data: *mut c_void) {
+    let packet = &mut *(data as *mut Packet<F, R>);
+    if let Some(func) = packet.func.take() {
+        packet.result = Some(func());
+    }
+}
+
+///Spawns new thread running `func`
+///
+///Panic within `func` aborts process, as it cannot unwind through nng's thread.
+///
+///Returns error if unable to create thread.
+pub fn spawn<F: FnOnce() -> R + Send + 'static, R: Send + 'static>(func: F) -> Result<JoinHandle<R>, ErrorCode> {
+    let packet = try_box(Packet::<F, R> {
+        func: Some(func),
+        result: None,
+    })?;
+    let packet = Box::into_raw(packet);
+
+    let mut thread = ptr::null_mut();
+    let result = unsafe {
+        sys::nng_thread_create(&mut thread, Some(thread_entry::<F, R>), packet as *mut c_void)
+    };
+
+    match ptr::NonNull::new(thread) {
+        Some(thread) if result == 0 => Ok(JoinHandle {
+            thread,
+            packet: packet as *mut c_void,
+            result: take_result::<F, R>,
+        }),
+        _ => {
+            drop(unsafe {
+                Box::from_raw(packet)
+            });
+            Err(error(result))
+        }
+    }
+}
+
+//Frees packet, returning result of the thread
+unsafe fn take_result<F, R>(packet: *mut c_void) -> Option<R> {
+    Box::from_raw(packet as *mut Packet<F, R>).result
+}
+
+///Handle to the thread, spawned via [spawn]
+///
+///nng's threads cannot be detached, hence dropping handle waits for thread to finish.
+pub struct JoinHandle<R> {
+    thread: ptr::NonNull<sys::nng_thread>,
+    packet: *mut c_void,
+    result: unsafe fn(*mut c_void) -> Option<R>,
+}
+
+unsafe impl<R: Send> Send for JoinHandle<R> {}
+unsafe impl<R: Send> Sync for JoinHandle<R> {}
+
+impl<R> JoinHandle<R> {
+    #[inline]
+    ///Sets name of the thread, which is visible in debugger, if supported by platform.
+    pub fn set_name(&self, name: String<'_>) {
+        unsafe {
+            sys::nng_thread_set_name(self.thread.as_ptr(), name.as_ptr() as _)
+        }
+    }
+
+    #[inline]
+    ///Waits for thread to finish, returning its result
+    pub fn join(self) -> R {
+        let this = core::mem::ManuallyDrop::new(self);
+        unsafe {
+            sys::nng_thread_destroy(this.thread.as_ptr());
+            match (this.result)(this.packet) {
+                Some(result) => result,
+                //Thread always runs function to completion as panic aborts
+                None => unreachable!(),
+            }
+        }
+    }
+}
+
+impl<R> Drop for JoinHandle<R> {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe {
+            sys::nng_thread_destroy(self.thread.as_ptr());
+            (self.result)(self.packet);
+        }
+    }
+}
+
+impl<R> fmt::Debug for JoinHandle<R> {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("JoinHandle").field("thread", &self.thread).finish()
+    }
+}
@@ -0,0 +1,139 @@
+//!URI percent-encoding utilities
+//!
+//!Follows RFC 3986, the same way nng's HTTP layer parses URIs: only unreserved characters are
+//!left as they are, and everything else is encoded as `%XX`.
+//!
+//!## Usage
+//!
+//!```rust
+//!use nng_c::utils::uri;
+//!
+//!assert_eq!(uri::encode_path("/files/my report.txt"), "/files/my%20report.txt");
+//!assert_eq!(uri::encode_query("a&b c"), "a%26b+c");
+//!
+//!let (path, query) = uri::split("/search?q=nng+c");
+//!assert_eq!(path, "/search");
+//!assert_eq!(uri::decode_query(query.unwrap()).unwrap(), "q=nng c");
+//!```
+
+use crate::ErrorCode;
+use crate::error::error;
+use crate::sys;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+const HEX: &[u8; 16] = b"0123456789ABCDEF";
+
+#[inline(always)]
+const fn is_unreserved(byt: u8) -> bool {
+    byt.is_ascii_alphanumeric() || matches!(byt, b'-' | b'.' | b'_' | b'~')
+}
+
+fn encode_with(input: &str, keep: fn(u8) -> bool, space_as_plus: bool) -> String {
+    let mut out = String::with_capacity(input.len());
+    for byt in input.bytes() {
+        if keep(byt) {
+            out.push(byt as char);
+        } else if space_as_plus && byt == b' ' {
+            out.push('+');
+        } else {
+            out.push('%');
+            out.push(HEX[(byt >> 4) as usize] as char);
+            out.push(HEX[(byt & 0xF) as usize] as char);
+        }
+    }
+    out
+}
+
+#[inline(always)]
+const fn hex_value(byt: u8) -> Option<u8> {
+    match byt {
+        b'0'..=b'9' => Some(byt - b'0'),
+        b'a'..=b'f' => Some(byt - b'a' + 10),
+        b'A'..=b'F' => Some(byt - b'A' + 10),
+        _ => None,
+    }
+}
+
+fn decode_with(input: &str, plus_as_space: bool) -> Result<String, ErrorCode> {
+    let input = input.as_bytes();
+    let mut out = Vec::with_capacity(input.len());
+    let mut idx = 0;
+    while idx < input.len() {
+        match input[idx] {
+            b'%' => match (input.get(idx + 1).copied().and_then(hex_value), input.get(idx + 2).copied().and_then(hex_value)) {
+                (Some(high), Some(low)) => {
+                    out.push(high << 4 | low);
+                    idx += 3;
+                    continue;
+                },
+                _ => return Err(error(sys::nng_errno_enum::NNG_EINVAL)),
+            },
+            b'+' if plus_as_space => out.push(b' '),
+            byt => out.push(byt),
+        }
+        idx += 1;
+    }
+
+    String::from_utf8(out).map_err(|_| error(sys::nng_errno_enum::NNG_EINVAL))
+}
+
+#[inline]
+///Encodes `input` to be used as single path segment or query key/value.
+///
+///All characters, except unreserved ones, are encoded, including `/`, `?`, `&` and `=`.
+pub fn encode_component(input: &str) -> String {
+    encode_with(input, is_unreserved, false)
+}
+
+#[inline]
+///Encodes `input` to be used as path, keeping `/` separators as they are.
+pub fn encode_path(input: &str) -> String {
+    encode_with(input, |byt| is_unreserved(byt) || byt == b'/', false)
+}
+
+#[inline]
+///Encodes `input` to be used as query key or value, using `+` for spaces.
+pub fn encode_query(input: &str) -> String {
+    encode_with(input, is_unreserved, true)
+}
+
+#[inline]
+///Decodes percent-encoded `input`, leaving `+` as it is.
+///
+///Returns error if `input` contains invalid escape sequence or decoded bytes are not valid UTF-8.
+pub fn decode(input: &str) -> Result<String, ErrorCode> {
+    decode_with(input, false)
+}
+
+#[inline]
+///Decodes percent-encoded query `input`, treating `+` as space.
+///
+///Returns error if `input` contains invalid escape sequence or decoded bytes are not valid UTF-8.
+pub fn decode_query(input: &str) -> Result<String, ErrorCode> {
+    decode_with(input, true)
+}
+
+///Splits request URI into path and optional query, dropping fragment if any.
+pub fn split(uri: &str) -> (&str, Option<&str>) {
+    let uri = match uri.find('#') {
+        Some(idx) => &uri[..idx],
+        None => uri,
+    };
+
+    match uri.find('?') {
+        Some(idx) => (&uri[..idx], Some(&uri[idx + 1..])),
+        None => (uri, None),
+    }
+}
+
+///Iterates over `key=value` pairs of the query, without decoding them.
+///
+///Pair without `=` yields empty value.
+pub fn query_pairs(query: &str) -> impl Iterator<Item = (&str, &str)> {
+    query.split('&').filter(|pair| !pair.is_empty()).map(|pair| match pair.find('=') {
+        Some(idx) => (&pair[..idx], &pair[idx + 1..]),
+        None => (pair, ""),
+    })
+}
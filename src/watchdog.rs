@@ -0,0 +1,320 @@
+//!Watchdog for stalled operations
+//!
+//![Watchdog] runs background thread, monitoring operations performed through it, and invokes
+//!callback once operation exceeds configured limit, so that deadlocked request loops are
+//!detected in production, rather than silently hanging.
+//!
+//!Callback decides whether to abort stalled operation, which then fails with `NNG_ETIMEDOUT`.
+//!Only socket operations started via watchdog can be aborted, while arbitrary code, watched via
+//![guard](Watchdog::guard), is only reported.
+//!
+//!## Usage
+//!
+//!```rust
+//!use nng_c::{NngError, Socket};
+//!use nng_c::watchdog::Watchdog;
+//!
+//!use core::time;
+//!
+//!let watchdog = Watchdog::new(time::Duration::from_millis(50), |stall| {
+//!    eprintln!("{} is stalled for {:?}", stall.name, stall.elapsed);
+//!    true
+//!}).expect("start watchdog");
+//!
+//!let socket = Socket::pull0().expect("create socket");
+//!let error = watchdog.recv(&socket, "pull").expect_err("abort stalled receive");
+//!assert!(error.is_timed_out());
+//!```
+//!
+//!Requires feature `std`
+
+use crate::ErrorCode;
+use crate::error::error;
+use crate::msg::Message;
+use crate::socket::{FutureReq, FutureResp, Socket};
+use crate::sys;
+use crate::utils::block_on;
+
+use core::{fmt, task, time};
+use core::future::Future;
+use core::pin::Pin;
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread;
+use std::time::Instant;
+
+//Limit on how rarely operations are checked, so that stalls are reported close to the limit
+const MIN_INTERVAL: time::Duration = time::Duration::from_millis(1);
+
+#[derive(Debug)]
+///Stalled operation, reported to the callback
+pub struct Stall {
+    ///Name of the operation
+    pub name: &'static str,
+    ///Time since operation started
+    pub elapsed: time::Duration,
+}
+
+struct Aio(*mut sys::nng_aio);
+
+//AIO is only accessed while operation is registered, and nng_aio_abort is thread safe
+unsafe impl Send for Aio {}
+
+struct Operation {
+    id: u64,
+    name: &'static str,
+    started: Instant,
+    aio: Option<Aio>,
+    reported: bool,
+}
+
+type Callback = Box<dyn FnMut(&Stall) -> bool + Send>;
+
+struct State {
+    operations: Vec<Operation>,
+    next_id: u64,
+    stopped: bool,
+}
+
+struct Inner {
+    limit: time::Duration,
+    state: Mutex<State>,
+    stop: Condvar,
+}
+
+impl Inner {
+    #[inline]
+    fn lock(&self) -> MutexGuard<'_, State> {
+        match self.state.lock() {
+            Ok(state) => state,
+            Err(error) => error.into_inner(),
+        }
+    }
+
+    fn register(&self, name: &'static str, aio: Option<Aio>) -> u64 {
+        let mut state = self.lock();
+        let id = state.next_id;
+        state.next_id = state.next_id.wrapping_add(1);
+        state.operations.push(Operation {
+            id,
+            name,
+            started: Instant::now(),
+            aio,
+            reported: false,
+        });
+        id
+    }
+
+    fn unregister(&self, id: u64) {
+        let mut state = self.lock();
+        if let Some(idx) = state.operations.iter().position(|op| op.id == id) {
+            state.operations.swap_remove(idx);
+        }
+    }
+
+    fn run(&self, mut on_stall: Callback) {
+        let interval = core::cmp::max(self.limit / 4, MIN_INTERVAL);
+        let mut state = self.lock();
+        while !state.stopped {
+            state = match self.stop.wait_timeout(state, interval) {
+                Ok((state, _)) => state,
+                Err(error) => error.into_inner().0,
+            };
+
+            let now = Instant::now();
+            for op in state.operations.iter_mut().filter(|op| !op.reported) {
+                let elapsed = now.duration_since(op.started);
+                if elapsed < self.limit {
+                    continue;
+                }
+                op.reported = true;
+
+                let stall = Stall {
+                    name: op.name,
+                    elapsed,
+                };
+                if on_stall(&stall) {
+                    if let Some(aio) = &op.aio {
+                        //Operation is unregistered under the same lock before AIO is freed
+                        unsafe {
+                            sys::nng_aio_abort(aio.0, sys::nng_errno_enum::NNG_ETIMEDOUT);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+///Guard of watched operation, created via [Watchdog::guard]
+///
+///Operation is complete when guard is dropped.
+pub struct Guard<'a> {
+    inner: &'a Inner,
+    id: u64,
+}
+
+impl fmt::Debug for Guard<'_> {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Guard").field("id", &self.id).finish()
+    }
+}
+
+impl Drop for Guard<'_> {
+    #[inline(always)]
+    fn drop(&mut self) {
+        self.inner.unregister(self.id);
+    }
+}
+
+///Watchdog, reporting operations exceeding configured limit
+///
+///Background thread is stopped on drop.
+pub struct Watchdog {
+    inner: Arc<Inner>,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+impl Watchdog {
+    ///Starts watchdog thread, calling `on_stall` once for every operation that exceeds `limit`.
+    ///
+    ///`on_stall` returns whether operation should be aborted.
+    ///It is called while watchdog is locked, hence it must not use watchdog itself.
+    ///
+    ///Returns error if unable to spawn thread.
+    pub fn new<F: FnMut(&Stall) -> bool + Send + 'static>(limit: time::Duration, on_stall: F) -> Result<Self, ErrorCode> {
+        let inner = Arc::new(Inner {
+            limit,
+            state: Mutex::new(State {
+                operations: Vec::new(),
+                next_id: 0,
+                stopped: false,
+            }),
+            stop: Condvar::new(),
+        });
+
+        let on_stall: Callback = Box::new(on_stall);
+        let thread = {
+            let inner = inner.clone();
+            thread::Builder::new().name("nng-watchdog".into()).spawn(move || inner.run(on_stall))
+        };
+
+        match thread {
+            Ok(thread) => Ok(Self {
+                inner,
+                thread: Some(thread),
+            }),
+            Err(_) => Err(error(sys::nng_errno_enum::NNG_ENOMEM)),
+        }
+    }
+
+    #[inline(always)]
+    ///Returns limit of the operation duration
+    pub fn limit(&self) -> time::Duration {
+        self.inner.limit
+    }
+
+    #[inline]
+    ///Returns number of operations in progress
+    pub fn len(&self) -> usize {
+        self.inner.lock().operations.len()
+    }
+
+    #[inline]
+    ///Returns whether there are no operations in progress
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[inline]
+    ///Starts watching operation `name`, which lasts until returned guard is dropped.
+    pub fn guard(&self, name: &'static str) -> Guard<'_> {
+        Guard {
+            inner: &self.inner,
+            id: self.inner.register(name, None),
+        }
+    }
+
+    #[inline]
+    ///Receives message from `socket`, waiting forever if none is available, unless aborted by watchdog.
+    pub fn recv(&self, socket: &Socket, name: &'static str) -> Result<Message, ErrorCode> {
+        block_on(self.recv_async(socket, name)?)?
+    }
+
+    #[inline]
+    ///Sends `msg` on `socket`, waiting forever if it cannot be sent, unless aborted by watchdog.
+    ///
+    ///Message is dropped on failure.
+    pub fn send(&self, socket: &Socket, msg: Message, name: &'static str) -> Result<(), ErrorCode> {
+        block_on(self.send_async(socket, msg, name)?)?.map_err(|(_, error)| error)
+    }
+
+    ///Starts receiving message from `socket`, returning future that completes unless aborted by watchdog.
+    pub fn recv_async<'a>(&'a self, socket: &Socket, name: &'static str) -> Result<impl Future<Output = Result<Message, ErrorCode>> + 'a, ErrorCode> {
+        let fut = FutureResp::new(socket)?;
+        let watched = Watched {
+            _guard: Guard {
+                inner: &self.inner,
+                id: self.inner.register(name, Some(Aio(fut.aio_ptr()))),
+            },
+            fut,
+        };
+        Ok(async move {
+            match watched.await {
+                Ok(Some(msg)) => Ok(msg),
+                Ok(None) => Err(error(sys::nng_errno_enum::NNG_EINTERNAL)),
+                Err(error) => Err(error),
+            }
+        })
+    }
+
+    ///Starts sending `msg` on `socket`, returning future that completes unless aborted by watchdog.
+    pub fn send_async<'a>(&'a self, socket: &Socket, msg: Message, name: &'static str) -> Result<impl Future<Output = Result<(), (Message, ErrorCode)>> + 'a, ErrorCode> {
+        let fut = FutureReq::new(socket, msg)?;
+        Ok(Watched {
+            _guard: Guard {
+                inner: &self.inner,
+                id: self.inner.register(name, Some(Aio(fut.aio_ptr()))),
+            },
+            fut,
+        })
+    }
+}
+
+//Fields are dropped in order of declaration, hence operation is unregistered before AIO is freed
+struct Watched<'a, F> {
+    _guard: Guard<'a>,
+    fut: F,
+}
+
+impl<F: Future + Unpin> Future for Watched<'_, F> {
+    type Output = F::Output;
+
+    #[inline(always)]
+    fn poll(mut self: Pin<&mut Self>, ctx: &mut task::Context<'_>) -> task::Poll<Self::Output> {
+        Pin::new(&mut self.fut).poll(ctx)
+    }
+}
+
+impl fmt::Debug for Watchdog {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Watchdog").field("limit", &self.inner.limit)
+                                    .field("operations", &self.len())
+                                    .finish()
+    }
+}
+
+impl Drop for Watchdog {
+    fn drop(&mut self) {
+        self.inner.lock().stopped = true;
+        self.inner.stop.notify_all();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
@@ -0,0 +1,140 @@
+//!WebSocket client
+//!
+//!Allows to talk to any WebSocket server (not necessary nng based one) over `ws://` or `wss://` URL.
+//!
+//!Unlike raw [streams](crate::stream), client operates on whole messages, which are sent as single
+//!WebSocket message.
+
+use crate::ErrorCode;
+use crate::error::error;
+use crate::msg::Message;
+use crate::options::Options;
+use crate::socket::{FutureReq, FutureResp};
+use crate::str::String;
+use crate::stream::{Dialer, Stream};
+use crate::sys;
+
+use core::fmt;
+
+const OPT_MSGMODE: &[u8] = b"ws:msgmode\0";
+const OPT_SEND_TEXT: &[u8] = b"ws:send-text\0";
+const OPT_RECV_TEXT: &[u8] = b"ws:recv-text\0";
+
+fn set_dialer_bool(dialer: &Dialer, name: &[u8], value: bool) -> Result<(), ErrorCode> {
+    let result = unsafe {
+        sys::nng_stream_dialer_set_bool(dialer.0.as_ptr(), name.as_ptr() as _, value)
+    };
+
+    match result {
+        0 => Ok(()),
+        code => Err(error(code)),
+    }
+}
+
+#[derive(Clone, Default)]
+///Client options
+pub struct ClientOptions<T> {
+    text: bool,
+    dialer: T,
+}
+
+impl ClientOptions<()> {
+    #[inline]
+    ///Initializes default options, sending messages as binary frames.
+    pub const fn new() -> Self {
+        Self {
+            text: false,
+            dialer: (),
+        }
+    }
+}
+
+impl<T> ClientOptions<T> {
+    #[inline]
+    ///Sends all messages as text frames.
+    ///
+    ///It is user responsibility to send only valid UTF-8 in this mode.
+    pub const fn with_text(mut self) -> Self {
+        self.text = true;
+        self
+    }
+
+    #[inline]
+    ///Creates new options with custom dialer options
+    ///
+    ///This is useful to provide TLS config for `wss://` URLs
+    pub fn with_dialer<R: Options<Dialer>>(self, dialer: R) -> ClientOptions<R> {
+        ClientOptions {
+            text: self.text,
+            dialer,
+        }
+    }
+}
+
+///WebSocket client connection
+pub struct Client {
+    stream: Stream,
+    //Dialer must outlive its streams
+    _dialer: Dialer,
+}
+
+impl Client {
+    ///Connects to the `url`
+    ///
+    ///Messages received from the server are accepted regardless of whether they are sent as text
+    ///or binary frames.
+    pub async fn connect<T: Options<Dialer>>(url: String<'_>, options: ClientOptions<T>) -> Result<Self, ErrorCode> {
+        let dialer = Dialer::new(url)?;
+        set_dialer_bool(&dialer, OPT_MSGMODE, true)?;
+        set_dialer_bool(&dialer, OPT_RECV_TEXT, true)?;
+        set_dialer_bool(&dialer, OPT_SEND_TEXT, options.text)?;
+        dialer.set_opt(options.dialer)?;
+
+        let stream = dialer.dial()?.await?;
+        Ok(Self {
+            stream,
+            _dialer: dialer,
+        })
+    }
+
+    #[inline]
+    ///Sends `msg` as single WebSocket message, returning future that resolves once it is sent.
+    ///
+    ///Both header and body of the message are sent.
+    pub fn send_msg(&self, msg: Message) -> Result<FutureReq, ErrorCode> {
+        FutureReq::start(msg, |aio| unsafe {
+            sys::nng_stream_send(self.stream.as_ptr(), aio)
+        })
+    }
+
+    ///Sends `bytes` as single WebSocket message, returning future that resolves once it is sent.
+    pub fn send(&self, bytes: &[u8]) -> Result<FutureReq, ErrorCode> {
+        let mut msg = match Message::new() {
+            Some(msg) => msg,
+            None => return Err(error(sys::nng_errno_enum::NNG_ENOMEM)),
+        };
+        msg.append(bytes)?;
+        self.send_msg(msg)
+    }
+
+    #[inline]
+    ///Receives next WebSocket message
+    pub fn recv_msg(&self) -> Result<FutureResp, ErrorCode> {
+        FutureResp::start(|aio| unsafe {
+            sys::nng_stream_recv(self.stream.as_ptr(), aio)
+        })
+    }
+
+    #[inline]
+    ///Closes connection, aborting any pending operation
+    pub fn close(&self) {
+        self.stream.close()
+    }
+}
+
+impl fmt::Debug for Client {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Client").field("stream", &self.stream).finish()
+    }
+}
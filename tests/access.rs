@@ -0,0 +1,117 @@
+use nng_c::{options, NngError, Socket};
+use nng_c::access::{AccessControl, Action, Rule};
+use nng_c::options::Address;
+
+use core::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use core::time;
+
+fn inet(ip: IpAddr) -> Address {
+    SocketAddr::new(ip, 4242).into()
+}
+
+#[test]
+fn should_evaluate_rules_in_order() {
+    let local = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+    let policy = AccessControl::new(Action::Deny).deny(Rule::Ip(local))
+                                                 .allow(Rule::Network(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 0)), 8))
+                                                 .allow(Rule::Network(IpAddr::V6(Ipv6Addr::new(0xfd00, 0, 0, 0, 0, 0, 0, 0)), 8))
+                                                 .allow(Rule::TlsCn("client".into()))
+                                                 .allow(Rule::Local);
+    assert_eq!(policy.default_action(), Action::Deny);
+    assert_eq!(policy.rules().len(), 5);
+
+    assert_eq!(policy.check_addr(&inet(local)), Action::Deny);
+    assert_eq!(policy.check_addr(&inet(IpAddr::V4(Ipv4Addr::new(10, 1, 2, 3)))), Action::Allow);
+    assert_eq!(policy.check_addr(&inet(IpAddr::V4(Ipv4Addr::new(11, 0, 0, 1)))), Action::Deny);
+    //IPv4-mapped address is treated as IPv4
+    assert_eq!(policy.check_addr(&inet(IpAddr::V6(Ipv4Addr::new(10, 0, 0, 1).to_ipv6_mapped()))), Action::Deny);
+    assert_eq!(policy.check_addr(&inet(IpAddr::V6(Ipv4Addr::new(10, 2, 0, 1).to_ipv6_mapped()))), Action::Allow);
+    assert_eq!(policy.check_addr(&inet(IpAddr::V6(Ipv6Addr::new(0xfd12, 0, 0, 0, 0, 0, 0, 1)))), Action::Allow);
+    assert_eq!(policy.check_addr(&inet(IpAddr::V6(Ipv6Addr::LOCALHOST))), Action::Deny);
+    assert_eq!(policy.check_addr(&Address::Ipc("/tmp/socket".into())), Action::Allow);
+    assert_eq!(policy.check_addr(&Address::Unspecified), Action::Deny);
+
+    let policy = AccessControl::new(Action::Allow).deny(Rule::Any);
+    assert_eq!(policy.check_addr(&Address::Unspecified), Action::Deny);
+    let policy = AccessControl::new(Action::Deny).allow(Rule::Network(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0));
+    assert_eq!(policy.check_addr(&inet(IpAddr::V4(Ipv4Addr::BROADCAST))), Action::Allow);
+}
+
+fn connect(policy: &AccessControl) -> (Socket, Socket) {
+    let port = std::net::TcpListener::bind(("127.0.0.1", 0)).expect("bind").local_addr().expect("get address").port();
+    let url = format!("tcp://127.0.0.1:{}", port);
+
+    let server = Socket::pair0().expect("create server");
+    server.listen_with(url.as_str().into(), policy).expect("listen");
+
+    let client = Socket::pair0().expect("create client");
+    client.set_opt(options::Reconnect {
+        min_time: Some(time::Duration::from_millis(10)),
+        max_time: Some(time::Duration::from_millis(10)),
+    }).expect("set reconnect");
+    client.connect_with(url.as_str().into(), nng_c::socket::ConnectOptions::new().with_async()).expect("connect");
+    (server, client)
+}
+
+#[test]
+fn should_close_pipes_of_denied_peers() {
+    let policy = AccessControl::new(Action::Allow).deny(Rule::Ip(IpAddr::V4(Ipv4Addr::LOCALHOST)));
+    let (server, client) = connect(&policy);
+
+    client.set_opt(options::SendTimeout(time::Duration::from_millis(100))).expect("set send timeout");
+    server.set_opt(options::RecvTimeout(time::Duration::from_millis(200))).expect("set recv timeout");
+    let _ = client.send(b"hello".into());
+    let error = server.recv_msg().expect_err("should not have connection");
+    assert!(error.is_timed_out(), "unexpected error: {}", error);
+}
+
+#[test]
+fn should_accept_allowed_peers() {
+    let policy = AccessControl::new(Action::Deny).allow(Rule::Network(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 0)), 8));
+    let (server, client) = connect(&policy);
+
+    client.set_opt(options::SendTimeout(time::Duration::from_secs(5))).expect("set send timeout");
+    server.set_opt(options::RecvTimeout(time::Duration::from_secs(5))).expect("set recv timeout");
+    client.send(b"hello".into()).expect("send");
+    let msg = server.recv_msg().expect("receive");
+    assert_eq!(msg.body(), b"hello");
+}
+
+#[test]
+fn should_build_policy_from_ip_lists() {
+    assert_eq!("10.0.0.0/8".parse::<Rule>().expect("parse network"), Rule::Network(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 0)), 8));
+    assert_eq!("::1".parse::<Rule>().expect("parse address"), Rule::Ip(IpAddr::V6(Ipv6Addr::LOCALHOST)));
+    for invalid in ["10.0.0.0/33", "::/129", "10.0.0/8", "10.0.0.0/", "localhost"].iter() {
+        let error = invalid.parse::<Rule>().expect_err("invalid rule");
+        assert_eq!(error.raw_code(), nng_c::sys::nng_errno_enum::NNG_EADDRINVAL);
+    }
+
+    let policy = AccessControl::ip_lists(&["10.0.0.0/8", "fd00::/8"], &["10.0.0.1"]).expect("create policy");
+    assert_eq!(policy.default_action(), Action::Deny);
+    assert_eq!(policy.check_addr(&inet(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)))), Action::Deny);
+    assert_eq!(policy.check_addr(&inet(IpAddr::V4(Ipv4Addr::new(10, 1, 2, 3)))), Action::Allow);
+    assert_eq!(policy.check_addr(&inet(IpAddr::V6(Ipv6Addr::new(0xfd12, 0, 0, 0, 0, 0, 0, 1)))), Action::Allow);
+    assert_eq!(policy.check_addr(&inet(IpAddr::V4(Ipv4Addr::new(192, 168, 0, 1)))), Action::Deny);
+    assert_eq!(policy.check_addr(&Address::Inproc("local".into())), Action::Allow);
+
+    let policy = AccessControl::ip_lists(&[], &["192.168.0.0/16"]).expect("create policy");
+    assert_eq!(policy.default_action(), Action::Allow);
+    assert_eq!(policy.check_addr(&inet(IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1)))), Action::Deny);
+    assert_eq!(policy.check_addr(&inet(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)))), Action::Allow);
+
+    let error = AccessControl::ip_lists(&["10.0.0.0/8"], &["invalid"]).expect_err("invalid entry");
+    assert_eq!(error.raw_code(), nng_c::sys::nng_errno_enum::NNG_EADDRINVAL);
+}
+
+#[test]
+fn should_detach_policy_of_failed_listener() {
+    use nng_c::notify::PipeNotifier;
+
+    let taken = std::net::TcpListener::bind(("127.0.0.1", 0)).expect("bind");
+    let url = format!("tcp://127.0.0.1:{}", taken.local_addr().expect("get address").port());
+
+    let server = Socket::pair0().expect("create server");
+    let notifier = PipeNotifier::install(&server).expect("install notifier");
+    server.listen_with(url.as_str().into(), &AccessControl::new(Action::Deny)).expect_err("address is in use");
+    assert_eq!(notifier.subscribers(), 0);
+}
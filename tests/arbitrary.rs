@@ -0,0 +1,27 @@
+use nng_c::Message;
+
+use arbitrary::{Arbitrary, Unstructured};
+
+#[test]
+fn should_generate_arbitrary_messages() {
+    let seed: Vec<u8> = (0..=u8::MAX).cycle().take(256 * 1024).collect();
+    let mut input = Unstructured::new(&seed);
+
+    let mut has_header = false;
+    let mut has_body = false;
+    for _ in 0..16 {
+        let msg = Message::arbitrary(&mut input).expect("generate message");
+        assert_eq!(msg.header().len() % 4, 0);
+        assert!(msg.header().len() <= 60);
+        assert!(msg.body().len() <= 64 * 1024);
+
+        has_header |= !msg.header().is_empty();
+        has_body |= !msg.body().is_empty();
+    }
+    assert!(has_header);
+    assert!(has_body);
+
+    let msg = Message::arbitrary(&mut Unstructured::new(&[])).expect("generate from empty input");
+    assert!(msg.header().is_empty());
+    assert!(msg.body().is_empty());
+}
@@ -0,0 +1,76 @@
+use nng_c::{options, Socket, Message};
+use nng_c::balance::Balancer;
+
+use core::time;
+
+fn serve(server: &Socket, id: u8) {
+    loop {
+        let msg = match server.recv_msg() {
+            Ok(msg) => msg,
+            Err(_) => break,
+        };
+        if msg.body() == b"quit" {
+            break;
+        }
+
+        let mut reply = Message::new().expect("create message");
+        reply.append(&[id]).expect("append id");
+        server.send_msg(reply).expect("send reply");
+    }
+}
+
+#[test]
+fn should_balance_requests_between_healthy_endpoints() {
+    const FIRST: &str = "inproc://should_balance_requests_first\0";
+    const SECOND: &str = "inproc://should_balance_requests_second\0";
+    const DEAD: &str = "inproc://should_balance_requests_dead\0";
+
+    let options = options::RecvTimeout(time::Duration::from_secs(1));
+
+    let first = Socket::rep0().expect("Create server");
+    first.listen(FIRST.into()).expect("listen");
+    let second = Socket::rep0().expect("Create server");
+    second.listen(SECOND.into()).expect("listen");
+
+    let mut client = Balancer::new();
+    assert!(client.is_empty());
+    client.request(Message::new().expect("create message")).expect_err("no endpoints");
+
+    client.add_with(DEAD.into(), &options).expect("add dead endpoint");
+    client.add_with(FIRST.into(), &options).expect("add first endpoint");
+    client.add_with(SECOND.into(), &options).expect("add second endpoint");
+    assert_eq!(client.len(), 3);
+
+    let mut attempts = 0;
+    while !client.health(1).unwrap().is_connected() || !client.health(2).unwrap().is_connected() {
+        attempts += 1;
+        assert!(attempts < 100, "Failed to connect");
+        std::thread::sleep(time::Duration::from_millis(10));
+    }
+    assert!(!client.health(0).unwrap().is_connected());
+
+    std::thread::scope(|scope| {
+        scope.spawn(|| serve(&first, 1));
+        scope.spawn(|| serve(&second, 2));
+
+        let mut replies = Vec::new();
+        for _ in 0..4 {
+            let reply = client.request(Message::new().expect("create message")).expect("get reply");
+            replies.push(reply.body()[0]);
+        }
+        assert_eq!(replies, [1, 2, 1, 2]);
+        assert_eq!(client.health(1).unwrap().failures, 0);
+        assert_eq!(client.health(2).unwrap().failures, 0);
+
+        first.close();
+        second.close();
+    });
+
+    let mut attempts = 0;
+    while client.health(1).unwrap().is_connected() {
+        attempts += 1;
+        assert!(attempts < 100, "Failed to disconnect");
+        std::thread::sleep(time::Duration::from_millis(10));
+    }
+    assert_eq!(client.health(1).unwrap().failures, 1);
+}
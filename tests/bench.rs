@@ -0,0 +1,30 @@
+use nng_c::Socket;
+use nng_c::utils::bench;
+
+mod rt;
+
+#[test]
+fn should_benchmark_round_trip() {
+    const ADDR: &str = "inproc://should_benchmark_round_trip\0";
+
+    let server = Socket::rep0().expect("Create server");
+    server.listen(ADDR.into()).expect("listen");
+    let client = Socket::req0().expect("Create client");
+    client.connect(ADDR.into()).expect("connect");
+
+    let config = bench::Config::new().messages(50).warmup(5).size(128);
+    let report = bench::round_trip(&client, &server, &config).expect("run benchmark");
+    assert_eq!(report.messages, 50);
+    assert_eq!(report.size, 128);
+    assert!(report.min <= report.p50);
+    assert!(report.p50 <= report.p99);
+    assert!(report.p99 <= report.max);
+    assert!(report.mean <= report.max);
+    assert!(report.round_trips_per_sec() > 0.0);
+    assert_eq!(report.bytes_per_sec(), report.round_trips_per_sec() * 256.0);
+
+    let report = rt::run(bench::round_trip_async(&client, &server, &config)).expect("run async benchmark");
+    assert_eq!(report.messages, 50);
+    assert!(report.min <= report.max);
+    assert!(!report.to_string().is_empty());
+}
@@ -0,0 +1,58 @@
+use nng_c::{checksum, options, Message, Socket};
+use nng_c::checksum::{ChecksumError, Checked};
+
+use core::time;
+
+#[test]
+fn should_compute_crc32() {
+    assert_eq!(checksum::crc32(b""), 0);
+    assert_eq!(checksum::crc32(b"123456789"), 0xCBF4_3926);
+
+    let mut msg = Message::new().expect("create message");
+    assert_eq!(checksum::verify(&mut msg), Err(ChecksumError::Truncated));
+    msg.append(b"payload").expect("append");
+    checksum::append(&mut msg).expect("append checksum");
+    assert_eq!(msg.len(), 7 + checksum::SIZE);
+
+    let mut corrupted = Message::new().expect("create message");
+    corrupted.append(b"qayload").expect("append");
+    corrupted.append(&msg.body()[7..]).expect("append checksum");
+    let error = checksum::verify(&mut corrupted).expect_err("should detect corruption");
+    assert!(error.is_corrupted());
+    assert_eq!(corrupted.len(), 7 + checksum::SIZE);
+
+    checksum::verify(&mut msg).expect("valid checksum");
+    assert_eq!(msg.body(), b"payload");
+}
+
+#[test]
+fn should_verify_messages_over_socket() {
+    const ADDR: &str = "inproc://should_verify_messages_over_socket\0";
+
+    let server = Socket::pair0().expect("create server");
+    server.set_opt(options::RecvTimeout(time::Duration::from_secs(5))).expect("set recv timeout");
+    server.set_opt(options::RecvBuf(4)).expect("set recv buffer");
+    server.listen(ADDR.into()).expect("listen");
+    let client = Socket::pair0().expect("create client");
+    client.connect(ADDR.into()).expect("connect");
+
+    let sender = Checked::new(&client);
+    let receiver = Checked::new(&server);
+
+    let mut msg = Message::new().expect("create message");
+    msg.append(b"hello").expect("append");
+    sender.send_msg(msg).expect("send");
+    let msg = receiver.recv_msg().expect("receive");
+    assert_eq!(msg.body(), b"hello");
+
+    //Message without valid checksum, sent bypassing wrapper
+    let mut msg = Message::new().expect("create message");
+    msg.append(b"tampered").expect("append");
+    client.send_msg(msg).expect("send");
+    match receiver.recv_msg() {
+        Err(ChecksumError::Mismatch { .. }) => (),
+        other => panic!("unexpected result: {:?}", other.map(|msg| msg.len())),
+    }
+
+    assert_eq!(receiver.try_recv_msg().expect("no error").map(|msg| msg.len()), None);
+}
@@ -0,0 +1,130 @@
+use nng_c::{options, Socket, Message, Op};
+use nng_c::config::{Changes, Protocol, Reconciler, SocketConfig};
+
+#[test]
+fn should_create_sockets_from_config() {
+    let server: SocketConfig = serde_json::from_str(r#"{
+        "protocol": "rep0",
+        "name": "server",
+        "listen": ["inproc://should_create_sockets_from_config"],
+        "recv_timeout_ms": 1000,
+        "recv_buf": 4,
+        "tls": { "auth": "none" }
+    }"#).expect("parse server config");
+    assert_eq!(server.protocol, Protocol::Rep0);
+
+    let client: SocketConfig = serde_json::from_str(r#"{
+        "protocol": "req0",
+        "connect": ["inproc://should_create_sockets_from_config"],
+        "send_timeout_ms": 1000,
+        "recv_timeout_ms": 1000,
+        "reconnect": { "min_ms": 10, "max_ms": 100 },
+        "tls": { "server_name": "localhost" }
+    }"#).expect("parse client config");
+
+    //TLS config is only applied to TLS transports
+    let server = Socket::from_config(&server).expect("create server");
+    let client = Socket::from_config(&client).expect("create client");
+
+    let name = server.get_prop::<options::SocketName>().expect("get name");
+    assert_eq!(name.as_str(), Some("server"));
+
+    let mut req = Message::new().expect("create message");
+    req.append(b"ping").expect("append");
+    client.send_msg(req).expect("send request");
+
+    let req = server.recv_msg().expect("receive request");
+    assert_eq!(req.body(), b"ping");
+    server.send_msg(req).expect("send reply");
+
+    let reply = client.recv_msg().expect("receive reply");
+    assert_eq!(reply.body(), b"ping");
+}
+
+#[test]
+fn should_reject_invalid_config() {
+    serde_json::from_str::<SocketConfig>(r#"{ "protocol": "bus0" }"#).expect_err("unknown protocol");
+    serde_json::from_str::<SocketConfig>(r#"{ "protocol": "req0", "timeout": 1 }"#).expect_err("unknown field");
+
+    let mut config = SocketConfig::new(Protocol::Req0);
+    config.subscribe.push("topic".to_owned());
+    let error = Socket::from_config(&config).expect_err("subscribe is not valid for req0");
+    assert_eq!(error.op(), Op::Configure);
+
+    let mut config = SocketConfig::new(Protocol::Rep0);
+    config.listen.push("inproc://should_reject_invalid_config".to_owned());
+    config.listen.push("unknown://should_reject_invalid_config".to_owned());
+    let error = Socket::from_config(&config).expect_err("unknown scheme");
+    assert_eq!(error.op(), Op::Listen);
+    assert_eq!(error.url(), Some("unknown://should_reject_invalid_config"));
+}
+
+#[test]
+fn should_reconcile_config_on_live_socket() {
+    const FIRST: &str = "inproc://should_reconcile_config_first";
+    const SECOND: &str = "inproc://should_reconcile_config_second";
+
+    let config: SocketConfig = serde_json::from_str(&format!(r#"{{
+        "protocol": "pair1",
+        "listen": ["{}"],
+        "recv_timeout_ms": 1000
+    }}"#, FIRST)).expect("parse config");
+
+    let server = config.protocol.open(config.raw).expect("create server");
+    let mut reconciler = Reconciler::new(&server, config.clone()).expect("apply config");
+    assert_eq!(reconciler.endpoints().len(), 1);
+    assert_eq!(reconciler.reconcile(&config).expect("reconcile"), Changes::default());
+
+    let client = Socket::pair1().expect("create client");
+    client.set_opt(options::RecvTimeout(std::time::Duration::from_secs(1))).expect("set timeout");
+    client.connect(FIRST.into()).expect("connect");
+
+    //Adding listener keeps existing connection
+    let mut desired = config.clone();
+    desired.listen.push(SECOND.to_owned());
+    desired.name = Some("reloaded".to_owned());
+    let changes = reconciler.reconcile(&desired).expect("reconcile");
+    assert_eq!(changes, Changes { added: 1, removed: 0, options: true });
+    assert!(reconciler.endpoints().find(SECOND).is_some());
+    assert_eq!(server.get_prop::<options::SocketName>().expect("get name").as_str(), Some("reloaded"));
+
+    let mut msg = Message::new().expect("create message");
+    msg.append(b"ping").expect("append");
+    client.send_msg(msg).expect("send");
+    assert_eq!(server.recv_msg().expect("receive").body(), b"ping");
+
+    //Removing listener closes its connection
+    desired.listen.remove(0);
+    let changes = reconciler.reconcile(&desired).expect("reconcile");
+    assert_eq!(changes, Changes { added: 0, removed: 1, options: false });
+    assert!(reconciler.endpoints().find(FIRST).is_none());
+    assert_eq!(reconciler.config(), &desired);
+    Socket::pair1().expect("create client").connect(FIRST.into()).expect_err("no listener");
+
+    let mut desired = desired.clone();
+    desired.protocol = Protocol::Pair0;
+    reconciler.reconcile(&desired).expect_err("protocol cannot be changed");
+}
+
+#[test]
+fn should_retry_failed_reconcile() {
+    const ADDR: &str = "inproc://should_retry_failed_reconcile";
+
+    let mut config = SocketConfig::new(Protocol::Sub0);
+    config.subscribe.push("topic".to_owned());
+    let socket = config.protocol.open(config.raw).expect("create socket");
+    let mut reconciler = Reconciler::new(&socket, config.clone()).expect("apply config");
+
+    //Topic is unsubscribed before listener fails to start
+    let mut desired = config.clone();
+    desired.subscribe.clear();
+    desired.listen.push("unknown://should_retry_failed_reconcile".to_owned());
+    let error = reconciler.reconcile(&desired).expect_err("invalid url");
+    assert_eq!(error.op(), Op::Listen);
+    assert_eq!(reconciler.config(), &config);
+
+    desired.listen[0] = ADDR.to_owned();
+    let changes = reconciler.reconcile(&desired).expect("reconcile");
+    assert_eq!(changes, Changes { added: 1, removed: 0, options: true });
+    assert_eq!(reconciler.config(), &desired);
+}
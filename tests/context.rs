@@ -0,0 +1,165 @@
+use nng_c::{Context, Socket, Message};
+
+#[test]
+fn should_reply_on_correct_context() {
+    const ADDR: &str = "inproc://should_reply_on_correct_context\0";
+
+    let server = Socket::rep0().expect("Create server");
+    server.listen(ADDR.into()).expect("listen");
+
+    let first = Socket::req0().expect("Create client");
+    first.connect(ADDR.into()).expect("connect");
+    let second = Socket::req0().expect("Create client");
+    second.connect(ADDR.into()).expect("connect");
+
+    let mut first_ctx = Context::new(&server).expect("create context");
+    let mut second_ctx = Context::new(&server).expect("create context");
+    assert_ne!(first_ctx.id(), second_ctx.id());
+
+    let mut req = Message::new().expect("Create message");
+    req.append(b"first").expect("append bytes");
+    first.send_msg(req).expect("Send message");
+    let (first_req, first_responder) = first_ctx.recv_request().expect("get request");
+
+    let mut req = Message::new().expect("Create message");
+    req.append(b"second").expect("append bytes");
+    second.send_msg(req).expect("Send message");
+    let (second_req, second_responder) = second_ctx.recv_request().expect("get request");
+
+    assert_eq!(first_req.body(), b"first");
+    assert_eq!(second_req.body(), b"second");
+
+    second_responder.reply(second_req).expect("reply");
+    first_responder.reply(first_req).expect("reply");
+
+    assert_eq!(first.recv_msg().expect("get reply").body(), b"first");
+    assert_eq!(second.recv_msg().expect("get reply").body(), b"second");
+
+    assert!(first_ctx.close());
+    assert!(!first_ctx.close());
+}
+
+#[test]
+fn should_serve_requests_concurrently() {
+    use core::time;
+    use nng_c::options::RecvTimeout;
+
+    const ADDR: &str = "inproc://should_serve_requests_concurrently\0";
+    const WORKERS: usize = 4;
+
+    let server = Socket::rep0().expect("Create server");
+    server.listen(ADDR.into()).expect("listen");
+
+    let workers = (0..WORKERS).map(|_| {
+        let ctx = server.context().expect("create context");
+        ctx.set_opt(RecvTimeout(time::Duration::from_secs(5))).expect("set timeout");
+        std::thread::spawn(move || {
+            let req = ctx.recv_msg().expect("get request");
+            //Every worker holds its request until all of them are received
+            std::thread::sleep(time::Duration::from_millis(50));
+            ctx.send_msg(req).expect("reply");
+        })
+    }).collect::<Vec<_>>();
+
+    let clients = (0..WORKERS).map(|idx| {
+        let client = Socket::req0().expect("Create client");
+        client.connect(ADDR.into()).expect("connect");
+        let mut req = Message::new().expect("Create message");
+        req.append_u32(idx as u32).expect("append");
+        client.send_msg(req).expect("Send message");
+        client
+    }).collect::<Vec<_>>();
+
+    for (idx, client) in clients.iter().enumerate() {
+        let mut reply = client.recv_msg().expect("get reply");
+        assert_eq!(reply.pop_front_u32(), Some(idx as u32));
+    }
+    for worker in workers {
+        worker.join().expect("finish worker");
+    }
+}
+
+#[test]
+fn should_not_block_on_empty_context() {
+    let server = Socket::rep0().expect("Create server");
+    let ctx = server.context().expect("create context");
+    assert!(ctx.try_recv_msg().expect("try receive").is_none());
+
+    let msg = Message::new().expect("Create message");
+    let (_, error) = ctx.try_send_msg(msg).expect_err("reply without request");
+    assert_eq!(error.raw_code(), nng_c::sys::nng_errno_enum::NNG_ESTATE);
+}
+
+#[test]
+fn should_request_reply_roundtrip() {
+    use nng_c::utils::block_on;
+
+    const ADDR: &str = "inproc://should_request_reply_roundtrip\0";
+
+    let server = Socket::rep0().expect("Create server");
+    server.listen(ADDR.into()).expect("listen");
+    let client = Socket::req0().expect("Create client");
+    client.connect(ADDR.into()).expect("connect");
+
+    let worker = std::thread::spawn(move || {
+        for _ in 0..2 {
+            let mut req = server.recv_msg().expect("get request");
+            let value = req.pop_front_u32().expect("get value");
+            let mut reply = Message::new().expect("Create message");
+            reply.append_u32(value + 1).expect("append");
+            server.send_msg(reply).expect("reply");
+        }
+    });
+
+    let mut req = Message::new().expect("Create message");
+    req.append_u32(1).expect("append");
+    let mut reply = block_on(client.request(req)).expect("run executor").expect("get reply");
+    assert_eq!(reply.pop_front_u32(), Some(2));
+
+    let ctx = client.context().expect("create context");
+    let mut req = Message::new().expect("Create message");
+    req.append_u32(41).expect("append");
+    let mut reply = block_on(ctx.request(req)).expect("run executor").expect("get reply");
+    assert_eq!(reply.pop_front_u32(), Some(42));
+
+    worker.join().expect("finish worker");
+}
+
+#[test]
+fn should_cancel_pending_request() {
+    use nng_c::utils::block_on;
+
+    const ADDR: &str = "inproc://should_cancel_pending_request\0";
+
+    let server = Socket::rep0().expect("Create server");
+    server.listen(ADDR.into()).expect("listen");
+    let client = Socket::req0().expect("Create client");
+    client.connect(ADDR.into()).expect("connect");
+    let ctx = client.context().expect("create context");
+
+    let mut req = Message::new().expect("Create message");
+    req.append(b"first").expect("append");
+    let mut pending = ctx.request(req);
+    assert!(pending.is_pending());
+
+    //Request is received, but left without reply until it is cancelled
+    let first = server.recv_msg().expect("get request");
+    assert_eq!(first.body(), b"first");
+    pending.cancel();
+    assert!(!pending.is_pending());
+    let error = block_on(pending).expect("run executor").expect_err("cancelled");
+    assert_eq!(error.raw_code(), nng_c::sys::nng_errno_enum::NNG_ECANCELED);
+
+    let mut req = Message::new().expect("Create message");
+    req.append(b"second").expect("append");
+    let pending = ctx.request(req);
+
+    //Late reply to the cancelled request is discarded
+    server.send_msg(first).expect("reply");
+    let second = server.recv_msg().expect("get request");
+    assert_eq!(second.body(), b"second");
+    server.send_msg(second).expect("reply");
+
+    let reply = block_on(pending).expect("run executor").expect("get reply");
+    assert_eq!(reply.body(), b"second");
+}
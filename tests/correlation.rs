@@ -0,0 +1,100 @@
+use nng_c::{options, Message, Socket};
+use nng_c::correlation::{self, CorrelationId, TraceContext};
+use nng_c::metadata;
+
+use core::time;
+
+#[test]
+fn should_stamp_and_extract_correlation_id() {
+    let mut msg = Message::new().expect("create message");
+    msg.append(b"payload").expect("append");
+    assert_eq!(CorrelationId::peek(&msg), None);
+    assert_eq!(CorrelationId::extract(&mut msg), None);
+    assert_eq!(msg.body(), b"payload");
+
+    let id = CorrelationId(0x0102_0304_0506_0708);
+    id.stamp(&mut msg).expect("stamp");
+    assert_eq!(msg.len(), metadata::size(&msg) + 7);
+    assert_eq!(CorrelationId::peek(&msg), Some(id));
+    assert_eq!(id.to_string(), "0102030405060708");
+
+    //Stamping again replaces existing ID
+    let next = CorrelationId::generate();
+    next.stamp(&mut msg).expect("stamp");
+    assert_eq!(msg.len(), metadata::size(&msg) + 7);
+    assert_eq!(CorrelationId::extract(&mut msg), Some(next));
+    assert_eq!(msg.body(), b"payload");
+}
+
+#[test]
+fn should_propagate_correlation_id_over_req_rep() {
+    const ADDR: &str = "inproc://should_propagate_correlation_id_over_req_rep\0";
+
+    let server = Socket::rep0().expect("create server");
+    server.set_opt(options::RecvTimeout(time::Duration::from_secs(5))).expect("set timeout");
+    server.listen(ADDR.into()).expect("listen");
+    let client = Socket::req0().expect("create client");
+    client.set_opt(options::RecvTimeout(time::Duration::from_secs(5))).expect("set timeout");
+    client.connect(ADDR.into()).expect("connect");
+
+    let id = CorrelationId::generate();
+    let mut msg = Message::new().expect("create message");
+    msg.append(b"ping").expect("append");
+    correlation::send_msg(&client, msg, id).expect("send request");
+
+    let (request, received) = correlation::recv_msg(&server).expect("receive request");
+    assert_eq!(request.body(), b"ping");
+    assert_eq!(received, Some(id));
+
+    let mut msg = Message::new().expect("create message");
+    msg.append(b"pong").expect("append");
+    correlation::send_msg(&server, msg, received.unwrap()).expect("send reply");
+
+    let (reply, received) = correlation::recv_msg(&client).expect("receive reply");
+    assert_eq!(reply.body(), b"pong");
+    assert_eq!(received, Some(id));
+}
+
+#[test]
+fn should_keep_trace_context_with_correlation_id_in_any_order() {
+    let trace = TraceContext {
+        trace_id: 1,
+        span_id: 2,
+        flags: 1,
+    };
+    let id = CorrelationId(3);
+
+    let mut msg = Message::new().expect("create message");
+    msg.append(b"payload").expect("append");
+    trace.stamp(&mut msg).expect("stamp trace");
+    id.stamp(&mut msg).expect("stamp id");
+    assert_eq!(TraceContext::peek(&msg), Some(trace));
+    assert_eq!(CorrelationId::peek(&msg), Some(id));
+
+    assert_eq!(TraceContext::extract(&mut msg), Some(trace));
+    assert_eq!(CorrelationId::peek(&msg), Some(id));
+    trace.stamp(&mut msg).expect("stamp trace");
+    assert_eq!(CorrelationId::extract(&mut msg), Some(id));
+    assert_eq!(TraceContext::extract(&mut msg), Some(trace));
+    assert_eq!(metadata::size(&msg), 0);
+    assert_eq!(msg.body(), b"payload");
+}
+
+#[test]
+fn should_not_misparse_payload_starting_with_marker() {
+    let mut payload = Vec::new();
+    payload.extend_from_slice(&metadata::MARKER.to_be_bytes());
+    payload.extend_from_slice(&[0; 8]);
+    payload.extend_from_slice(b"payload");
+
+    let mut msg = Message::new().expect("create message");
+    msg.append(&payload).expect("append");
+    assert_eq!(metadata::size(&msg), 0);
+    assert_eq!(CorrelationId::extract(&mut msg), None);
+    assert_eq!(msg.body(), payload.as_slice());
+
+    let id = CorrelationId(4);
+    id.stamp(&mut msg).expect("stamp id");
+    assert_eq!(CorrelationId::extract(&mut msg), Some(id));
+    assert_eq!(msg.body(), payload.as_slice());
+}
@@ -0,0 +1,42 @@
+use nng_c::{options, Socket, Message};
+use nng_c::socket::Counters;
+
+use core::time;
+
+#[test]
+fn should_count_messages() {
+    const ADDR: &str = "inproc://should_count_messages\0";
+
+    let server = Socket::pair0().expect("Create server");
+    let client = Socket::pair0().expect("Create client");
+    server.listen(ADDR.into()).expect("listen");
+    client.connect(ADDR.into()).expect("connect");
+    assert_eq!(client.counters(), Counters::default());
+
+    let mut msg = Message::new().expect("create message");
+    msg.append(b"ping").expect("append");
+    client.send_msg(msg).expect("send message");
+    client.send(b"pong".into()).expect("send bytes");
+
+    let msg = server.recv_msg().expect("receive message");
+    assert_eq!(msg.body(), b"ping");
+    let mut buf = [0u8; 16];
+    assert_eq!(server.recv(&mut buf[..]).expect("receive bytes"), b"pong");
+    assert!(server.try_recv_msg().expect("try receive").is_none());
+
+    server.set_opt(options::RecvTimeout(time::Duration::from_millis(10))).expect("set timeout");
+    server.recv_msg().expect_err("should time out");
+
+    let client_counters = client.counters();
+    assert_eq!(client_counters.msgs_sent, 2);
+    assert_eq!(client_counters.bytes_sent, 8);
+    assert_eq!(client_counters.send_failures, 0);
+
+    let server_counters = server.counters();
+    assert_eq!(server_counters.msgs_recv, 2);
+    assert_eq!(server_counters.bytes_recv, 8);
+    assert_eq!(server_counters.recv_failures, 1);
+
+    server.reset_counters();
+    assert_eq!(server.counters(), Counters::default());
+}
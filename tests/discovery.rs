@@ -0,0 +1,18 @@
+use nng_c::discovery::{self, Discovery};
+
+use core::time;
+
+#[test]
+fn should_advertise_service() {
+    assert_eq!(discovery::service_type("orders"), "_orders._tcp.local.");
+
+    let discovery = Discovery::new().expect("start mDNS");
+    discovery.advertise("should-advertise", "server", "tcp://0.0.0.0").expect_err("no port");
+    discovery.advertise("should-advertise", "server", "0.0.0.0:5555").expect_err("no scheme");
+    let advertisement = discovery.advertise("should-advertise", "server", "tls+tcp://0.0.0.0:5555\0").expect("advertise");
+    assert_eq!(advertisement.fullname(), "server._should-advertise._tcp.local.");
+    drop(advertisement);
+
+    let urls = discovery.resolve("should-not-exist", time::Duration::from_millis(100)).expect("resolve");
+    assert!(urls.is_empty());
+}
@@ -0,0 +1,88 @@
+use nng_c::{options, Message, NngError, Socket};
+use nng_c::dispatch::Dispatcher;
+use nng_c::headers::Headers;
+
+use core::cell::Cell;
+use core::time;
+
+fn message(routing_key: Option<&str>, body: &[u8]) -> Message {
+    let mut msg = Message::new().expect("create message");
+    msg.append(body).expect("append");
+    if let Some(routing_key) = routing_key {
+        let mut headers = Headers::new();
+        headers.set_str(Headers::ROUTING_KEY, routing_key).expect("set routing key");
+        headers.write(&mut msg).expect("write headers");
+    }
+    msg
+}
+
+#[test]
+fn should_route_by_header() {
+    let created = Cell::new(0);
+    let fallback = Cell::new(0);
+    let mut dispatcher = Dispatcher::by_header(Headers::ROUTING_KEY).route(b"create", |mut msg| {
+        Headers::extract(&mut msg).expect("valid headers");
+        assert_eq!(msg.body(), b"order");
+        created.set(created.get() + 1);
+        None
+    }).route(b"delete", |_| unreachable!());
+
+    assert!(dispatcher.has_route(b"create"));
+    assert!(dispatcher.dispatch(message(Some("create"), b"order")).expect("routed").is_none());
+    let msg = dispatcher.dispatch(message(Some("update"), b"order")).expect_err("no route");
+    assert_eq!(Headers::read(&msg).expect("valid headers").expect("have headers").routing_key(), Some("update"));
+    dispatcher.dispatch(message(None, b"order")).expect_err("no routing key");
+
+    let mut dispatcher = dispatcher.fallback(|_| {
+        fallback.set(fallback.get() + 1);
+        None
+    });
+    dispatcher.dispatch(message(Some("update"), b"order")).expect("fallback");
+    dispatcher.dispatch(message(None, b"order")).expect("fallback");
+    drop(dispatcher);
+
+    assert_eq!(created.get(), 1);
+    assert_eq!(fallback.get(), 2);
+}
+
+#[test]
+fn should_route_by_longest_topic() {
+    let mut dispatcher = Dispatcher::by_topic().route(b"orders.", |_| message(None, b"orders").into())
+                                               .route(b"orders.eu.", |_| message(None, b"orders.eu").into());
+
+    let reply = dispatcher.dispatch(message(None, b"orders.eu.1")).expect("routed").expect("have reply");
+    assert_eq!(reply.body(), b"orders.eu");
+    let reply = dispatcher.dispatch(message(None, b"orders.us.1")).expect("routed").expect("have reply");
+    assert_eq!(reply.body(), b"orders");
+    dispatcher.dispatch(message(None, b"users.1")).expect_err("no route");
+
+    let mut dispatcher = dispatcher.route(b"orders.", |_| None);
+    assert!(dispatcher.dispatch(message(None, b"orders.us.1")).expect("routed").is_none());
+}
+
+#[test]
+fn should_serve_requests_over_socket() {
+    const ADDR: &str = "inproc://should_serve_requests_over_socket\0";
+
+    let server = Socket::rep0().expect("create server");
+    server.listen(ADDR.into()).expect("listen");
+    let client = Socket::req0().expect("create client");
+    client.set_opt(options::RecvTimeout(time::Duration::from_secs(5))).expect("set timeout");
+    client.connect(ADDR.into()).expect("connect");
+
+    std::thread::scope(|scope| {
+        let service = scope.spawn(|| {
+            let mut dispatcher = Dispatcher::by_header(Headers::ROUTING_KEY).route(b"ping", |_| message(None, b"pong").into())
+                                                                               .fallback(|_| message(None, b"unknown").into());
+            dispatcher.serve(&server)
+        });
+
+        client.send_msg(message(Some("ping"), b"")).expect("send");
+        assert_eq!(client.recv_msg().expect("receive").body(), b"pong");
+        client.send_msg(message(Some("pong"), b"")).expect("send");
+        assert_eq!(client.recv_msg().expect("receive").body(), b"unknown");
+
+        server.close();
+        assert!(service.join().expect("join service").is_closed());
+    });
+}
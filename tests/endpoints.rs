@@ -0,0 +1,61 @@
+use nng_c::{options, Message, Socket};
+use nng_c::endpoints::Kind;
+use nng_c::notify::{PipeEvent, PipeNotifier};
+
+use core::time;
+
+#[test]
+fn should_add_and_remove_endpoints_at_runtime() {
+    const FIRST: &str = "inproc://should_add_and_remove_endpoints_first\0";
+    const SECOND: &str = "inproc://should_add_and_remove_endpoints_second\0";
+
+    let server = Socket::pair1_poly().expect("create server");
+    server.set_opt(options::RecvTimeout(time::Duration::from_millis(100))).expect("set timeout");
+    let mut endpoints = server.endpoints();
+    let first = endpoints.listen(FIRST.into()).expect("listen");
+    let second = endpoints.listen(SECOND.into()).expect("listen");
+    endpoints.listen(FIRST.into()).expect_err("already listening");
+    assert_eq!(endpoints.len(), 2);
+    assert_eq!(endpoints.get(first).expect("have endpoint").kind, Kind::Listener);
+    assert_eq!(endpoints.find(SECOND).expect("have endpoint").id, second);
+    assert_eq!(endpoints.find("inproc://should_add_and_remove_endpoints_second").expect("have endpoint").id, second);
+
+    let first_client = Socket::pair1().expect("create client");
+    first_client.connect(FIRST.into()).expect("connect");
+    let second_client = Socket::pair1().expect("create client");
+    let client_events = PipeNotifier::install(&second_client).expect("install notifier").events().expect("subscribe");
+    let mut client_endpoints = second_client.endpoints();
+    let dialer = client_endpoints.connect(SECOND.into()).expect("connect");
+    assert_eq!(client_endpoints.get(dialer).expect("have endpoint").kind, Kind::Dialer);
+
+    //Removing listener closes its pipes, leaving other connections intact
+    assert!(endpoints.remove(first).expect("remove"));
+    assert!(!endpoints.remove(first).expect("remove"));
+    assert!(endpoints.find(FIRST).is_none());
+    second_client.send_msg(Message::new().expect("create message")).expect("send");
+    server.recv_msg().expect("receive over remaining listener");
+
+    //Url can be reused once listener is closed
+    endpoints.listen(FIRST.into()).expect("listen again");
+    assert_eq!(endpoints.remove_url(FIRST).expect("remove"), 1);
+
+    assert!(client_endpoints.remove(dialer).expect("remove"));
+    assert!(client_endpoints.is_empty());
+    //Removing dialer closes its pipe asynchronously
+    let start = std::time::Instant::now();
+    loop {
+        match client_events.try_next() {
+            Some((_, PipeEvent::RemPost)) => break,
+            Some(_) => (),
+            None => {
+                assert!(start.elapsed() < time::Duration::from_secs(5), "dialer's connection is not closed");
+                std::thread::sleep(time::Duration::from_millis(1));
+            },
+        }
+    }
+    second_client.try_send_msg(Message::new().expect("create message")).expect_err("no peer");
+
+    endpoints.clear().expect("clear");
+    assert!(endpoints.is_empty());
+    Socket::pair1().expect("create client").connect(SECOND.into()).expect_err("no listener");
+}
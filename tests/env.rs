@@ -0,0 +1,52 @@
+use nng_c::{Socket, Message};
+use nng_c::env::{self, Error};
+
+use std::sync::Mutex;
+
+//Modifying environment is not thread safe, while tests run concurrently
+static ENV: Mutex<()> = Mutex::new(());
+
+#[test]
+fn should_configure_socket_from_env() {
+    let _env = ENV.lock().unwrap_or_else(|error| error.into_inner());
+    std::env::set_var("NNG_C_TEST_LISTEN", " inproc://should_configure_socket_from_env , ");
+    std::env::set_var("NNG_C_TEST_CONNECT", "inproc://should_configure_socket_from_env");
+    std::env::set_var("NNG_C_TEST_RECV_TIMEOUT_MS", "1000");
+    std::env::set_var("NNG_C_TEST_SEND_BUF", "8");
+
+    let server = Socket::pair0().expect("create server");
+    server.apply_env("NNG_C_TEST").expect("apply options");
+    server.listen_from_env("NNG_C_TEST_LISTEN").expect("listen");
+
+    let client = Socket::pair0().expect("create client");
+    client.apply_env("NNG_C_TEST").expect("apply options");
+    client.connect_from_env("NNG_C_TEST_CONNECT").expect("connect");
+
+    let mut msg = Message::new().expect("create message");
+    msg.append(b"ping").expect("append");
+    client.send_msg(msg).expect("send");
+    let msg = server.recv_msg().expect("receive");
+    assert_eq!(msg.body(), b"ping");
+}
+
+#[test]
+fn should_report_invalid_env() {
+    let _env = ENV.lock().unwrap_or_else(|error| error.into_inner());
+    match env::urls("NNG_C_TEST_MISSING") {
+        Err(Error::Missing(var)) => assert_eq!(var, "NNG_C_TEST_MISSING"),
+        result => panic!("unexpected result: {:?}", result),
+    }
+
+    std::env::set_var("NNG_C_TEST_BAD_URL", "tcp://127.0.0.1:5555,localhost");
+    let error = env::urls("NNG_C_TEST_BAD_URL").expect_err("invalid url");
+    assert_eq!(error.to_string(), "NNG_C_TEST_BAD_URL: invalid value 'localhost': expected URL in format <scheme>://<address>");
+
+    std::env::set_var("NNG_C_TEST_BAD_RECV_BUF", "many");
+    let socket = Socket::pair0().expect("create socket");
+    let error = socket.apply_env("NNG_C_TEST_BAD").expect_err("invalid number");
+    assert!(matches!(error, Error::Invalid { ref var, .. } if var == "NNG_C_TEST_BAD_RECV_BUF"), "{}", error);
+
+    std::env::set_var("NNG_C_TEST_UNKNOWN_LISTEN", "unknown://address");
+    let error = socket.listen_from_env("NNG_C_TEST_UNKNOWN_LISTEN").expect_err("unknown scheme");
+    assert!(matches!(error, Error::Nng { .. }), "{}", error);
+}
@@ -0,0 +1,144 @@
+use nng_c::{Errno, ErrorCode, NngError, Op, OpError, Socket};
+use nng_c::socket::ConnectOptions;
+
+#[test]
+fn should_attach_context_to_endpoint_errors() {
+    const ADDR: &str = "inproc://should_attach_context_to_endpoint_errors\0";
+
+    let server = Socket::pair0().expect("create server");
+    let first = server.add_listener(ADDR.into(), &()).expect("listen");
+    let error = server.add_listener(ADDR.into(), &()).expect_err("listen twice");
+    assert_eq!(error.op(), Op::Listen);
+    assert_eq!(error.url(), Some("inproc://should_attach_context_to_endpoint_errors"));
+    let endpoint = error.endpoint().expect("listener is created before start");
+    assert_ne!(endpoint, first);
+    assert_eq!(
+        error.to_string(),
+        format!("listen inproc://should_attach_context_to_endpoint_errors (endpoint {}): {}", endpoint, error.code())
+    );
+
+    let client = Socket::pair0().expect("create client");
+    let error = client.add_dialer("invalid://url".into(), ConnectOptions::new()).expect_err("dial invalid url");
+    assert_eq!(error.op(), Op::Dial);
+    assert_eq!(error.url(), Some("invalid://url"));
+    assert_eq!(error.endpoint(), None);
+
+    let code: ErrorCode = error.clone().into();
+    assert_eq!(code, error.code());
+    assert!(!code.is_timed_out());
+
+    client.add_dialer(ADDR.into(), ConnectOptions::new()).expect("connect");
+
+    assert!(client.close());
+    let error = client.send(b"ping".into()).expect_err("send on closed socket");
+    assert_eq!(error.op(), Op::Send);
+    assert_eq!(error.url(), None);
+    assert_eq!(error.endpoint(), None);
+    assert!(error.is_closed());
+    assert_eq!(error.to_string(), format!("send: {}", error.code()));
+
+    let error = client.recv_msg().expect_err("receive on closed socket");
+    assert_eq!(error.op(), Op::Recv);
+    assert_eq!(error.url(), None);
+    assert_eq!(error.endpoint(), None);
+    assert!(error.is_closed());
+}
+
+#[test]
+fn should_convert_errno() {
+    use core::convert::TryFrom;
+
+    let code: ErrorCode = Errno::TimedOut.into();
+    assert!(code.is_timed_out());
+    assert_eq!(Errno::try_from(code), Ok(Errno::TimedOut));
+    assert_eq!(Errno::TimedOut.to_string(), code.to_string());
+
+    assert_eq!(Errno::try_from(nng_c::sys::nng_errno_enum::NNG_EADDRINVAL), Ok(Errno::AddrInval));
+    assert_eq!(Errno::try_from(nng_c::sys::nng_errno_enum::NNG_ESYSERR | 2), Ok(Errno::System(2)));
+    assert_eq!(Errno::System(2).into_raw(), nng_c::sys::nng_errno_enum::NNG_ESYSERR | 2);
+    assert_eq!(Errno::try_from(999), Err(999));
+
+    let code = nng_c::nng_error(nng_c::sys::nng_errno_enum::NNG_ECONNSHUT);
+    match Errno::try_from(code) {
+        Ok(Errno::ConnShut) => (),
+        other => panic!("unexpected errno: {:?}", other),
+    }
+}
+
+#[test]
+fn should_retry_if_would_block() {
+    use nng_c::{options, ResultExt};
+    use core::time;
+
+    const ADDR: &str = "inproc://should_retry_if_would_block\0";
+
+    let server = Socket::pair0().expect("create server");
+    server.set_opt(options::RecvBuf(1)).expect("set recv buffer");
+    server.listen(ADDR.into()).expect("listen");
+    let client = Socket::pair0().expect("create client");
+    client.connect(ADDR.into()).expect("connect");
+
+    let mut buf = [0u8; 8];
+    let result = server.try_recv(&mut buf[..]).map(|msg| msg.len());
+    assert_eq!(result.ok_if_would_block(), Ok(None));
+
+    let mut attempts = 0;
+    let result = server.try_recv(&mut buf[..]).map(|msg| msg.len()).retry_if_would_block(2, time::Duration::from_millis(1), || {
+        attempts += 1;
+        Err(OpError::new(Op::Recv, nng_c::nng_error(nng_c::sys::nng_errno_enum::NNG_EAGAIN)))
+    });
+    assert!(result.expect_err("exhaust attempts").is_would_block());
+    assert_eq!(attempts, 2);
+
+    client.send(b"ping".into()).expect("send");
+    let mut buf = [0u8; 8];
+    let result: Result<usize, OpError> = Err(OpError::new(Op::Recv, nng_c::nng_error(nng_c::sys::nng_errno_enum::NNG_EAGAIN)));
+    let len = result.retry_if_would_block(100, time::Duration::from_millis(1), || server.try_recv(&mut buf[..]).map(|msg| msg.len())).expect("receive");
+    assert_eq!(&buf[..len], b"ping");
+
+    let result: Result<usize, ErrorCode> = Err(nng_c::nng_error(nng_c::sys::nng_errno_enum::NNG_ECLOSED));
+    assert!(result.ok_if_would_block().expect_err("keep other errors").is_closed());
+}
+
+#[cfg(debug_assertions)]
+#[test]
+fn should_report_protocol_misuse() {
+    use core::convert::TryFrom;
+
+    const ADDR: &str = "inproc://should_report_protocol_misuse\0";
+
+    let publisher = Socket::pub0().expect("create publisher");
+    let error = publisher.try_recv_msg().expect_err("receive on pub0");
+    assert_eq!(error.op(), Op::Recv);
+    let error = error.code();
+    assert_eq!(error.raw_code(), nng_c::sys::nng_errno_enum::NNG_ENOTSUP);
+    assert_eq!(error, nng_c::nng_error(nng_c::sys::nng_errno_enum::NNG_ENOTSUP));
+    assert_eq!(Errno::try_from(error), Ok(Errno::NotSup));
+    assert!(error.to_string().contains("can only send"));
+
+    let subscriber = Socket::sub0().expect("create subscriber");
+    let error = subscriber.send(b"ping".into()).expect_err("send on sub0");
+    assert_eq!(error.op(), Op::Send);
+    assert_eq!(error.code().raw_code(), nng_c::sys::nng_errno_enum::NNG_ENOTSUP);
+    assert!(error.to_string().contains("can only receive"));
+
+    let server = Socket::rep0().expect("create server");
+    server.listen(ADDR.into()).expect("listen");
+    let client = Socket::req0().expect("create client");
+    client.connect(ADDR.into()).expect("connect");
+
+    let msg = nng_c::Message::new().expect("create message");
+    let (msg, error) = server.send_msg(msg).expect_err("reply without request");
+    let error = error.code();
+    assert_eq!(error.raw_code(), nng_c::sys::nng_errno_enum::NNG_ESTATE);
+    assert_eq!(error, nng_c::nng_error(nng_c::sys::nng_errno_enum::NNG_ESTATE));
+    assert!(error.to_string().contains("must receive request"));
+
+    client.send(b"first".into()).expect("send request");
+    let req = server.recv_msg().expect("receive request");
+    assert_eq!(req.body(), b"first");
+    server.send_msg(msg).expect("reply");
+    client.recv_msg().expect("receive reply");
+    let (_, error) = server.send_msg(req).expect_err("reply twice");
+    assert_eq!(error.code().raw_code(), nng_c::sys::nng_errno_enum::NNG_ESTATE);
+}
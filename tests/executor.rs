@@ -0,0 +1,45 @@
+use nng_c::utils::block_on;
+
+use core::future::Future;
+use core::pin::Pin;
+use core::task;
+use core::time;
+
+use std::sync::{Arc, Mutex};
+
+//Future that is woken from another thread
+struct Delayed {
+    waker: Arc<Mutex<Option<task::Waker>>>,
+    polls: usize,
+}
+
+impl Future for Delayed {
+    type Output = usize;
+
+    fn poll(mut self: Pin<&mut Self>, ctx: &mut task::Context<'_>) -> task::Poll<Self::Output> {
+        self.polls += 1;
+        if self.polls > 2 {
+            return task::Poll::Ready(self.polls);
+        }
+
+        *self.waker.lock().unwrap() = Some(ctx.waker().clone());
+        let waker = self.waker.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(time::Duration::from_millis(10));
+            if let Some(waker) = waker.lock().unwrap().take() {
+                waker.wake();
+            }
+        });
+        task::Poll::Pending
+    }
+}
+
+#[test]
+fn should_block_on_future_woken_from_other_thread() {
+    let waker = Arc::new(Mutex::new(None));
+    let polls = block_on(Delayed {
+        waker,
+        polls: 0,
+    }).expect("create executor");
+    assert_eq!(polls, 3);
+}
@@ -0,0 +1,29 @@
+use nng_c::sys;
+use nng_c::utils::{self, Level};
+
+#[test]
+fn should_log_into_rotating_file() {
+    let path = std::env::temp_dir().join(format!("nng-c-file-log-{}.log", std::process::id()));
+    let backup = path.with_extension("log.1");
+    let _ = std::fs::remove_file(&path);
+    let _ = std::fs::remove_file(&backup);
+
+    utils::enable_rotating_file_logging(&path, Level::Info, 64).expect("enable logging");
+    unsafe {
+        sys::nng_log_warn(b"TEST-FIRST\0".as_ptr() as _, b"first message\0".as_ptr() as _);
+        sys::nng_log_info(b"TEST-SKIP\0".as_ptr() as _, b"filtered by level\0".as_ptr() as _);
+        sys::nng_log_err(b"TEST-SECOND\0".as_ptr() as _, b"second message\0".as_ptr() as _);
+    }
+    utils::disable_logging();
+
+    let first = std::fs::read_to_string(&backup).expect("read backup");
+    let second = std::fs::read_to_string(&path).expect("read log");
+    let _ = std::fs::remove_file(&path);
+    let _ = std::fs::remove_file(&backup);
+
+    assert!(first.ends_with(" WARN TEST-FIRST: first message\n"), "unexpected line: {}", first);
+    assert!(second.ends_with(" ERROR TEST-SECOND: second message\n"), "unexpected line: {}", second);
+    assert_eq!(second.lines().count(), 1);
+    let timestamp = second.split(' ').next().expect("get timestamp");
+    assert!(timestamp.parse::<f64>().is_ok(), "invalid timestamp: {}", timestamp);
+}
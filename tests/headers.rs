@@ -0,0 +1,68 @@
+use nng_c::{options, Message, Socket};
+use nng_c::correlation::CorrelationId;
+use nng_c::headers::Headers;
+use nng_c::metadata;
+
+use core::time;
+
+#[test]
+fn should_replace_and_remove_entries() {
+    let mut headers = Headers::new();
+    headers.set_str(Headers::ROUTING_KEY, "orders.eu").expect("set routing key");
+    headers.set_duration(Headers::TTL, time::Duration::from_secs(30)).expect("set ttl");
+    headers.set_str(Headers::ROUTING_KEY, "orders.us").expect("replace routing key");
+    assert_eq!(headers.len(), 2);
+    assert_eq!(headers.routing_key(), Some("orders.us"));
+    assert_eq!(headers.ttl(), Some(time::Duration::from_secs(30)));
+    assert_eq!(headers.get_u64(Headers::ROUTING_KEY), None);
+    assert_eq!(headers.content_type(), None);
+
+    assert!(headers.remove(Headers::TTL));
+    assert!(!headers.remove(Headers::TTL));
+    assert!(headers.set_bytes(&"k".repeat(256), b"").is_err());
+
+    let mut msg = Message::new().expect("create message");
+    msg.append(b"payload").expect("append");
+    assert_eq!(Headers::read(&msg), Ok(None));
+    headers.write(&mut msg).expect("write");
+    headers.set_str(Headers::CONTENT_TYPE, "text/plain").expect("set content type");
+    headers.write(&mut msg).expect("replace");
+    assert_eq!(Headers::read(&msg), Ok(Some(headers.clone())));
+    assert_eq!(Headers::extract(&mut msg), Ok(Some(headers)));
+    assert_eq!(msg.body(), b"payload");
+
+    //Payload, starting with marker, is not region
+    let mut msg = Message::new().expect("create message");
+    msg.append(&metadata::MARKER.to_be_bytes()).expect("append marker");
+    msg.append(&10u32.to_be_bytes()).expect("append length");
+    assert_eq!(Headers::read(&msg), Ok(None));
+    assert_eq!(msg.len(), 8);
+}
+
+#[test]
+fn should_send_headers_with_correlation_id() {
+    const ADDR: &str = "inproc://should_send_headers_with_correlation_id\0";
+
+    let server = Socket::pair0().expect("create server");
+    server.set_opt(options::RecvTimeout(time::Duration::from_secs(5))).expect("set recv timeout");
+    server.listen(ADDR.into()).expect("listen");
+    let client = Socket::pair0().expect("create client");
+    client.set_opt(options::SendBuf(1)).expect("set send buffer");
+    client.connect(ADDR.into()).expect("connect");
+
+    let mut headers = Headers::new();
+    headers.set_str(Headers::CONTENT_TYPE, "text/plain").expect("set content type");
+    let mut msg = Message::new().expect("create message");
+    msg.append(b"hello").expect("append");
+    headers.write(&mut msg).expect("write headers");
+    let id = CorrelationId::generate();
+    id.stamp(&mut msg).expect("stamp id");
+    client.send_msg(msg).expect("send");
+
+    let mut msg = server.recv_msg().expect("receive");
+    assert_eq!(CorrelationId::peek(&msg), Some(id));
+    let received = Headers::extract(&mut msg).expect("valid headers").expect("have headers");
+    assert_eq!(received.content_type(), Some("text/plain"));
+    assert_eq!(CorrelationId::extract(&mut msg), Some(id));
+    assert_eq!(msg.body(), b"hello");
+}
@@ -0,0 +1,105 @@
+use nng_c::http::{Handler, Response, Server};
+use nng_c::stream::Dialer;
+
+use std::io::{Read, Write};
+
+mod rt;
+
+#[test]
+fn should_serve_http_and_websocket_on_same_port() {
+    let port = std::net::TcpListener::bind(("127.0.0.1", 0)).expect("bind").local_addr().expect("get address").port();
+    let url = format!("http://127.0.0.1:{}", port);
+
+    let server = Server::hold(url.as_str().into()).expect("hold server");
+    let handler = Handler::new("/hello", |req| {
+        Response::ok().header("Content-Type", "text/plain").body(format!("hello {}", req.method()))
+    }).expect("create handler");
+    server.add_handler(handler).expect("add handler");
+    let listener = server.websocket("/ws").expect("create websocket listener");
+    server.start().expect("start server");
+
+    let mut conn = std::net::TcpStream::connect(("127.0.0.1", port)).expect("connect");
+    write!(conn, "GET /hello HTTP/1.1\r\nHost: 127.0.0.1:{}\r\nConnection: close\r\n\r\n", port).expect("write request");
+    let mut response = String::new();
+    conn.read_to_string(&mut response).expect("read response");
+    assert!(response.starts_with("HTTP/1.1 200"), "unexpected response: {}", response);
+    assert!(response.ends_with("hello GET"), "unexpected response: {}", response);
+
+    let dialer = Dialer::new(format!("ws://127.0.0.1:{}/ws", port).as_str().into()).expect("create dialer");
+    let client = rt::run(dialer.dial().expect("dial")).expect("connect websocket");
+    let conn = rt::run(listener.accept()).expect("accept websocket");
+
+    assert_eq!(rt::run(client.send(b"ping").expect("send")).expect("complete send"), 4);
+    let msg = rt::run(conn.recv(64).expect("recv")).expect("complete recv");
+    assert_eq!(msg, b"ping");
+
+    listener.close();
+    server.stop();
+}
+
+fn http_get(port: u16, path: &str, headers: &str) -> String {
+    let mut conn = std::net::TcpStream::connect(("127.0.0.1", port)).expect("connect");
+    write!(conn, "GET {} HTTP/1.1\r\nHost: 127.0.0.1:{}\r\n{}Connection: close\r\n\r\n", path, port, headers).expect("write request");
+    let mut response = String::new();
+    conn.read_to_string(&mut response).expect("read response");
+    response
+}
+
+#[test]
+fn should_mount_scopes_with_middleware() {
+    use nng_c::http::Scope;
+
+    let port = std::net::TcpListener::bind(("127.0.0.1", 0)).expect("bind").local_addr().expect("get address").port();
+    let server = Server::hold(format!("http://127.0.0.1:{}", port).as_str().into()).expect("hold server");
+
+    let admin = Scope::new("/admin").middleware(|req, next| match req.header("Authorization") {
+        Some("secret") => next(req),
+        _ => Response::new(401),
+    }).get("/stats", |_| Response::ok().body("stats"));
+    let api = Scope::new("/api/v1/").middleware(|req, next| {
+        let status = next(req).status();
+        Response::ok().header("X-Inner-Status", &status.to_string()).body(req.uri())
+    }).get("/", |_| Response::ok())
+      .get("users", |_| Response::ok())
+      .scope(admin);
+    server.mount(api).expect("mount");
+    server.start().expect("start server");
+
+    let response = http_get(port, "/api/v1", "");
+    assert!(response.ends_with("\r\n\r\n/api/v1"), "unexpected response: {}", response);
+    let response = http_get(port, "/api/v1/users", "");
+    assert!(response.contains("X-Inner-Status: 200"), "unexpected response: {}", response);
+    let response = http_get(port, "/api/v1/admin/stats", "");
+    assert!(response.contains("X-Inner-Status: 401"), "unexpected response: {}", response);
+    let response = http_get(port, "/api/v1/admin/stats", "Authorization: secret\r\n");
+    assert!(response.contains("X-Inner-Status: 200"), "unexpected response: {}", response);
+    let response = http_get(port, "/users", "");
+    assert!(response.starts_with("HTTP/1.1 404"), "unexpected response: {}", response);
+
+    server.stop();
+}
+
+#[test]
+fn should_exchange_messages_with_websocket_client() {
+    use nng_c::websocket::{Client, ClientOptions};
+
+    let port = std::net::TcpListener::bind(("127.0.0.1", 0)).expect("bind").local_addr().expect("get address").port();
+    let server = Server::hold(format!("http://127.0.0.1:{}", port).as_str().into()).expect("hold server");
+    let listener = server.websocket("/echo").expect("create websocket listener");
+    server.start().expect("start server");
+
+    let url = format!("ws://127.0.0.1:{}/echo", port);
+    let client = rt::run(Client::connect(url.as_str().into(), ClientOptions::new())).expect("connect");
+    let conn = rt::run(listener.accept()).expect("accept websocket");
+
+    rt::run(client.send(b"hello").expect("send")).expect("complete send");
+    let msg = rt::run(conn.recv(64).expect("recv")).expect("complete recv");
+    assert_eq!(msg, b"hello");
+
+    assert_eq!(rt::run(conn.send(b"world").expect("send")).expect("complete send"), 5);
+    let msg = rt::run(client.recv_msg().expect("recv")).expect("complete recv").expect("have message");
+    assert_eq!(msg.body(), b"world");
+
+    client.close();
+    server.stop();
+}
@@ -0,0 +1,90 @@
+use nng_c::{options, Message, Socket};
+use nng_c::idle::IdleReaper;
+use nng_c::notify::PipeNotifier;
+
+use core::time;
+
+fn ping(socket: &Socket) {
+    let mut msg = Message::new().expect("create message");
+    msg.append(b"ping").expect("append");
+    socket.send_msg(msg).expect("send");
+}
+
+#[test]
+fn should_close_idle_pipes() {
+    const ADDR: &str = "inproc://should_close_idle_pipes\0";
+
+    let server = Socket::pair1_poly().expect("create server");
+    server.set_opt(options::RecvTimeout(time::Duration::from_secs(5))).expect("set recv timeout");
+    server.listen(ADDR.into()).expect("listen");
+    let reaper = IdleReaper::new(time::Duration::from_millis(100)).expect("create reaper");
+    assert_eq!(reaper.timeout(), time::Duration::from_millis(100));
+
+    let idle = Socket::pair1().expect("create client");
+    idle.set_opt(options::SendTimeout(time::Duration::from_secs(5))).expect("set send timeout");
+    idle.connect(ADDR.into()).expect("connect");
+    let active = Socket::pair1().expect("create client");
+    active.set_opt(options::SendTimeout(time::Duration::from_secs(5))).expect("set send timeout");
+    active.connect(ADDR.into()).expect("connect");
+
+    //Pipe is tracked from its first activity
+    ping(&idle);
+    let msg = reaper.recv_msg(&server).expect("receive");
+    let idle_pipe = msg.pipe().expect("get pipe");
+    assert_eq!(reaper.len(), 1);
+
+    //Active client keeps its pipe alive, while idle one is closed once timeout passes
+    let mut active_pipe = None;
+    let mut closed = 0;
+    for _ in 0..6 {
+        std::thread::sleep(time::Duration::from_millis(40));
+        ping(&active);
+        let msg = server.recv_msg().expect("receive");
+        reaper.observe(&msg);
+        let pipe = msg.pipe().expect("get pipe");
+        assert_ne!(pipe, idle_pipe);
+        assert!(reaper.idle_time(&pipe).expect("get idle time") < time::Duration::from_millis(100));
+        active_pipe = Some(pipe);
+        closed += reaper.reap();
+    }
+    assert_eq!(closed, 1);
+    assert_eq!(reaper.len(), 1);
+    assert!(reaper.idle_time(&idle_pipe).is_none());
+    let active_pipe = active_pipe.expect("active pipe");
+
+    //Active pipe is still connected
+    ping(&active);
+    let msg = reaper.recv_msg(&server).expect("receive");
+    assert_eq!(msg.pipe(), Some(active_pipe));
+
+    reaper.forget(&active_pipe);
+    assert!(reaper.is_empty());
+}
+
+#[test]
+fn should_track_pipes_via_notifier() {
+    const ADDR: &str = "inproc://should_track_pipes_via_notifier\0";
+
+    let server = Socket::pair1_poly().expect("create server");
+    let notifier = PipeNotifier::install(&server).expect("install notifier");
+    let reaper = IdleReaper::new(time::Duration::from_secs(60)).expect("create reaper");
+    let subscription = reaper.attach(&notifier);
+    server.listen(ADDR.into()).expect("listen");
+
+    let client = Socket::pair1().expect("create client");
+    client.connect(ADDR.into()).expect("connect");
+    assert_eq!(reaper.len(), 1);
+    assert_eq!(reaper.reap(), 0);
+
+    client.close();
+    let start = std::time::Instant::now();
+    while !reaper.is_empty() {
+        assert!(start.elapsed() < time::Duration::from_secs(5), "removed pipe is still tracked");
+        std::thread::sleep(time::Duration::from_millis(1));
+    }
+
+    drop(subscription);
+    let client = Socket::pair1().expect("create client");
+    client.connect(ADDR.into()).expect("connect");
+    assert!(reaper.is_empty());
+}
@@ -0,0 +1,70 @@
+use nng_c::{Message, Socket};
+use nng_c::instrument::{Histogram, Instrumented, BUCKETS};
+
+use core::time;
+use std::sync::Arc;
+
+fn message(body: &[u8]) -> Message {
+    let mut msg = Message::new().expect("create message");
+    msg.append(body).expect("append");
+    msg
+}
+
+#[test]
+fn should_record_socket_operations() {
+    const ADDR: &str = "inproc://should_record_socket_operations\0";
+
+    let server = Instrumented::new(Arc::new(Socket::pair0().expect("create server")));
+    server.listen(ADDR.into()).expect("listen");
+    let client_socket = Socket::pair0().expect("create client");
+    let client = Instrumented::new(&client_socket);
+    client.connect(ADDR.into()).expect("connect");
+
+    assert!(server.try_recv_msg().expect("no message").is_none());
+    client.send_msg(message(b"hello")).expect("send");
+    client.send_msg(message(b"world!")).expect("send");
+    assert_eq!(server.recv_msg().expect("receive").body(), b"hello");
+    assert_eq!(server.recv_msg().expect("receive").body(), b"world!");
+
+    let snapshot = client.snapshot();
+    assert_eq!(snapshot.send.msgs, 2);
+    assert_eq!(snapshot.send.bytes, 11);
+    assert_eq!(snapshot.send.failures, 0);
+    assert_eq!(snapshot.send.latency.count(), 2);
+    assert_eq!(snapshot.recv.msgs, 0);
+
+    let snapshot = server.snapshot();
+    assert_eq!(snapshot.recv.msgs, 2);
+    assert_eq!(snapshot.recv.bytes, 11);
+    assert_eq!(snapshot.recv.failures, 0);
+    assert!(snapshot.recv.latency.percentile(100) <= snapshot.recv.latency.max);
+
+    server.close();
+    server.recv_msg().expect_err("closed");
+    assert_eq!(server.snapshot().recv.failures, 1);
+
+    server.reset();
+    assert_eq!(server.snapshot(), Default::default());
+}
+
+#[test]
+fn should_compute_histogram_percentiles() {
+    let mut histogram = Histogram::default();
+    assert_eq!(histogram.percentile(50), time::Duration::ZERO);
+    assert_eq!(histogram.mean(), time::Duration::ZERO);
+
+    //90 operations below 4us and 10 operations below 1024us
+    histogram.buckets[2] = 90;
+    histogram.buckets[10] = 10;
+    histogram.total = time::Duration::from_micros(90 * 3 + 10 * 1000);
+    histogram.max = time::Duration::from_micros(1000);
+
+    assert_eq!(histogram.count(), 100);
+    assert_eq!(histogram.mean(), time::Duration::from_micros(102));
+    assert_eq!(histogram.percentile(0), time::Duration::from_micros(4));
+    assert_eq!(histogram.percentile(50), time::Duration::from_micros(4));
+    assert_eq!(histogram.percentile(90), time::Duration::from_micros(4));
+    assert_eq!(histogram.percentile(91), time::Duration::from_micros(1000));
+    assert_eq!(histogram.percentile(200), time::Duration::from_micros(1000));
+    assert_eq!(histogram.buckets.len(), BUCKETS);
+}
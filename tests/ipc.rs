@@ -0,0 +1,50 @@
+use nng_c::{options, Socket};
+use nng_c::utils::ipc::{self, IpcPath};
+
+use core::time;
+
+fn exchange(url: &str) {
+    let server = Socket::pair0().expect("create server");
+    server.set_opt(options::RecvTimeout(time::Duration::from_secs(5))).expect("set recv timeout");
+    server.listen(url.into()).expect("listen");
+    let client = Socket::pair0().expect("create client");
+    client.set_opt(options::SendTimeout(time::Duration::from_secs(5))).expect("set send timeout");
+    client.connect(url.into()).expect("connect");
+
+    client.send(b"ping".into()).expect("send");
+    let msg = server.recv_msg().expect("receive");
+    assert_eq!(msg.body(), b"ping");
+}
+
+#[test]
+fn should_generate_unique_ipc_paths() {
+    let first = IpcPath::new("ipc");
+    let second = IpcPath::new("ipc");
+    assert_ne!(first.url(), second.url());
+    exchange(first.url());
+    exchange(second.url());
+
+    let long = "long".repeat(64);
+    let path = IpcPath::file(&long);
+    let file = path.path().expect("have file").to_path_buf();
+    assert!(file.as_os_str().len() < 104, "path is too long: {}", file.display());
+    exchange(path.url());
+    drop(path);
+    assert!(!file.exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn should_remove_stale_socket_file() {
+    let path = IpcPath::file("stale");
+    let file = path.path().expect("have file").to_path_buf();
+    assert!(!ipc::remove_stale(&file).expect("check missing file"));
+
+    let listener = std::os::unix::net::UnixListener::bind(&file).expect("bind");
+    assert!(!ipc::remove_stale(&file).expect("check listened file"));
+    drop(listener);
+
+    assert!(file.exists());
+    assert!(ipc::remove_stale(&file).expect("remove stale file"));
+    assert!(!file.exists());
+}
@@ -0,0 +1,43 @@
+use nng_c::{Context, Message, Socket};
+use nng_c::json::{self, JsonError};
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+struct Order {
+    id: u64,
+    item: String,
+}
+
+#[test]
+fn should_send_and_recv_json() {
+    const ADDR: &str = "inproc://should_send_and_recv_json\0";
+
+    let server = Socket::rep0().expect("create server");
+    server.listen(ADDR.into()).expect("listen");
+    let client = Socket::req0().expect("create client");
+    client.connect(ADDR.into()).expect("connect");
+    let ctx = Context::new(&server).expect("create context");
+
+    let order = Order {
+        id: 1,
+        item: "book".to_owned(),
+    };
+    client.send_json(&order).expect("send");
+    assert_eq!(ctx.recv_json::<Order>().expect("receive"), order);
+
+    ctx.send_json(&["accepted"]).expect("reply");
+    assert_eq!(client.recv_json::<Vec<String>>().expect("receive reply"), ["accepted"]);
+}
+
+#[test]
+fn should_report_invalid_json_body() {
+    let mut msg = Message::new().expect("create message");
+    msg.append(b"{\"id\":1}").expect("append");
+
+    let error = json::decode::<Order>(&msg).expect_err("missing field");
+    assert!(error.is_codec());
+    assert!(matches!(error, JsonError::Decode(_)));
+    assert!(error.to_string().starts_with("unable to decode JSON message body: missing field `item`"));
+
+    let msg = json::encode(&Order { id: 2, item: "pen".to_owned() }).expect("encode");
+    assert_eq!(json::decode::<Order>(&msg).expect("decode").id, 2);
+}
@@ -0,0 +1,35 @@
+use nng_c::sys;
+use nng_c::utils::{self, Facility, Level, LogEvent};
+
+use std::sync::Mutex;
+
+type Event = (Level, Facility, Option<String>, String);
+
+static EVENTS: Mutex<Vec<Event>> = Mutex::new(Vec::new());
+
+fn collect(event: &LogEvent<'_>) {
+    EVENTS.lock().unwrap().push((event.level, event.facility, event.msg_id.map(String::from), event.msg.to_owned()));
+}
+
+#[test]
+fn should_deliver_structured_log_events() {
+    utils::enable_event_logging(Level::Info, collect);
+    unsafe {
+        sys::nng_log_warn(b"TEST-WARN\0".as_ptr() as _, b"warning\0".as_ptr() as _);
+        sys::nng_log_info(b"TEST-INFO\0".as_ptr() as _, b"filtered by level\0".as_ptr() as _);
+        sys::nng_log_auth(sys::nng_log_level::NNG_LOG_ERR, core::ptr::null(), b"denied\0".as_ptr() as _);
+    }
+    utils::set_log_facility(Facility::Local(3));
+    unsafe {
+        sys::nng_log_notice(b"TEST-LOCAL\0".as_ptr() as _, b"local\0".as_ptr() as _);
+    }
+    utils::set_log_facility(Facility::User);
+    utils::disable_logging();
+
+    let events = EVENTS.lock().unwrap();
+    assert_eq!(*events, [
+        (Level::Warn, Facility::User, Some("TEST-WARN".to_owned()), "warning".to_owned()),
+        (Level::Error, Facility::Auth, None, "denied".to_owned()),
+        (Level::Info, Facility::Local(3), Some("TEST-LOCAL".to_owned()), "local".to_owned()),
+    ]);
+}
@@ -0,0 +1,132 @@
+use nng_c::{options, Message, NngError, Socket};
+use nng_c::mailbox::{Actor, Mailbox};
+
+use core::convert::TryFrom;
+use core::time;
+use std::sync::Arc;
+
+enum Command {
+    Add(u8),
+    Stop,
+}
+
+impl TryFrom<Message> for Command {
+    type Error = Message;
+
+    fn try_from(msg: Message) -> Result<Self, Self::Error> {
+        match msg.body() {
+            b"stop" => Ok(Command::Stop),
+            [value] => Ok(Command::Add(*value)),
+            _ => Err(msg),
+        }
+    }
+}
+
+struct Counter {
+    total: u8,
+}
+
+impl Actor for Counter {
+    type Message = Command;
+
+    fn handle(&mut self, msg: Command, mailbox: &mut Mailbox<'_>) {
+        match msg {
+            Command::Add(value) => {
+                self.total += value;
+                mailbox.reply(message(&[self.total])).expect("reply");
+                mailbox.send("log", message(&[value])).expect("send to log");
+                let (_, error) = mailbox.send("unknown", message(&[value])).expect_err("unknown peer");
+                assert_eq!(error.raw_code(), nng_c::sys::nng_errno_enum::NNG_ENOENT);
+            },
+            Command::Stop => {
+                mailbox.reply(message(b"stopped")).expect("reply");
+                mailbox.stop();
+            },
+        }
+    }
+}
+
+fn message(body: &[u8]) -> Message {
+    let mut msg = Message::new().expect("create message");
+    msg.append(body).expect("append");
+    msg
+}
+
+#[test]
+fn should_deliver_typed_messages_to_actor() {
+    const ADDR: &str = "inproc://should_deliver_typed_messages_to_actor\0";
+    const LOG_ADDR: &str = "inproc://should_deliver_typed_messages_to_actor_log\0";
+
+    let server = Arc::new(Socket::rep0().expect("create server"));
+    server.listen(ADDR.into()).expect("listen");
+    let log = Arc::new(Socket::push0().expect("create log"));
+    log.listen(LOG_ADDR.into()).expect("listen");
+    let log_reader = Socket::pull0().expect("create log reader");
+    log_reader.set_opt(options::RecvTimeout(time::Duration::from_secs(5))).expect("set timeout");
+    log_reader.connect(LOG_ADDR.into()).expect("connect");
+
+    let client = Socket::req0().expect("create client");
+    client.set_opt(options::RecvTimeout(time::Duration::from_secs(5))).expect("set timeout");
+    client.connect(ADDR.into()).expect("connect");
+
+    let actor = {
+        let server = server.clone();
+        let log = log.clone();
+        std::thread::spawn(move || {
+            let mut counter = Counter {
+                total: 0,
+            };
+            let mut mailbox = Mailbox::with_context(&server).expect("create mailbox").peer("log", &log);
+            assert!(mailbox.has_peer("log"));
+            mailbox.run(&mut counter).expect("run actor");
+            assert!(mailbox.is_stopped());
+            counter.total
+        })
+    };
+
+    client.send_msg(message(&[2])).expect("send");
+    assert_eq!(client.recv_msg().expect("receive").body(), [2]);
+    client.send_msg(message(&[3])).expect("send");
+    assert_eq!(client.recv_msg().expect("receive").body(), [5]);
+    assert_eq!(log_reader.recv_msg().expect("receive log").body(), [2]);
+    assert_eq!(log_reader.recv_msg().expect("receive log").body(), [3]);
+
+    //Message of unknown type is dropped without reply
+    client.set_opt(options::RecvTimeout(time::Duration::from_millis(100))).expect("set timeout");
+    client.send_msg(message(b"unknown")).expect("send");
+    assert!(client.recv_msg().expect_err("no reply").is_timed_out());
+
+    client.set_opt(options::RecvTimeout(time::Duration::from_secs(5))).expect("set timeout");
+    client.send_msg(message(b"stop")).expect("send");
+    assert_eq!(client.recv_msg().expect("receive").body(), b"stopped");
+    assert_eq!(actor.join().expect("join actor"), 5);
+}
+
+#[test]
+fn should_stop_mailbox_when_inbox_closed() {
+    struct Idle;
+
+    impl Actor for Idle {
+        type Message = Message;
+
+        fn handle(&mut self, _: Message, _: &mut Mailbox<'_>) {
+            unreachable!();
+        }
+    }
+
+    let inbox = Arc::new(Socket::pull0().expect("create socket"));
+    let actor = {
+        let inbox = inbox.clone();
+        std::thread::spawn(move || {
+            let mut mailbox = Mailbox::new(&inbox);
+            let result = mailbox.run(&mut Idle);
+            (result, mailbox.is_stopped())
+        })
+    };
+
+    std::thread::sleep(time::Duration::from_millis(50));
+    inbox.close();
+    let (result, stopped) = actor.join().expect("join actor");
+    result.expect("stop on close");
+    assert!(!stopped);
+}
@@ -0,0 +1,110 @@
+use nng_c::Message;
+
+#[test]
+fn should_restore_message_from_wire() {
+    let mut msg = Message::new().expect("create message");
+    msg.header_append_u32(0x8000_0001).expect("append header");
+    msg.append(b"payload").expect("append body");
+
+    let wire = msg.to_wire().expect("serialize");
+    assert_eq!(wire, [
+        &[Message::WIRE_VERSION, 0, 0, 0, 4, 0, 0, 0, 7][..],
+        &[0x80, 0, 0, 1][..],
+        &b"payload"[..],
+    ].concat());
+
+    let restored = Message::from_wire(&wire).expect("restore");
+    assert_eq!(restored.header(), msg.header());
+    assert_eq!(restored.body(), msg.body());
+
+    let empty = Message::from_wire(&Message::new().expect("create message").to_wire().expect("serialize")).expect("restore empty");
+    assert!(empty.header().is_empty());
+    assert!(empty.body().is_empty());
+}
+
+#[test]
+fn should_split_concatenated_wire_frames() {
+    let mut first = Message::new().expect("create message");
+    first.append(b"first").expect("append body");
+    let mut second = Message::new().expect("create message");
+    second.append(b"second").expect("append body");
+
+    let mut wire = first.to_wire().expect("serialize");
+    wire.extend_from_slice(&second.to_wire().expect("serialize"));
+    Message::from_wire(&wire).expect_err("should reject trailing bytes");
+
+    let (msg, rest) = Message::split_wire(&wire).expect("split first");
+    assert_eq!(msg.body(), b"first");
+    let (msg, rest) = Message::split_wire(rest).expect("split second");
+    assert_eq!(msg.body(), b"second");
+    assert!(rest.is_empty());
+}
+
+#[test]
+fn should_reject_invalid_wire_frames() {
+    let mut msg = Message::new().expect("create message");
+    msg.append(b"body").expect("append body");
+    let wire = msg.to_wire().expect("serialize");
+
+    Message::from_wire(&[]).expect_err("should reject empty input");
+    Message::from_wire(&wire[..wire.len() - 1]).expect_err("should reject truncated frame");
+
+    let mut wrong_version = wire.clone();
+    wrong_version[0] = 0;
+    Message::from_wire(&wrong_version).expect_err("should reject unknown version");
+}
+
+#[test]
+fn should_insert_with_partial_space_in_front() {
+    //New message has 32 bytes in front of the body out of 64 bytes capacity, hence inserting more
+    //than that, while total size still fits, hits nng's chunk_insert path that shifts body
+    let prefix = [b'p'; 40];
+    let mut msg = Message::new().expect("create message");
+    msg.append(b"payload").expect("append");
+    msg.insert(&prefix).expect("insert");
+    assert_eq!(&msg.body()[..40], &prefix[..]);
+    assert_eq!(&msg.body()[40..], b"payload");
+
+    //Only 4 bytes remain in front of the body after inserting 28 bytes
+    let mut msg = Message::new().expect("create message");
+    msg.append(b"payload").expect("append");
+    msg.insert(&prefix[..28]).expect("insert");
+    msg.insert_u64(0x0102_0304_0506_0708).expect("insert u64");
+    assert_eq!(msg.pop_front_u64(), Some(0x0102_0304_0506_0708));
+    assert_eq!(&msg.body()[..28], &prefix[..28]);
+    assert_eq!(&msg.body()[28..], b"payload");
+}
+
+#[test]
+fn should_format_hexdump() {
+    let mut msg = Message::new().expect("create message");
+    msg.header_append_u32(0x8000_0001).expect("append header");
+    msg.append(b"payload\nwith 20 bytes").expect("append body");
+
+    let expected = "header (4 bytes):\n\
+                    00000000  80 00 00 01                                       |....|\n\
+                    body (21 bytes):\n\
+                    00000000  70 61 79 6c 6f 61 64 0a  77 69 74 68 20 32 30 20  |payload.with 20 |\n\
+                    00000010  62 79 74 65 73                                    |bytes|";
+    assert_eq!(msg.hexdump().to_string(), expected);
+
+    let expected = "header (4 bytes):\n\
+                    00000000  80 00                                             |..|\n\
+                    ... 2 more bytes\n\
+                    body (21 bytes):\n\
+                    00000000  70 61                                             |pa|\n\
+                    ... 19 more bytes";
+    assert_eq!(msg.hexdump().limit(2).to_string(), expected);
+
+    let empty = Message::new().expect("create message");
+    assert_eq!(format!("{:?}", empty.hexdump()), "header (0 bytes):\nbody (0 bytes):");
+}
+
+#[test]
+fn should_serialize_wire_fallibly() {
+    let mut msg = Message::new().expect("create message");
+    msg.header_append_u32(1).expect("append header");
+    msg.append(b"payload").expect("append body");
+
+    assert_eq!(msg.try_to_wire().expect("serialize"), msg.to_wire().expect("serialize"));
+}
@@ -0,0 +1,155 @@
+use nng_c::{options, Message, NngError, Socket};
+use nng_c::noise::{Keypair, Session};
+
+use core::time;
+
+fn create_pair(addr: &str) -> (Socket, Socket) {
+    let server = Socket::pair0().expect("create server");
+    server.set_opt(options::RecvTimeout(time::Duration::from_secs(5))).expect("set recv timeout");
+    server.set_opt(options::RecvBuf(4)).expect("set recv buffer");
+    server.listen(addr.into()).expect("listen");
+    let client = Socket::pair0().expect("create client");
+    client.set_opt(options::RecvTimeout(time::Duration::from_secs(5))).expect("set recv timeout");
+    client.set_opt(options::RecvBuf(4)).expect("set recv buffer");
+    client.connect(addr.into()).expect("connect");
+    (server, client)
+}
+
+#[test]
+fn should_exchange_encrypted_messages() {
+    const ADDR: &str = "inproc://should_exchange_encrypted_messages\0";
+
+    let (server, client) = create_pair(ADDR);
+    let server_key = Keypair::generate().expect("generate key");
+    let client_key = Keypair::generate().expect("generate key");
+    assert_ne!(server_key.public(), client_key.public());
+
+    let large = vec![7u8; 200_000];
+    std::thread::scope(|scope| {
+        let server = scope.spawn(|| {
+            let mut session = Session::respond(&server, &server_key).expect("handshake");
+            assert_eq!(session.remote_public_key(), client_key.public());
+
+            for _ in 0..3 {
+                let msg = session.recv_msg().expect("receive");
+                session.send_msg(msg).expect("echo");
+            }
+        });
+
+        let mut session = Session::initiate(&client, &client_key).expect("handshake");
+        assert_eq!(session.remote_public_key(), server_key.public());
+
+        let mut msg = Message::new().expect("create message");
+        msg.append(b"secret").expect("append");
+        session.send_msg(msg).expect("send");
+        let msg = session.recv_msg().expect("receive");
+        assert_eq!(msg.body(), b"secret");
+
+        let msg = Message::new().expect("create message");
+        session.send_msg(msg).expect("send");
+        let msg = session.recv_msg().expect("receive");
+        assert!(msg.body().is_empty());
+
+        let mut msg = Message::new().expect("create message");
+        msg.append(&large).expect("append");
+        session.send_msg(msg).expect("send");
+        let msg = session.recv_msg().expect("receive");
+        assert_eq!(msg.body(), large.as_slice());
+
+        server.join().expect("join server");
+    });
+}
+
+#[test]
+fn should_reject_tampered_and_replayed_messages() {
+    const ADDR: &str = "inproc://should_reject_tampered_and_replayed_messages\0";
+
+    let (server, client) = create_pair(ADDR);
+    let server_key = Keypair::generate().expect("generate key");
+    let client_key = Keypair::generate().expect("generate key");
+
+    let (mut server_session, mut client_session) = std::thread::scope(|scope| {
+        let server = scope.spawn(|| Session::respond(&server, &server_key).expect("handshake"));
+        let client = Session::initiate(&client, &client_key).expect("handshake");
+        (server.join().expect("join server"), client)
+    });
+
+    //Capture encrypted message, bypassing session
+    let mut msg = Message::new().expect("create message");
+    msg.append(b"secret").expect("append");
+    client_session.send_msg(msg).expect("send");
+    let encrypted = server.recv_msg().expect("receive");
+    assert_ne!(encrypted.body(), b"secret");
+    let encrypted = encrypted.body().to_vec();
+
+    let mut tampered = encrypted.clone();
+    let last = tampered.len() - 1;
+    tampered[last] ^= 1;
+    let mut msg = Message::new().expect("create message");
+    msg.append(&tampered).expect("append");
+    client.send_msg(msg).expect("send");
+    let error = server_session.recv_msg().expect_err("should reject tampered message");
+    assert!(error.is_crypto());
+
+    let mut msg = Message::new().expect("create message");
+    msg.append(&encrypted).expect("append");
+    client.send_msg(msg).expect("send");
+    let msg = server_session.recv_msg().expect("receive");
+    assert_eq!(msg.body(), b"secret");
+
+    let mut msg = Message::new().expect("create message");
+    msg.append(&encrypted).expect("append");
+    client.send_msg(msg).expect("send");
+    let error = server_session.recv_msg().expect_err("should reject replayed message");
+    assert!(error.is_crypto());
+
+    let mut msg = Message::new().expect("create message");
+    msg.append(b"plain").expect("append");
+    client.send_msg(msg).expect("send");
+    let error = server_session.recv_msg().expect_err("should reject plain message");
+    assert!(error.is_crypto());
+}
+
+#[test]
+fn should_reject_truncated_messages() {
+    const ADDR: &str = "inproc://should_reject_truncated_messages\0";
+
+    let (server, client) = create_pair(ADDR);
+    let server_key = Keypair::generate().expect("generate key");
+    let client_key = Keypair::generate().expect("generate key");
+
+    let (mut server_session, mut client_session) = std::thread::scope(|scope| {
+        let server = scope.spawn(|| Session::respond(&server, &server_key).expect("handshake"));
+        let client = Session::initiate(&client, &client_key).expect("handshake");
+        (server.join().expect("join server"), client)
+    });
+
+    //Capture multi frame message, bypassing session
+    let large = vec![7u8; 100_000];
+    let mut msg = Message::new().expect("create message");
+    msg.append(&large).expect("append");
+    client_session.send_msg(msg).expect("send");
+    let encrypted = server.recv_msg().expect("receive").body().to_vec();
+
+    let send_raw = |body: &[u8]| {
+        let mut msg = Message::new().expect("create message");
+        msg.append(body).expect("append");
+        client.send_msg(msg).expect("send");
+    };
+
+    //Nonce without frames
+    send_raw(&encrypted[..8]);
+    let error = server_session.recv_msg().expect_err("should reject message without frames");
+    assert!(error.is_crypto());
+
+    //Only first frame
+    let first_len = u16::from_be_bytes([encrypted[8], encrypted[9]]) as usize;
+    send_raw(&encrypted[..8 + 2 + first_len]);
+    let error = server_session.recv_msg().expect_err("should reject truncated message");
+    assert!(error.is_crypto());
+
+    //Rejected messages do not advance nonce
+    send_raw(&encrypted);
+    let msg = server_session.recv_msg().expect("receive");
+    assert_eq!(msg.body(), large.as_slice());
+}
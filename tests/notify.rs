@@ -0,0 +1,142 @@
+mod rt;
+
+use nng_c::Socket;
+use nng_c::notify::{PipeEvent, PipeNotifier};
+
+use core::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+#[test]
+fn should_fan_out_pipe_events() {
+    const ADDR: &str = "inproc://should_fan_out_pipe_events\0";
+
+    let server = Socket::pair0().expect("create server");
+    let notifier = PipeNotifier::install(&server).expect("install notifier");
+
+    let added = Arc::new(AtomicUsize::new(0));
+    let removed = Arc::new(AtomicUsize::new(0));
+    let counter = added.clone();
+    let added_sub = notifier.subscribe(move |_, event| if event == PipeEvent::AddPost {
+        counter.fetch_add(1, Ordering::AcqRel);
+    });
+    let counter = removed.clone();
+    let _removed_sub = notifier.subscribe(move |_, event| if event == PipeEvent::RemPost {
+        counter.fetch_add(1, Ordering::AcqRel);
+    });
+    let events = notifier.events().expect("create stream");
+    assert_eq!(notifier.subscribers(), 3);
+
+    server.listen(ADDR.into()).expect("listen");
+    let client = Socket::pair0().expect("create client");
+    client.connect(ADDR.into()).expect("connect");
+
+    let (pipe, event) = rt::run(events.next());
+    assert_eq!(event, PipeEvent::AddPre);
+    let (added_pipe, event) = rt::run(events.next());
+    assert_eq!(event, PipeEvent::AddPost);
+    assert_eq!(pipe, added_pipe);
+    assert_eq!(added.load(Ordering::Acquire), 1);
+
+    drop(added_sub);
+    assert_eq!(notifier.subscribers(), 2);
+
+    client.close();
+    let (removed_pipe, event) = rt::run(events.next());
+    assert_eq!(event, PipeEvent::RemPost);
+    assert_eq!(pipe, removed_pipe);
+    assert_eq!(removed.load(Ordering::Acquire), 1);
+    assert!(events.try_next().is_none());
+
+    let client = Socket::pair0().expect("create client");
+    client.connect(ADDR.into()).expect("connect");
+    let (_, event) = rt::run(events.next());
+    assert_eq!(event, PipeEvent::AddPre);
+    let (_, event) = rt::run(events.next());
+    assert_eq!(event, PipeEvent::AddPost);
+    assert_eq!(added.load(Ordering::Acquire), 1);
+
+    drop(events);
+    assert_eq!(notifier.subscribers(), 1);
+}
+
+#[test]
+fn should_share_notifier_of_socket() {
+    const ADDR: &str = "inproc://should_share_notifier_of_socket\0";
+
+    let server = Socket::pair0().expect("create server");
+    let first = PipeNotifier::install(&server).expect("install notifier");
+    let connected = Arc::new(AtomicUsize::new(0));
+    let counter = connected.clone();
+    let _subscription = first.subscribe(move |_, event| if event == PipeEvent::AddPost {
+        counter.fetch_add(1, Ordering::AcqRel);
+    });
+
+    let second = PipeNotifier::install(&server).expect("install notifier");
+    assert_eq!(second.subscribers(), 1);
+    let events = second.events().expect("create stream");
+    assert_eq!(first.subscribers(), 2);
+
+    server.listen(ADDR.into()).expect("listen");
+    let client = Socket::pair0().expect("create client");
+    client.connect(ADDR.into()).expect("connect");
+
+    let (_, event) = rt::run(events.next());
+    assert_eq!(event, PipeEvent::AddPre);
+    let (_, event) = rt::run(events.next());
+    assert_eq!(event, PipeEvent::AddPost);
+    assert_eq!(connected.load(Ordering::Acquire), 1);
+}
+
+#[test]
+fn should_monitor_connections() {
+    use nng_c::notify::ConnectionEvent;
+    use nng_c::options::Address;
+
+    let port = std::net::TcpListener::bind(("127.0.0.1", 0)).expect("bind").local_addr().expect("get address").port();
+    let url = format!("tcp://127.0.0.1:{}", port);
+
+    let server = Socket::pair1().expect("create server");
+    let monitor = server.monitor().expect("create monitor");
+    assert!(monitor.try_next().is_none());
+    server.listen(url.as_str().into()).expect("listen");
+
+    let client = Socket::pair1().expect("create client");
+    client.connect(url.as_str().into()).expect("connect");
+
+    let peer = match rt::run(monitor.next()) {
+        ConnectionEvent::Connected(peer) => peer,
+        other => panic!("unexpected event: {:?}", other),
+    };
+    assert!(!peer.is_dialer);
+    match peer.remote_addr.as_ref() {
+        Some(Address::Inet(addr)) => assert!(addr.ip().is_loopback()),
+        other => panic!("unexpected address: {:?}", other),
+    }
+
+    client.close();
+    let event = rt::run(monitor.next());
+    assert_eq!(event, ConnectionEvent::Disconnected(peer.clone()));
+    assert_eq!(event.peer(), &peer);
+}
+
+#[test]
+fn should_keep_earlier_monitors() {
+    use nng_c::notify::ConnectionEvent;
+
+    const ADDR: &str = "inproc://should_keep_earlier_monitors\0";
+
+    let server = Socket::pair0().expect("create server");
+    let first = server.monitor().expect("create monitor");
+    let second = server.monitor().expect("create monitor");
+    server.listen(ADDR.into()).expect("listen");
+
+    let client = Socket::pair0().expect("create client");
+    client.connect(ADDR.into()).expect("connect");
+
+    for monitor in [&first, &second].iter() {
+        match rt::run(monitor.next()) {
+            ConnectionEvent::Connected(peer) => assert!(!peer.is_dialer),
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
+}
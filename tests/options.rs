@@ -32,3 +32,119 @@ fn should_read_peer_name() {
     peer = client.get_prop().expect("get peer name");
     assert_eq!("rep", peer);
 }
+
+#[test]
+fn should_track_pair1_hops() {
+    const ADDR: &str =  "inproc://should_track_pair1_hops\0";
+
+    let client = Socket::pair1().expect("Create client");
+    let server = Socket::pair1().expect("Create server");
+
+    let ttl: options::MaxTtl = server.get_prop().expect("get ttl");
+    assert_eq!(ttl, options::MaxTtl(8));
+    server.set_opt(options::MaxTtl(4)).expect("set ttl");
+    let ttl: options::MaxTtl = server.get_prop().expect("get ttl");
+    assert_eq!(ttl, options::MaxTtl(4));
+
+    server.listen(ADDR.into()).expect("listen");
+    client.connect(ADDR.into()).expect("connect");
+
+    let mut msg = nng_c::Message::new().expect("Create message");
+    msg.get_prop::<options::HopCount>().expect_err("no header");
+    msg.append(b"hop").expect("append");
+    client.send_msg(msg).expect("send");
+
+    let msg = server.recv_msg().expect("recv");
+    let hops: options::HopCount = msg.get_prop().expect("get hop count");
+    assert_eq!(hops, options::HopCount(1));
+    assert_eq!(hops.remaining(ttl), 3);
+}
+
+#[test]
+fn should_dump_socket_options() {
+    use core::time;
+
+    let socket = Socket::pair1().expect("Create socket");
+    socket.set_opt(options::SocketName::new("dump").expect("to fit name")).expect("set name");
+    socket.set_opt(options::RecvBuf(4)).expect("set recv buffer");
+    socket.set_opt(options::SendTimeout(time::Duration::from_millis(250))).expect("set send timeout");
+
+    let dump = socket.dump_options().expect("dump options");
+    assert_eq!(dump.name, "dump");
+    assert_eq!(dump.protocol, "pair1");
+    assert_eq!(dump.peer, "pair1");
+    assert!(!dump.raw);
+    assert_eq!(dump.recv_buf, 4);
+    assert_eq!(dump.recv_timeout, None);
+    assert_eq!(dump.send_timeout, Some(time::Duration::from_millis(250)));
+    assert_eq!(dump.max_ttl, Some(8));
+    assert!(dump.recv_fd.is_some());
+
+    let raw: options::Raw = Socket::pair1_raw().expect("Create raw socket").get_prop().expect("get raw");
+    assert!(raw.0);
+
+    let dump = Socket::pub0().expect("Create publisher").dump_options().expect("dump options");
+    assert_eq!(dump.protocol, "pub");
+    assert_eq!(dump.max_ttl, None);
+    assert_eq!(dump.recv_fd, None);
+    assert!(dump.send_fd.is_some());
+}
+
+#[test]
+fn should_format_socket_with_protocol() {
+    let socket = Socket::req0().expect("Create client");
+    let id = format!("{:?}", socket).split(&['=', ','][..]).nth(1).expect("get id").to_owned();
+    assert_eq!(format!("{:?}", socket), format!("Socket(id={}, protocol=req, raw=false)", id));
+    assert_eq!(socket.to_string(), format!("req#{}", id));
+
+    let socket = Socket::rep0_raw().expect("Create raw server");
+    assert!(socket.to_string().starts_with("rep#"), "unexpected: {}", socket);
+    assert!(socket.to_string().ends_with("(raw)"), "unexpected: {}", socket);
+}
+
+#[test]
+fn should_check_peer_protocol() {
+    use nng_c::socket::{PeerError, Protocol};
+
+    let socket = Socket::req0().expect("create socket");
+    socket.check_peer(Protocol::Rep0).expect("req talks to rep");
+    socket.check_peer(Protocol::Req0.peer()).expect("req talks to its peer");
+
+    match socket.check_peer(Protocol::Sub0) {
+        Err(error @ PeerError::Mismatch { expected: Protocol::Sub0, .. }) => {
+            assert_eq!(error.to_string(), "expected socket for 'sub' peer, but it communicates with 'rep'");
+        },
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    let socket = Socket::pair1().expect("create socket");
+    socket.check_peer(Protocol::Pair1).expect("pair1 talks to pair1");
+    assert!(socket.check_peer(Protocol::Pair0).is_err());
+}
+
+#[test]
+fn should_get_protocol_numbers() {
+    use nng_c::options::{PeerId, ProtocolId, ProtocolName, PeerName};
+    use nng_c::socket::Protocol;
+
+    const PROTOCOLS: [Protocol; 10] = [
+        Protocol::Pair0, Protocol::Pair1, Protocol::Pub0, Protocol::Sub0, Protocol::Req0,
+        Protocol::Rep0, Protocol::Surveyor0, Protocol::Respondent0, Protocol::Push0, Protocol::Pull0,
+    ];
+
+    for protocol in PROTOCOLS {
+        let socket = protocol.open(false).expect("create socket");
+
+        let id = socket.get_prop::<ProtocolId>().expect("get protocol");
+        assert_eq!(id, ProtocolId(protocol.id()));
+        assert_eq!(id.protocol(), Some(protocol));
+        assert_eq!(socket.get_prop::<ProtocolName>().expect("get protocol name"), protocol.name());
+
+        let peer = socket.get_prop::<PeerId>().expect("get peer");
+        assert_eq!(peer, PeerId(protocol.peer().id()));
+        assert_eq!(peer.protocol(), Some(protocol.peer()));
+        assert_eq!(socket.get_prop::<PeerName>().expect("get peer name"), protocol.peer().name());
+    }
+
+    assert_eq!(Protocol::from_id(0), None);
+}
@@ -0,0 +1,75 @@
+use nng_c::{options, Message, Socket};
+use nng_c::otel::{self, Traced};
+
+use opentelemetry::Context;
+use opentelemetry::trace::{SpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState};
+
+use core::time;
+
+mod rt;
+
+fn parent() -> Context {
+    let span = SpanContext::new(
+        TraceId::from(0x0102_0304_0506_0708_090a_0b0c_0d0e_0f10u128),
+        SpanId::from(0x1112_1314_1516_1718u64),
+        TraceFlags::SAMPLED,
+        true,
+        TraceState::default(),
+    );
+    Context::new().with_remote_span_context(span)
+}
+
+#[test]
+fn should_inject_and_extract_trace_context() {
+    let mut msg = Message::new().expect("create message");
+    msg.append(b"payload").expect("append");
+    otel::inject_context(&Context::new(), &mut msg).expect("inject nothing");
+    assert_eq!(msg.body(), b"payload");
+
+    let parent = parent();
+    otel::inject_context(&parent, &mut msg).expect("inject");
+    let context = otel::extract_context(&mut msg).expect("extract");
+    assert_eq!(msg.body(), b"payload");
+    assert_eq!(context.span().span_context(), parent.span().span_context());
+    assert!(otel::extract_context(&mut msg).is_none());
+}
+
+#[test]
+fn should_propagate_trace_context() {
+    const ADDR: &str = "inproc://should_propagate_trace_context\0";
+
+    let server = Socket::pair0().expect("create server");
+    server.set_opt(options::RecvBuf(16)).expect("set recv buffer");
+    server.set_opt(options::RecvTimeout(time::Duration::from_secs(5))).expect("set recv timeout");
+    server.listen(ADDR.into()).expect("listen");
+    let client = Socket::pair0().expect("create client");
+    client.set_opt(options::SendBuf(16)).expect("set send buffer");
+    client.connect(ADDR.into()).expect("connect");
+
+    let sender = Traced::new(&client);
+    let receiver = Traced::new(&server);
+    let parent = parent();
+    let trace_id = parent.span().span_context().trace_id();
+
+    let mut msg = Message::new().expect("create message");
+    msg.append(b"sync").expect("append");
+    sender.send_msg(&parent, msg).expect("send");
+    let (msg, context) = receiver.recv_msg().expect("receive");
+    assert_eq!(msg.body(), b"sync");
+    assert_eq!(context.span().span_context().trace_id(), trace_id);
+
+    let mut msg = Message::new().expect("create message");
+    msg.append(b"async").expect("append");
+    rt::run(sender.send_msg_async(&parent, msg)).expect("send async");
+    let (msg, context) = rt::run(receiver.recv_msg_async()).expect("receive async").expect("have message");
+    assert_eq!(msg.body(), b"async");
+    assert_eq!(context.span().span_context().trace_id(), trace_id);
+
+    //Message without trace context is received as it is
+    let mut msg = Message::new().expect("create message");
+    msg.append(b"plain").expect("append");
+    client.send_msg(msg).expect("send plain");
+    let (msg, context) = receiver.recv_msg().expect("receive plain");
+    assert_eq!(msg.body(), b"plain");
+    assert!(!context.span().span_context().is_valid());
+}
@@ -0,0 +1,59 @@
+use nng_c::{options, Message, Socket};
+use nng_c::outbox::Outbox;
+use nng_c::socket::ConnectOptions;
+
+use core::time;
+
+fn msg(body: &[u8]) -> Message {
+    let mut msg = Message::new().expect("create message");
+    msg.append(body).expect("append");
+    msg
+}
+
+#[test]
+fn should_replay_spooled_messages_after_reconnect() {
+    let port = std::net::TcpListener::bind(("127.0.0.1", 0)).expect("bind").local_addr().expect("get addr").port();
+    let addr = format!("tcp://127.0.0.1:{}\0", port);
+    let path = std::env::temp_dir().join(format!("nng-c-outbox-{}.spool", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    let client = Socket::pair0().expect("create client");
+    client.set_opt(options::Reconnect { min_time: Some(time::Duration::from_millis(10)), max_time: None }).expect("set reconnect");
+    client.connect_with(addr.as_str().into(), ConnectOptions::new().with_async()).expect("connect");
+    let outbox = Outbox::open(client, &path).expect("open outbox");
+    outbox.send(msg(b"1")).expect("spool");
+    outbox.send(msg(b"2")).expect("spool");
+    assert_eq!(outbox.len(), 2);
+    assert_eq!(outbox.flush().expect("flush"), 0);
+    drop(outbox);
+    assert!(path.exists());
+
+    //Messages survive restart of the client
+    let client = Socket::pair0().expect("create client");
+    client.set_opt(options::Reconnect { min_time: Some(time::Duration::from_millis(10)), max_time: None }).expect("set reconnect");
+    client.connect_with(addr.as_str().into(), ConnectOptions::new().with_async()).expect("connect");
+    let outbox = Outbox::open(client, &path).expect("open outbox");
+    assert_eq!(outbox.len(), 2);
+    outbox.send(msg(b"3")).expect("spool");
+
+    let server = Socket::pair0().expect("create server");
+    server.set_opt(options::RecvTimeout(time::Duration::from_secs(2))).expect("set timeout");
+    server.listen(addr.as_str().into()).expect("listen");
+
+    for expected in [&b"1"[..], b"2", b"3"].iter() {
+        let msg = server.recv_msg().expect("receive replayed");
+        assert_eq!(msg.body(), *expected);
+    }
+    assert!(outbox.is_empty());
+    //Spool is removed by replay once it is complete
+    for _ in 0..100 {
+        if !path.exists() {
+            break;
+        }
+        std::thread::sleep(time::Duration::from_millis(10));
+    }
+    assert!(!path.exists());
+
+    outbox.send(msg(b"4")).expect("send");
+    assert_eq!(server.recv_msg().expect("receive").body(), b"4");
+}
@@ -0,0 +1,284 @@
+use nng_c::{options, Message, NngError, Pipe, Socket};
+use nng_c::options::{Address, RemoteAddr};
+use nng_c::socket::AcceptFilter;
+
+use core::time;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+static ALLOW: AtomicBool = AtomicBool::new(false);
+static SEEN: AtomicUsize = AtomicUsize::new(0);
+
+fn accept_filter(pipe: &Pipe) -> bool {
+    assert!(pipe.id() > 0);
+    assert!(pipe.is_listener());
+    assert!(!pipe.is_dialer());
+    match pipe.get_prop::<RemoteAddr>().expect("get remote address") {
+        RemoteAddr(Address::Inet(addr)) => assert!(addr.ip().is_loopback()),
+        RemoteAddr(addr) => panic!("unexpected address: {}", addr),
+    }
+
+    SEEN.fetch_add(1, Ordering::AcqRel);
+    ALLOW.load(Ordering::Acquire)
+}
+
+#[test]
+fn should_filter_accepted_connections() {
+    let port = std::net::TcpListener::bind(("127.0.0.1", 0)).expect("bind").local_addr().expect("get address").port();
+    let url = format!("tcp://127.0.0.1:{}", port);
+
+    let server = Socket::pair0().expect("create server");
+    server.set_opt(options::RecvTimeout(time::Duration::from_millis(50))).expect("set recv timeout");
+    server.listen_with(url.as_str().into(), &AcceptFilter(accept_filter)).expect("listen");
+
+    let client = Socket::pair0().expect("create client");
+    client.set_opt(options::Reconnect {
+        min_time: Some(time::Duration::from_millis(10)),
+        max_time: Some(time::Duration::from_millis(10)),
+    }).expect("set reconnect");
+    client.set_opt(options::SendTimeout(time::Duration::from_secs(5))).expect("set send timeout");
+    client.connect_with(url.as_str().into(), nng_c::socket::ConnectOptions::new().with_async()).expect("connect");
+
+    let start = std::time::Instant::now();
+    while SEEN.load(Ordering::Acquire) < 2 {
+        assert!(start.elapsed() < time::Duration::from_secs(5), "client is not reconnecting");
+        std::thread::sleep(time::Duration::from_millis(1));
+    }
+    let error = server.recv_msg().expect_err("should not have connection");
+    assert!(nng_c::NngError::is_timed_out(&error), "unexpected error: {}", error);
+
+    ALLOW.store(true, Ordering::Release);
+    client.send(b"hello".into()).expect("send");
+    server.set_opt(options::RecvTimeout(time::Duration::from_secs(5))).expect("set recv timeout");
+    let msg = server.recv_msg().expect("receive");
+    assert_eq!(msg.body(), b"hello");
+}
+
+fn reject_all(_: &Pipe) -> bool {
+    false
+}
+
+fn accept_all(_: &Pipe) -> bool {
+    true
+}
+
+#[test]
+fn should_filter_connections_per_listener() {
+    const REJECTED: &str = "inproc://should_filter_connections_per_listener_rejected\0";
+    const ACCEPTED: &str = "inproc://should_filter_connections_per_listener_accepted\0";
+
+    let server = Socket::pair1_poly().expect("create server");
+    server.set_opt(options::RecvTimeout(time::Duration::from_millis(200))).expect("set recv timeout");
+    server.listen_with(REJECTED.into(), &AcceptFilter(reject_all)).expect("listen");
+    server.listen_with(ACCEPTED.into(), &AcceptFilter(accept_all)).expect("listen");
+
+    let rejected = Socket::pair1().expect("create client");
+    rejected.set_opt(options::SendTimeout(time::Duration::from_millis(10))).expect("set send timeout");
+    rejected.connect_with(REJECTED.into(), nng_c::socket::ConnectOptions::new().with_async()).expect("connect");
+    let _ = rejected.send(b"rejected".into());
+
+    let accepted = Socket::pair1().expect("create client");
+    accepted.set_opt(options::SendTimeout(time::Duration::from_secs(5))).expect("set send timeout");
+    accepted.connect(ACCEPTED.into()).expect("connect");
+    accepted.send(b"accepted".into()).expect("send");
+
+    assert_eq!(server.recv_msg().expect("receive").body(), b"accepted");
+    let error = server.recv_msg().expect_err("should not have connection");
+    assert!(error.is_timed_out(), "unexpected error: {}", error);
+}
+
+#[test]
+fn should_connect_from_local_address() {
+    use nng_c::socket::ConnectOptions;
+
+    let port = std::net::TcpListener::bind(("127.0.0.1", 0)).expect("bind").local_addr().expect("get address").port();
+    let url = format!("tcp://127.0.0.1:{}", port);
+    let local = std::net::IpAddr::from([127, 0, 0, 2]);
+
+    let server = Socket::pair0().expect("create server");
+    server.set_opt(options::RecvTimeout(time::Duration::from_secs(5))).expect("set recv timeout");
+    server.listen(url.as_str().into()).expect("listen");
+
+    let client = Socket::pair0().expect("create client");
+    client.set_opt(options::SendTimeout(time::Duration::from_secs(5))).expect("set send timeout");
+    client.connect_with(url.as_str().into(), ConnectOptions::new().with_local_address(local)).expect("connect");
+    client.send(b"hello".into()).expect("send");
+
+    let msg = server.recv_msg().expect("receive");
+    let pipe = msg.pipe().expect("have pipe");
+    match pipe.get_prop::<RemoteAddr>().expect("get remote address") {
+        RemoteAddr(Address::Inet(addr)) => assert_eq!(addr.ip(), local),
+        RemoteAddr(addr) => panic!("unexpected address: {}", addr),
+    }
+
+    client.connect_with("inproc://should_connect_from_local_address".into(), ConnectOptions::new().with_local_address(local)).expect_err("inproc has no local address");
+}
+
+#[test]
+fn should_convert_address() {
+    use core::convert::TryFrom;
+    use std::net::SocketAddr;
+
+    let ipv4: SocketAddr = "127.0.0.1:8080".parse().expect("parse ipv4");
+    let addr = Address::from(ipv4);
+    assert_eq!(addr.to_string(), "tcp://127.0.0.1:8080");
+    assert_eq!(addr.socket_addr(), Some(ipv4));
+    assert_eq!(SocketAddr::try_from(addr), Ok(ipv4));
+
+    let ipv6: SocketAddr = "[::1]:8080".parse().expect("parse ipv6");
+    let addr = Address::from(ipv6);
+    assert_eq!(addr.to_string(), "tcp://[::1]:8080");
+    assert_eq!(SocketAddr::try_from(addr), Ok(ipv6));
+
+    let addr = Address::Inproc("name".into());
+    assert_eq!(addr.socket_addr(), None);
+    assert_eq!(SocketAddr::try_from(addr.clone()), Err(addr));
+}
+
+#[test]
+fn should_send_to_specific_pipe() {
+    const ADDR: &str = "inproc://should_send_to_specific_pipe\0";
+
+    let server = Socket::pair1_poly().expect("create server");
+    server.set_opt(options::RecvTimeout(time::Duration::from_secs(5))).expect("set recv timeout");
+    server.listen(ADDR.into()).expect("listen");
+
+    let clients = [Socket::pair1().expect("create client"), Socket::pair1().expect("create client")];
+    for (idx, client) in clients.iter().enumerate() {
+        client.set_opt(options::RecvTimeout(time::Duration::from_millis(100))).expect("set recv timeout");
+        client.connect(ADDR.into()).expect("connect");
+        let mut msg = Message::new().expect("create message");
+        msg.append(&[idx as u8]).expect("append");
+        client.send_msg(msg).expect("send");
+    }
+
+    let mut pipes = [None, None];
+    for _ in 0..2 {
+        let msg = server.recv_msg().expect("receive");
+        pipes[msg.body()[0] as usize] = msg.pipe();
+    }
+    let pipe = pipes[1].expect("have second pipe");
+
+    let mut msg = Message::new().expect("create message");
+    msg.append(b"reply").expect("append");
+    server.send_msg_to(&pipe, msg).expect("send to pipe");
+
+    let reply = clients[1].recv_msg().expect("receive reply");
+    assert_eq!(reply.body(), b"reply");
+    let error = clients[0].recv_msg().expect_err("should not receive reply");
+    assert!(error.is_timed_out());
+}
+
+#[test]
+fn should_reply_to_peer_of_poly_socket() {
+    const ADDR: &str = "inproc://should_reply_to_peer_of_poly_socket\0";
+
+    let server = Socket::pair1_poly().expect("create server");
+    assert_eq!(server.get_prop::<options::Pair1Poly>().expect("get poly"), options::Pair1Poly(true));
+    assert_eq!(Socket::pair1().expect("create socket").get_prop::<options::Pair1Poly>().expect("get poly"), options::Pair1Poly(false));
+    server.listen(ADDR.into()).expect("listen");
+
+    let clients = [Socket::pair1().expect("create client"), Socket::pair1().expect("create client")];
+    for (idx, client) in clients.iter().enumerate() {
+        client.set_opt(options::RecvTimeout(time::Duration::from_millis(100))).expect("set recv timeout");
+        client.connect(ADDR.into()).expect("connect");
+        client.send((&[idx as u8][..]).into()).expect("send");
+    }
+
+    for _ in 0..2 {
+        let request = server.recv_msg().expect("receive");
+        let mut msg = Message::new().expect("create message");
+        msg.append(request.body()).expect("append");
+        server.reply_to(&request, msg).expect("reply");
+    }
+    for (idx, client) in clients.iter().enumerate() {
+        assert_eq!(client.recv_msg().expect("receive reply").body(), [idx as u8]);
+    }
+
+    let msg = Message::new().expect("create message");
+    let (_, error) = server.reply_to(&Message::new().expect("create message"), msg).expect_err("no pipe");
+    assert_eq!(error.code().raw_code(), nng_c::sys::nng_errno_enum::NNG_EINVAL);
+}
+
+#[test]
+fn should_limit_number_of_connections() {
+    use nng_c::socket::{ConnectOptions, MaxConnections};
+
+    let port = std::net::TcpListener::bind(("127.0.0.1", 0)).expect("bind").local_addr().expect("get address").port();
+    let url = format!("tcp://127.0.0.1:{}", port);
+
+    let server = Socket::pair1_poly().expect("create server");
+    server.set_opt(options::RecvTimeout(time::Duration::from_secs(5))).expect("set recv timeout");
+    server.listen_with(url.as_str().into(), &MaxConnections(2)).expect("listen");
+
+    let connect = || {
+        let client = Socket::pair1().expect("create client");
+        client.set_opt(options::Reconnect {
+            min_time: Some(time::Duration::from_millis(10)),
+            max_time: Some(time::Duration::from_millis(10)),
+        }).expect("set reconnect");
+        client.set_opt(options::SendTimeout(time::Duration::from_secs(5))).expect("set send timeout");
+        client.connect_with(url.as_str().into(), ConnectOptions::new().with_async()).expect("connect");
+        client
+    };
+
+    let first = connect();
+    first.send(b"first".into()).expect("send");
+    let second = connect();
+    second.send(b"second".into()).expect("send");
+    assert_eq!(server.recv_msg().expect("receive").body(), b"first");
+    assert_eq!(server.recv_msg().expect("receive").body(), b"second");
+
+    //Rejected client still sees connection established, so messages sent over it are lost
+    let third = connect();
+    third.set_opt(options::SendTimeout(time::Duration::from_millis(10))).expect("set send timeout");
+    let _ = third.send(b"third".into());
+    server.set_opt(options::RecvTimeout(time::Duration::from_millis(200))).expect("set recv timeout");
+    let error = server.recv_msg().expect_err("should not have connection");
+    assert!(error.is_timed_out(), "unexpected error: {}", error);
+    server.set_opt(options::RecvTimeout(time::Duration::from_millis(10))).expect("set recv timeout");
+
+    //Frees slot for the third client
+    drop(first);
+    let start = std::time::Instant::now();
+    loop {
+        assert!(start.elapsed() < time::Duration::from_secs(5), "third client is not accepted");
+        let _ = third.send(b"third".into());
+        if let Ok(msg) = server.recv_msg() {
+            assert_eq!(msg.body(), b"third");
+            break;
+        }
+    }
+}
+
+#[test]
+fn should_describe_pipe_of_received_message() {
+    use nng_c::options::{LocalAddr, PeerId, PeerName};
+    use nng_c::socket::Protocol;
+    use std::collections::HashSet;
+
+    let port = std::net::TcpListener::bind(("127.0.0.1", 0)).expect("bind").local_addr().expect("get address").port();
+    let url = format!("tcp://127.0.0.1:{}", port);
+
+    let server = Socket::rep0().expect("create server");
+    server.set_opt(options::RecvTimeout(time::Duration::from_secs(5))).expect("set recv timeout");
+    server.listen(url.as_str().into()).expect("listen");
+
+    let client = Socket::req0().expect("create client");
+    client.connect(url.as_str().into()).expect("connect");
+    client.send(b"hello".into()).expect("send");
+
+    let msg = server.recv_msg().expect("receive");
+    let pipe = msg.pipe().expect("have pipe");
+    assert!(pipe.is_listener());
+    assert_eq!(pipe.socket_id(), server.id as i32);
+
+    match pipe.get_prop::<LocalAddr>().expect("get local address") {
+        LocalAddr(Address::Inet(addr)) => assert_eq!(addr.port(), port),
+        LocalAddr(addr) => panic!("unexpected address: {}", addr),
+    }
+    assert_eq!(pipe.get_prop::<PeerName>().expect("get peer name"), "req");
+    assert_eq!(pipe.get_prop::<PeerId>().expect("get peer id").protocol(), Some(Protocol::Req0));
+
+    let pipes = [pipe, msg.pipe().expect("have pipe")].iter().copied().collect::<HashSet<_>>();
+    assert_eq!(pipes.len(), 1);
+}
@@ -0,0 +1,44 @@
+use nng_c::{Socket, Message};
+use nng_c::pool::WorkerPool;
+
+mod rt;
+
+#[test]
+fn should_serve_requests_with_worker_pool() {
+    const ADDR: &str = "inproc://should_serve_requests_with_worker_pool\0";
+    const CLIENTS: u32 = 8;
+
+    let server = Socket::rep0().expect("Create server");
+    server.listen(ADDR.into()).expect("listen");
+
+    let error = WorkerPool::new(&server, 0).expect_err("empty pool");
+    assert_eq!(error.raw_code(), nng_c::sys::nng_errno_enum::NNG_EINVAL);
+
+    std::thread::scope(|scope| {
+        let worker = scope.spawn(|| {
+            let mut pool = WorkerPool::new(&server, 4).expect("create pool");
+            assert_eq!(pool.size(), 4);
+            rt::run(pool.serve(|mut req| {
+                let value = req.pop_front_u32().expect("get value");
+                req.append_u32(value + 1).expect("append");
+                req
+            }))
+        });
+
+        let clients = (0..CLIENTS).map(|value| scope.spawn(move || {
+            let client = Socket::req0().expect("Create client");
+            client.connect(ADDR.into()).expect("connect");
+            let mut req = Message::new().expect("Create message");
+            req.append_u32(value).expect("append");
+            let mut reply = rt::run(client.request(req)).expect("get reply");
+            assert_eq!(reply.pop_front_u32(), Some(value + 1));
+        })).collect::<Vec<_>>();
+
+        for client in clients {
+            client.join().expect("finish client");
+        }
+
+        server.close();
+        worker.join().expect("finish worker").expect("close cleanly");
+    });
+}
@@ -0,0 +1,47 @@
+use nng_c::{Context, Socket};
+use nng_c::postcard::{self, PostcardError};
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+struct Reading {
+    sensor: u16,
+    value: i32,
+    label: String,
+}
+
+#[test]
+fn should_send_and_recv_postcard() {
+    const ADDR: &str = "inproc://should_send_and_recv_postcard\0";
+
+    let server = Socket::rep0().expect("create server");
+    server.listen(ADDR.into()).expect("listen");
+    let client = Socket::req0().expect("create client");
+    client.connect(ADDR.into()).expect("connect");
+    let ctx = Context::new(&server).expect("create context");
+
+    let reading = Reading {
+        sensor: 7,
+        value: -20,
+        label: "outside".to_owned(),
+    };
+    client.send_postcard(&reading).expect("send");
+    assert_eq!(ctx.recv_postcard::<Reading>().expect("receive"), reading);
+
+    ctx.send_postcard(&true).expect("reply");
+    assert!(client.recv_postcard::<bool>().expect("receive reply"));
+}
+
+#[test]
+fn should_reject_mismatched_postcard_body() {
+    let msg = postcard::encode(&Reading {
+        sensor: 1,
+        value: 2,
+        label: "a".to_owned(),
+    }).expect("encode");
+    assert_eq!(msg.body(), [1, 4, 1, b'a']);
+
+    let error = postcard::decode::<u16>(&msg).expect_err("trailing bytes");
+    assert!(error.is_codec());
+    assert!(matches!(error, PostcardError::Decode(_)));
+    let error = postcard::decode::<(Reading, u8)>(&msg).expect_err("truncated");
+    assert!(matches!(error, PostcardError::Decode(_)));
+}
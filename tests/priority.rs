@@ -0,0 +1,46 @@
+use nng_c::{options, Message, Socket};
+use nng_c::priority::{Priority, Prioritized};
+
+use core::time;
+
+fn message(byte: u8) -> Message {
+    let mut msg = Message::new().expect("create message");
+    msg.append(&[byte]).expect("append");
+    msg
+}
+
+#[test]
+fn should_send_higher_priority_first() {
+    const ADDR: &str = "inproc://should_send_higher_priority_first\0";
+
+    let client = Socket::pair0().expect("create client");
+    let queue = Prioritized::new(&client).expect("create queue");
+
+    //Nobody is connected, so every message stays queued
+    assert_eq!(queue.send(message(1), Priority::Low), Ok(0));
+    assert_eq!(queue.send(message(2), Priority::Low), Ok(0));
+    assert_eq!(queue.send(message(3), Priority::Normal), Ok(0));
+    queue.enqueue(message(4), Priority::High);
+    assert_eq!(queue.len(), 4);
+    assert_eq!(queue.queued(Priority::Low), 2);
+    assert_eq!(queue.queued(Priority::Normal), 1);
+    assert_eq!(queue.queued(Priority::High), 1);
+
+    let server = Socket::pair0().expect("create server");
+    server.set_opt(options::RecvBuf(8)).expect("set recv buffer");
+    server.set_opt(options::RecvTimeout(time::Duration::from_secs(5))).expect("set recv timeout");
+    server.listen(ADDR.into()).expect("listen");
+    client.connect(ADDR.into()).expect("connect");
+
+    assert_eq!(queue.send_next(), Ok(Some(Priority::High)));
+    assert_eq!(queue.send_next(), Ok(Some(Priority::Normal)));
+    assert_eq!(queue.send_next(), Ok(Some(Priority::Low)));
+    assert_eq!(queue.send_next(), Ok(Some(Priority::Low)));
+    assert_eq!(queue.send_next(), Ok(None));
+    assert!(queue.is_empty());
+
+    for expected in [4, 3, 1, 2].iter() {
+        let msg = server.recv_msg().expect("receive");
+        assert_eq!(msg.body(), [*expected]);
+    }
+}
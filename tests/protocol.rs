@@ -288,3 +288,167 @@ fn should_do_req_resp_async_ipc() {
     let result = server.recv(&mut buffer).expect("to receive data");
     assert_eq!(result, BYTES);
 }
+
+#[test]
+fn should_abort_futures_with_error() {
+    const SHUTDOWN: i32 = 0x1000;
+
+    let client = Socket::req0().expect("Create client");
+    let server = Socket::rep0().expect("Create server");
+
+    let resp = server.recv_msg_async().expect("create future");
+    resp.abort(nng_c::nng_error(SHUTDOWN));
+    let error = rt::run(resp).expect_err("should be aborted");
+    assert_eq!(error.raw_code(), SHUTDOWN);
+
+    let mut req = Message::new().expect("Create message");
+    req.append(b"request").expect("append");
+    let req = client.send_msg_async(req).expect("Send message");
+    req.abort(nng_c::nng_error(SHUTDOWN));
+    let (msg, error) = rt::run(req).expect_err("should be aborted");
+    assert_eq!(error.raw_code(), SHUTDOWN);
+    assert_eq!(msg.body(), b"request");
+}
+
+#[test]
+fn should_report_in_flight_futures() {
+    let server = Socket::rep0().expect("Create server");
+
+    let resp = server.recv_msg_async().expect("create future");
+    std::thread::sleep(time::Duration::from_millis(20));
+    assert!(resp.is_in_flight());
+    assert!(!resp.is_finished());
+    assert!(resp.elapsed() >= time::Duration::from_millis(10));
+
+    resp.cancel();
+    for _ in 0..100 {
+        //Completion callback may still be running
+        if resp.is_finished() && !resp.is_in_flight() {
+            break;
+        }
+        std::thread::sleep(time::Duration::from_millis(1));
+    }
+    assert!(resp.is_finished());
+    assert!(!resp.is_in_flight());
+    rt::run(resp).expect_err("should be cancelled");
+}
+
+#[test]
+fn should_detach_socket_without_closing() {
+    const ADDR: &str = "inproc://should_detach_socket_without_closing\0";
+
+    let socket = Socket::pair0().expect("Create socket");
+    let raw = socket.into_raw();
+
+    let socket = unsafe {
+        Socket::from_raw(raw)
+    };
+    socket.listen(ADDR.into()).expect("Listen on detached socket");
+    let raw = socket.into_raw();
+
+    let client = Socket::pair0().expect("Create client").leak();
+    client.connect(ADDR.into()).expect("Connect");
+    client.send(b"ping".into()).expect("Send");
+
+    let socket = unsafe {
+        Socket::from_raw(raw)
+    };
+    let msg = socket.recv_msg().expect("Receive");
+    assert_eq!(msg.body(), b"ping");
+
+    assert!(socket.close());
+    assert!(client.close());
+    assert!(!client.close());
+}
+
+#[test]
+fn should_connect_once_peer_starts_listening() {
+    let port = std::net::TcpListener::bind(("127.0.0.1", 0)).expect("bind").local_addr().expect("addr").port();
+    let addr = format!("tcp://127.0.0.1:{}", port);
+
+    let client = Socket::pair0().expect("create client");
+    let error = client.connect_until(addr.as_str().into(), time::Duration::from_millis(30)).expect_err("nobody listens");
+    assert!(error.is_conn_refused(), "unexpected error: {}", error);
+
+    let listen_addr = addr.clone();
+    let server = std::thread::spawn(move || {
+        std::thread::sleep(time::Duration::from_millis(50));
+        let server = Socket::pair0().expect("create server");
+        server.listen(listen_addr.as_str().into()).expect("listen");
+        server
+    });
+
+    client.connect_until(addr.as_str().into(), time::Duration::from_secs(5)).expect("connect once server listens");
+    let _server = server.join().expect("start server");
+}
+
+#[test]
+fn should_drain_and_iterate_with_timeout() {
+    const ADDR: &str = "inproc://should_drain_and_iterate_with_timeout\0";
+
+    let server = Socket::pair0().expect("create server");
+    server.set_opt(options::RecvBuf(8)).expect("set recv buffer");
+    server.listen(ADDR.into()).expect("listen");
+    let client = Socket::pair0().expect("create client");
+    client.connect(ADDR.into()).expect("connect");
+
+    assert_eq!(server.drain().count(), 0);
+
+    for idx in 0u8..3 {
+        let mut msg = Message::new().expect("create message");
+        msg.append(&[idx]).expect("append");
+        client.send_msg(msg).expect("send");
+    }
+
+    let received = server.iter_timeout(time::Duration::from_millis(200)).map(|msg| msg.expect("receive").body()[0]).collect::<Vec<_>>();
+    assert_eq!(received, [0, 1, 2]);
+
+    for idx in 3u8..5 {
+        let mut msg = Message::new().expect("create message");
+        msg.append(&[idx]).expect("append");
+        client.send_msg(msg).expect("send");
+    }
+
+    let msg = server.iter_timeout(time::Duration::from_secs(5)).next().expect("have message").expect("receive");
+    assert_eq!(msg.body(), [3]);
+    std::thread::sleep(time::Duration::from_millis(50));
+    let received = server.drain().map(|msg| msg.expect("receive").body()[0]).collect::<Vec<_>>();
+    assert_eq!(received, [4]);
+
+    let mut messages = server.iter_timeout(time::Duration::from_secs(u64::MAX));
+    let error = messages.next().expect("have error").expect_err("reject too long timeout");
+    assert_eq!(error.raw_code(), nng_c::sys::nng_errno_enum::NNG_EINVAL);
+    assert!(messages.next().is_none());
+}
+
+#[test]
+fn should_serve_requests_until_closed() {
+    const ADDR: &str = "inproc://should_serve_requests_until_closed\0";
+
+    let server = Socket::rep0().expect("create server");
+    server.listen(ADDR.into()).expect("listen");
+    let client = Socket::req0().expect("create client");
+    client.connect(ADDR.into()).expect("connect");
+
+    let handler = |mut req: Message| {
+        let value = req.pop_front_u32().expect("get value");
+        req.append_u32(value * 2).expect("append");
+        req
+    };
+
+    std::thread::scope(|scope| {
+        let blocking = scope.spawn(|| server.serve(handler));
+        let concurrent = scope.spawn(|| rt::run(server.serve_async(handler)));
+
+        for value in 1u32..5 {
+            let mut req = Message::new().expect("create message");
+            req.append_u32(value).expect("append");
+            let mut resp = rt::run(client.request(req)).expect("get reply");
+            assert_eq!(resp.pop_front_u32(), Some(value * 2));
+        }
+
+        server.close();
+        blocking.join().expect("finish blocking loop").expect("serve until closed");
+        concurrent.join().expect("finish async loop").expect("serve until closed");
+    });
+}
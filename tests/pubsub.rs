@@ -0,0 +1,355 @@
+use nng_c::{options, NngError, Socket};
+use nng_c::pubsub::{self, LastValueCache, Pattern, Subscriber, Topics};
+
+use core::time;
+
+#[test]
+fn should_split_topic_and_payload() {
+    let topics = Topics::new().topic(b"weather").topic(b"weather.eu").topic(b"news");
+    assert_eq!(topics.split(b"weather.eu:rain"), Some((&b"weather.eu"[..], &b":rain"[..])));
+    assert_eq!(topics.split(b"weather.us:sun"), Some((&b"weather"[..], &b".us:sun"[..])));
+    assert_eq!(topics.split(b"news"), Some((&b"news"[..], &b""[..])));
+    assert_eq!(topics.split(b"sports"), None);
+
+    let mut topics = topics;
+    assert!(!topics.add(b"news"));
+    assert!(topics.remove(b"weather.eu"));
+    assert!(!topics.remove(b"weather.eu"));
+    assert_eq!(topics.split(b"weather.eu:rain"), Some((&b"weather"[..], &b".eu:rain"[..])));
+}
+
+#[test]
+fn should_receive_subscribed_topics() {
+    const ADDR: &str = "inproc://should_receive_subscribed_topics\0";
+
+    let topics = Topics::new().topic(b"a.").topic(b"b.");
+    let subscriber = Socket::sub0().expect("create subscriber");
+    subscriber.set_opt(topics.clone()).expect("subscribe");
+    subscriber.set_opt(options::RecvTimeout(time::Duration::from_millis(100))).expect("set timeout");
+    subscriber.listen(ADDR.into()).expect("listen");
+    let publisher = Socket::pub0().expect("create publisher");
+    publisher.connect(ADDR.into()).expect("connect");
+    std::thread::sleep(time::Duration::from_millis(10));
+
+    for (topic, payload) in [(&b"c."[..], &b"ignored"[..]), (b"a.", b"first"), (b"b.", b"second")].iter() {
+        let msg = pubsub::message(topic, payload).expect("create message");
+        publisher.send_msg(msg).expect("publish");
+    }
+
+    let msg = subscriber.recv_msg().expect("receive");
+    assert_eq!(topics.split(msg.body()), Some((&b"a."[..], &b"first"[..])));
+    let msg = subscriber.recv_msg().expect("receive");
+    assert_eq!(topics.split(msg.body()), Some((&b"b."[..], &b"second"[..])));
+    subscriber.recv_msg().expect_err("no more messages");
+}
+
+#[test]
+fn should_keep_subscriptions_after_reconnect() {
+    const ADDR: &str = "inproc://should_keep_subscriptions_after_reconnect\0";
+
+    let socket = Socket::sub0().expect("create subscriber");
+    socket.set_opt(options::RecvTimeout(time::Duration::from_millis(10))).expect("set timeout");
+    socket.set_opt(options::Reconnect {
+        min_time: Some(time::Duration::from_millis(10)),
+        max_time: Some(time::Duration::from_millis(10)),
+    }).expect("set reconnect");
+    let subscriber = Subscriber::new(&socket).expect("create subscriber");
+
+    assert!(subscriber.subscribe(b"a.").expect("subscribe"));
+    assert!(subscriber.subscribe(b"b.").expect("subscribe"));
+    assert!(!subscriber.subscribe(b"a.").expect("subscribe"));
+    assert!(subscriber.unsubscribe(b"b.").expect("unsubscribe"));
+    assert!(!subscriber.unsubscribe(b"b.").expect("unsubscribe"));
+    assert_eq!(subscriber.topics().iter().collect::<Vec<_>>(), [&b"a."[..]]);
+
+    let receive = |publisher: &Socket| {
+        let start = std::time::Instant::now();
+        loop {
+            assert!(start.elapsed() < time::Duration::from_secs(5), "subscription is lost");
+            for topic in [&b"b."[..], &b"a."[..]].iter() {
+                let msg = pubsub::message(topic, b"payload").expect("create message");
+                publisher.send_msg(msg).expect("publish");
+            }
+            if let Ok(msg) = socket.recv_msg() {
+                break msg;
+            }
+        }
+    };
+
+    let publisher = Socket::pub0().expect("create publisher");
+    publisher.listen(ADDR.into()).expect("listen");
+    socket.connect_with(ADDR.into(), nng_c::socket::ConnectOptions::new().with_async()).expect("connect");
+    let msg = receive(&publisher);
+    assert_eq!(msg.body(), b"a.payload");
+
+    //Broker restart
+    drop(publisher);
+    let publisher = Socket::pub0().expect("create publisher");
+    publisher.listen(ADDR.into()).expect("listen");
+    let msg = receive(&publisher);
+    assert_eq!(msg.body(), b"a.payload");
+
+    subscriber.resubscribe().expect("resubscribe");
+    assert_eq!(subscriber.topics().iter().count(), 1);
+}
+
+#[test]
+fn should_match_topic_patterns() {
+    let pattern = Pattern::new(b"weather.*.temp");
+    assert_eq!(pattern.prefix(), b"weather.");
+    assert!(pattern.is_wildcard());
+    assert_eq!(pattern.split(b"weather.eu.temp:25"), Some((&b"weather.eu.temp"[..], &b":25"[..])));
+    assert!(pattern.matches(b"weather.us.temperature"));
+    assert!(!pattern.matches(b"weather..temp"));
+    assert!(!pattern.matches(b"weather.eu.wind"));
+    assert!(!pattern.matches(b"weather.eu.west.temp"));
+    assert!(!pattern.matches(b"news.eu.temp"));
+
+    let pattern = Pattern::new(b"news.#");
+    assert_eq!(pattern.prefix(), b"news.");
+    assert_eq!(pattern.split(b"news.eu.sports"), Some((&b"news.eu.sports"[..], &b""[..])));
+    assert!(!pattern.matches(b"weather.eu"));
+
+    let pattern = Pattern::new(b"*/alerts").with_separator(b'/');
+    assert_eq!(pattern.prefix(), b"");
+    assert!(pattern.matches(b"eu/alerts"));
+    assert!(!pattern.matches(b"eu.alerts"));
+
+    let pattern = Pattern::new(b"a.b");
+    assert!(!pattern.is_wildcard());
+    assert_eq!(pattern.prefix(), b"a.b");
+    assert!(pattern.matches(b"a.bc"));
+    assert!(!Pattern::new(b"#.a").matches(b"x.a"));
+}
+
+#[test]
+fn should_receive_messages_matching_pattern() {
+    const ADDR: &str = "inproc://should_receive_messages_matching_pattern\0";
+
+    let socket = Socket::sub0().expect("create subscriber");
+    socket.set_opt(options::RecvTimeout(time::Duration::from_millis(100))).expect("set timeout");
+    socket.listen(ADDR.into()).expect("listen");
+    let subscriber = Subscriber::new(&socket).expect("create subscriber");
+    let pattern = Pattern::new(b"weather.*.temp");
+    assert!(subscriber.subscribe_pattern(pattern.clone()).expect("subscribe"));
+    assert!(!subscriber.subscribe_pattern(pattern.clone()).expect("subscribe"));
+    assert!(subscriber.subscribe(b"weather.").expect("subscribe"));
+    //Prefix is still used by pattern
+    assert!(subscriber.unsubscribe(b"weather.").expect("unsubscribe"));
+    assert_eq!(subscriber.patterns(), core::slice::from_ref(&pattern));
+
+    let publisher = Socket::pub0().expect("create publisher");
+    publisher.connect(ADDR.into()).expect("connect");
+    std::thread::sleep(time::Duration::from_millis(10));
+
+    for body in [&b"weather.eu.wind:5"[..], b"news.eu.temp:1", b"weather.eu.temp:25"].iter() {
+        let msg = pubsub::message(body, b"").expect("create message");
+        publisher.send_msg(msg).expect("publish");
+    }
+
+    let msg = subscriber.recv_msg().expect("receive");
+    assert_eq!(msg.body(), b"weather.eu.temp:25");
+    subscriber.recv_msg().expect_err("no more messages");
+
+    assert!(subscriber.unsubscribe_pattern(&pattern).expect("unsubscribe"));
+    assert!(!subscriber.unsubscribe_pattern(&pattern).expect("unsubscribe"));
+    let msg = pubsub::message(b"weather.eu.temp:25", b"").expect("create message");
+    publisher.send_msg(msg).expect("publish");
+    socket.recv_msg().expect_err("unsubscribed");
+}
+
+#[test]
+fn should_serve_last_value_snapshot_to_late_subscriber() {
+    const PUBLISHERS: &str = "inproc://should_serve_last_value_snapshot_to_late_subscriber_pub\0";
+    const SUBSCRIBERS: &str = "inproc://should_serve_last_value_snapshot_to_late_subscriber_sub\0";
+    const SNAPSHOTS: &str = "inproc://should_serve_last_value_snapshot_to_late_subscriber_snapshot\0";
+
+    let sockets = std::sync::Arc::new((Socket::sub0_raw().expect("create backend"), Socket::pub0_raw().expect("create frontend"), Socket::rep0().expect("create snapshots")));
+    let (backend, frontend, snapshots) = &*sockets;
+    backend.listen(PUBLISHERS.into()).expect("listen");
+    frontend.listen(SUBSCRIBERS.into()).expect("listen");
+    snapshots.listen(SNAPSHOTS.into()).expect("listen");
+
+    let cache = LastValueCache::new(Topics::new().topic(b"a.").topic(b"b.")).expect("create cache");
+    let relay = {
+        let sockets = sockets.clone();
+        let cache = cache.clone();
+        std::thread::spawn(move || cache.run(&sockets.0, &sockets.1))
+    };
+    let server = {
+        let sockets = sockets.clone();
+        let cache = cache.clone();
+        std::thread::spawn(move || cache.serve_snapshots(&sockets.2))
+    };
+
+    let publisher = Socket::pub0().expect("create publisher");
+    publisher.connect(PUBLISHERS.into()).expect("connect");
+    let start = std::time::Instant::now();
+    //Messages may be lost until publisher's pipe is attached
+    while cache.get(b"a.").as_deref() != Some(&b"a.2"[..]) || cache.get(b"b.").is_none() {
+        assert!(start.elapsed() < time::Duration::from_secs(5), "messages are not cached");
+        for (topic, payload) in [(&b"a."[..], &b"1"[..]), (b"b.", b"1"), (b"c.", b"1"), (b"a.", b"2")].iter() {
+            let msg = pubsub::message(topic, payload).expect("create message");
+            publisher.send_msg(msg).expect("publish");
+        }
+        std::thread::sleep(time::Duration::from_millis(10));
+    }
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.get(b"b.").as_deref(), Some(&b"b.1"[..]));
+    assert_eq!(cache.get(b"c."), None);
+
+    //Early subscriber receives no replay of state requested by late one
+    let early = Socket::sub0().expect("create subscriber");
+    early.set_opt(Topics::new().topic(b"a.").topic(b"b.")).expect("subscribe");
+    early.set_opt(options::RecvTimeout(time::Duration::from_millis(100))).expect("set timeout");
+    early.connect(SUBSCRIBERS.into()).expect("connect");
+    while early.recv_msg().is_ok() {}
+
+    //Late subscriber requests current state after connecting
+    let subscriber = Socket::sub0().expect("create subscriber");
+    subscriber.set_opt(Topics::new().topic(b"a.").topic(b"b.")).expect("subscribe");
+    subscriber.set_opt(options::RecvTimeout(time::Duration::from_millis(100))).expect("set timeout");
+    subscriber.connect(SUBSCRIBERS.into()).expect("connect");
+    let client = Socket::req0().expect("create client");
+    client.connect(SNAPSHOTS.into()).expect("connect");
+
+    let mut state: Vec<_> = LastValueCache::request_snapshot(&client, b"").expect("request snapshot").iter().map(|msg| msg.body().to_vec()).collect();
+    state.sort();
+    assert_eq!(state, [b"a.2".to_vec(), b"b.1".to_vec()]);
+    let state = LastValueCache::request_snapshot(&client, b"b.").expect("request snapshot");
+    assert_eq!(state.len(), 1);
+    assert_eq!(state[0].body(), b"b.1");
+    early.recv_msg().expect_err("no replay");
+    //Cached messages are never replayed over frontend
+    subscriber.recv_msg().expect_err("no replay");
+
+    backend.close();
+    snapshots.close();
+    assert!(relay.join().expect("join relay").is_closed());
+    assert!(server.join().expect("join server").is_closed());
+}
+
+#[test]
+fn should_unsubscribe_when_guard_is_dropped() {
+    const ADDR: &str = "inproc://should_unsubscribe_when_guard_is_dropped\0";
+
+    let subscriber = Socket::sub0().expect("create subscriber");
+    subscriber.set_opt(options::RecvTimeout(time::Duration::from_millis(100))).expect("set timeout");
+    subscriber.listen(ADDR.into()).expect("listen");
+    let publisher = Socket::pub0().expect("create publisher");
+    publisher.connect(ADDR.into()).expect("connect");
+    std::thread::sleep(time::Duration::from_millis(10));
+
+    let publish = |topic: &[u8]| {
+        let msg = pubsub::message(topic, b"payload").expect("create message");
+        publisher.send_msg(msg).expect("publish");
+    };
+
+    let first = subscriber.subscribe(b"a.").expect("subscribe");
+    assert_eq!(first.topic(), b"a.");
+    let second = subscriber.subscribe(b"b.").expect("subscribe");
+
+    publish(b"a.");
+    publish(b"b.");
+    assert_eq!(subscriber.recv_msg().expect("receive").body(), b"a.payload");
+    assert_eq!(subscriber.recv_msg().expect("receive").body(), b"b.payload");
+
+    drop(first);
+    second.unsubscribe().expect("unsubscribe");
+    subscriber.subscribe(b"c.").expect("subscribe").forget();
+
+    publish(b"a.");
+    publish(b"b.");
+    publish(b"c.");
+    assert_eq!(subscriber.recv_msg().expect("receive").body(), b"c.payload");
+    let error = subscriber.recv_msg().expect_err("no more messages");
+    assert!(error.is_timed_out());
+}
+
+#[test]
+fn should_subscribe_to_topics_in_batch() {
+    const ADDR: &str = "inproc://should_subscribe_to_topics_in_batch\0";
+
+    let subscriber = Socket::sub0().expect("create subscriber");
+    subscriber.set_opt(options::RecvTimeout(time::Duration::from_millis(100))).expect("set timeout");
+    subscriber.listen(ADDR.into()).expect("listen");
+    let publisher = Socket::pub0().expect("create publisher");
+    publisher.connect(ADDR.into()).expect("connect");
+    std::thread::sleep(time::Duration::from_millis(10));
+
+    let publish = |topics: &[&[u8]]| for topic in topics {
+        let msg = pubsub::message(topic, b"").expect("create message");
+        publisher.send_msg(msg).expect("publish");
+    };
+
+    pubsub::subscribe_all(&subscriber, &[b"a", b"b", b"c"]).expect("subscribe");
+
+    let error = pubsub::unsubscribe_all(&subscriber, &[b"a", b"d", b"b"]).expect_err("unknown topic");
+    assert_eq!(error.index, 1);
+    assert_eq!(error.error.raw_code(), nng_c::sys::nng_errno_enum::NNG_ENOENT);
+
+    pubsub::unsubscribe_all(&subscriber, &[b"a", b"c"]).expect("unsubscribe");
+
+    publish(&[b"a", b"b", b"c", b"d"]);
+    assert_eq!(subscriber.recv_msg().expect("receive").body(), b"b");
+    let error = subscriber.recv_msg().expect_err("no more messages");
+    assert!(error.is_timed_out());
+}
+
+#[test]
+fn should_choose_message_to_drop_when_buffer_is_full() {
+    use nng_c::options::{RecvBuf, SubPreferNew};
+
+    const ADDR: &str = "inproc://should_choose_message_to_drop_when_buffer_is_full\0";
+
+    let subscriber = Socket::sub0().expect("create subscriber");
+    assert_eq!(subscriber.get_prop::<SubPreferNew>().expect("get option"), SubPreferNew(true));
+    subscriber.set_opt((RecvBuf(1), options::Subscribe(b""))).expect("set options");
+    subscriber.listen(ADDR.into()).expect("listen");
+    let publisher = Socket::pub0().expect("create publisher");
+    publisher.connect(ADDR.into()).expect("connect");
+    std::thread::sleep(time::Duration::from_millis(10));
+
+    let publish = |payloads: &[&[u8]]| {
+        for payload in payloads {
+            let msg = pubsub::message(b"", payload).expect("create message");
+            publisher.send_msg(msg).expect("publish");
+        }
+        std::thread::sleep(time::Duration::from_millis(50));
+    };
+
+    publish(&[b"1", b"2", b"3"]);
+    assert_eq!(subscriber.recv_msg().expect("receive").body(), b"3");
+
+    subscriber.set_opt(SubPreferNew(false)).expect("set option");
+    assert_eq!(subscriber.get_prop::<SubPreferNew>().expect("get option"), SubPreferNew(false));
+    publish(&[b"4", b"5", b"6"]);
+    assert_eq!(subscriber.recv_msg().expect("receive").body(), b"4");
+}
+
+#[test]
+fn should_publish_framed_topics() {
+    const ADDR: &str = "inproc://should_publish_framed_topics\0";
+
+    assert_eq!(pubsub::split_framed(b"weather\0rain"), Some((&b"weather"[..], &b"rain"[..])));
+    assert_eq!(pubsub::split_framed(b"weather"), None);
+    assert_eq!(pubsub::framed_prefix(b"weather").expect("create prefix"), b"weather\0");
+    let error = pubsub::framed(b"wea\0ther", b"rain").expect_err("delimiter in topic");
+    assert_eq!(error.raw_code(), nng_c::sys::nng_errno_enum::NNG_EINVAL);
+
+    let subscriber = Socket::sub0().expect("create subscriber");
+    subscriber.set_opt(options::RecvTimeout(time::Duration::from_millis(100))).expect("set timeout");
+    subscriber.listen(ADDR.into()).expect("listen");
+    let publisher = Socket::pub0().expect("create publisher");
+    publisher.connect(ADDR.into()).expect("connect");
+    std::thread::sleep(time::Duration::from_millis(10));
+
+    let _subscription = subscriber.subscribe_topic(b"weather").expect("subscribe");
+    publisher.publish(b"weather.eu", b"sun").expect("publish");
+    publisher.publish(b"weather", b"rain").expect("publish");
+
+    let msg = subscriber.recv_msg().expect("receive");
+    assert_eq!(msg.split_topic(), Some((&b"weather"[..], &b"rain"[..])));
+    let error = subscriber.recv_msg().expect_err("no more messages");
+    assert!(error.is_timed_out());
+}
@@ -0,0 +1,64 @@
+use nng_c::{options, Message, Socket};
+use nng_c::rate::{RateLimit, RateLimited, TokenBucket};
+
+use core::time;
+use std::time::Instant;
+
+mod rt;
+
+#[test]
+fn should_refill_token_bucket() {
+    let bucket = TokenBucket::new(RateLimit::new(100).burst(3));
+    for _ in 0..3 {
+        bucket.try_acquire().expect("take token from burst");
+    }
+    let wait = bucket.try_acquire().expect_err("run out of tokens");
+    assert!(wait <= time::Duration::from_millis(10), "unexpected wait: {:?}", wait);
+
+    std::thread::sleep(time::Duration::from_millis(25));
+    bucket.try_acquire().expect("refilled token");
+}
+
+#[test]
+fn should_limit_send_rate() {
+    const ADDR: &str = "inproc://should_limit_send_rate\0";
+
+    let server = Socket::pair0().expect("create server");
+    server.set_opt(options::RecvBuf(16)).expect("set recv buffer");
+    server.listen(ADDR.into()).expect("listen");
+    let client = Socket::pair0().expect("create client");
+    client.set_opt(options::SendBuf(16)).expect("set send buffer");
+    client.connect(ADDR.into()).expect("connect");
+
+    let limited = RateLimited::new(&client, RateLimit::new(100).burst(2));
+    limited.try_send(b"1".into()).expect("send within burst");
+    limited.try_send(b"2".into()).expect("send within burst");
+    let error = limited.try_send(b"3".into()).expect_err("exceed burst");
+    assert!(error.is_would_block(), "unexpected error: {}", error);
+
+    let msg = Message::new().expect("create message");
+    let (_, error) = limited.try_send_msg(msg).expect_err("exceed burst");
+    assert!(error.is_would_block(), "unexpected error: {}", error);
+
+    let start = Instant::now();
+    for _ in 0..5 {
+        limited.send(b"blocking".into()).expect("send");
+    }
+    assert!(start.elapsed() >= time::Duration::from_millis(40), "too fast: {:?}", start.elapsed());
+
+    let start = Instant::now();
+    for _ in 0..5 {
+        let mut msg = Message::new().expect("create message");
+        msg.append(b"async").expect("append");
+        rt::run(limited.send_msg_async(msg)).expect("send");
+    }
+    assert!(start.elapsed() >= time::Duration::from_millis(40), "too fast: {:?}", start.elapsed());
+
+    let mut expected = vec![&b"1"[..], b"2"];
+    expected.extend([&b"blocking"[..]; 5].iter());
+    expected.extend([&b"async"[..]; 5].iter());
+    for expected in expected {
+        let msg = server.recv_msg().expect("receive");
+        assert_eq!(msg.body(), expected);
+    }
+}
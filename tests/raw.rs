@@ -0,0 +1,118 @@
+use nng_c::{raw, Socket, Message};
+
+#[test]
+fn should_manipulate_backtrace() {
+    let mut msg = Message::new().expect("Create message");
+    assert!(raw::request_id(&msg).is_none());
+    assert!(raw::pop_hop(&mut msg).is_none());
+
+    raw::set_request_id(&mut msg, 5).expect("set request id");
+    raw::push_hop(&mut msg, 1).expect("push hop");
+    raw::push_hop(&mut msg, 2).expect("push hop");
+    assert_eq!(msg.header(), [0, 0, 0, 2, 0, 0, 0, 1, 0x80, 0, 0, 5]);
+    assert_eq!(raw::backtrace(&msg).len(), 3);
+    assert_eq!(raw::request_id(&msg), Some(5));
+    assert_eq!(raw::hops(&msg).collect::<Vec<_>>(), [2, 1]);
+
+    assert_eq!(raw::pop_hop(&mut msg), Some(2));
+    assert_eq!(raw::pop_hop(&mut msg), Some(1));
+    assert_eq!(raw::pop_hop(&mut msg), None);
+    assert_eq!(raw::request_id(&msg), Some(5));
+
+    raw::set_request_id(&mut msg, 6).expect("set request id");
+    assert_eq!(msg.header(), [0x80, 0, 0, 6]);
+}
+
+#[test]
+fn should_route_reply_via_raw_rep() {
+    const ADDR: &str = "inproc://should_route_reply_via_raw_rep\0";
+    const BYTES: &[u8] = &[1, 10, 20, 50, 100];
+
+    let client = Socket::req0().expect("Create client");
+    let server = Socket::rep0_raw().expect("Create server");
+
+    server.listen(ADDR.into()).expect("listen");
+    client.connect(ADDR.into()).expect("connect");
+
+    let mut req = Message::new().expect("Create message");
+    req.append(BYTES).expect("append bytes");
+    client.send_msg(req).expect("Send message");
+
+    let req = server.recv_msg().expect("Get request");
+    assert_eq!(req.body(), BYTES);
+    assert_eq!(raw::hops(&req).count(), 1);
+    assert!(raw::request_id(&req).is_some());
+
+    let mut reply = Message::new().expect("Create message");
+    reply.header_append(req.header()).expect("copy header");
+    reply.append(b"reply").expect("append bytes");
+    server.send_msg(reply).expect("Send reply");
+
+    let reply = client.recv_msg().expect("Get reply");
+    assert_eq!(reply.body(), b"reply");
+}
+
+#[test]
+fn should_send_request_via_raw_req() {
+    const ADDR: &str = "inproc://should_send_request_via_raw_req\0";
+
+    let client = Socket::req0_raw().expect("Create client");
+    let server = Socket::rep0().expect("Create server");
+
+    server.listen(ADDR.into()).expect("listen");
+    client.connect(ADDR.into()).expect("connect");
+
+    let mut req = Message::new().expect("Create message");
+    raw::set_request_id(&mut req, 42).expect("set request id");
+    req.append(b"request").expect("append bytes");
+    client.send_msg(req).expect("Send message");
+
+    let req = server.recv_msg().expect("Get request");
+    assert_eq!(req.body(), b"request");
+    let mut reply = Message::new().expect("Create message");
+    reply.append(b"reply").expect("append bytes");
+    server.send_msg(reply).expect("Send reply");
+
+    let reply = client.recv_msg().expect("Get reply");
+    assert_eq!(raw::request_id(&reply), Some(42));
+    assert_eq!(reply.body(), b"reply");
+}
+
+#[test]
+fn should_reply_via_captured_return_address() {
+    const ADDR: &str = "inproc://should_reply_via_captured_return_address\0";
+
+    let server = Socket::rep0_raw().expect("Create server");
+    server.listen(ADDR.into()).expect("listen");
+    let clients = [Socket::req0().expect("Create client"), Socket::req0().expect("Create client")];
+    for (idx, client) in clients.iter().enumerate() {
+        client.connect(ADDR.into()).expect("connect");
+        let mut req = Message::new().expect("Create message");
+        req.append(&[idx as u8]).expect("append bytes");
+        client.send_msg(req).expect("Send message");
+    }
+
+    let mut pending = Vec::new();
+    for _ in 0..clients.len() {
+        let req = server.recv_msg().expect("Get request");
+        let reply_to = raw::ReplyTo::new(&req).expect("have backtrace");
+        assert_eq!(Some(reply_to.request_id()), raw::request_id(&req));
+        assert_eq!(reply_to.pipe(), req.pipe());
+        pending.push((req.body()[0], reply_to));
+    }
+
+    //Answer in reverse order
+    for (idx, reply_to) in pending.into_iter().rev() {
+        let mut reply = reply_to.reply().expect("create reply");
+        reply.append(&[idx, idx]).expect("append bytes");
+        server.send_msg(reply).expect("Send reply");
+    }
+
+    for (idx, client) in clients.iter().enumerate() {
+        let reply = client.recv_msg().expect("Get reply");
+        assert_eq!(reply.body(), [idx as u8, idx as u8]);
+    }
+
+    let msg = Message::new().expect("Create message");
+    assert!(raw::ReplyTo::new(&msg).is_none());
+}
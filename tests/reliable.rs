@@ -0,0 +1,104 @@
+use nng_c::{options, Message, Socket};
+use nng_c::reliable::Channel;
+
+use core::time;
+
+#[test]
+fn should_retransmit_until_acknowledged() {
+    const ADDR: &str = "inproc://should_retransmit_until_acknowledged\0";
+    const RETRANSMIT: time::Duration = time::Duration::from_millis(20);
+
+    let server = Socket::pair0().expect("create server");
+    server.set_opt(options::RecvBuf(16)).expect("set recv buffer");
+    server.set_opt(options::SendBuf(16)).expect("set send buffer");
+    server.set_opt(options::RecvTimeout(time::Duration::from_secs(5))).expect("set recv timeout");
+    server.listen(ADDR.into()).expect("listen");
+    let client = Socket::pair0().expect("create client");
+    client.set_opt(options::RecvBuf(16)).expect("set recv buffer");
+    client.set_opt(options::SendBuf(16)).expect("set send buffer");
+    client.connect(ADDR.into()).expect("connect");
+
+    let mut sender = Channel::new(&client).with_retransmit_time(RETRANSMIT);
+    let mut msg = Message::new().expect("create message");
+    msg.append(b"first").expect("append");
+    assert_eq!(sender.send(msg).expect("send"), 0);
+    assert_eq!(sender.pending(), 1);
+
+    //Lose first delivery, so that it is never acknowledged
+    let lost = server.recv_msg().expect("receive first delivery");
+    assert_eq!(&lost.body()[8..], b"first");
+
+    std::thread::sleep(RETRANSMIT);
+    assert_eq!(sender.retransmit().expect("retransmit"), 1);
+
+    let mut msg = Message::new().expect("create message");
+    msg.append(b"second").expect("append");
+    assert_eq!(sender.send(msg).expect("send"), 1);
+
+    let mut receiver = Channel::new(&server);
+    let msg = receiver.recv().expect("receive first");
+    assert_eq!(msg.body(), b"first");
+    let msg = receiver.recv().expect("receive second");
+    assert_eq!(msg.body(), b"second");
+
+    sender.flush(time::Duration::from_secs(5)).expect("all acknowledged");
+    assert_eq!(sender.pending(), 0);
+    assert_eq!(sender.retransmit().expect("retransmit"), 0);
+}
+
+#[test]
+fn should_discard_duplicates() {
+    const ADDR: &str = "inproc://should_discard_duplicates\0";
+
+    let server = Socket::pair0().expect("create server");
+    server.set_opt(options::RecvBuf(16)).expect("set recv buffer");
+    server.set_opt(options::SendBuf(16)).expect("set send buffer");
+    server.set_opt(options::RecvTimeout(time::Duration::from_millis(100))).expect("set recv timeout");
+    server.listen(ADDR.into()).expect("listen");
+    let client = Socket::pair0().expect("create client");
+    client.set_opt(options::RecvBuf(16)).expect("set recv buffer");
+    client.set_opt(options::SendBuf(16)).expect("set send buffer");
+    client.connect(ADDR.into()).expect("connect");
+
+    //Retransmit everything immediately, before acknowledgment can arrive
+    let mut sender = Channel::new(&client).with_retransmit_time(time::Duration::from_millis(0));
+    let mut msg = Message::new().expect("create message");
+    msg.append(b"ping").expect("append");
+    sender.send(msg).expect("send");
+    assert_eq!(sender.retransmit().expect("retransmit"), 1);
+    assert_eq!(sender.retransmit().expect("retransmit"), 1);
+
+    let mut receiver = Channel::new(&server);
+    let msg = receiver.recv().expect("receive");
+    assert_eq!(msg.body(), b"ping");
+    let error = receiver.recv().expect_err("duplicates are discarded");
+    assert!(nng_c::NngError::is_timed_out(&error), "unexpected error: {}", error);
+
+    sender.flush(time::Duration::from_secs(5)).expect("acknowledged");
+    assert_eq!(sender.pending(), 0);
+}
+
+#[test]
+fn should_accept_sequence_of_restarted_peer() {
+    const ADDR: &str = "inproc://should_accept_sequence_of_restarted_peer\0";
+
+    let server = Socket::pair0().expect("create server");
+    server.set_opt(options::RecvTimeout(time::Duration::from_secs(5))).expect("set recv timeout");
+    server.listen(ADDR.into()).expect("listen");
+    let client = Socket::pair0().expect("create client");
+    client.set_opt(options::RecvTimeout(time::Duration::from_secs(5))).expect("set recv timeout");
+    client.connect(ADDR.into()).expect("connect");
+
+    let mut receiver = Channel::new(&server);
+    for payload in [&b"first"[..], b"second"] {
+        //New channel starts its sequence from zero
+        let mut sender = Channel::new(&client);
+        let mut msg = Message::new().expect("create message");
+        msg.append(payload).expect("append");
+        assert_eq!(sender.send(msg).expect("send"), 0);
+
+        let msg = receiver.recv().expect("receive");
+        assert_eq!(msg.body(), payload);
+        sender.flush(time::Duration::from_secs(5)).expect("acknowledged");
+    }
+}
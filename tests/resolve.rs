@@ -0,0 +1,55 @@
+use nng_c::{Message, Socket};
+use nng_c::balance::Balancer;
+use nng_c::resolve::{Resolver, StaticResolver};
+
+use core::time;
+
+#[test]
+fn should_resolve_static_endpoints() {
+    let mut resolver = StaticResolver::new().endpoint("orders", "tcp://10.0.0.1:5555")
+                                            .endpoint("users", "tcp://10.0.0.2:5555")
+                                            .endpoint("orders", "tcp://10.0.0.3:5555");
+    assert!(!resolver.add("orders", "tcp://10.0.0.1:5555"));
+    assert_eq!(resolver.resolve("orders").expect("resolve"), ["tcp://10.0.0.1:5555", "tcp://10.0.0.3:5555"]);
+    assert!(resolver.resolve("billing").expect("resolve").is_empty());
+
+    assert_eq!(resolver.remove("orders"), 2);
+    assert!(resolver.resolve("orders").expect("resolve").is_empty());
+
+    let resolver = |service: &str| Ok(vec![format!("inproc://{}", service)]);
+    assert_eq!(resolver.resolve("orders").expect("resolve"), ["inproc://orders"]);
+}
+
+#[test]
+fn should_connect_to_resolved_endpoints() {
+    let resolver = StaticResolver::new().endpoint("service", "inproc://should_connect_to_resolved_endpoints_1")
+                                        .endpoint("service", "inproc://should_connect_to_resolved_endpoints_2");
+
+    let servers = [Socket::pull0().expect("create server"), Socket::pull0().expect("create server")];
+    for (server, url) in servers.iter().zip(resolver.resolve("service").expect("resolve").iter()) {
+        server.listen(url.as_str().into()).expect("listen");
+    }
+
+    let client = Socket::push0().expect("create client");
+    client.connect_all(&resolver, "unknown").expect_err("no endpoints");
+    assert_eq!(client.connect_all(&resolver, "service").expect("connect"), 2);
+
+    //Both endpoints eventually receive messages
+    let mut received = [false; 2];
+    let start = std::time::Instant::now();
+    while received != [true, true] {
+        assert!(start.elapsed() < time::Duration::from_secs(5), "endpoint is not connected");
+        client.send_msg(Message::new().expect("create message")).expect("send");
+        std::thread::sleep(time::Duration::from_millis(1));
+        for (server, received) in servers.iter().zip(received.iter_mut()) {
+            while server.try_recv_msg().expect("receive").is_some() {
+                *received = true;
+            }
+        }
+    }
+
+    let mut balancer = Balancer::new();
+    assert_eq!(balancer.add_resolved(&resolver, "service").expect("add"), 2);
+    assert_eq!(balancer.add_resolved(&resolver, "unknown").expect("add"), 0);
+    assert_eq!(balancer.len(), 2);
+}
@@ -0,0 +1,83 @@
+use nng_c::{NngError, Socket};
+use nng_c::shutdown::Shutdown;
+
+use core::time;
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn should_run_hooks_in_reverse_order() {
+    let order = Arc::new(Mutex::new(Vec::new()));
+    let shutdown = Shutdown::new();
+    for idx in 0..3 {
+        let order = order.clone();
+        shutdown.on_shutdown(move || order.lock().expect("lock").push(idx));
+    }
+
+    assert!(!shutdown.is_triggered());
+    assert!(!shutdown.wait_timeout(time::Duration::from_millis(10)));
+    assert!(shutdown.clone().trigger());
+    assert!(!shutdown.trigger());
+    assert!(shutdown.is_triggered());
+    shutdown.wait();
+    assert_eq!(*order.lock().expect("lock"), [2, 1, 0]);
+
+    //Late hook runs right away
+    let late = order.clone();
+    shutdown.on_shutdown(move || late.lock().expect("lock").push(3));
+    assert_eq!(*order.lock().expect("lock"), [2, 1, 0, 3]);
+}
+
+#[test]
+fn should_close_sockets_on_signal() {
+    const ADDR: &str = "inproc://should_close_sockets_on_signal\0";
+
+    let shutdown = Shutdown::new();
+    shutdown.install().expect("install signal handler");
+    assert_eq!(shutdown.install().expect_err("second handler").raw_code(), nng_c::sys::nng_errno_enum::NNG_EBUSY);
+
+    let server = Arc::new(Socket::rep0().expect("create server"));
+    server.listen(ADDR.into()).expect("listen");
+    shutdown.socket(server.clone());
+    let worker = std::thread::spawn(move || server.recv_msg().expect_err("closed"));
+
+    let status = std::process::Command::new("kill").arg("-TERM").arg(std::process::id().to_string()).status().expect("run kill");
+    assert!(status.success());
+
+    assert!(shutdown.wait_timeout(time::Duration::from_secs(5)));
+    assert!(worker.join().expect("join worker").is_closed());
+}
+
+#[test]
+fn should_drain_sockets_before_closing() {
+    use nng_c::{options, Message};
+
+    const ADDR: &str = "inproc://should_drain_sockets_before_closing\0";
+
+    let shutdown = Shutdown::new();
+    shutdown.set_linger(time::Duration::from_secs(1));
+
+    let backend = Arc::new(Socket::pair0().expect("create backend"));
+    let frontend = Arc::new(Socket::pair0().expect("create frontend"));
+    frontend.listen(ADDR.into()).expect("listen");
+    shutdown.device(backend.clone(), frontend.clone());
+
+    let client = Socket::pair0().expect("create client");
+    client.set_opt(options::RecvTimeout(time::Duration::from_secs(5))).expect("set timeout");
+    client.connect(ADDR.into()).expect("connect");
+
+    let mut msg = Message::new().expect("create message");
+    msg.append(b"reply").expect("append");
+    frontend.send_msg(msg).expect("send");
+
+    let trigger = std::thread::spawn({
+        let shutdown = shutdown.clone();
+        move || shutdown.trigger()
+    });
+    //Sockets keep operating during linger period
+    assert_eq!(client.recv_msg().expect("receive").body(), b"reply");
+    assert!(!shutdown.wait_timeout(time::Duration::from_millis(10)));
+
+    assert!(trigger.join().expect("join trigger"));
+    assert!(!frontend.close());
+    assert!(!backend.close());
+}
@@ -0,0 +1,37 @@
+use nng_c::{Message, Socket};
+use nng_c::utils::{spin_on, spin_on_with};
+
+use core::time;
+
+#[test]
+fn should_spin_on_async_request() {
+    const ADDR: &str = "inproc://should_spin_on_async_request\0";
+
+    let server = Socket::rep0().expect("create server");
+    server.listen(ADDR.into()).expect("listen");
+    let client = Socket::req0().expect("create client");
+    client.connect(ADDR.into()).expect("connect");
+
+    let mut msg = Message::new().expect("create message");
+    msg.append(b"ping").expect("append");
+    spin_on(client.send_msg_async(msg).expect("start send")).expect("send");
+
+    let resp = server.recv_msg_async().expect("start recv");
+    let msg = spin_on_with(resp, || std::thread::sleep(time::Duration::from_micros(100))).expect("receive").expect("have message");
+    assert_eq!(msg.body(), b"ping");
+}
+
+#[test]
+fn should_call_wait_hook_while_pending() {
+    let mut polled = false;
+    let mut waits = 0;
+    let fut = core::future::poll_fn(|_| if polled {
+        core::task::Poll::Ready(1)
+    } else {
+        polled = true;
+        core::task::Poll::Pending
+    });
+
+    assert_eq!(spin_on_with(fut, || waits += 1), 1);
+    assert_eq!(waits, 1);
+}
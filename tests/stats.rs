@@ -0,0 +1,228 @@
+use nng_c::{options, Socket};
+use nng_c::stats::{Kind, Queue, QueueDepth, Snapshot, Watermarks, WatermarkEvent};
+
+use core::time;
+
+fn free_port() -> u16 {
+    std::net::TcpListener::bind(("127.0.0.1", 0)).expect("bind").local_addr().expect("get addr").port()
+}
+
+fn wait_depth(socket: &Socket, expected: QueueDepth) {
+    for _ in 0..100 {
+        if QueueDepth::of(socket).expect("get depth") == expected {
+            return;
+        }
+        std::thread::sleep(time::Duration::from_millis(10));
+    }
+    panic!("queue depth {:?} is not reached, last: {:?}", expected, QueueDepth::of(socket));
+}
+
+#[test]
+fn should_find_socket_stats() {
+    let socket = Socket::pair0().expect("create socket");
+    let snapshot = Snapshot::get().expect("get stats");
+    let stat = snapshot.socket(&socket).expect("find socket");
+    assert_eq!(stat.kind(), Kind::Scope);
+    assert_eq!(stat.name(), "socket");
+    assert_eq!(stat.child("protocol").expect("find protocol").string(), "pair");
+    assert_eq!(snapshot.pipes(&socket).count(), 0);
+}
+
+#[test]
+fn should_reject_queue_depth_of_inproc_socket() {
+    const ADDR: &str = "inproc://should_reject_queue_depth_of_inproc_socket\0";
+
+    let server = Socket::pair0().expect("create server");
+    server.listen(ADDR.into()).expect("listen");
+    assert_eq!(QueueDepth::of(&server).expect("get depth"), QueueDepth::default());
+    let client = Socket::pair0().expect("create client");
+    client.connect(ADDR.into()).expect("connect");
+
+    for _ in 0..100 {
+        if snapshot_pipes(&server) == 1 {
+            break;
+        }
+        std::thread::sleep(time::Duration::from_millis(10));
+    }
+    let error = QueueDepth::of(&server).expect_err("inproc depth");
+    assert_eq!(error.raw_code(), nng_c::sys::nng_errno_enum::NNG_ENOTSUP);
+}
+
+#[test]
+fn should_emit_watermark_events() {
+    let addr = format!("tcp://127.0.0.1:{}\0", free_port());
+
+    let server = Socket::pair0().expect("create server");
+    server.set_opt(options::RecvBuf(10)).expect("set recv buffer");
+    server.listen(addr.as_str().into()).expect("listen");
+    let client = Socket::pair0().expect("create client");
+    client.connect(addr.as_str().into()).expect("connect");
+
+    let mut events = Vec::new();
+    let mut watermarks = Watermarks::new(|event| events.push(event)).with_watermarks(40, 10);
+    assert_eq!(watermarks.check(&server).expect("check"), QueueDepth::default());
+
+    for _ in 0..5 {
+        client.send(b"slow".into()).expect("send");
+    }
+    wait_depth(&server, QueueDepth { send: 0, recv: 5 });
+    wait_depth(&client, QueueDepth::default());
+
+    watermarks.check(&server).expect("check");
+    watermarks.check(&server).expect("check");
+    for _ in 0..4 {
+        server.recv_msg().expect("receive");
+    }
+    assert_eq!(watermarks.check(&server).expect("check"), QueueDepth { send: 0, recv: 1 });
+
+    assert_eq!(events, [
+        WatermarkEvent { queue: Queue::Recv, high: true, depth: 5, capacity: 10 },
+        WatermarkEvent { queue: Queue::Recv, high: false, depth: 1, capacity: 10 },
+    ]);
+}
+
+#[test]
+fn should_detect_slow_consumers() {
+    use nng_c::stats::{SlowConsumerAction, SlowConsumerEvent, SlowConsumers};
+
+    let addr = format!("tcp://127.0.0.1:{}\0", free_port());
+
+    let publisher = Socket::pub0().expect("create publisher");
+    publisher.listen(addr.as_str().into()).expect("listen");
+    let _subscriber = connect_stalled_subscriber(&addr);
+
+    let mut events = Vec::<SlowConsumerEvent>::new();
+    let mut monitor = SlowConsumers::new(|event| events.push(event)).with_max_backlog(10)
+                                                                      .with_strikes(2)
+                                                                      .with_action(SlowConsumerAction::Close);
+    //Starts tracking pipe
+    for _ in 0..100 {
+        if snapshot_pipes(&publisher) == 1 {
+            break;
+        }
+        std::thread::sleep(time::Duration::from_millis(10));
+    }
+    assert_eq!(monitor.check(&publisher).expect("check"), 0);
+    //Without messages consumer is not slow
+    assert_eq!(monitor.check(&publisher).expect("check"), 0);
+
+    //Subscriber never reads, hence messages are eventually dropped once buffers are full
+    let payload = vec![0u8; 1024 * 1024];
+    let mut slow = 0;
+    for _ in 0..20 {
+        for _ in 0..20 {
+            publisher.send(payload.as_slice().into()).expect("send");
+        }
+        std::thread::sleep(time::Duration::from_millis(10));
+        slow = monitor.check(&publisher).expect("check");
+        if slow > 0 {
+            break;
+        }
+    }
+    assert_eq!(slow, 1);
+    drop(monitor);
+
+    assert_eq!(events.len(), 1);
+    let event = events[0];
+    assert!(event.backlog >= 10);
+    assert_eq!(event.strikes, 2);
+    assert!(event.closed);
+    assert!(event.pipe.close().is_err());
+}
+
+#[test]
+fn should_detect_slow_consumers_of_async_publisher() {
+    use nng_c::Message;
+    use nng_c::stats::{SlowConsumerEvent, SlowConsumers};
+
+    let addr = format!("tcp://127.0.0.1:{}\0", free_port());
+
+    let publisher = Socket::pub0().expect("create publisher");
+    publisher.listen(addr.as_str().into()).expect("listen");
+    let _stalled = connect_stalled_subscriber(&addr);
+    let subscriber = Socket::sub0().expect("create subscriber");
+    subscriber.set_opt(options::Subscribe(b"")).expect("subscribe");
+    subscriber.connect(addr.as_str().into()).expect("connect");
+
+    let mut events = Vec::<SlowConsumerEvent>::new();
+    let mut monitor = SlowConsumers::new(|event| events.push(event)).with_max_backlog(10)
+                                                                      .with_strikes(2);
+    for _ in 0..100 {
+        if snapshot_pipes(&publisher) == 2 {
+            break;
+        }
+        std::thread::sleep(time::Duration::from_millis(10));
+    }
+    assert_eq!(monitor.check(&publisher).expect("check"), 0);
+
+    //Socket doesn't count messages sent asynchronously, but subscriber, that keeps up, writes all of them,
+    //hence stalled subscriber falls behind it
+    let payload = vec![0u8; 1024 * 1024];
+    let mut slow = 0;
+    for _ in 0..20 {
+        for _ in 0..20 {
+            let mut msg = Message::new().expect("create message");
+            msg.append(&payload).expect("append");
+            let send = publisher.send_msg_async(msg).expect("start send");
+            nng_c::utils::block_on(send).expect("block on").expect("send");
+        }
+        std::thread::sleep(time::Duration::from_millis(10));
+        slow = monitor.check(&publisher).expect("check");
+        if slow > 0 {
+            break;
+        }
+    }
+    assert_eq!(slow, 1);
+    drop(monitor);
+
+    assert_eq!(events.len(), 1);
+    assert!(events[0].backlog >= 10);
+    assert_eq!(publisher.counters().msgs_sent, 0);
+}
+
+//sub0 keeps reading from network, dropping messages on its own, so consumer that stops reading
+//is emulated via raw connection, which only performs SP handshake as sub0 peer
+fn connect_stalled_subscriber(addr: &str) -> std::net::TcpStream {
+    use std::io::{Read, Write};
+
+    let mut subscriber = std::net::TcpStream::connect(&addr[6..addr.len() - 1]).expect("connect");
+    subscriber.write_all(&[0, b'S', b'P', 0, 0, 0x21, 0, 0]).expect("send handshake");
+    let mut handshake = [0u8; 8];
+    subscriber.read_exact(&mut handshake).expect("receive handshake");
+    assert_eq!(handshake, [0, b'S', b'P', 0, 0, 0x20, 0, 0]);
+    subscriber
+}
+
+fn snapshot_pipes(socket: &Socket) -> usize {
+    Snapshot::get().expect("get stats").pipes(socket).count()
+}
+
+#[test]
+fn should_sample_stats_periodically() {
+    const ADDR: &str = "inproc://should_sample_stats_periodically\0";
+    const INTERVAL: time::Duration = time::Duration::from_millis(50);
+
+    let server = Socket::pair1().expect("create server");
+    server.listen(ADDR.into()).expect("listen");
+    let client = Socket::pair1().expect("create client");
+    client.connect(ADDR.into()).expect("connect");
+
+    let mut sampler = nng_c::stats::sampler(INTERVAL).expect("create sampler");
+    for _ in 0..5 {
+        client.send(b"ping".into()).expect("send");
+        server.recv_msg().expect("receive");
+    }
+
+    let sample = sampler.wait().expect("take sample");
+    assert!(!sample.elapsed().is_zero());
+    assert_eq!(sample.delta(|snapshot| snapshot.socket(&client)?.child("tx_msgs")), Some(5));
+    assert_eq!(sample.delta(|snapshot| snapshot.socket(&server)?.child("tx_msgs")), Some(0));
+    let rate = sample.rate(|snapshot| snapshot.socket(&client)?.child("tx_msgs")).expect("rate");
+    assert!(rate > 0.0);
+    assert_eq!(sample.delta(|snapshot| snapshot.socket(&client)?.child("unknown")), None);
+
+    client.send(b"ping".into()).expect("send");
+    server.recv_msg().expect("receive");
+    let sample = nng_c::utils::block_on(sampler.next()).expect("block on").expect("take sample");
+    assert_eq!(sample.delta(|snapshot| snapshot.socket(&client)?.child("tx_msgs")), Some(1));
+}
@@ -31,3 +31,16 @@ fn should_create_valid_dynamic_str() {
         assert_eq!(str, string.as_slice());
     }
 }
+
+#[test]
+fn should_create_string_fallibly() {
+    let input = "long enough string to be allocated on heap";
+    let string = String::try_new(input.as_bytes()).expect("allocate");
+    assert_eq!(string, input);
+
+    let string = String::try_new(b"short").expect("static buffer");
+    assert_eq!(string, "short");
+
+    assert!(String::try_new_c(b"").is_none());
+    assert_eq!(String::new(b""), "");
+}
@@ -0,0 +1,91 @@
+use nng_c::stream::{self, Dialer};
+
+use std::io::{Read, Write};
+
+mod rt;
+
+#[test]
+fn should_send_vectored_over_stream() {
+    let listener = std::net::TcpListener::bind(("127.0.0.1", 0)).expect("bind");
+    let url = format!("tcp://{}", listener.local_addr().expect("get address"));
+
+    let dialer = Dialer::new(url.as_str().into()).expect("create dialer");
+    let stream = rt::run(dialer.dial().expect("dial")).expect("connect");
+    let (mut peer, _) = listener.accept().expect("accept");
+
+    let bufs = vec![b"header:".to_vec(), b"payload".to_vec()];
+    let sent = rt::run(stream.send_vectored(bufs).expect("send")).expect("complete send");
+    assert_eq!(sent, 14);
+
+    let mut received = [0u8; 14];
+    peer.read_exact(&mut received).expect("read");
+    assert_eq!(&received, b"header:payload");
+
+    peer.write_all(b"reply").expect("write");
+    let reply = rt::run(stream.recv(64).expect("recv")).expect("complete recv");
+    assert_eq!(reply, b"reply");
+
+    let bufs = (0..=stream::MAX_IOV).map(|_| b"x".as_slice()).collect::<Vec<_>>();
+    assert!(stream.send_vectored(bufs).is_err());
+
+    drop(stream);
+    let mut rest = Vec::new();
+    peer.read_to_end(&mut rest).expect("read until closed");
+    assert!(rest.is_empty());
+}
+
+#[test]
+fn should_accept_streams() {
+    use nng_c::stream::Listener;
+
+    let url = format!("ipc://{}", std::env::temp_dir().join(format!("nng-c-should-accept-streams-{}", std::process::id())).display());
+
+    let listener = Listener::new(url.as_str().into()).expect("create listener");
+    listener.listen().expect("listen");
+    let dialer = Dialer::new(url.as_str().into()).expect("create dialer");
+
+    std::thread::scope(|scope| {
+        let server = scope.spawn(|| rt::run(async {
+            let mut accepted = 0;
+            while let Ok(conn) = listener.accept().await {
+                let msg = conn.recv(16).expect("recv").await.expect("complete recv");
+                let sent = conn.send(msg).expect("send").await.expect("complete send");
+                assert_eq!(sent, 4);
+                accepted += 1;
+            }
+            accepted
+        }));
+
+        for _ in 0..2 {
+            let stream = rt::run(dialer.dial().expect("dial")).expect("connect");
+            assert_eq!(rt::run(stream.send(b"ping").expect("send")).expect("complete send"), 4);
+            let reply = rt::run(stream.recv(16).expect("recv")).expect("complete recv");
+            assert_eq!(reply, b"ping");
+        }
+
+        listener.close();
+        assert_eq!(server.join().expect("finish server"), 2);
+    });
+}
+
+#[test]
+fn should_get_stream_properties() {
+    use nng_c::options::{Address, LocalAddr, RemoteAddr, TlsPeerCn, TlsVerified};
+
+    let listener = std::net::TcpListener::bind(("127.0.0.1", 0)).expect("bind");
+    let addr = listener.local_addr().expect("get address");
+    let url = format!("tcp://{}", addr);
+
+    let dialer = Dialer::new(url.as_str().into()).expect("create dialer");
+    let stream = rt::run(dialer.dial().expect("dial")).expect("connect");
+    let (_peer, peer_addr) = listener.accept().expect("accept");
+
+    let RemoteAddr(remote) = stream.get_prop().expect("get remote address");
+    assert_eq!(remote, Address::Inet(addr));
+    assert_eq!(remote.to_string(), url);
+    let LocalAddr(local) = stream.get_prop().expect("get local address");
+    assert_eq!(local, Address::Inet(peer_addr));
+
+    assert!(stream.get_prop::<TlsVerified>().is_err());
+    assert!(stream.get_prop::<TlsPeerCn>().is_err());
+}
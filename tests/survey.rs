@@ -0,0 +1,132 @@
+use nng_c::{options, Socket, Message, NngError};
+use nng_c::survey::{Respondent, Surveyor};
+
+use core::time;
+
+mod rt;
+
+#[test]
+fn should_answer_surveys_async() {
+    const ADDR: &str = "inproc://should_answer_surveys_async\0";
+
+    let surveyor = Socket::surveyor0().expect("Create surveyor");
+    surveyor.set_opt(options::SurveyTime(time::Duration::from_millis(200))).expect("set survey time");
+    let respondent = Socket::respondent0().expect("Create respondent");
+
+    //Surveyor must dial so that its pipe is ready before first survey is sent
+    respondent.listen(ADDR.into()).expect("listen");
+    surveyor.connect(ADDR.into()).expect("connect");
+
+    std::thread::scope(|scope| {
+        let server = scope.spawn(|| {
+            let mut helper = Respondent::new(&respondent).expect("create respondent");
+            rt::run(helper.serve(|msg| {
+                if msg.body() == b"ignore" {
+                    None
+                } else {
+                    Some(msg)
+                }
+            }))
+        });
+
+        let mut survey = Message::new().expect("Create message");
+        survey.append(b"answer").expect("append");
+        surveyor.send_msg(survey).expect("send survey");
+        let answer = surveyor.recv_msg().expect("get answer");
+        assert_eq!(answer.body(), b"answer");
+
+        let mut survey = Message::new().expect("Create message");
+        survey.append(b"ignore").expect("append");
+        surveyor.send_msg(survey).expect("send survey");
+        let error = surveyor.recv_msg().expect_err("no answer");
+        assert!(error.is_timed_out());
+
+        respondent.close();
+        server.join().expect("finish server").expect("close cleanly");
+    });
+}
+
+#[test]
+fn should_answer_survey_manually() {
+    const ADDR: &str = "inproc://should_answer_survey_manually\0";
+
+    let surveyor = Socket::surveyor0().expect("Create surveyor");
+    let respondent = Socket::respondent0().expect("Create respondent");
+
+    //Surveyor must dial so that its pipe is ready before first survey is sent
+    respondent.listen(ADDR.into()).expect("listen");
+    surveyor.connect(ADDR.into()).expect("connect");
+
+    let mut helper = Respondent::new(&respondent).expect("create respondent");
+
+    let mut survey = Message::new().expect("Create message");
+    survey.append(b"survey").expect("append");
+    surveyor.send_msg(survey).expect("send survey");
+
+    let survey = rt::run(helper.next()).expect("get survey");
+    assert_eq!(survey.message().body(), b"survey");
+    let answer = survey.message().clone();
+    assert!(rt::run(survey.answer(answer)).expect("answer"));
+    assert_eq!(surveyor.recv_msg().expect("get answer").body(), b"survey");
+}
+
+#[test]
+fn should_collect_responses_until_deadline() {
+    const ADDR: &str = "inproc://should_collect_responses_until_deadline\0";
+
+    let surveyor = Socket::surveyor0().expect("Create surveyor");
+    let first = Socket::respondent0().expect("Create respondent");
+    let second = Socket::respondent0().expect("Create respondent");
+
+    surveyor.listen(ADDR.into()).expect("listen");
+    first.connect(ADDR.into()).expect("connect");
+    second.connect(ADDR.into()).expect("connect");
+    //Wait for pipes to be ready before first survey is sent
+    std::thread::sleep(time::Duration::from_millis(50));
+
+    std::thread::scope(|scope| {
+        let servers = [&first, &second].map(|respondent| scope.spawn(move || {
+            let mut helper = Respondent::new(respondent).expect("create respondent");
+            rt::run(helper.serve(Some))
+        }));
+
+        let mut helper = Surveyor::new(&surveyor).expect("create surveyor");
+        helper.set_deadline(time::Duration::from_millis(200)).expect("set deadline");
+
+        let mut survey = Message::new().expect("Create message");
+        survey.append(b"blocking").expect("append");
+        let mut responses = helper.survey(survey).expect("send survey");
+        let answers = responses.by_ref().map(|answer| answer.expect("get answer").body().to_vec()).collect::<Vec<_>>();
+        assert_eq!(answers, [b"blocking", b"blocking"]);
+        assert!(responses.is_done());
+        assert!(responses.next().is_none());
+
+        let mut survey = Message::new().expect("Create message");
+        survey.append(b"async").expect("append");
+        let answers = rt::run(async {
+            let mut responses = helper.survey_async(survey).await.expect("send survey");
+            let mut answers = Vec::new();
+            while let Some(answer) = responses.next_async().await.expect("get answer") {
+                answers.push(answer.body().to_vec());
+            }
+            answers
+        });
+        assert_eq!(answers, [b"async", b"async"]);
+
+        first.close();
+        second.close();
+        for server in servers {
+            server.join().expect("finish server").expect("close cleanly");
+        }
+
+        //Other errors end responses after being reported
+        let mut survey = Message::new().expect("Create message");
+        survey.append(b"closed").expect("append");
+        let mut responses = helper.survey(survey).expect("send survey");
+        surveyor.close();
+        let error = responses.next().expect("get error").expect_err("socket is closed");
+        assert!(error.is_closed());
+        assert!(responses.is_done());
+        assert!(responses.next().is_none());
+    });
+}
@@ -0,0 +1,43 @@
+use nng_c::utils::sync::{Condvar, Mutex};
+
+use core::time;
+
+#[test]
+fn should_synchronize_threads() {
+    const THREADS: usize = 4;
+    const INCREMENTS: usize = 1000;
+
+    let counter = Mutex::new(0usize).expect("create mutex");
+    let cond = Condvar::new(&counter).expect("create condvar");
+
+    std::thread::scope(|scope| {
+        for _ in 0..THREADS {
+            scope.spawn(|| {
+                for _ in 0..INCREMENTS {
+                    *counter.lock() += 1;
+                }
+                cond.notify_all();
+            });
+        }
+
+        let guard = cond.wait_while(counter.lock(), |counter| *counter < THREADS * INCREMENTS);
+        assert_eq!(*guard, THREADS * INCREMENTS);
+    });
+
+    let (guard, timed_out) = cond.wait_timeout(counter.lock(), time::Duration::from_millis(10));
+    assert!(timed_out);
+    assert_eq!(*guard, THREADS * INCREMENTS);
+    drop(guard);
+
+    drop(cond);
+    assert_eq!(counter.into_inner(), THREADS * INCREMENTS);
+}
+
+#[test]
+#[should_panic]
+fn should_reject_guard_of_other_mutex() {
+    let first = Mutex::new(()).expect("create mutex");
+    let second = Mutex::new(()).expect("create mutex");
+    let cond = Condvar::new(&first).expect("create condvar");
+    let _guard = cond.wait(second.lock());
+}
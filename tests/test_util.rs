@@ -0,0 +1,85 @@
+use nng_c::{Socket, Message};
+use nng_c::socket::Protocol;
+use nng_c::test_util::{self, PipeCounter};
+
+use core::time;
+
+const TIMEOUT: time::Duration = time::Duration::from_secs(1);
+
+#[test]
+fn should_generate_unique_urls() {
+    let first = test_util::inproc_url("unique");
+    let second = test_util::inproc_url("unique");
+    assert!(first.starts_with("inproc://"));
+    assert_ne!(first, second);
+
+    let first = test_util::ipc_url("unique");
+    let second = test_util::ipc_url("unique");
+    assert!(first.starts_with("ipc://"));
+    assert_ne!(first, second);
+    //Long name is truncated to fit socket path
+    assert!(test_util::ipc_url(&"long".repeat(64)).len() < 110);
+
+    let url = test_util::tcp_url().expect("allocate port");
+    assert!(url.starts_with("tcp://127.0.0.1:"));
+}
+
+#[test]
+fn should_wait_for_connection() {
+    for url in [test_util::inproc_url("wait"), test_util::ipc_url("wait"), test_util::tcp_url().expect("allocate port")] {
+        let server = Socket::pair0().expect("create server");
+        let counter = PipeCounter::install(&server).expect("install counter");
+        server.listen(url.as_str().into()).expect("listen");
+        assert_eq!(counter.count(), 0);
+
+        let client = Socket::pair0().expect("create client");
+        client.connect(url.as_str().into()).expect("connect");
+        counter.wait_connected(1, TIMEOUT).expect("to connect");
+        assert_eq!(counter.count(), 1);
+
+        client.close();
+        counter.wait_disconnected(TIMEOUT).expect("to disconnect");
+        counter.wait_connected(1, time::Duration::from_millis(10)).expect_err("should time out");
+    }
+}
+
+#[test]
+fn should_create_linked_pairs() {
+    for proto in [Protocol::Pair0, Protocol::Pair1, Protocol::Req0, Protocol::Rep0, Protocol::Pub0, Protocol::Sub0] {
+        let pair = test_util::linked_pair(proto).expect("create pair");
+        assert!(!pair.has_latency());
+
+        let (sender, receiver) = match proto {
+            Protocol::Rep0 | Protocol::Sub0 => (&pair.dialer, &pair.listener),
+            _ => (&pair.listener, &pair.dialer),
+        };
+
+        let mut msg = Message::new().expect("create message");
+        msg.append(b"ping").expect("append");
+        sender.send_msg(msg).expect("send");
+        let msg = receiver.recv_msg().expect("receive");
+        assert_eq!(msg.body(), b"ping", "{:?}", proto);
+    }
+}
+
+#[test]
+fn should_inject_latency_into_linked_pair() {
+    const LATENCY: time::Duration = time::Duration::from_millis(50);
+
+    let pair = test_util::linked_pair_with_latency(Protocol::Req0, LATENCY).expect("create pair");
+    assert!(pair.has_latency());
+
+    let start = std::time::Instant::now();
+    let mut msg = Message::new().expect("create message");
+    msg.append(b"ping").expect("append");
+    pair.listener.send_msg(msg).expect("send request");
+
+    let msg = pair.dialer.recv_msg().expect("receive request");
+    assert_eq!(msg.body(), b"ping");
+    assert!(start.elapsed() >= LATENCY);
+    pair.dialer.send_msg(msg).expect("send reply");
+
+    let msg = pair.listener.recv_msg().expect("receive reply");
+    assert_eq!(msg.body(), b"ping");
+    assert!(start.elapsed() >= LATENCY * 2);
+}
@@ -0,0 +1,51 @@
+use nng_c::{options, utils, Socket};
+
+use core::time;
+
+#[test]
+fn should_run_receive_loop_in_nng_thread() {
+    const ADDR: &str = "inproc://should_run_receive_loop_in_nng_thread\0";
+
+    let server = Socket::pair0().expect("create server");
+    server.set_opt(options::RecvTimeout(time::Duration::from_secs(5))).expect("set recv timeout");
+    server.listen(ADDR.into()).expect("listen");
+    let client = Socket::pair0().expect("create client");
+    client.set_opt(options::SendTimeout(time::Duration::from_secs(5))).expect("set send timeout");
+    client.connect(ADDR.into()).expect("connect");
+
+    let handle = utils::spawn(move || {
+        let mut received = 0;
+        loop {
+            let msg = server.recv_msg().expect("receive");
+            if msg.body() == b"quit" {
+                break received;
+            }
+            received += 1;
+        }
+    }).expect("spawn thread");
+    handle.set_name("receiver".into());
+
+    for _ in 0..3 {
+        client.send(b"ping".into()).expect("send");
+    }
+    client.send(b"quit".into()).expect("send quit");
+
+    assert_eq!(handle.join(), 3);
+}
+
+#[test]
+fn should_wait_for_thread_on_drop() {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    let done = Arc::new(AtomicBool::new(false));
+    let handle = {
+        let done = done.clone();
+        utils::spawn(move || {
+            std::thread::sleep(time::Duration::from_millis(20));
+            done.store(true, Ordering::Release);
+        }).expect("spawn thread")
+    };
+    drop(handle);
+    assert!(done.load(Ordering::Acquire));
+}
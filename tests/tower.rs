@@ -0,0 +1,106 @@
+use nng_c::{options, Message, NngError, Socket};
+use nng_c::tower::{Client, ServeError, Server};
+use nng_c::utils::block_on;
+
+use tower_service::Service;
+
+use core::{future, task, time};
+use std::sync::Arc;
+
+//Replies with reversed body, failing requests with empty body
+#[derive(Clone)]
+struct Reverse;
+
+impl Service<Message> for Reverse {
+    type Response = Message;
+    type Error = &'static str;
+    type Future = future::Ready<Result<Message, &'static str>>;
+
+    fn poll_ready(&mut self, _: &mut task::Context<'_>) -> task::Poll<Result<(), Self::Error>> {
+        task::Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: Message) -> Self::Future {
+        if req.body().is_empty() {
+            return future::ready(Err("empty request"));
+        }
+        let mut body = req.body().to_vec();
+        body.reverse();
+        let mut resp = Message::new().expect("create message");
+        resp.append(&body).expect("append");
+        future::ready(Ok(resp))
+    }
+}
+
+struct Broken;
+
+impl Service<Message> for Broken {
+    type Response = Message;
+    type Error = &'static str;
+    type Future = future::Ready<Result<Message, &'static str>>;
+
+    fn poll_ready(&mut self, _: &mut task::Context<'_>) -> task::Poll<Result<(), Self::Error>> {
+        task::Poll::Ready(Err("broken"))
+    }
+
+    fn call(&mut self, _: Message) -> Self::Future {
+        unreachable!()
+    }
+}
+
+fn request(body: &[u8]) -> Message {
+    let mut msg = Message::new().expect("create message");
+    msg.append(body).expect("append");
+    msg
+}
+
+#[test]
+fn should_serve_tower_service() {
+    const ADDR: &str = "inproc://should_serve_tower_service\0";
+
+    let server = Arc::new(Socket::rep0().expect("create server"));
+    server.listen(ADDR.into()).expect("listen");
+    let client = Socket::req0().expect("create client");
+    client.connect(ADDR.into()).expect("connect");
+
+    let workers = (0..2).map(|_| {
+        let mut worker = Server::new(&server).expect("create server context");
+        std::thread::spawn(move || block_on(worker.serve(Reverse)).expect("run executor"))
+    }).collect::<Vec<_>>();
+
+    let mut client = Client::new(&client);
+    let first = client.call(request(b"abc"));
+    let second = client.call(request(b"xyz"));
+    assert_eq!(block_on(second).expect("run executor").expect("get response").body(), b"zyx");
+    assert_eq!(block_on(first).expect("run executor").expect("get response").body(), b"cba");
+
+    //Failed request is left without reply, while server keeps running
+    let sync_client = Socket::req0().expect("create client");
+    sync_client.set_opt(options::RecvTimeout(time::Duration::from_millis(200))).expect("set timeout");
+    sync_client.connect(ADDR.into()).expect("connect");
+    sync_client.send_msg(request(b"")).expect("send");
+    assert!(sync_client.recv_msg().expect_err("no reply").is_timed_out());
+    assert_eq!(block_on(client.call(request(b"ok"))).expect("run executor").expect("get response").body(), b"ko");
+
+    server.close();
+    for worker in workers {
+        worker.join().expect("join worker").expect("stop on close");
+    }
+}
+
+#[test]
+fn should_stop_serving_when_service_fails() {
+    const ADDR: &str = "inproc://should_stop_serving_when_service_fails\0";
+
+    let server = Socket::rep0().expect("create server");
+    server.listen(ADDR.into()).expect("listen");
+    let client = Socket::req0().expect("create client");
+    client.connect(ADDR.into()).expect("connect");
+    client.send_msg(request(b"abc")).expect("send");
+
+    let mut worker = Server::new(&server).expect("create server context");
+    match block_on(worker.serve(Broken)).expect("run executor") {
+        Err(ServeError::Service(error)) => assert_eq!(error, "broken"),
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
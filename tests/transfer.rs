@@ -0,0 +1,90 @@
+use nng_c::{options, Message, Socket};
+
+use core::convert::TryFrom;
+use nng_c::transfer::{Progress, Receiver, Sender};
+
+use core::time;
+
+fn connect(name: &str) -> (Socket, Socket) {
+    let addr = format!("inproc://{}", name);
+    let pull = Socket::pull0().expect("create pull socket");
+    pull.set_opt(options::RecvTimeout(time::Duration::from_secs(5))).expect("set recv timeout");
+    pull.listen(addr.as_str().into()).expect("listen");
+    let push = Socket::push0().expect("create push socket");
+    push.connect(addr.as_str().into()).expect("connect");
+    (push, pull)
+}
+
+#[test]
+fn should_transfer_file() {
+    let (push, pull) = connect("should_transfer_file");
+    let dir = std::env::temp_dir();
+    let source = dir.join(format!("nng-c-transfer-source-{}", std::process::id()));
+    let destination = dir.join(format!("nng-c-transfer-destination-{}", std::process::id()));
+    let content = (0..10_000u32).map(|idx| idx as u8).collect::<Vec<_>>();
+    std::fs::write(&source, &content).expect("write source");
+
+    std::thread::scope(|scope| {
+        let sender = scope.spawn(|| {
+            let mut chunks = 0;
+            let sent = Sender::new(&push).with_chunk_size(4096).send_file(&source, |_| chunks += 1).expect("send file");
+            (sent, chunks)
+        });
+
+        let mut reports = Vec::new();
+        let received = Receiver::new(&pull).receive_file(&destination, |progress| reports.push(progress)).expect("receive file");
+        assert_eq!(received, content.len() as u64);
+        assert_eq!(sender.join().expect("join sender"), (content.len() as u64, 3));
+        assert_eq!(reports.len(), 3);
+        assert_eq!(reports[2], Progress {
+            transferred: content.len() as u64,
+            total: Some(content.len() as u64),
+        });
+    });
+
+    assert_eq!(std::fs::read(&destination).expect("read destination"), content);
+    let _ = std::fs::remove_file(&source);
+    let _ = std::fs::remove_file(&destination);
+}
+
+#[test]
+fn should_reject_corrupted_transfer() {
+    let (push, pull) = connect("should_reject_corrupted_transfer");
+
+    let frame = |kind: u8, rest: &[u8]| {
+        let mut msg = Message::new().expect("create message");
+        msg.append(&[kind]).expect("append kind");
+        msg.append_u64(7).expect("append id");
+        msg.append(rest).expect("append rest");
+        push.send_msg(msg).expect("send");
+    };
+
+    std::thread::scope(|scope| {
+        scope.spawn(|| {
+            frame(0, &u64::MAX.to_be_bytes());
+            frame(1, &[0, 0, 0, 0, 0, 0, 0, 0, b'a', b'b']);
+            frame(2, &[0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0]);
+        });
+
+        let mut received = Vec::new();
+        let error = Receiver::new(&pull).receive(&mut received, |_| ()).expect_err("should fail checksum");
+        assert_eq!(nng_c::Errno::try_from(error), Ok(nng_c::Errno::Proto));
+        assert_eq!(received, b"ab");
+    });
+}
+
+#[test]
+fn should_send_from_reader_of_unknown_size() {
+    let (push, pull) = connect("should_send_from_reader_of_unknown_size");
+
+    std::thread::scope(|scope| {
+        scope.spawn(|| Sender::new(&push).send(&b"hello"[..], None, |_| ()).expect("send"));
+
+        let mut total = Some(0);
+        let mut received = Vec::new();
+        let size = Receiver::new(&pull).receive(&mut received, |progress| total = progress.total).expect("receive");
+        assert_eq!(size, 5);
+        assert_eq!(total, None);
+        assert_eq!(received, b"hello");
+    });
+}
@@ -0,0 +1,33 @@
+use nng_c::utils::uri;
+
+#[test]
+fn should_encode_uri_parts() {
+    assert_eq!(uri::encode_component("a/b c?d=e&f~-._"), "a%2Fb%20c%3Fd%3De%26f~-._");
+    assert_eq!(uri::encode_path("/dir/файл 1"), "/dir/%D1%84%D0%B0%D0%B9%D0%BB%201");
+    assert_eq!(uri::encode_query("1+1 = 2"), "1%2B1+%3D+2");
+}
+
+#[test]
+fn should_decode_uri_parts() {
+    assert_eq!(uri::decode("/dir/%D1%84%D0%B0%D0%B9%D0%BB%201+").expect("decode"), "/dir/файл 1+");
+    assert_eq!(uri::decode_query("1%2b1+%3D+2").expect("decode"), "1+1 = 2");
+
+    assert!(uri::decode("%").is_err());
+    assert!(uri::decode("%4").is_err());
+    assert!(uri::decode("%zz").is_err());
+    assert!(uri::decode("%FF").is_err());
+
+    let input = "key with spaces & symbols=/?#";
+    assert_eq!(uri::decode_query(&uri::encode_query(input)).expect("decode"), input);
+    assert_eq!(uri::decode(&uri::encode_component(input)).expect("decode"), input);
+}
+
+#[test]
+fn should_split_uri_query() {
+    assert_eq!(uri::split("/path"), ("/path", None));
+    assert_eq!(uri::split("/path?a=1&b#frag"), ("/path", Some("a=1&b")));
+    assert_eq!(uri::split("/path#frag?not-query"), ("/path", None));
+
+    let pairs = uri::query_pairs("a=1&&b&c=x=y").collect::<Vec<_>>();
+    assert_eq!(pairs, [("a", "1"), ("b", ""), ("c", "x=y")]);
+}
@@ -0,0 +1,149 @@
+use nng_c::{url, Socket};
+use nng_c::url::{Scheme, Url};
+
+#[test]
+fn should_accept_builtin_schemes() {
+    url::check_scheme(b"tcp://127.0.0.1:0").expect("tcp");
+    url::check_scheme(b"ipc:///tmp/socket").expect("ipc");
+    url::check_scheme(b"inproc://name").expect("inproc");
+    url::check_scheme(b"unknown://name").expect("leave unknown to nng");
+    url::check_scheme(b"wsx://name").expect("not websocket");
+}
+
+#[cfg(not(feature = "tls"))]
+#[test]
+fn should_report_disabled_tls() {
+    use nng_c::Errno;
+    use core::convert::TryFrom;
+
+    for addr in ["tls+tcp://127.0.0.1:0", "tls+tcp4://127.0.0.1:0", "tls+tcp6://[::1]:0"] {
+        let error = url::check_scheme(addr.as_bytes()).expect_err("tls is disabled");
+        assert_eq!(Errno::try_from(error), Ok(Errno::NotSup));
+        assert!(error.to_string().contains("`tls` feature"));
+    }
+
+    let socket = Socket::rep0().expect("create socket");
+    let error = socket.listen("tls+tcp://127.0.0.1:0".into()).expect_err("listen");
+    assert_eq!(error.raw_code(), nng_c::sys::nng_errno_enum::NNG_ENOTSUP);
+    assert!(error.to_string().contains("`tls` feature"));
+}
+
+#[cfg(not(feature = "websocket"))]
+#[test]
+fn should_report_disabled_websocket() {
+    let error = url::check_scheme(b"ws://127.0.0.1:0/path").expect_err("websocket is disabled");
+    assert!(error.to_string().contains("`websocket` feature"));
+
+    let error = url::check_scheme(b"wss6://[::1]:0/path").expect_err("websocket is disabled");
+    assert!(error.to_string().contains("`websocket` and `tls` features"));
+
+    let socket = Socket::req0().expect("create socket");
+    let error = socket.add_dialer("ws://127.0.0.1:0/path".into(), nng_c::socket::ConnectOptions::new()).expect_err("connect");
+    assert_eq!(error.code().raw_code(), nng_c::sys::nng_errno_enum::NNG_ENOTSUP);
+    assert_eq!(error.url(), Some("ws://127.0.0.1:0/path"));
+}
+
+#[test]
+fn should_detect_scheme() {
+    assert_eq!(Scheme::from_url(b"inproc://name"), Some(Scheme::Inproc));
+    assert_eq!(Scheme::from_url(b"ipc:///tmp/socket"), Some(Scheme::Ipc));
+    assert_eq!(Scheme::from_url(b"abstract://name"), Some(Scheme::Abstract));
+    assert_eq!(Scheme::from_url(b"tcp4://127.0.0.1:80"), Some(Scheme::Tcp));
+    assert_eq!(Scheme::from_url(b"tls+tcp6://[::1]:80"), Some(Scheme::Tls));
+    assert_eq!(Scheme::from_url(b"ws://localhost:80/"), Some(Scheme::Ws));
+    assert_eq!(Scheme::from_url(b"wss://localhost:80/"), Some(Scheme::Wss));
+    assert_eq!(Scheme::from_url(b"ipc4://name"), None);
+    assert_eq!(Scheme::from_url(b"tcp:/localhost:80"), None);
+    assert_eq!(Scheme::from_url(b"udp://localhost:80"), None);
+}
+
+#[test]
+fn should_build_url() {
+    let url = Url::tcp("127.0.0.1", 5555).expect("create url");
+    assert_eq!(url.scheme(), Scheme::Tcp);
+    assert_eq!(url.as_str(), "tcp://127.0.0.1:5555");
+    assert_eq!(url.as_bytes_with_nul(), b"tcp://127.0.0.1:5555\0");
+    assert_eq!(url.to_string(), "tcp://127.0.0.1:5555");
+
+    assert_eq!(Url::tls("localhost", 443).expect("create url").as_str(), "tls+tcp://localhost:443");
+    assert_eq!(Url::ws("localhost", 80, "").expect("create url").as_str(), "ws://localhost:80/");
+    assert_eq!(Url::wss("localhost", 443, "/api/v1").expect("create url").as_str(), "wss://localhost:443/api/v1");
+    assert_eq!(Url::ipc("/tmp/socket").expect("create url").as_str(), "ipc:///tmp/socket");
+    assert_eq!(Url::abstract_socket("name").expect("create url").as_str(), "abstract://name");
+    assert_eq!(Url::new(Scheme::Inproc, "name").expect("create url"), Url::inproc("name").expect("create url"));
+
+    assert!(Url::inproc("na\0me").is_err());
+    assert!(Url::new(Scheme::Ipc, "/tmp/socket\0").is_err());
+    assert!(Url::tcp("local\0host", 80).is_err());
+}
+
+#[test]
+fn should_parse_url() {
+    let url = Url::parse("tcp://localhost:80\0").expect("parse");
+    assert_eq!(url, Url::tcp("localhost", 80).expect("create url"));
+    assert_eq!(Url::parse("ws6://[::1]:80/").expect("parse").scheme(), Scheme::Ws);
+
+    assert!(Url::parse("udp://localhost:80").is_err());
+    assert!(Url::parse("tcp://local\0host:80").is_err());
+}
+
+#[test]
+fn should_connect_via_url() {
+    let url = Url::inproc("should_connect_via_url").expect("create url");
+
+    let server = Socket::rep0().expect("create server");
+    server.listen((&url).into()).expect("listen");
+    let client = Socket::req0().expect("create client");
+    client.connect((&url).into()).expect("connect");
+
+    client.send(b"ping".into()).expect("send");
+    let msg = server.recv_msg().expect("recv");
+    assert_eq!(msg.body(), b"ping");
+}
+
+#[test]
+fn should_build_ipv6_url() {
+    assert_eq!(Url::tcp("::1", 5555).expect("create url").as_str(), "tcp://[::1]:5555");
+    assert_eq!(Url::tcp("[::1]", 5555).expect("create url").as_str(), "tcp://[::1]:5555");
+    assert_eq!(Url::tls("fe80::1%eth0", 443).expect("create url").as_str(), "tls+tcp://[fe80::1%eth0]:443");
+    assert_eq!(Url::ws("[fe80::1%25eth0]", 80, "/path").expect("create url").as_str(), "ws://[fe80::1%eth0]:80/path");
+    assert_eq!(Url::wss("2001:db8::1", 443, "api").expect("create url").as_str(), "wss://[2001:db8::1]:443/api");
+    assert_eq!(Url::tcp("localhost", 80).expect("create url").as_str(), "tcp://localhost:80");
+}
+
+#[test]
+fn should_build_wildcard_url() {
+    assert_eq!(Url::tcp("", 5555).expect("create url").as_str(), "tcp://:5555");
+    assert_eq!(Url::tcp("*", 5555).expect("create url").as_str(), "tcp://:5555");
+    assert_eq!(Url::tcp("::", 5555).expect("create url").as_str(), "tcp://[::]:5555");
+    assert_eq!(Url::wildcard(Scheme::Tls, 443).expect("create url").as_str(), "tls+tcp://:443");
+    assert_eq!(Url::wildcard(Scheme::Ws, 80).expect("create url").as_str(), "ws://:80/");
+    assert!(Url::wildcard(Scheme::Ipc, 80).is_err());
+}
+
+#[test]
+fn should_build_url_from_socket_addr() {
+    use core::net::{Ipv6Addr, SocketAddr, SocketAddrV6};
+
+    let addr = SocketAddr::from(([127, 0, 0, 1], 5555));
+    assert_eq!(Url::from_socket_addr(Scheme::Tcp, addr).expect("create url").as_str(), "tcp://127.0.0.1:5555");
+    assert_eq!(Url::from_socket_addr(Scheme::Ws, addr).expect("create url").as_str(), "ws://127.0.0.1:5555/");
+
+    let addr = SocketAddr::from((Ipv6Addr::LOCALHOST, 5555));
+    assert_eq!(Url::from_socket_addr(Scheme::Tls, addr).expect("create url").as_str(), "tls+tcp://[::1]:5555");
+
+    let addr = SocketAddr::V6(SocketAddrV6::new("fe80::1".parse().expect("ipv6"), 5555, 0, 2));
+    assert_eq!(Url::from_socket_addr(Scheme::Tcp, addr).expect("create url").as_str(), "tcp://[fe80::1%2]:5555");
+    assert!(Url::from_socket_addr(Scheme::Inproc, addr).is_err());
+}
+
+#[test]
+fn should_listen_on_ipv6_url() {
+    let url = Url::tcp("::1", 0).expect("create url");
+    let server = Socket::rep0().expect("create server");
+    match server.listen((&url).into()) {
+        Ok(()) => (),
+        //Host may have no IPv6 support, but url itself must be valid
+        Err(error) => assert_ne!(error.raw_code(), nng_c::sys::nng_errno_enum::NNG_EINVAL),
+    }
+}
@@ -0,0 +1,74 @@
+use nng_c::{options, Message, NngError, Socket};
+use nng_c::utils::block_on;
+use nng_c::watchdog::Watchdog;
+
+use core::time;
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn should_abort_stalled_operations() {
+    let stalls = Arc::new(Mutex::new(Vec::new()));
+    let watchdog = {
+        let stalls = stalls.clone();
+        Watchdog::new(time::Duration::from_millis(50), move |stall| {
+            stalls.lock().expect("lock").push(stall.name);
+            stall.name != "report-only"
+        }).expect("start watchdog")
+    };
+
+    let socket = Socket::pull0().expect("create socket");
+    let error = watchdog.recv(&socket, "recv").expect_err("abort receive");
+    assert!(error.is_timed_out());
+
+    let push = Socket::push0().expect("create socket");
+    let mut msg = Message::new().expect("create message");
+    msg.append(b"stalled").expect("append");
+    let error = watchdog.send(&push, msg, "send").expect_err("abort send");
+    assert!(error.is_timed_out());
+
+    let fut = watchdog.recv_async(&socket, "recv-async").expect("start receive");
+    assert_eq!(watchdog.len(), 1);
+    assert!(block_on(fut).expect("run executor").expect_err("abort receive").is_timed_out());
+    assert!(watchdog.is_empty());
+
+    {
+        let _guard = watchdog.guard("report-only");
+        std::thread::sleep(time::Duration::from_millis(100));
+    }
+    assert!(watchdog.is_empty());
+
+    drop(watchdog);
+    assert_eq!(*stalls.lock().expect("lock"), ["recv", "send", "recv-async", "report-only"]);
+}
+
+#[test]
+fn should_not_report_fast_operations() {
+    const ADDR: &str = "inproc://should_not_report_fast_operations\0";
+
+    let stalls = Arc::new(Mutex::new(0));
+    let watchdog = {
+        let stalls = stalls.clone();
+        Watchdog::new(time::Duration::from_secs(5), move |_| {
+            *stalls.lock().expect("lock") += 1;
+            true
+        }).expect("start watchdog")
+    };
+
+    let server = Socket::pull0().expect("create socket");
+    server.listen(ADDR.into()).expect("listen");
+    let client = Socket::push0().expect("create socket");
+    client.set_opt(options::SendTimeout(time::Duration::from_secs(1))).expect("set timeout");
+    client.connect(ADDR.into()).expect("connect");
+
+    let mut msg = Message::new().expect("create message");
+    msg.append(b"fast").expect("append");
+    watchdog.send(&client, msg, "send").expect("send");
+    assert_eq!(watchdog.recv(&server, "recv").expect("receive").body(), b"fast");
+
+    //Dropped future no longer watched
+    drop(watchdog.recv_async(&server, "dropped").expect("start receive"));
+    assert!(watchdog.is_empty());
+
+    drop(watchdog);
+    assert_eq!(*stalls.lock().expect("lock"), 0);
+}